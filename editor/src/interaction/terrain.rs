@@ -29,15 +29,20 @@ use crate::{
             log::{Log, MessageKind},
             math::vector_to_quat,
             pool::Handle,
+            reflect::prelude::*,
             uuid::{uuid, Uuid},
             TypeUuidProvider,
         },
         engine::Engine,
         graph::SceneGraph,
         gui::{
+            button::{ButtonBuilder, ButtonMessage},
+            grid::{Column, GridBuilder, Row},
             inspector::{
                 editors::{
-                    enumeration::EnumPropertyEditorDefinition, PropertyEditorDefinitionContainer,
+                    enumeration::EnumPropertyEditorDefinition,
+                    inspectable::InspectablePropertyEditorDefinition,
+                    PropertyEditorDefinitionContainer,
                 },
                 Inspector, InspectorBuilder, InspectorContext, InspectorMessage, PropertyAction,
             },
@@ -57,8 +62,10 @@ use crate::{
             },
             node::Node,
             terrain::{
-                brushstroke::{Brush, BrushMode, BrushShape, BrushStroke, BrushTarget},
-                Terrain, TerrainRayCastResult,
+                brushstroke::{Brush, BrushMode, BrushShape, BrushStroke, BrushTarget, ChunkData},
+                HeightBlend, HydraulicErosionSettings, LayerMaskRule, NoiseSettings,
+                TerraceSettings, Terrain, TerrainGenerator, TerrainRayCastResult,
+                ThermalErosionSettings,
             },
         },
     },
@@ -67,7 +74,8 @@ use crate::{
     message::MessageSender,
     scene::{
         commands::terrain::{
-            ModifyTerrainHeightCommand, ModifyTerrainHolesCommand, ModifyTerrainLayerMaskCommand,
+            ModifyTerrainFoliageMaskCommand, ModifyTerrainHeightCommand, ModifyTerrainHolesCommand,
+            ModifyTerrainLayerMaskCommand,
         },
         controller::SceneController,
         GameScene, Selection, SelectionContainer,
@@ -102,6 +110,9 @@ fn handle_undo_chunks(undo_chunks: UndoData, sender: &MessageSender) {
             undo_chunks.chunks,
             layer,
         )),
+        BrushTarget::FoliageMask { layer } => sender.do_command(
+            ModifyTerrainFoliageMaskCommand::new(undo_chunks.node, undo_chunks.chunks, layer),
+        ),
     }
 }
 
@@ -115,6 +126,7 @@ pub struct TerrainInteractionMode {
     brush_value: f32,
     brush: Brush,
     brush_panel: BrushPanel,
+    generator_panel: GeneratorPanel,
     scene_viewer_frame: Handle<Image>,
 }
 
@@ -136,11 +148,14 @@ impl TerrainInteractionMode {
 
         let brush_panel =
             BrushPanel::new(&mut engine.user_interfaces.first_mut().build_ctx(), &brush);
+        let generator_panel =
+            GeneratorPanel::new(&mut engine.user_interfaces.first_mut().build_ctx());
 
         Self {
             message_sender,
             brush_sender: None,
             brush_panel,
+            generator_panel,
             brush_gizmo: BrushGizmo::new(game_scene, engine),
             interacting: false,
             brush,
@@ -182,6 +197,11 @@ impl TerrainInteractionMode {
                 return;
             }
         }
+        if let BrushTarget::FoliageMask { layer } = brush.target {
+            if layer >= terrain.foliage_layers().len() {
+                return;
+            }
+        }
         // Reverse the behavior of a brush when shift is held.
         if shift {
             match &mut brush.mode {
@@ -208,11 +228,13 @@ impl TerrainInteractionMode {
         let position = match self.brush.target {
             BrushTarget::HeightMap => terrain.local_to_height_pixel(position),
             BrushTarget::LayerMask { .. } => terrain.local_to_mask_pixel(position),
+            BrushTarget::FoliageMask { .. } => terrain.local_to_mask_pixel(position),
             BrushTarget::HoleMask => terrain.local_to_hole_pixel(position),
         };
         let scale = match self.brush.target {
             BrushTarget::HeightMap => terrain.height_grid_scale(),
             BrushTarget::LayerMask { .. } => terrain.mask_grid_scale(),
+            BrushTarget::FoliageMask { .. } => terrain.mask_grid_scale(),
             BrushTarget::HoleMask => terrain.hole_grid_scale(),
         };
         if let Some(sender) = &self.brush_sender {
@@ -431,6 +453,20 @@ impl InteractionMode for TerrainInteractionMode {
                 focus_content: false,
             },
         );
+
+        engine.user_interfaces.first_mut().send(
+            self.generator_panel.window,
+            WindowMessage::Open {
+                alignment: WindowAlignment::Relative {
+                    relative_to: self.scene_viewer_frame.to_base(),
+                    horizontal_alignment: HorizontalAlignment::Left,
+                    vertical_alignment: VerticalAlignment::Top,
+                    margin: Thickness::top_left(5.0),
+                },
+                modal: false,
+                focus_content: false,
+            },
+        );
     }
 
     fn deactivate(&mut self, controller: &dyn SceneController, engine: &mut Engine) {
@@ -447,18 +483,35 @@ impl InteractionMode for TerrainInteractionMode {
             .user_interfaces
             .first()
             .send(self.brush_panel.window, WindowMessage::Close);
+        engine
+            .user_interfaces
+            .first()
+            .send(self.generator_panel.window, WindowMessage::Close);
     }
 
     fn handle_ui_message(
         &mut self,
         message: &UiMessage,
         editor_selection: &Selection,
-        _controller: &mut dyn SceneController,
-        _engine: &mut Engine,
+        controller: &mut dyn SceneController,
+        engine: &mut Engine,
     ) {
         if let Some(selection) = editor_selection.as_graph() {
             if selection.is_single_selection() {
                 self.brush_panel.handle_ui_message(message, &mut self.brush);
+
+                if let Some(game_scene) = controller.downcast_mut::<GameScene>() {
+                    let graph = &mut engine.scenes[game_scene.scene].graph;
+                    let handle = selection.nodes()[0];
+                    if let Some(terrain) = graph[handle].cast_mut::<Terrain>() {
+                        self.generator_panel.handle_ui_message(
+                            message,
+                            terrain,
+                            handle,
+                            &self.message_sender,
+                        );
+                    }
+                }
             }
         }
     }
@@ -468,6 +521,10 @@ impl InteractionMode for TerrainInteractionMode {
             .user_interfaces
             .first()
             .send(self.brush_panel.window, WidgetMessage::Remove);
+        engine
+            .user_interfaces
+            .first()
+            .send(self.generator_panel.window, WidgetMessage::Remove);
     }
 
     fn on_hot_key_pressed(
@@ -514,12 +571,16 @@ impl InteractionMode for TerrainInteractionMode {
             self.modify_brush_opacity(1.0);
             processed = true;
         } else if hotkey == &key_bindings.prev_layer {
-            if let BrushTarget::LayerMask { layer, .. } = &mut self.brush.target {
+            if let BrushTarget::LayerMask { layer, .. } | BrushTarget::FoliageMask { layer } =
+                &mut self.brush.target
+            {
                 *layer = layer.saturating_sub(1);
             }
             processed = true;
         } else if hotkey == &key_bindings.next_layer {
-            if let BrushTarget::LayerMask { layer, .. } = &mut self.brush.target {
+            if let BrushTarget::LayerMask { layer, .. } | BrushTarget::FoliageMask { layer } =
+                &mut self.brush.target
+            {
                 *layer = layer.saturating_add(1);
             }
             processed = true;
@@ -589,18 +650,21 @@ fn make_brush_target_enum_property_editor_definition() -> EnumPropertyEditorDefi
             0 => BrushTarget::HeightMap,
             1 => BrushTarget::LayerMask { layer: 0 },
             2 => BrushTarget::HoleMask,
+            3 => BrushTarget::FoliageMask { layer: 0 },
             _ => unreachable!(),
         },
         index_generator: |v| match v {
             BrushTarget::HeightMap => 0,
             BrushTarget::LayerMask { .. } => 1,
             BrushTarget::HoleMask => 2,
+            BrushTarget::FoliageMask { .. } => 3,
         },
         names_generator: || {
             vec![
                 "Height Map".to_string(),
                 "Layer Mask".to_string(),
                 "Holes".to_string(),
+                "Foliage".to_string(),
             ]
         },
     }
@@ -686,3 +750,176 @@ impl BrushPanel {
         Some(())
     }
 }
+
+#[derive(Default, Debug, Clone, Reflect)]
+struct GeneratorOptions {
+    generator: TerrainGenerator,
+}
+
+struct GeneratorPanel {
+    window: Handle<Window>,
+    inspector: Handle<Inspector>,
+    apply: Handle<Button>,
+    options: GeneratorOptions,
+}
+
+fn make_terrain_generator_enum_property_editor_definition(
+) -> EnumPropertyEditorDefinition<TerrainGenerator> {
+    EnumPropertyEditorDefinition {
+        variant_generator: |i| match i {
+            0 => TerrainGenerator::FractalNoise(Default::default()),
+            1 => TerrainGenerator::RidgedNoise(Default::default()),
+            2 => TerrainGenerator::Terrace(Default::default()),
+            3 => TerrainGenerator::ThermalErosion(Default::default()),
+            4 => TerrainGenerator::HydraulicErosion(Default::default()),
+            5 => TerrainGenerator::SlopeMask(Default::default()),
+            6 => TerrainGenerator::HeightMask(LayerMaskRule {
+                range: 0.0..10.0,
+                ..Default::default()
+            }),
+            _ => unreachable!(),
+        },
+        index_generator: |v| match v {
+            TerrainGenerator::FractalNoise(_) => 0,
+            TerrainGenerator::RidgedNoise(_) => 1,
+            TerrainGenerator::Terrace(_) => 2,
+            TerrainGenerator::ThermalErosion(_) => 3,
+            TerrainGenerator::HydraulicErosion(_) => 4,
+            TerrainGenerator::SlopeMask(_) => 5,
+            TerrainGenerator::HeightMask(_) => 6,
+        },
+        names_generator: || {
+            vec![
+                "Fractal Noise".to_string(),
+                "Ridged Noise".to_string(),
+                "Terrace".to_string(),
+                "Thermal Erosion".to_string(),
+                "Hydraulic Erosion".to_string(),
+                "Slope Mask".to_string(),
+                "Height Mask".to_string(),
+            ]
+        },
+    }
+}
+
+impl GeneratorPanel {
+    fn new(ctx: &mut BuildContext) -> Self {
+        let options = GeneratorOptions::default();
+
+        let property_editors = PropertyEditorDefinitionContainer::with_default_editors();
+        property_editors.insert(make_terrain_generator_enum_property_editor_definition());
+        property_editors.insert(EnumPropertyEditorDefinition::<HeightBlend>::new());
+        property_editors.insert(InspectablePropertyEditorDefinition::<NoiseSettings>::new());
+        property_editors.insert(InspectablePropertyEditorDefinition::<TerraceSettings>::new());
+        property_editors
+            .insert(InspectablePropertyEditorDefinition::<ThermalErosionSettings>::new());
+        property_editors.insert(InspectablePropertyEditorDefinition::<
+            HydraulicErosionSettings,
+        >::new());
+        property_editors.insert(InspectablePropertyEditorDefinition::<LayerMaskRule>::new());
+
+        let context = InspectorContext::from_object(InspectorContextArgs {
+            object: &options,
+            ctx,
+            definition_container: Arc::new(property_editors),
+            environment: None,
+            layer_index: 0,
+            generate_property_string_values: true,
+            filter: Default::default(),
+            name_column_width: 150.0,
+            base_path: Default::default(),
+            has_parent_object: false,
+        });
+
+        let inspector;
+        let apply;
+        let window = WindowBuilder::new(WidgetBuilder::new().with_width(300.0).with_height(400.0))
+            .can_minimize(false)
+            .can_maximize(false)
+            .with_content(
+                GridBuilder::new(
+                    WidgetBuilder::new()
+                        .with_child({
+                            inspector = InspectorBuilder::new(WidgetBuilder::new().on_row(0))
+                                .with_context(context)
+                                .build(ctx);
+                            inspector
+                        })
+                        .with_child({
+                            apply = ButtonBuilder::new(
+                                WidgetBuilder::new()
+                                    .on_row(1)
+                                    .with_margin(Thickness::uniform(1.0)),
+                            )
+                            .with_text("Apply")
+                            .build(ctx);
+                            apply
+                        }),
+                )
+                .add_column(Column::stretch())
+                .add_row(Row::stretch())
+                .add_row(Row::strict(24.0))
+                .build(ctx),
+            )
+            .open(false)
+            .with_title(WindowTitle::text("Terrain Generator"))
+            .build(ctx);
+
+        Self {
+            window,
+            inspector,
+            apply,
+            options,
+        }
+    }
+
+    fn handle_ui_message(
+        &mut self,
+        message: &UiMessage,
+        terrain: &mut Terrain,
+        handle: Handle<Node>,
+        sender: &MessageSender,
+    ) {
+        if let Some(InspectorMessage::PropertyChanged(msg)) =
+            message.data_from::<InspectorMessage>(self.inspector)
+        {
+            PropertyAction::from_field_action(&msg.action).apply(
+                &msg.path(),
+                &mut self.options,
+                &mut |result| {
+                    Log::verify(result);
+                },
+            );
+        } else if let Some(ButtonMessage::Click) = message.data_from::<ButtonMessage>(self.apply) {
+            let generator = &self.options.generator;
+            if let Some(layer) = generator.layer() {
+                if layer >= terrain.layers().len() {
+                    Log::err(format!("Terrain has no layer {layer}."));
+                    return;
+                }
+            }
+
+            // Apply the generator immediately and remember the previous state of the terrain,
+            // so the change could be undone.
+            let chunks = terrain
+                .chunks_ref()
+                .iter()
+                .map(|c| match generator.layer() {
+                    Some(layer) => {
+                        ChunkData::from_texture(c.grid_position(), &c.layer_masks[layer])
+                    }
+                    None => ChunkData::from_texture(c.grid_position(), c.heightmap()),
+                })
+                .collect::<Vec<_>>();
+
+            terrain.apply_generator(generator);
+
+            match generator.layer() {
+                Some(layer) => {
+                    sender.do_command(ModifyTerrainLayerMaskCommand::new(handle, chunks, layer))
+                }
+                None => sender.do_command(ModifyTerrainHeightCommand::new(handle, chunks)),
+            }
+        }
+    }
+}
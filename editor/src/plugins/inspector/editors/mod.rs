@@ -122,6 +122,7 @@ use crate::{
                 BaseLight,
             },
             mesh::{
+                lod::{LodGenerationSettings, LodLevelSettings},
                 surface::{BlendShape, Surface, SurfaceResource},
                 BatchingMode, Mesh, RenderPath,
             },
@@ -146,18 +147,20 @@ use crate::{
                     HighShelfFilterEffect, LowPassFilterEffect, LowShelfFilterEffect,
                 },
                 listener::Listener,
-                reverb::Reverb,
+                reverb::{Reverb, ReverbParameters, ReverbPreset},
+                reverb_zone::{ReverbZone, ReverbZoneShape},
                 Attenuate, AudioBus, Biquad, DistanceModel, Effect, Sound, SoundBuffer,
                 SoundBufferResource, Status,
             },
             sprite::Sprite,
-            terrain::{Chunk, Layer, Terrain},
+            terrain::{Chunk, FoliageLayer, FoliageLod, FoliageWind, Layer, Terrain},
             tilemap::{
                 brush::{TileMapBrush, TileMapBrushResource},
                 tileset::TileSet,
                 Tile, TileCollider, TileDefinitionHandle, TileMap,
             },
             transform::Transform,
+            vehicle::VehicleWheel,
             EnvironmentLightingSource,
         },
     },
@@ -336,12 +339,22 @@ pub fn make_property_editors_container(
 
     container.register_inheritable_vec_collection::<Layer>();
     container.register_inheritable_inspectable::<Layer>();
+    container.register_inheritable_vec_collection::<FoliageLayer>();
+    container.register_inheritable_inspectable::<FoliageLayer>();
+    container.register_inheritable_vec_collection::<FoliageLod>();
+    container.register_inheritable_inspectable::<FoliageLod>();
+    container.register_inheritable_inspectable::<FoliageWind>();
 
     container.register_inheritable_vec_collection::<Emitter>();
 
     container.register_inheritable_vec_collection::<LevelOfDetail>();
     container.register_inheritable_inspectable::<LevelOfDetail>();
 
+    container.register_inheritable_option::<LodGenerationSettings>();
+    container.register_inheritable_inspectable::<LodGenerationSettings>();
+    container.register_inheritable_vec_collection::<LodLevelSettings>();
+    container.register_inheritable_inspectable::<LodLevelSettings>();
+
     container.register_inheritable_vec_collection::<ErasedHandle>();
     container.register_inheritable_inspectable::<ErasedHandle>();
 
@@ -354,6 +367,9 @@ pub fn make_property_editors_container(
     container.register_inheritable_vec_collection::<dim2::collider::GeometrySource>();
     container.register_inheritable_inspectable::<dim2::collider::GeometrySource>();
 
+    container.register_inheritable_vec_collection::<VehicleWheel>();
+    container.register_inheritable_inspectable::<VehicleWheel>();
+
     container.insert(make_status_enum_editor_definition());
 
     container.insert(InspectablePropertyEditorDefinition::<SceneNodeId>::new());
@@ -442,6 +458,7 @@ pub fn make_property_editors_container(
     container.register_inheritable_inspectable::<dim2::joint::FixedJoint>();
     container.register_inheritable_inspectable::<RevoluteJoint>();
     container.register_inheritable_inspectable::<PrismaticJoint>();
+    container.register_inheritable_inspectable::<SpringJoint>();
     container.register_inheritable_inspectable::<dim2::joint::PrismaticJoint>();
 
     container.register_inheritable_inspectable::<Base>();
@@ -458,6 +475,9 @@ pub fn make_property_editors_container(
     container.insert(InspectablePropertyEditorDefinition::<LowShelfFilterEffect>::new());
     container.insert(InspectablePropertyEditorDefinition::<HighShelfFilterEffect>::new());
     container.insert(InspectablePropertyEditorDefinition::<Reverb>::new());
+    container.insert(InspectablePropertyEditorDefinition::<ReverbParameters>::new());
+    container.register_inheritable_enum::<ReverbPreset, _>();
+    container.register_inheritable_enum::<ReverbZoneShape, _>();
 
     container.register_inheritable_enum::<Emitter, _>();
 
@@ -760,6 +780,7 @@ pub fn make_property_editors_container(
         ParticleSystem,
         Sound,
         Listener,
+        ReverbZone,
         Terrain,
         TileMap
     );
@@ -27,7 +27,10 @@ use crate::fyrox::{
 };
 use crate::scene::commands::{GameSceneContext, RevertSceneNodePropertyCommand};
 use crate::{
-    scene::commands::terrain::{AddTerrainLayerCommand, DeleteTerrainLayerCommand},
+    scene::commands::terrain::{
+        AddTerrainFoliageLayerCommand, AddTerrainLayerCommand, DeleteTerrainFoliageLayerCommand,
+        DeleteTerrainLayerCommand,
+    },
     Command,
 };
 use fyrox::graph::SceneGraph;
@@ -56,6 +59,20 @@ impl SceneNodePropertyChangedHandler {
                 },
                 _ => None,
             }
+        } else if args.path() == Terrain::FOLIAGE_LAYERS && node.is_terrain() {
+            match args.action {
+                FieldAction::CollectionAction(ref collection_changed) => match **collection_changed
+                {
+                    CollectionAction::Add(_) => {
+                        Some(Command::new(AddTerrainFoliageLayerCommand::new(handle)))
+                    }
+                    CollectionAction::Remove(index) => Some(Command::new(
+                        DeleteTerrainFoliageLayerCommand::new(handle, index),
+                    )),
+                    CollectionAction::ItemChanged { .. } => None,
+                },
+                _ => None,
+            }
         } else {
             None
         }
@@ -20,9 +20,18 @@
 
 use crate::command::CommandContext;
 use crate::fyrox::{
-    core::pool::Handle,
+    core::pool::{Handle, Ticket},
+    graph::SceneGraph,
     resource::texture::TextureResource,
-    scene::{mesh::Mesh, node::Node},
+    scene::{
+        base::LodGroup,
+        graph::SubGraph,
+        mesh::{
+            lod::{self, LodGenerationSettings},
+            Mesh,
+        },
+        node::Node,
+    },
 };
 use crate::{command::CommandTrait, scene::commands::GameSceneContext};
 use fyrox::material::{MaterialResourceBinding, MaterialTextureBinding};
@@ -116,3 +125,129 @@ impl CommandTrait for SetMeshTextureCommand {
         }
     }
 }
+
+#[derive(Debug)]
+pub struct GenerateMeshLodsCommand {
+    mesh: Handle<Node>,
+    settings: LodGenerationSettings,
+    // Parent of the mesh before the generation.
+    old_parent: Handle<Node>,
+    // A node that holds the LOD group, it is created by the first generation for the mesh.
+    group: Handle<Node>,
+    group_created: bool,
+    group_node: Option<(Ticket<Node>, Node)>,
+    lods: Vec<(Handle<Node>, Option<Ticket<Node>>, Option<Node>)>,
+    // Levels of the previous LOD group, that were replaced by the generated ones.
+    replaced_lods: Vec<(Handle<Node>, Option<SubGraph>)>,
+    lod_group: Option<LodGroup>,
+    generated: bool,
+}
+
+impl GenerateMeshLodsCommand {
+    pub fn new(mesh: Handle<Node>, settings: LodGenerationSettings) -> Self {
+        Self {
+            mesh,
+            settings,
+            old_parent: Handle::NONE,
+            group: Handle::NONE,
+            group_created: false,
+            group_node: None,
+            lods: Default::default(),
+            replaced_lods: Default::default(),
+            lod_group: None,
+            generated: false,
+        }
+    }
+}
+
+impl CommandTrait for GenerateMeshLodsCommand {
+    fn name(&mut self, _context: &dyn CommandContext) -> String {
+        "Generate Mesh LODs".to_owned()
+    }
+
+    fn execute(&mut self, context: &mut dyn CommandContext) {
+        let context = context.get_mut::<GameSceneContext>();
+        let graph = &mut context.scene.graph;
+        if self.generated {
+            if let Some((ticket, node)) = self.group_node.take() {
+                let new_handle = graph.put_back(ticket, node);
+                assert_eq!(new_handle, self.group);
+                graph.link_nodes(self.group, self.old_parent);
+                graph.link_nodes(self.mesh, self.group);
+            }
+            for (handle, sub_graph) in self.replaced_lods.iter_mut() {
+                *sub_graph = Some(graph.take_reserve_sub_graph(*handle));
+            }
+            for (handle, ticket, node) in self.lods.iter_mut() {
+                let new_handle = graph.put_back(ticket.take().unwrap(), node.take().unwrap());
+                assert_eq!(new_handle, *handle);
+                graph.link_nodes(*handle, self.group);
+            }
+            self.lod_group = graph[self.group].set_lod_group(self.lod_group.take());
+        } else {
+            self.old_parent = graph[self.mesh].parent();
+            let old_lod_group = graph
+                .try_get_node(lod::generated_lod_group(graph, self.mesh))
+                .ok()
+                .and_then(|group| group.lod_group().cloned());
+            // Take the old levels out of the graph before the generation, so they could be put back
+            // on revert.
+            self.replaced_lods = lod::replaced_lod_objects(graph, self.mesh)
+                .into_iter()
+                .map(|handle| (handle, Some(graph.take_reserve_sub_graph(handle))))
+                .collect();
+            let generated = lod::generate_lods(graph, self.mesh, &self.settings);
+            self.group = generated.group;
+            self.group_created = generated.group_created;
+            self.lods = generated
+                .levels
+                .into_iter()
+                .map(|handle| (handle, None, None))
+                .collect();
+            self.lod_group = old_lod_group;
+            self.generated = true;
+        }
+    }
+
+    fn revert(&mut self, context: &mut dyn CommandContext) {
+        let context = context.get_mut::<GameSceneContext>();
+        let graph = &mut context.scene.graph;
+        if self.group.is_none() {
+            return;
+        }
+        self.lod_group = graph[self.group].set_lod_group(self.lod_group.take());
+        for (handle, ticket, node) in self.lods.iter_mut().rev() {
+            let (new_ticket, lod) = graph.take_reserve(*handle);
+            *ticket = Some(new_ticket);
+            *node = Some(lod);
+        }
+        for (handle, sub_graph) in self.replaced_lods.iter_mut() {
+            let new_handle = graph.put_sub_graph_back(sub_graph.take().unwrap());
+            assert_eq!(new_handle, *handle);
+        }
+        if self.group_created {
+            graph.link_nodes(self.mesh, self.old_parent);
+            self.group_node = Some(graph.take_reserve(self.group));
+        }
+    }
+
+    fn finalize(&mut self, context: &mut dyn CommandContext) {
+        let context = context.get_mut::<GameSceneContext>();
+        for (_, ticket, node) in self.lods.iter_mut() {
+            if let Some(ticket) = ticket.take() {
+                context
+                    .scene
+                    .graph
+                    .forget_ticket(ticket, node.take().unwrap());
+            }
+        }
+        if let Some((ticket, node)) = self.group_node.take() {
+            context.scene.graph.forget_ticket(ticket, node);
+        }
+        for (_, sub_graph) in self.replaced_lods.iter_mut() {
+            if let Some(sub_graph) = sub_graph.take() {
+                context.scene.graph.forget_sub_graph(sub_graph);
+            }
+        }
+    }
+}
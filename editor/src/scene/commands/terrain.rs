@@ -24,7 +24,10 @@ use crate::command::CommandContext;
 use crate::fyrox::{
     core::pool::Handle,
     resource::texture::TextureResource,
-    scene::{node::Node, terrain::Layer},
+    scene::{
+        node::Node,
+        terrain::{FoliageLayer, Layer},
+    },
 };
 use crate::{
     command::CommandTrait, create_terrain_layer_material, scene::commands::GameSceneContext,
@@ -115,6 +118,94 @@ impl CommandTrait for DeleteTerrainLayerCommand {
     }
 }
 
+#[derive(Debug)]
+pub struct AddTerrainFoliageLayerCommand {
+    terrain: Handle<Node>,
+    layer: Option<FoliageLayer>,
+    masks: Vec<TextureResource>,
+}
+
+impl AddTerrainFoliageLayerCommand {
+    pub fn new(terrain_handle: Handle<Node>) -> Self {
+        Self {
+            terrain: terrain_handle,
+            layer: Some(Default::default()),
+            masks: Default::default(),
+        }
+    }
+}
+
+impl CommandTrait for AddTerrainFoliageLayerCommand {
+    fn name(&mut self, _context: &dyn CommandContext) -> String {
+        "Add Terrain Foliage Layer".to_owned()
+    }
+
+    fn execute(&mut self, context: &mut dyn CommandContext) {
+        let context = context.get_mut::<GameSceneContext>();
+        let terrain = context.scene.graph[self.terrain].as_terrain_mut();
+        let index = terrain.foliage_layers().len();
+        terrain.insert_foliage_layer(
+            self.layer.take().unwrap(),
+            std::mem::take(&mut self.masks),
+            index,
+        );
+    }
+
+    fn revert(&mut self, context: &mut dyn CommandContext) {
+        let context = context.get_mut::<GameSceneContext>();
+        let terrain = context.scene.graph[self.terrain].as_terrain_mut();
+        let index = terrain.foliage_layers().len() - 1;
+        let (layer, masks) = terrain.remove_foliage_layer(index);
+        self.layer = Some(layer);
+        self.masks = masks;
+    }
+}
+
+#[derive(Debug)]
+pub struct DeleteTerrainFoliageLayerCommand {
+    terrain: Handle<Node>,
+    layer: Option<FoliageLayer>,
+    index: usize,
+    masks: Vec<TextureResource>,
+}
+
+impl DeleteTerrainFoliageLayerCommand {
+    pub fn new(terrain: Handle<Node>, index: usize) -> Self {
+        Self {
+            terrain,
+            layer: Default::default(),
+            index,
+            masks: Default::default(),
+        }
+    }
+}
+
+impl CommandTrait for DeleteTerrainFoliageLayerCommand {
+    fn name(&mut self, _context: &dyn CommandContext) -> String {
+        "Delete Terrain Foliage Layer".to_owned()
+    }
+
+    fn execute(&mut self, context: &mut dyn CommandContext) {
+        let context = context.get_mut::<GameSceneContext>();
+        let (layer, masks) = context.scene.graph[self.terrain]
+            .as_terrain_mut()
+            .remove_foliage_layer(self.index);
+
+        self.layer = Some(layer);
+        self.masks = masks;
+    }
+
+    fn revert(&mut self, context: &mut dyn CommandContext) {
+        let context = context.get_mut::<GameSceneContext>();
+        let terrain = context.scene.graph[self.terrain].as_terrain_mut();
+        terrain.insert_foliage_layer(
+            self.layer.take().unwrap(),
+            std::mem::take(&mut self.masks),
+            self.index,
+        );
+    }
+}
+
 #[derive(Debug)]
 pub struct ModifyTerrainHeightCommand {
     terrain: Handle<Node>,
@@ -250,3 +341,49 @@ impl CommandTrait for ModifyTerrainLayerMaskCommand {
         self.swap(context);
     }
 }
+
+#[derive(Debug)]
+pub struct ModifyTerrainFoliageMaskCommand {
+    terrain: Handle<Node>,
+    masks: Vec<ChunkData>,
+    layer: usize,
+    skip_first_execute: bool,
+}
+
+impl ModifyTerrainFoliageMaskCommand {
+    pub fn new(terrain: Handle<Node>, masks: Vec<ChunkData>, layer: usize) -> Self {
+        Self {
+            terrain,
+            masks,
+            layer,
+            skip_first_execute: true,
+        }
+    }
+
+    pub fn swap(&mut self, context: &mut dyn CommandContext) {
+        let context = context.get_mut::<GameSceneContext>();
+        let terrain = context.scene.graph[self.terrain].as_terrain_mut();
+        let current_chunks = terrain.chunks_mut();
+        for c in self.masks.iter_mut() {
+            c.swap_foliage_mask_from_list(current_chunks, self.layer);
+        }
+    }
+}
+
+impl CommandTrait for ModifyTerrainFoliageMaskCommand {
+    fn name(&mut self, _context: &dyn CommandContext) -> String {
+        "Modify Terrain Foliage Mask".to_owned()
+    }
+
+    fn execute(&mut self, context: &mut dyn CommandContext) {
+        if self.skip_first_execute {
+            self.skip_first_execute = false;
+            return;
+        }
+        self.swap(context);
+    }
+
+    fn revert(&mut self, context: &mut dyn CommandContext) {
+        self.swap(context);
+    }
+}
@@ -239,6 +239,8 @@ impl GameScene {
                 // Update only editor's camera.
                 node_overrides: Some(Default::default()),
                 paused: false,
+                // Do not stream the world in, the cells must not be baked into the edited scene.
+                streaming: false,
             },
             sender,
             camera_state: Default::default(),
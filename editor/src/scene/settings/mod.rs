@@ -42,7 +42,7 @@ use crate::{
         scene::{
             dim2,
             graph::{
-                physics::{IntegrationParameters, PhysicsWorld},
+                physics::{DeterminismSettings, IntegrationParameters, PhysicsWorld},
                 Graph, NodePool,
             },
             SceneRenderingOptions,
@@ -91,6 +91,7 @@ impl SceneSettingsWindow {
 
         property_definitions.register_inheritable_inspectable::<Graph>();
         property_definitions.register_inheritable_inspectable::<IntegrationParameters>();
+        property_definitions.register_inheritable_inspectable::<DeterminismSettings>();
         property_definitions.register_inheritable_inspectable::<PhysicsWorld>();
         property_definitions.register_inheritable_inspectable::<dim2::physics::PhysicsWorld>();
         property_definitions.register_inheritable_inspectable::<SceneRenderingOptions>();
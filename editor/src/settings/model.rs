@@ -18,7 +18,10 @@
 // OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 // SOFTWARE.
 
-use crate::fyrox::core::{algebra::Vector3, reflect::prelude::*};
+use crate::fyrox::{
+    core::{algebra::Vector3, reflect::prelude::*},
+    scene::mesh::lod::LodGenerationSettings,
+};
 use serde::{Deserialize, Serialize};
 
 #[derive(Deserialize, Serialize, PartialEq, Clone, Debug, Reflect)]
@@ -26,12 +29,16 @@ pub struct ModelSettings {
     /// Initial scale the root of the instance will have after instantiation. Useful when you have
     /// lots of huge models and don't want to rescale them manually.
     pub instantiation_scale: Vector3<f32>,
+    /// Settings of LOD chains created by "Generate Mesh LODs" action of the world viewer.
+    #[serde(default)]
+    pub lod_generation: LodGenerationSettings,
 }
 
 impl Default for ModelSettings {
     fn default() -> Self {
         Self {
             instantiation_scale: Vector3::new(1.0, 1.0, 1.0),
+            lod_generation: Default::default(),
         }
     }
 }
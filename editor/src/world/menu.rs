@@ -39,6 +39,7 @@ use crate::{
             window::WindowMessage,
             BuildContext, RcUiNodeHandle, UiNode,
         },
+        scene::mesh::Mesh,
     },
     make_save_file_selector,
     menu::{create::CreateEntityMenu, create_menu_item, create_menu_item_shortcut},
@@ -49,7 +50,9 @@ use crate::{
                 AddNodeCommand, LinkNodesCommand, MoveNodeCommand, ReplaceNodeCommand,
                 SetGraphRootCommand, SetNodeTransformCommand,
             },
-            make_delete_selection_command, RevertSceneNodePropertyCommand,
+            make_delete_selection_command,
+            mesh::GenerateMeshLodsCommand,
+            RevertSceneNodePropertyCommand,
         },
         controller::SceneController,
         GameScene, Selection,
@@ -79,6 +82,7 @@ pub struct SceneNodeContextMenu {
     make_root: Handle<MenuItem>,
     open_asset: Handle<MenuItem>,
     reset_inheritable_properties: Handle<MenuItem>,
+    generate_lods: Handle<MenuItem>,
 }
 
 impl WorldViewerItemContextMenu for SceneNodeContextMenu {
@@ -119,6 +123,7 @@ impl SceneNodeContextMenu {
     pub const OPEN_PARENT_PREFAB: Uuid = uuid!("99903c9e-d7ac-49bd-9ee3-3bbd8536f595");
     pub const SAVE_AS_PREFAB: Uuid = uuid!("82a70cff-b536-46fb-83c0-4a886585d871");
     pub const RESET_INHERITABLE: Uuid = uuid!("95c6437f-dc23-4ec1-9490-d35f0864f027");
+    pub const GENERATE_LODS: Uuid = uuid!("d3b6e2a4-7c1f-4e59-8a0d-2f6c9b4e1a73");
     pub const SAVE_AS_PREFAB_FILE_SELECTOR: Uuid = uuid!("5d438037-a4be-4d70-a830-138185e1a049");
 
     pub fn new(
@@ -133,6 +138,7 @@ impl SceneNodeContextMenu {
         let make_root;
         let open_asset;
         let reset_inheritable_properties;
+        let generate_lods;
         let create_parent;
         let create_child;
         let replace_with;
@@ -249,6 +255,16 @@ impl SceneNodeContextMenu {
                                     ctx,
                                 );
                                 reset_inheritable_properties
+                            })
+                            .with_child(menu::make_menu_splitter(ctx))
+                            .with_child({
+                                generate_lods = create_menu_item(
+                                    "Generate Mesh LODs",
+                                    Self::GENERATE_LODS,
+                                    vec![],
+                                    ctx,
+                                );
+                                generate_lods
                             }),
                     )
                     .build(ctx),
@@ -278,6 +294,7 @@ impl SceneNodeContextMenu {
             make_root,
             open_asset,
             reset_inheritable_properties,
+            generate_lods,
             create_parent_entity_menu,
         }
     }
@@ -478,6 +495,29 @@ impl SceneNodeContextMenu {
                         }
                         sender.do_command(CommandGroup::from(commands));
                     }
+                } else if message.destination() == self.generate_lods {
+                    if let Some(graph_selection) = editor_selection.as_graph() {
+                        let scene = &engine.scenes[game_scene.scene];
+                        let commands = graph_selection
+                            .nodes
+                            .iter()
+                            .filter(|h| {
+                                scene
+                                    .graph
+                                    .try_get_node(**h)
+                                    .is_ok_and(|n| n.cast::<Mesh>().is_some())
+                            })
+                            .map(|h| {
+                                Command::new(GenerateMeshLodsCommand::new(
+                                    *h,
+                                    settings.model.lod_generation.clone(),
+                                ))
+                            })
+                            .collect::<Vec<_>>();
+                        if !commands.is_empty() {
+                            sender.do_command(CommandGroup::from(commands));
+                        }
+                    }
                 }
             } else if let Some(PopupMessage::Placement(Placement::Cursor(target))) = message.data()
             {
@@ -490,6 +530,19 @@ impl SceneNodeContextMenu {
                         WidgetMessage::Enabled(!game_scene.clipboard.is_empty()),
                     );
 
+                    let has_meshes = editor_selection.as_graph().is_some_and(|s| {
+                        s.nodes.iter().any(|h| {
+                            engine.scenes[game_scene.scene]
+                                .graph
+                                .try_get_node(*h)
+                                .is_ok_and(|n| n.cast::<Mesh>().is_some())
+                        })
+                    });
+                    engine
+                        .user_interfaces
+                        .first()
+                        .send(self.generate_lods, WidgetMessage::Enabled(has_meshes));
+
                     engine.user_interfaces.first().send(
                         self.open_asset,
                         WidgetMessage::Enabled(
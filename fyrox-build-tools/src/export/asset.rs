@@ -24,8 +24,8 @@ use crate::export::utils;
 use fyrox_core::{
     futures::executor, futures::future::join_all, log::Log, platform::TargetPlatform, SafeLock,
 };
-use fyrox_resource::manager::ResourceManager;
-use std::{fs, io, path::Path};
+use fyrox_resource::{cache::DerivedDataCache, manager::ResourceManager};
+use std::{ffi::OsStr, fs, io, path::Path};
 
 pub fn copy_and_convert_assets(
     src_folder: impl AsRef<Path>,
@@ -35,6 +35,11 @@ pub fn copy_and_convert_assets(
     resource_manager: &ResourceManager,
     convert: bool,
 ) -> io::Result<()> {
+    // The derived data cache is a local artifact of asset imports, it must not be shipped.
+    let filter = |path: &Path| {
+        path.file_name() != Some(OsStr::new(DerivedDataCache::FOLDER_NAME)) && filter(path)
+    };
+
     if convert {
         let rm = resource_manager.state();
         let io = rm.resource_io.clone();
@@ -26,10 +26,13 @@ use fyrox_core::{
     log::{Log, MessageKind},
     reflect::prelude::*,
 };
-use fyrox_resource::manager::ResourceManager;
+use fyrox_resource::{
+    manager::ResourceManager,
+    pack::{self, PackCompression, PackedResourceIo},
+};
 use std::{
     io::{BufRead, BufReader},
-    path::PathBuf,
+    path::{Path, PathBuf},
     sync::{
         atomic::{AtomicBool, Ordering},
         Arc,
@@ -63,6 +66,7 @@ pub struct ExportOptions {
     pub open_destination_folder: bool,
     pub convert_assets: bool,
     pub enable_optimization: bool,
+    pub pack_assets: bool,
 }
 
 impl Default for ExportOptions {
@@ -78,6 +82,7 @@ impl Default for ExportOptions {
             open_destination_folder: true,
             convert_assets: true,
             enable_optimization: true,
+            pack_assets: true,
         }
     }
 }
@@ -144,6 +149,34 @@ pub fn build_package(
     Ok(())
 }
 
+/// Packs the copied assets into a single pack file (see [`PackedResourceIo`]) and removes the loose
+/// files. The executor will pick the pack automatically if it exists in the working directory.
+fn pack_assets(destination_folder: &Path, assets_folders: &[PathBuf]) -> Result<(), String> {
+    let pack_path = destination_folder.join(PackedResourceIo::DEFAULT_FILE_NAME);
+
+    Log::info(format!(
+        "Trying to pack the assets into {}...",
+        pack_path.display()
+    ));
+
+    pack::pack_folders(
+        destination_folder,
+        assets_folders,
+        &pack_path,
+        PackCompression::Lz4,
+        &|_| true,
+    )
+    .map_err(|err| format!("Failed to pack the assets. Reason: {err:?}"))?;
+
+    for folder in assets_folders {
+        Log::verify(std::fs::remove_dir_all(destination_folder.join(folder)));
+    }
+
+    Log::info("The assets were packed successfully.");
+
+    Ok(())
+}
+
 pub fn export(
     export_options: ExportOptions,
     cancel_flag: Arc<AtomicBool>,
@@ -179,7 +212,7 @@ pub fn export(
         TargetPlatform::PC | TargetPlatform::WebAssembly => {
             Log::info("Trying to copy the assets...");
 
-            for folder in export_options.assets_folders {
+            for folder in export_options.assets_folders.iter() {
                 Log::info(format!(
                     "Trying to copy assets from {} to {}...",
                     folder.display(),
@@ -187,14 +220,21 @@ pub fn export(
                 ));
 
                 Log::verify(asset::copy_and_convert_assets(
-                    &folder,
-                    export_options.destination_folder.join(&folder),
+                    folder,
+                    export_options.destination_folder.join(folder),
                     export_options.target_platform,
                     &|_| true,
                     &resource_manager,
                     export_options.convert_assets,
                 ));
             }
+
+            if export_options.pack_assets && export_options.target_platform == TargetPlatform::PC {
+                pack_assets(
+                    &export_options.destination_folder,
+                    &export_options.assets_folders,
+                )?;
+            }
         }
         TargetPlatform::Android => android::copy_assets(
             &export_options,
@@ -327,6 +367,11 @@ struct CliExportOptions {
     /// If specified, enables all possible optimizations for the build.
     #[clap(short, long, default_value = "true")]
     pub enable_optimization: bool,
+
+    /// If specified, the exporter will pack all the assets into a single compressed pack file instead
+    /// of shipping them as loose files. Packs are supported only on PC.
+    #[clap(short, long, default_value = "true")]
+    pub pack_assets: bool,
 }
 
 pub fn cli_export(resource_manager: ResourceManager) {
@@ -348,6 +393,7 @@ pub fn cli_export(resource_manager: ResourceManager) {
         open_destination_folder: args.open_destination_folder,
         convert_assets: args.convert_assets,
         enable_optimization: args.enable_optimization,
+        pack_assets: args.pack_assets,
     };
 
     export(options, Default::default(), resource_manager).unwrap();
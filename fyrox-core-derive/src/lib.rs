@@ -132,6 +132,28 @@ use syn::{parse_macro_input, DeriveInput};
 /// }
 /// # fn main() {}
 /// ```
+///
+/// # Schema versioning
+///
+/// Structs and enums could declare their schema version and a function that returns migrations
+/// of their data, see `fyrox_core::visitor::migration` docs for more info. The version is
+/// visited right after entering the region of the type. Migrations cannot be specified without
+/// a schema version:
+///
+/// ```compile_fail
+/// use fyrox_core::visitor::{migration::Migration, Visit, VisitResult, Visitor};
+/// #[derive(Visit)]
+/// #[visit(migrations = "Foo::migrations")]
+/// struct Foo {
+///     bar: u32,
+/// }
+/// impl Foo {
+///     fn migrations() -> Vec<Migration> {
+///         Vec::new()
+///     }
+/// }
+/// # fn main() {}
+/// ```
 #[proc_macro_derive(Visit, attributes(visit))]
 pub fn visit(input: TokenStream) -> TokenStream {
     let ast = parse_macro_input!(input as DeriveInput);
@@ -41,6 +41,14 @@ fn impl_visit_struct(
     field_args: &ast::Fields<args::FieldArgs>,
 ) -> TokenStream2 {
     let visit_fn_body = if field_args.style == ast::Style::Unit {
+        if ty_args.schema_version.is_some() || ty_args.migrations.is_some() {
+            return syn::Error::new_spanned(
+                &ty_args.ident,
+                "unit structs have no region to store a schema version in",
+            )
+            .to_compile_error();
+        }
+
         quote! { Ok(()) }
     } else {
         // `field.visit(..)?;` parts
@@ -65,6 +73,11 @@ fn impl_visit_struct(
         let pre_visit_method = make_method_call(ty_args.pre_visit_method.as_ref());
         let post_visit_method = make_method_call(ty_args.post_visit_method.as_ref());
 
+        let schema_version = match self::schema_version_visit(ty_args) {
+            Ok(schema_version) => schema_version,
+            Err(err) => return err.to_compile_error(),
+        };
+
         quote! {
             #pre_visit_method
 
@@ -73,6 +86,8 @@ fn impl_visit_struct(
                 Err(err) => return Err(err),
             };
 
+            #schema_version
+
             #(#field_visits)*
 
             ::std::mem::drop(region);
@@ -86,11 +101,38 @@ fn impl_visit_struct(
     utils::create_impl(ty_args, visit_fn_body)
 }
 
+/// `region.visit_schema_version(..)?;` part, empty if the type has no schema version.
+fn schema_version_visit(ty_args: &args::TypeArgs) -> syn::Result<TokenStream2> {
+    match (ty_args.schema_version, ty_args.migrations.as_ref()) {
+        (None, None) => Ok(quote! {}),
+        (None, Some(migrations)) => Err(syn::Error::new_spanned(
+            migrations,
+            "`migrations` requires `schema_version` to be specified",
+        )),
+        (Some(version), migrations) => {
+            let migrations = match migrations {
+                Some(migrations) => quote! { &#migrations() },
+                None => quote! { &[] },
+            };
+            Ok(quote! {
+                if let Err(err) = region.visit_schema_version(#version, #migrations) {
+                    return Err(err);
+                }
+            })
+        }
+    }
+}
+
 /// impl `Visit` for `enum`
 fn impl_visit_enum(ty_args: &args::TypeArgs, variant_args: &[args::VariantArgs]) -> TokenStream2 {
     let ty_ident = &ty_args.ident;
     let ty_name = format!("{ty_ident}");
 
+    let schema_version = match self::schema_version_visit(ty_args) {
+        Ok(schema_version) => schema_version,
+        Err(err) => return err.to_compile_error(),
+    };
+
     // variant ID = variant index
     let id_type = quote!(u32);
 
@@ -241,6 +283,8 @@ fn impl_visit_enum(ty_args: &args::TypeArgs, variant_args: &[args::VariantArgs])
                  Err(err) => return Err(err),
              };
 
+             #schema_version
+
              let mut id = id(self);
              if let Err(err) = id.visit("Id", &mut region) {
                  return Err(err);
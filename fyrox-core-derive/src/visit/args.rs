@@ -33,6 +33,18 @@ pub struct TypeArgs {
     pub pre_visit_method: Option<Ident>,
     #[darling(default)]
     pub post_visit_method: Option<Ident>,
+
+    /// `#[visit(schema_version = 2)]`
+    ///
+    /// Current schema version of the type, see `fyrox_core::visitor::migration`.
+    #[darling(default)]
+    pub schema_version: Option<u32>,
+
+    /// `#[visit(migrations = "Self::migrations")]`
+    ///
+    /// A function that returns a list of schema migrations of the type.
+    #[darling(default)]
+    pub migrations: Option<Path>,
 }
 
 /// Parsed from struct's or enum variant's field
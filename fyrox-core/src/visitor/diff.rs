@@ -0,0 +1,888 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Semantic diff and three-way merge of visitor data.
+//!
+//! The ASCII format of [`Visitor`] is meant to be stored in version control systems, but text-based
+//! merge tools know nothing about its structure and often produce broken files. This module
+//! compares visitor trees instead of lines: regions are matched by their names and fields are
+//! matched by their names within a region. Items of collections (regions named `ItemN`) are
+//! additionally identified by the first `InstanceId` uuid found in their subtree, so a collection
+//! slot that was reused for a different object is reported as a replacement instead of a set of
+//! unrelated field changes. The merge aligns such items by their identities instead of their slots,
+//! so objects that were moved to other slots or added concurrently are not mixed together.
+//!
+//! [`diff`] produces a list of [`Change`]s between two visitors and [`merge`] performs three-way
+//! merge of two visitors that were derived from a common base, automatically merging every
+//! non-conflicting change.
+
+use crate::{
+    pool::Handle,
+    visitor::{
+        field::{Field, FieldKind},
+        writer::{ascii::AsciiWriter, Writer},
+        Visitor, VisitorNode,
+    },
+};
+use fxhash::{FxHashMap, FxHashSet};
+use std::{
+    collections::{hash_map::Entry, VecDeque},
+    fmt::{Display, Formatter},
+    hash::Hash,
+};
+use uuid::Uuid;
+
+/// Names of the uuid fields that are used to identify items of collections.
+pub const IDENTITY_FIELDS: &[&str] = &["InstanceId"];
+
+/// Maximum depth of the search of identity fields in a subtree of a collection item.
+const IDENTITY_SEARCH_DEPTH: usize = 8;
+
+/// A path to a region, starting from the topmost region (the root of the tree is not included).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RegionPath(pub Vec<String>);
+
+impl RegionPath {
+    fn join(&self, name: &str) -> Self {
+        let mut path = self.0.clone();
+        path.push(name.to_owned());
+        Self(path)
+    }
+}
+
+impl Display for RegionPath {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0.join(" > "))
+    }
+}
+
+/// A single difference between two visitors.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Change {
+    /// A field was added to a region.
+    FieldAdded {
+        /// Path to the region.
+        path: RegionPath,
+        /// The new field.
+        field: Field,
+    },
+    /// A field was removed from a region.
+    FieldRemoved {
+        /// Path to the region.
+        path: RegionPath,
+        /// The removed field.
+        field: Field,
+    },
+    /// A value of a field was changed.
+    FieldChanged {
+        /// Path to the region.
+        path: RegionPath,
+        /// Name of the field.
+        name: String,
+        /// Old value of the field.
+        old: FieldKind,
+        /// New value of the field.
+        new: FieldKind,
+    },
+    /// A new region was added.
+    RegionAdded {
+        /// Path to the new region.
+        path: RegionPath,
+    },
+    /// A region was removed.
+    RegionRemoved {
+        /// Path to the removed region.
+        path: RegionPath,
+    },
+    /// A region was replaced by a region with a different identity (see [`IDENTITY_FIELDS`]).
+    RegionReplaced {
+        /// Path to the region.
+        path: RegionPath,
+        /// Identity of the old region.
+        old: Uuid,
+        /// Identity of the new region.
+        new: Uuid,
+    },
+}
+
+fn format_value(kind: &FieldKind) -> String {
+    let mut bytes = Vec::new();
+    let field = Field::new("", kind.clone());
+    AsciiWriter::default()
+        .write_field(&field, &mut bytes)
+        .unwrap();
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+impl Display for Change {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Change::FieldAdded { path, field } => {
+                write!(f, "+ {path} : {}{}", field.name, format_value(&field.kind))
+            }
+            Change::FieldRemoved { path, field } => {
+                write!(f, "- {path} : {}{}", field.name, format_value(&field.kind))
+            }
+            Change::FieldChanged {
+                path,
+                name,
+                old,
+                new,
+            } => write!(
+                f,
+                "~ {path} : {name}{} -> {}",
+                format_value(old),
+                format_value(new)
+            ),
+            Change::RegionAdded { path } => write!(f, "+ {path}"),
+            Change::RegionRemoved { path } => write!(f, "- {path}"),
+            Change::RegionReplaced { path, old, new } => {
+                write!(f, "! {path} : {old} -> {new}")
+            }
+        }
+    }
+}
+
+/// A set of differences between two visitors.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct VisitorDiff {
+    /// Every change in the order of their appearance in the tree.
+    pub changes: Vec<Change>,
+}
+
+impl VisitorDiff {
+    /// Returns true if the visitors are semantically equal.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+impl Display for VisitorDiff {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for change in self.changes.iter() {
+            writeln!(f, "{change}")?;
+        }
+        Ok(())
+    }
+}
+
+/// A change that was made in both merged visitors in incompatible ways. The merged visitor
+/// contains "our" version of a conflicting field, and the modified version of a region when it
+/// was modified in one visitor and removed in the other.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MergeConflict {
+    /// Path to the region with the conflict.
+    pub path: RegionPath,
+    /// Human-readable description of the conflict.
+    pub description: String,
+    /// False if the conflict could not be resolved automatically. For example, items of a pool
+    /// (regions with `Generation` field) that were added to the same slot on both sides cannot be
+    /// renumbered, because handles to them would point to wrong objects.
+    pub resolved: bool,
+}
+
+impl Display for MergeConflict {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} : {}", self.path, self.description)
+    }
+}
+
+/// Result of a three-way merge.
+pub struct MergeOutput {
+    /// Merged visitor, it always contains a valid tree even if there are conflicts.
+    pub visitor: Visitor,
+    /// A list of conflicts, that were resolved automatically and should be reviewed.
+    pub conflicts: Vec<MergeConflict>,
+}
+
+impl MergeOutput {
+    /// Returns true if the merge has no conflicts.
+    pub fn is_clean(&self) -> bool {
+        self.conflicts.is_empty()
+    }
+
+    /// Returns true if every conflict was resolved automatically. The merged visitor must not be
+    /// used otherwise.
+    pub fn is_resolved(&self) -> bool {
+        self.conflicts.iter().all(|conflict| conflict.resolved)
+    }
+}
+
+#[derive(Copy, Clone)]
+struct NodeRef<'a> {
+    visitor: &'a Visitor,
+    handle: Handle<VisitorNode>,
+}
+
+impl<'a> NodeRef<'a> {
+    fn root(visitor: &'a Visitor) -> Self {
+        Self {
+            visitor,
+            handle: visitor.root,
+        }
+    }
+
+    fn node(&self) -> &'a VisitorNode {
+        self.visitor.nodes.borrow(self.handle)
+    }
+
+    fn name(&self) -> &'a str {
+        &self.node().name
+    }
+
+    fn fields(&self) -> &'a [Field] {
+        &self.node().fields
+    }
+
+    fn field(&self, name: &str) -> Option<&'a FieldKind> {
+        self.fields()
+            .iter()
+            .find(|field| field.name == name)
+            .map(|field| &field.kind)
+    }
+
+    fn children(&self) -> impl Iterator<Item = NodeRef<'a>> + 'a {
+        let visitor = self.visitor;
+        self.node().children.iter().map(move |handle| NodeRef {
+            visitor,
+            handle: *handle,
+        })
+    }
+
+    fn collection_index(&self) -> Option<usize> {
+        self.name().strip_prefix("Item")?.parse().ok()
+    }
+
+    fn identity(&self) -> Option<Uuid> {
+        // Only items of collections have identities.
+        self.collection_index()?;
+        let mut queue = VecDeque::from([(*self, 0)]);
+        while let Some((node, depth)) = queue.pop_front() {
+            for field in node.fields() {
+                if let FieldKind::Uuid(uuid) = field.kind {
+                    if IDENTITY_FIELDS.contains(&field.name.as_str()) {
+                        return Some(uuid);
+                    }
+                }
+            }
+            if depth < IDENTITY_SEARCH_DEPTH {
+                queue.extend(node.children().map(|child| (child, depth + 1)));
+            }
+        }
+        None
+    }
+
+    // Children of a region are matched by their identity if they have one and by their names
+    // otherwise.
+    fn key(&self) -> ChildKey<'a> {
+        match self.identity() {
+            Some(identity) => ChildKey::Identity(identity),
+            None => ChildKey::Name(self.name()),
+        }
+    }
+
+    // Returns keys of the children in their order and a map to find the children by their keys.
+    // Keys are calculated once per child, because finding an identity requires a search in the
+    // subtree of the child. If there are multiple children with the same key, the first one wins.
+    fn children_by_key(&self) -> (Vec<ChildKey<'a>>, FxHashMap<ChildKey<'a>, NodeRef<'a>>) {
+        let mut keys = Vec::with_capacity(self.node().children.len());
+        let mut map = FxHashMap::default();
+        for child in self.children() {
+            let key = child.key();
+            if let Entry::Vacant(entry) = map.entry(key.clone()) {
+                keys.push(key);
+                entry.insert(child);
+            }
+        }
+        (keys, map)
+    }
+
+    // If there are multiple children with the same name, the first one wins.
+    fn children_by_name(&self) -> FxHashMap<&'a str, NodeRef<'a>> {
+        let mut map = FxHashMap::default();
+        for child in self.children() {
+            map.entry(child.name()).or_insert(child);
+        }
+        map
+    }
+
+    fn is_pool_record(&self) -> bool {
+        self.field("Generation").is_some()
+    }
+
+    fn subtree_eq(&self, other: &NodeRef) -> bool {
+        self.name() == other.name()
+            && self.fields().len() == other.fields().len()
+            && self
+                .fields()
+                .iter()
+                .all(|field| other.field(&field.name) == Some(&field.kind))
+            && self.node().children.len() == other.node().children.len()
+            && {
+                let other_children = other.children_by_name();
+                self.children().all(|child| {
+                    other_children
+                        .get(child.name())
+                        .is_some_and(|other_child| child.subtree_eq(other_child))
+                })
+            }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+enum ChildKey<'a> {
+    Name(&'a str),
+    Identity(Uuid),
+}
+
+impl Display for ChildKey<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ChildKey::Name(name) => write!(f, "{name}"),
+            ChildKey::Identity(identity) => write!(f, "{identity}"),
+        }
+    }
+}
+
+fn union<T: Clone + Eq + Hash>(first: &[T], second: &[T]) -> Vec<T> {
+    let mut names = first.to_vec();
+    let mut seen = first.iter().cloned().collect::<FxHashSet<_>>();
+    for name in second {
+        if seen.insert(name.clone()) {
+            names.push(name.clone());
+        }
+    }
+    names
+}
+
+fn diff_nodes(path: &RegionPath, old: NodeRef, new: NodeRef, changes: &mut Vec<Change>) {
+    for old_field in old.fields() {
+        match new.field(&old_field.name) {
+            Some(new_kind) if *new_kind != old_field.kind => changes.push(Change::FieldChanged {
+                path: path.clone(),
+                name: old_field.name.clone(),
+                old: old_field.kind.clone(),
+                new: new_kind.clone(),
+            }),
+            Some(_) => (),
+            None => changes.push(Change::FieldRemoved {
+                path: path.clone(),
+                field: old_field.clone(),
+            }),
+        }
+    }
+    for new_field in new.fields() {
+        if old.field(&new_field.name).is_none() {
+            changes.push(Change::FieldAdded {
+                path: path.clone(),
+                field: new_field.clone(),
+            });
+        }
+    }
+
+    let old_children = old.children_by_name();
+    let new_children = new.children_by_name();
+    for old_child in old.children() {
+        let child_path = path.join(old_child.name());
+        match new_children.get(old_child.name()).copied() {
+            Some(new_child) => match (old_child.identity(), new_child.identity()) {
+                (Some(old_id), Some(new_id)) if old_id != new_id => {
+                    changes.push(Change::RegionReplaced {
+                        path: child_path,
+                        old: old_id,
+                        new: new_id,
+                    })
+                }
+                _ => diff_nodes(&child_path, old_child, new_child, changes),
+            },
+            None => changes.push(Change::RegionRemoved { path: child_path }),
+        }
+    }
+    for new_child in new.children() {
+        if !old_children.contains_key(new_child.name()) {
+            changes.push(Change::RegionAdded {
+                path: path.join(new_child.name()),
+            });
+        }
+    }
+}
+
+/// Calculates semantic difference between two visitors. See [module docs](self) for more info.
+pub fn diff(old: &Visitor, new: &Visitor) -> VisitorDiff {
+    let mut changes = Vec::new();
+    diff_nodes(
+        &RegionPath::default(),
+        NodeRef::root(old),
+        NodeRef::root(new),
+        &mut changes,
+    );
+    VisitorDiff { changes }
+}
+
+struct Merger {
+    visitor: Visitor,
+    conflicts: Vec<MergeConflict>,
+}
+
+impl Merger {
+    fn conflict(&mut self, path: &RegionPath, description: String) {
+        self.conflicts.push(MergeConflict {
+            path: path.clone(),
+            description,
+            resolved: true,
+        })
+    }
+
+    fn unresolved_conflict(&mut self, path: &RegionPath, description: String) {
+        self.conflicts.push(MergeConflict {
+            path: path.clone(),
+            description,
+            resolved: false,
+        })
+    }
+
+    fn spawn(&mut self, name: &str, parent: Handle<VisitorNode>) -> Handle<VisitorNode> {
+        let handle = self.visitor.nodes.spawn(VisitorNode::new(name, parent));
+        self.visitor.nodes.borrow_mut(parent).children.push(handle);
+        handle
+    }
+
+    fn copy(&mut self, src: NodeRef, dest_parent: Handle<VisitorNode>) {
+        let handle = self.spawn(src.name(), dest_parent);
+        self.copy_contents(src, handle);
+    }
+
+    fn copy_contents(&mut self, src: NodeRef, dest: Handle<VisitorNode>) {
+        self.visitor.nodes.borrow_mut(dest).fields = src.fields().to_vec();
+        for child in src.children() {
+            self.copy(child, dest);
+        }
+    }
+
+    fn merge_fields(
+        &mut self,
+        path: &RegionPath,
+        base: Option<NodeRef>,
+        ours: NodeRef,
+        theirs: NodeRef,
+        dest: Handle<VisitorNode>,
+    ) {
+        let our_names = ours
+            .fields()
+            .iter()
+            .map(|f| f.name.as_str())
+            .collect::<Vec<_>>();
+        let their_names = theirs
+            .fields()
+            .iter()
+            .map(|f| f.name.as_str())
+            .collect::<Vec<_>>();
+        for name in union(&our_names, &their_names) {
+            let b = base.and_then(|base| base.field(name));
+            let o = ours.field(name);
+            let t = theirs.field(name);
+            let merged = if o == t || t == b {
+                o
+            } else if o == b {
+                t
+            } else {
+                self.conflict(
+                    path,
+                    format!(
+                        "field {name} was changed differently: ours {}, theirs {}",
+                        o.map_or_else(|| "<removed>".to_string(), format_value),
+                        t.map_or_else(|| "<removed>".to_string(), format_value)
+                    ),
+                );
+                o.or(t)
+            };
+            if let Some(kind) = merged {
+                self.visitor
+                    .nodes
+                    .borrow_mut(dest)
+                    .fields
+                    .push(Field::new(name, kind.clone()));
+            }
+        }
+    }
+
+    fn merge_one_sided(
+        &mut self,
+        path: &RegionPath,
+        base: Option<NodeRef>,
+        present: NodeRef,
+        present_side: &str,
+        dest_parent: Handle<VisitorNode>,
+    ) {
+        match base {
+            // Added on one side.
+            None => self.copy(present, dest_parent),
+            // Removed on the other side, the removal wins only if the region was not modified.
+            Some(base) => {
+                if !base.subtree_eq(&present) {
+                    self.conflict(
+                        path,
+                        format!("region was modified in {present_side}, but removed in the other"),
+                    );
+                    self.copy(present, dest_parent);
+                }
+            }
+        }
+    }
+
+    // Both nodes (and the base node) are matched by their keys (see `NodeRef::key`), so they have
+    // the same identity, if any.
+    fn merge_node(
+        &mut self,
+        path: &RegionPath,
+        base: Option<NodeRef>,
+        ours: NodeRef,
+        theirs: NodeRef,
+        dest_parent: Handle<VisitorNode>,
+    ) {
+        let is_root = ours.handle == ours.visitor.root;
+        if ours.subtree_eq(&theirs) {
+            if is_root {
+                self.copy_contents(ours, self.visitor.root);
+            } else {
+                self.copy(ours, dest_parent);
+            }
+            return;
+        }
+
+        let dest = if is_root {
+            self.visitor.root
+        } else {
+            self.spawn(ours.name(), dest_parent)
+        };
+
+        self.merge_fields(path, base, ours, theirs, dest);
+
+        let (our_keys, our_children) = ours.children_by_key();
+        let (their_keys, their_children) = theirs.children_by_key();
+        let base_children = base.map(|base| base.children_by_key().1);
+        for key in union(&our_keys, &their_keys) {
+            let o = our_children.get(&key).copied();
+            let t = their_children.get(&key).copied();
+            let name = o
+                .or(t)
+                .map_or_else(|| key.to_string(), |c| c.name().to_owned());
+            let child_path = path.join(&name);
+            let b = base_children
+                .as_ref()
+                .and_then(|children| children.get(&key).copied());
+            match (o, t) {
+                (Some(o), Some(t)) => self.merge_node(&child_path, b, o, t, dest),
+                (Some(o), None) => self.merge_one_sided(&child_path, b, o, "ours", dest),
+                (None, Some(t)) => self.merge_one_sided(&child_path, b, t, "theirs", dest),
+                (None, None) => unreachable!(),
+            }
+        }
+
+        self.fix_collection(path, dest);
+    }
+
+    /// Collections (`Vec`, `HashMap`, etc.) store their items in regions `Item0..ItemN` and the
+    /// number of items in `Length` or `Count` field. Items could be added on one side and removed
+    /// on the other, so the items must be renumbered and counted again to keep the data valid.
+    fn fix_collection(&mut self, path: &RegionPath, dest: Handle<VisitorNode>) {
+        let node = self.visitor.nodes.borrow(dest);
+        let Some(count_field) = node.fields.iter().position(|field| {
+            (field.name == "Length" || field.name == "Count")
+                && matches!(field.kind, FieldKind::U32(_))
+        }) else {
+            return;
+        };
+        let Some(mut items) = node
+            .children
+            .iter()
+            .map(|handle| {
+                NodeRef {
+                    visitor: &self.visitor,
+                    handle: *handle,
+                }
+                .collection_index()
+                .map(|index| (index, *handle))
+            })
+            .collect::<Option<Vec<_>>>()
+        else {
+            return;
+        };
+        items.sort_by_key(|(index, _)| *index);
+        let count = items.len() as u32;
+        let contiguous = items.iter().enumerate().all(|(i, (index, _))| i == *index);
+        let is_pool = items.iter().any(|(_, handle)| {
+            NodeRef {
+                visitor: &self.visitor,
+                handle: *handle,
+            }
+            .is_pool_record()
+        });
+        if !contiguous && is_pool {
+            // Handles store indices of pool records, renumbering would break them.
+            self.unresolved_conflict(
+                path,
+                "pool records were added to the same slots concurrently".to_string(),
+            );
+            return;
+        } else if !contiguous {
+            self.conflict(
+                path,
+                "collection items were added and removed concurrently and were renumbered"
+                    .to_string(),
+            );
+            for (i, (_, handle)) in items.iter().enumerate() {
+                self.visitor.nodes.borrow_mut(*handle).name = format!("Item{i}");
+            }
+        }
+        let node = self.visitor.nodes.borrow_mut(dest);
+        node.children = items.into_iter().map(|(_, handle)| handle).collect();
+        node.fields[count_field].kind = FieldKind::U32(count);
+    }
+}
+
+/// Performs three-way merge of two visitors (`ours` and `theirs`) that were derived from the
+/// common `base`. Every change made on only one side is applied automatically, the same change
+/// made on both sides is applied once. Incompatible changes are reported as conflicts, see
+/// [`MergeConflict`] for the rules of their automatic resolution.
+pub fn merge(base: &Visitor, ours: &Visitor, theirs: &Visitor) -> MergeOutput {
+    let mut merger = Merger {
+        visitor: Visitor::new(),
+        conflicts: Vec::new(),
+    };
+    merger.visitor.version = ours.version.max(theirs.version);
+    let root = merger.visitor.root;
+    merger.merge_node(
+        &RegionPath::default(),
+        Some(NodeRef::root(base)),
+        NodeRef::root(ours),
+        NodeRef::root(theirs),
+        root,
+    );
+    MergeOutput {
+        visitor: merger.visitor,
+        conflicts: merger.conflicts,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        pool::Pool,
+        visitor::{
+            diff::{diff, merge, Change, RegionPath},
+            field::FieldKind,
+            prelude::*,
+        },
+    };
+    use uuid::{uuid, Uuid};
+
+    #[derive(Visit, Default, Clone, Debug, PartialEq)]
+    struct Object {
+        instance_id: Uuid,
+        name: String,
+        position: f32,
+    }
+
+    #[derive(Visit, Default, Clone, Debug, PartialEq)]
+    struct Level {
+        objects: Vec<Object>,
+        gravity: f32,
+    }
+
+    fn base_level() -> Level {
+        Level {
+            objects: vec![
+                Object {
+                    instance_id: uuid!("2f3c6a1e-3f36-4d6c-8fd1-3c7d9e0c1a01"),
+                    name: "Player".to_string(),
+                    position: 0.0,
+                },
+                Object {
+                    instance_id: uuid!("2f3c6a1e-3f36-4d6c-8fd1-3c7d9e0c1a02"),
+                    name: "Enemy".to_string(),
+                    position: 5.0,
+                },
+            ],
+            gravity: 9.81,
+        }
+    }
+
+    fn to_ascii(level: &Level) -> Visitor {
+        let mut visitor = Visitor::new();
+        level.clone().visit("Level", &mut visitor).unwrap();
+        Visitor::load_ascii_from_memory(visitor.save_ascii_to_string().as_bytes()).unwrap()
+    }
+
+    fn from_visitor(visitor: Visitor) -> Level {
+        let mut visitor =
+            Visitor::load_ascii_from_memory(visitor.save_ascii_to_string().as_bytes()).unwrap();
+        let mut level = Level::default();
+        level.visit("Level", &mut visitor).unwrap();
+        level
+    }
+
+    #[test]
+    fn test_diff() {
+        let base = base_level();
+        let mut modified = base.clone();
+        modified.gravity = 1.62;
+        modified.objects.pop();
+
+        let changes = diff(&to_ascii(&base), &to_ascii(&modified)).changes;
+        let path = |p: &[&str]| RegionPath(p.iter().map(|s| s.to_string()).collect());
+        assert!(changes.contains(&Change::FieldChanged {
+            path: path(&["Level"]),
+            name: "Gravity".to_string(),
+            old: FieldKind::F32(9.81),
+            new: FieldKind::F32(1.62),
+        }));
+        assert!(changes.contains(&Change::RegionRemoved {
+            path: path(&["Level", "Objects", "Item1"]),
+        }));
+
+        let mut replaced = base.clone();
+        replaced.objects[1].instance_id = uuid!("2f3c6a1e-3f36-4d6c-8fd1-3c7d9e0c1a03");
+        let changes = diff(&to_ascii(&base), &to_ascii(&replaced)).changes;
+        assert!(matches!(
+            changes.as_slice(),
+            [Change::RegionReplaced { .. }]
+        ));
+    }
+
+    #[test]
+    fn test_merge_of_equal_visitors() {
+        let base = to_ascii(&base_level());
+        let output = merge(&base, &base, &base);
+        assert!(output.is_clean());
+        assert!(diff(&base, &output.visitor).is_empty());
+    }
+
+    #[test]
+    fn test_clean_merge() {
+        let base = base_level();
+        let mut ours = base.clone();
+        ours.objects[0].position = 10.0;
+        let mut theirs = base.clone();
+        theirs.gravity = 1.62;
+        theirs.objects[1].name = "Boss".to_string();
+
+        let output = merge(&to_ascii(&base), &to_ascii(&ours), &to_ascii(&theirs));
+        assert!(output.is_clean());
+
+        let mut expected = base.clone();
+        expected.objects[0].position = 10.0;
+        expected.gravity = 1.62;
+        expected.objects[1].name = "Boss".to_string();
+        assert_eq!(from_visitor(output.visitor), expected);
+    }
+
+    #[test]
+    fn test_conflicting_merge() {
+        let base = base_level();
+        let mut ours = base.clone();
+        ours.gravity = 1.0;
+        ours.objects[1].position = 1.0;
+        let mut theirs = base.clone();
+        theirs.gravity = 2.0;
+        theirs.objects.pop();
+
+        let output = merge(&to_ascii(&base), &to_ascii(&ours), &to_ascii(&theirs));
+        assert_eq!(output.conflicts.len(), 2);
+        // Ours wins in field conflicts, modification wins over removal.
+        assert_eq!(from_visitor(output.visitor), ours);
+    }
+
+    #[test]
+    fn test_merge_aligns_items_by_identity() {
+        let base = base_level();
+        let mut ours = base.clone();
+        // The enemy is moved to the first slot.
+        ours.objects.remove(0);
+        ours.objects[0].position = 1.0;
+        let mut theirs = base.clone();
+        theirs.objects[1].name = "Boss".to_string();
+
+        let output = merge(&to_ascii(&base), &to_ascii(&ours), &to_ascii(&theirs));
+        assert!(output.is_clean());
+
+        let mut expected = ours.clone();
+        expected.objects[0].name = "Boss".to_string();
+        assert_eq!(from_visitor(output.visitor), expected);
+    }
+
+    #[test]
+    fn test_merge_of_concurrently_added_items() {
+        let base = base_level();
+        let mut ours = base.clone();
+        ours.objects.push(Object {
+            instance_id: uuid!("2f3c6a1e-3f36-4d6c-8fd1-3c7d9e0c1a03"),
+            name: "Ally".to_string(),
+            position: 1.0,
+        });
+        let mut theirs = base.clone();
+        theirs.objects.push(Object {
+            instance_id: uuid!("2f3c6a1e-3f36-4d6c-8fd1-3c7d9e0c1a04"),
+            name: "Trap".to_string(),
+            position: 2.0,
+        });
+
+        let output = merge(&to_ascii(&base), &to_ascii(&ours), &to_ascii(&theirs));
+        // Items were renumbered, it is fine for vectors.
+        assert_eq!(output.conflicts.len(), 1);
+        assert!(output.is_resolved());
+
+        let mut expected = ours.clone();
+        expected.objects.push(theirs.objects[2].clone());
+        assert_eq!(from_visitor(output.visitor), expected);
+    }
+
+    #[test]
+    fn test_merge_of_pool_collision() {
+        fn to_pool_visitor(objects: &[Object]) -> Visitor {
+            let mut pool = Pool::<Object>::new();
+            for object in objects {
+                let _ = pool.spawn(object.clone());
+            }
+            let mut visitor = Visitor::new();
+            pool.visit("Pool", &mut visitor).unwrap();
+            Visitor::load_ascii_from_memory(visitor.save_ascii_to_string().as_bytes()).unwrap()
+        }
+
+        let base = base_level().objects;
+        let mut ours = base.clone();
+        ours.push(Object {
+            instance_id: uuid!("2f3c6a1e-3f36-4d6c-8fd1-3c7d9e0c1a03"),
+            ..Default::default()
+        });
+        let mut theirs = base.clone();
+        theirs.push(Object {
+            instance_id: uuid!("2f3c6a1e-3f36-4d6c-8fd1-3c7d9e0c1a04"),
+            ..Default::default()
+        });
+
+        let output = merge(
+            &to_pool_visitor(&base),
+            &to_pool_visitor(&ours),
+            &to_pool_visitor(&theirs),
+        );
+        assert!(!output.is_resolved());
+    }
+}
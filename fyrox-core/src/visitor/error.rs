@@ -100,6 +100,16 @@ pub enum VisitError {
     DecodeError(DecodeError),
     /// An error occurred when trying to parse uuid from a string.
     UuidError(uuid::Error),
+    /// Stored data of a type has a schema version that is newer than the version supported by
+    /// the type, which means that the data was written by a newer version of the type.
+    UnsupportedSchemaVersion {
+        /// Breadcrumbs of the region of the type.
+        region: String,
+        /// Version of the stored data.
+        stored: u32,
+        /// Current version of the type.
+        current: u32,
+    },
     /// Arbitrary error.
     Any(Box<dyn Error + Send + Sync>),
 }
@@ -168,6 +178,14 @@ impl Display for VisitError {
             Self::ParseFloatError(e) => write!(f, "unable to parse float: {e:?}"),
             Self::DecodeError(e) => write!(f, "base64 decoding error: {e:?}"),
             Self::UuidError(e) => write!(f, "uuid error: {e:?}"),
+            Self::UnsupportedSchemaVersion {
+                region,
+                stored,
+                current,
+            } => write!(
+                f,
+                "unsupported schema version {stored} of {region}, the latest supported is {current}"
+            ),
             Self::Any(e) => {
                 write!(f, "{e}")
             }
@@ -235,6 +253,18 @@ impl From<DecodeError> for VisitError {
     }
 }
 
+impl From<ron::Error> for VisitError {
+    fn from(err: ron::Error) -> Self {
+        Self::Any(Box::new(err))
+    }
+}
+
+impl From<ron::error::SpannedError> for VisitError {
+    fn from(err: ron::error::SpannedError) -> Self {
+        Self::Any(Box::new(err))
+    }
+}
+
 impl From<uuid::Error> for VisitError {
     fn from(value: uuid::Error) -> Self {
         Self::UuidError(value)
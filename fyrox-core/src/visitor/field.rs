@@ -28,7 +28,7 @@ use uuid::Uuid;
 /// of these types.
 /// Fields can be accessed from a visitor using [`crate::visitor::Visit::visit`] on a variable with the
 /// same type as the field.
-#[derive(PartialEq, Debug)]
+#[derive(PartialEq, Debug, Clone)]
 pub enum FieldKind {
     /// Boolean value.
     Bool(bool),
@@ -144,7 +144,7 @@ pub enum FieldKind {
 /// Values within a visitor are constructed from Fields. Each Field has a name and a value. The name
 /// is used as a key to access the value within the visitor using the [`crate::Visit::visit`] method,
 /// so each field within a value must have a unique name.
-#[derive(PartialEq, Debug)]
+#[derive(PartialEq, Debug, Clone)]
 pub struct Field {
     /// The key string that allows access to the field.
     pub name: String,
@@ -0,0 +1,552 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Indexed binary format, that allows to load individual subtrees of a visitor on demand.
+//!
+//! [`Visitor::load_from_memory`] reads and parses the whole tree before anything can be used, which
+//! could take a lot of time for large scenes or save files. The indexed binary format stores every
+//! node as a separate record and has a table with offsets of the records at the end of the data.
+//! [`LazyVisitor`] reads only the header and the offset table at creation and then reads the records
+//! on demand, so it is possible to inspect the structure of a tree or load only the parts of it
+//! that are needed right now.
+//!
+//! ```rust
+//! use fyrox_core::visitor::{lazy::LazyVisitor, prelude::*};
+//!
+//! let mut data = (0.5f32, vec![1u32, 2, 3]);
+//! let mut visitor = Visitor::new();
+//! data.0.visit("Time", &mut visitor).unwrap();
+//! data.1.visit("BigArray", &mut visitor).unwrap();
+//! let bytes = visitor.save_indexed_binary_to_vec().unwrap();
+//!
+//! let mut lazy = LazyVisitor::from_memory(bytes).unwrap();
+//! // Load only the `Time` field of the root, without touching `BigArray` region.
+//! let root = lazy.root();
+//! let mut time = 0.0f32;
+//! time.visit("Time", &mut lazy.load_fields_of(root).unwrap()).unwrap();
+//! assert_eq!(time, 0.5);
+//! ```
+//!
+//! # Layout
+//!
+//! All numbers are little-endian.
+//!
+//! | Part         | Content                                                                      |
+//! |--------------|------------------------------------------------------------------------------|
+//! | Header       | [`Visitor::MAGIC_INDEXED_BINARY_CURRENT`], version (`u32`), node count (`u32`), offset of the table (`u64`) |
+//! | Node records | name (`u32` length + UTF-8 bytes), parent index (`u32`, `u32::MAX` for the root), child count (`u32`) + child indices (`u32`), size of the fields block (`u64`), field count (`u32`) + fields in the regular binary format |
+//! | Offset table | offset of every node record (`u64`) from the start of the data, the root is at index 0 |
+
+use crate::{
+    pool::{Handle, Pool},
+    visitor::{
+        blackboard::Blackboard,
+        error::VisitError,
+        field::Field,
+        reader::{binary::BinaryReader, Reader},
+        writer::{binary::BinaryWriter, Writer},
+        VisitResult, Visitor, VisitorFlags, VisitorNode,
+    },
+};
+use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
+use fxhash::{FxHashMap, FxHashSet};
+use std::{
+    collections::VecDeque,
+    fs::File,
+    io::{BufReader, Cursor, Read, Seek, SeekFrom, Write},
+    path::Path,
+};
+
+const HEADER_SIZE: u64 = 4 + 4 + 4 + 8;
+const NO_PARENT: u32 = u32::MAX;
+
+/// An index of a node in the indexed binary data.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct NodeIndex(pub u32);
+
+/// Structural information about a node, that could be read without reading any fields.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NodeInfo {
+    /// Name of the node.
+    pub name: String,
+    /// Index of the parent node, `None` for the root.
+    pub parent: Option<NodeIndex>,
+    /// Indices of the child nodes.
+    pub children: Vec<NodeIndex>,
+    /// Number of fields of the node.
+    pub field_count: u32,
+    /// Size of all fields of the node in bytes.
+    pub fields_size: u64,
+}
+
+pub(super) fn write_indexed(visitor: &Visitor, dest: &mut dyn Write) -> VisitResult {
+    // Assign indices in breadth-first order, so the root is always at index 0.
+    let mut order = vec![visitor.root];
+    let mut indices = FxHashMap::default();
+    let mut i = 0;
+    while i < order.len() {
+        let handle = order[i];
+        indices.insert(handle, i as u32);
+        order.extend_from_slice(&visitor.nodes.borrow(handle).children);
+        i += 1;
+    }
+
+    let field_writer = BinaryWriter::default();
+    let mut records = Vec::new();
+    let mut offsets = Vec::with_capacity(order.len());
+    let mut fields = Vec::new();
+    for handle in order.iter() {
+        let node = visitor.nodes.borrow(*handle);
+        offsets.push(HEADER_SIZE + records.len() as u64);
+
+        let name = node.name.as_bytes();
+        records.write_u32::<LittleEndian>(name.len() as u32)?;
+        records.write_all(name)?;
+        records
+            .write_u32::<LittleEndian>(indices.get(&node.parent).copied().unwrap_or(NO_PARENT))?;
+        records.write_u32::<LittleEndian>(node.children.len() as u32)?;
+        for child in node.children.iter() {
+            records.write_u32::<LittleEndian>(indices[child])?;
+        }
+
+        fields.clear();
+        for field in node.fields.iter() {
+            field_writer.write_field(field, &mut fields)?;
+        }
+        records.write_u64::<LittleEndian>(fields.len() as u64)?;
+        records.write_u32::<LittleEndian>(node.fields.len() as u32)?;
+        records.write_all(&fields)?;
+    }
+
+    dest.write_all(Visitor::MAGIC_INDEXED_BINARY_CURRENT.as_bytes())?;
+    dest.write_u32::<LittleEndian>(visitor.version)?;
+    dest.write_u32::<LittleEndian>(order.len() as u32)?;
+    dest.write_u64::<LittleEndian>(HEADER_SIZE + records.len() as u64)?;
+    dest.write_all(&records)?;
+    for offset in offsets {
+        dest.write_u64::<LittleEndian>(offset)?;
+    }
+    Ok(())
+}
+
+/// A reader of the indexed binary format, that reads nodes on demand. See [module docs](self) for
+/// more info.
+pub struct LazyVisitor<S> {
+    src: S,
+    len: u64,
+    version: u32,
+    offsets: Vec<u64>,
+}
+
+impl LazyVisitor<Cursor<Vec<u8>>> {
+    /// Creates a new lazy visitor from the given data.
+    pub fn from_memory(data: Vec<u8>) -> Result<Self, VisitError> {
+        Self::new(Cursor::new(data))
+    }
+}
+
+impl LazyVisitor<BufReader<File>> {
+    /// Opens a file with the indexed binary data. Only the header and the offset table are read,
+    /// every other part of the file is read on demand.
+    pub fn open_file(path: impl AsRef<Path>) -> Result<Self, VisitError> {
+        Self::new(BufReader::new(File::open(path)?))
+    }
+}
+
+impl<S> LazyVisitor<S>
+where
+    S: Read + Seek,
+{
+    /// Creates a new lazy visitor from the given source of the indexed binary data.
+    pub fn new(mut src: S) -> Result<Self, VisitError> {
+        let mut magic: [u8; 4] = Default::default();
+        src.read_exact(&mut magic)?;
+        if !magic.eq(Visitor::MAGIC_INDEXED_BINARY_CURRENT.as_bytes()) {
+            return Err(VisitError::NotSupportedFormat);
+        }
+        let version = src.read_u32::<LittleEndian>()?;
+        let node_count = src.read_u32::<LittleEndian>()?;
+        let table_offset = src.read_u64::<LittleEndian>()?;
+        if node_count == 0 {
+            return Err(VisitError::User(
+                "indexed data has no root node".to_string(),
+            ));
+        }
+
+        // Every count in the data is checked against the length of the data, so malformed data
+        // cannot cause huge allocations.
+        let len = src.seek(SeekFrom::End(0))?;
+        let table_size = node_count as u64 * size_of::<u64>() as u64;
+        if table_offset < HEADER_SIZE || table_offset.saturating_add(table_size) > len {
+            return Err(VisitError::User(
+                "offset table of indexed data is out of bounds".to_string(),
+            ));
+        }
+
+        src.seek(SeekFrom::Start(table_offset))?;
+        let mut offsets = Vec::with_capacity(node_count as usize);
+        for _ in 0..node_count {
+            offsets.push(src.read_u64::<LittleEndian>()?);
+        }
+
+        Ok(Self {
+            src,
+            len,
+            version,
+            offsets,
+        })
+    }
+
+    fn ensure_remaining(&mut self, size: u64) -> Result<(), VisitError> {
+        let position = self.src.stream_position()?;
+        if size > self.len.saturating_sub(position) {
+            Err(VisitError::User(format!(
+                "indexed data is truncated: {size} bytes expected at {position}"
+            )))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Returns version number of the stored visitor.
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    /// Returns total number of nodes in the data.
+    pub fn node_count(&self) -> usize {
+        self.offsets.len()
+    }
+
+    /// Returns index of the root node.
+    pub fn root(&self) -> NodeIndex {
+        NodeIndex(0)
+    }
+
+    fn seek_node(&mut self, index: NodeIndex) -> Result<(), VisitError> {
+        match self.offsets.get(index.0 as usize) {
+            Some(offset) => {
+                self.src.seek(SeekFrom::Start(*offset))?;
+                Ok(())
+            }
+            None => Err(VisitError::User(format!(
+                "node index {} is out of bounds",
+                index.0
+            ))),
+        }
+    }
+
+    fn read_info(&mut self) -> Result<NodeInfo, VisitError> {
+        let name_len = self.src.read_u32::<LittleEndian>()?;
+        self.ensure_remaining(name_len as u64)?;
+        let mut name = vec![0; name_len as usize];
+        self.src.read_exact(&mut name)?;
+        let parent = self.src.read_u32::<LittleEndian>()?;
+        let child_count = self.src.read_u32::<LittleEndian>()?;
+        self.ensure_remaining(child_count as u64 * size_of::<u32>() as u64)?;
+        let mut children = Vec::with_capacity(child_count as usize);
+        for _ in 0..child_count {
+            children.push(NodeIndex(self.src.read_u32::<LittleEndian>()?));
+        }
+        let fields_size = self.src.read_u64::<LittleEndian>()?;
+        let field_count = self.src.read_u32::<LittleEndian>()?;
+        Ok(NodeInfo {
+            name: String::from_utf8(name)?,
+            parent: (parent != NO_PARENT).then_some(NodeIndex(parent)),
+            children,
+            field_count,
+            fields_size,
+        })
+    }
+
+    /// Reads structural information about a node, without reading its fields.
+    pub fn node_info(&mut self, index: NodeIndex) -> Result<NodeInfo, VisitError> {
+        self.seek_node(index)?;
+        self.read_info()
+    }
+
+    /// Tries to find a child node with the given name.
+    pub fn find_child(
+        &mut self,
+        parent: NodeIndex,
+        name: &str,
+    ) -> Result<Option<NodeIndex>, VisitError> {
+        for child in self.node_info(parent)?.children {
+            if self.node_info(child)?.name == name {
+                return Ok(Some(child));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Tries to find a node by a path of names starting from the root, for example
+    /// `["Scene", "Graph"]`.
+    pub fn find_path<'a>(
+        &mut self,
+        path: impl IntoIterator<Item = &'a str>,
+    ) -> Result<Option<NodeIndex>, VisitError> {
+        let mut current = self.root();
+        for name in path {
+            match self.find_child(current, name)? {
+                Some(child) => current = child,
+                None => return Ok(None),
+            }
+        }
+        Ok(Some(current))
+    }
+
+    /// Reads fields of a node.
+    pub fn read_fields(&mut self, index: NodeIndex) -> Result<Vec<Field>, VisitError> {
+        self.seek_node(index)?;
+        let info = self.read_info()?;
+        let mut reader = BinaryReader::new(&mut self.src);
+        (0..info.field_count).map(|_| reader.read_field()).collect()
+    }
+
+    fn read_node(
+        &mut self,
+        index: NodeIndex,
+        parent: Handle<VisitorNode>,
+    ) -> Result<(VisitorNode, Vec<NodeIndex>), VisitError> {
+        self.seek_node(index)?;
+        let info = self.read_info()?;
+        let mut reader = BinaryReader::new(&mut self.src);
+        let mut node = VisitorNode::new(&info.name, parent);
+        for _ in 0..info.field_count {
+            node.fields.push(reader.read_field()?);
+        }
+        Ok((node, info.children))
+    }
+
+    fn load_node(
+        &mut self,
+        visitor: &mut Visitor,
+        index: NodeIndex,
+        parent: Handle<VisitorNode>,
+        recursive: bool,
+    ) -> Result<Handle<VisitorNode>, VisitError> {
+        let (node, children) = self.read_node(index, parent)?;
+        let handle = visitor.nodes.spawn(node);
+        if !recursive {
+            return Ok(handle);
+        }
+
+        // Nodes are loaded iteratively and every node is allowed to be loaded only once, so
+        // malformed data with cycles or very deep trees cannot hang or overflow the stack.
+        let mut visited = FxHashSet::default();
+        visited.insert(index);
+        let mut queue = children
+            .into_iter()
+            .map(|child| (child, handle))
+            .collect::<VecDeque<_>>();
+        while let Some((index, parent)) = queue.pop_front() {
+            if !visited.insert(index) {
+                return Err(VisitError::User(format!(
+                    "node {} is referenced more than once in indexed data",
+                    index.0
+                )));
+            }
+            let (node, children) = self.read_node(index, parent)?;
+            let child = visitor.nodes.spawn(node);
+            visitor.nodes.borrow_mut(parent).children.push(child);
+            queue.extend(children.into_iter().map(|index| (index, child)));
+        }
+        Ok(handle)
+    }
+
+    fn make_visitor(&self) -> Visitor {
+        Visitor {
+            nodes: Pool::new(),
+            unique_id_counter: 1,
+            type_name_map: Default::default(),
+            rc_map: Default::default(),
+            arc_map: Default::default(),
+            reading: true,
+            current_node: Handle::NONE,
+            root: Handle::NONE,
+            version: self.version,
+            blackboard: Blackboard::new(),
+            flags: VisitorFlags::NONE,
+        }
+    }
+
+    /// Loads a subtree, that starts from the given node, into a new visitor. The node becomes a
+    /// child of the root of the new visitor, so the data can be deserialized as usual using the
+    /// name of the node: `value.visit(&node_name, &mut visitor)`.
+    ///
+    /// # Limitations
+    ///
+    /// Shared data (`Rc`/`Arc`) is stored only once - at the place where it was visited first,
+    /// every other place stores only an id of the data. If such data is shared with a node outside
+    /// of the subtree, it cannot be resolved and deserialization of the subtree will fail. Use
+    /// [`Self::load_all`] for such data.
+    pub fn load_subtree(&mut self, index: NodeIndex) -> Result<Visitor, VisitError> {
+        let mut visitor = self.make_visitor();
+        visitor.root = visitor
+            .nodes
+            .spawn(VisitorNode::new("__ROOT__", Handle::NONE));
+        visitor.current_node = visitor.root;
+        let root = visitor.root;
+        let node = self.load_node(&mut visitor, index, root, true)?;
+        visitor.nodes.borrow_mut(visitor.root).children.push(node);
+        Ok(visitor)
+    }
+
+    /// Loads fields of the given node into a new visitor, whose current region is the node. It is
+    /// useful for reading metadata of large objects without loading their content.
+    pub fn load_fields_of(&mut self, index: NodeIndex) -> Result<Visitor, VisitError> {
+        let mut visitor = self.make_visitor();
+        visitor.root = self.load_node(&mut visitor, index, Handle::NONE, false)?;
+        visitor.current_node = visitor.root;
+        Ok(visitor)
+    }
+
+    /// Loads the entire tree into a new visitor.
+    pub fn load_all(&mut self) -> Result<Visitor, VisitError> {
+        let mut visitor = self.make_visitor();
+        visitor.root = self.load_node(&mut visitor, self.root(), Handle::NONE, true)?;
+        visitor.current_node = visitor.root;
+        Ok(visitor)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::visitor::{
+        lazy::{LazyVisitor, NodeIndex, HEADER_SIZE},
+        prelude::*,
+        Format,
+    };
+    use byteorder::{LittleEndian, WriteBytesExt};
+
+    #[derive(Visit, Default, Debug, PartialEq)]
+    struct Chunk {
+        id: u32,
+        data: Vec<f32>,
+    }
+
+    #[derive(Visit, Default, Debug, PartialEq)]
+    struct World {
+        name: String,
+        chunks: Vec<Chunk>,
+    }
+
+    fn world() -> World {
+        World {
+            name: "World".to_string(),
+            chunks: (0..4)
+                .map(|id| Chunk {
+                    id,
+                    data: vec![id as f32; 16],
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_indexed_binary_full_load() {
+        let mut visitor = Visitor::new();
+        world().visit("World", &mut visitor).unwrap();
+        let data = visitor.save_indexed_binary_to_vec().unwrap();
+        assert_eq!(
+            Visitor::detect_format_from_slice(&data),
+            Format::IndexedBinary
+        );
+
+        let mut loaded = Visitor::load_from_memory(&data).unwrap();
+        assert!(crate::visitor::diff::diff(&visitor, &loaded).is_empty());
+        let mut world_loaded = World::default();
+        world_loaded.visit("World", &mut loaded).unwrap();
+        assert_eq!(world_loaded, world());
+    }
+
+    #[test]
+    fn test_lazy_subtree_load() {
+        let mut visitor = Visitor::new();
+        world().visit("World", &mut visitor).unwrap();
+        let data = visitor.save_indexed_binary_to_vec().unwrap();
+
+        let mut lazy = LazyVisitor::from_memory(data).unwrap();
+        let chunks = lazy.find_path(["World", "Chunks"]).unwrap().unwrap();
+        let info = lazy.node_info(chunks).unwrap();
+        assert_eq!(info.name, "Chunks");
+        // Length field + 4 items.
+        assert_eq!(info.field_count, 1);
+        assert_eq!(info.children.len(), 4);
+
+        let chunk2 = lazy.find_child(chunks, "Item2").unwrap().unwrap();
+        let mut chunk = Chunk::default();
+        chunk
+            .visit("Item2", &mut lazy.load_subtree(chunk2).unwrap())
+            .unwrap();
+        assert_eq!(chunk, world().chunks.remove(2));
+
+        let world_node = lazy.find_path(["World"]).unwrap().unwrap();
+        let mut name = String::new();
+        name.visit("Name", &mut lazy.load_fields_of(world_node).unwrap())
+            .unwrap();
+        assert_eq!(name, "World");
+    }
+
+    // Writes indexed data with a single node record. The node has the given name length (while
+    // the name itself is empty) and the given children.
+    fn malformed(node_count: u32, name_len: u32, children: &[u32]) -> Vec<u8> {
+        let mut record = Vec::new();
+        record.write_u32::<LittleEndian>(name_len).unwrap();
+        record.write_u32::<LittleEndian>(u32::MAX).unwrap();
+        record
+            .write_u32::<LittleEndian>(children.len() as u32)
+            .unwrap();
+        for child in children {
+            record.write_u32::<LittleEndian>(*child).unwrap();
+        }
+        record.write_u64::<LittleEndian>(0).unwrap();
+        record.write_u32::<LittleEndian>(0).unwrap();
+
+        let mut data = Visitor::MAGIC_INDEXED_BINARY_CURRENT.as_bytes().to_vec();
+        data.write_u32::<LittleEndian>(crate::visitor::CURRENT_VERSION)
+            .unwrap();
+        data.write_u32::<LittleEndian>(node_count).unwrap();
+        data.write_u64::<LittleEndian>(HEADER_SIZE + record.len() as u64)
+            .unwrap();
+        data.extend_from_slice(&record);
+        data.write_u64::<LittleEndian>(HEADER_SIZE).unwrap();
+        data
+    }
+
+    #[test]
+    fn test_lazy_malformed_data() {
+        // Sanity check.
+        let mut lazy = LazyVisitor::from_memory(malformed(1, 0, &[])).unwrap();
+        assert!(lazy.load_all().is_ok());
+
+        // The offset table is much smaller than the node count.
+        assert!(LazyVisitor::from_memory(malformed(u32::MAX, 0, &[])).is_err());
+
+        // The name is longer than the data.
+        let mut lazy = LazyVisitor::from_memory(malformed(1, u32::MAX, &[])).unwrap();
+        assert!(lazy.node_info(NodeIndex(0)).is_err());
+
+        // The node is a child of itself.
+        let mut lazy = LazyVisitor::from_memory(malformed(1, 0, &[0])).unwrap();
+        assert!(lazy.load_all().is_err());
+        assert!(lazy.load_subtree(NodeIndex(0)).is_err());
+    }
+}
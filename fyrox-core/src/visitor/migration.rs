@@ -0,0 +1,572 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Per-type schema versioning and migrations for visited data.
+//!
+//! Data stored by a [`Visitor`] often outlives the layout of the types that wrote it: fields get
+//! renamed, moved into sub-structures or removed entirely. Instead of sprinkling compatibility
+//! code over `visit` implementations, a type can declare its schema version and a set of
+//! [`Migration`]s. When the type is visited for reading, the stored version is compared with the
+//! current one and every migration between them is applied to the type's region of the tree,
+//! *before* any field is read. The migrations operate on the intermediate representation only,
+//! via [`MigrationContext`], so they never need to know anything about the actual types.
+//!
+//! ```rust
+//! use fyrox_core::visitor::{migration::{Migration, MigrationContext}, prelude::*};
+//!
+//! #[derive(Visit, Default)]
+//! #[visit(schema_version = 1, migrations = "Self::migrations")]
+//! struct Settings {
+//!     // Was called `Volume` in version 0.
+//!     master_volume: f32,
+//! }
+//!
+//! impl Settings {
+//!     fn migrations() -> Vec<Migration> {
+//!         vec![Migration::new(0, |ctx: &mut MigrationContext| {
+//!             ctx.rename_field("Volume", "MasterVolume");
+//!             Ok(())
+//!         })]
+//!     }
+//! }
+//! ```
+//!
+//! Types with manual [`crate::visitor::Visit`] implementations can do the same by calling
+//! [`Visitor::visit_schema_version`] right after entering their region.
+
+use crate::{
+    pool::Handle,
+    visitor::{
+        error::VisitError,
+        field::{Field, FieldKind},
+        Visit, VisitResult, Visitor, VisitorNode,
+    },
+};
+
+/// Name of the field that holds schema version of a type in its region.
+pub const SCHEMA_VERSION_FIELD: &str = "__SchemaVersion";
+
+/// A function that upgrades a region of a type from some version to the next one.
+pub type MigrationFn = fn(&mut MigrationContext) -> VisitResult;
+
+/// An upgrade step of a type's data from [`Self::from_version`] to `from_version + 1`.
+#[derive(Copy, Clone)]
+pub struct Migration {
+    /// A version of data that this migration is able to upgrade.
+    pub from_version: u32,
+    /// The function that rewrites the data.
+    pub upgrade: MigrationFn,
+}
+
+impl Migration {
+    /// Creates a new migration from the given version to the next one.
+    pub const fn new(from_version: u32, upgrade: MigrationFn) -> Self {
+        Self {
+            from_version,
+            upgrade,
+        }
+    }
+}
+
+/// Provides editing access to a single node (region) of a visitor's tree during a migration.
+pub struct MigrationContext<'a> {
+    visitor: &'a mut Visitor,
+    node: Handle<VisitorNode>,
+    from_version: u32,
+}
+
+impl<'a> MigrationContext<'a> {
+    fn node(&self) -> &VisitorNode {
+        self.visitor.nodes.borrow(self.node)
+    }
+
+    fn node_mut(&mut self) -> &mut VisitorNode {
+        self.visitor.nodes.borrow_mut(self.node)
+    }
+
+    fn find_child(&self, name: &str) -> Option<Handle<VisitorNode>> {
+        self.node()
+            .children
+            .iter()
+            .copied()
+            .find(|child| self.visitor.nodes.borrow(*child).name == name)
+    }
+
+    /// Returns the version of the data that is being upgraded.
+    pub fn from_version(&self) -> u32 {
+        self.from_version
+    }
+
+    /// Returns the name of the region.
+    pub fn name(&self) -> &str {
+        &self.node().name
+    }
+
+    /// Returns a slice with every field of the region.
+    pub fn fields(&self) -> &[Field] {
+        &self.node().fields
+    }
+
+    /// Returns true if the region has a field with the given name.
+    pub fn has_field(&self, name: &str) -> bool {
+        self.field(name).is_some()
+    }
+
+    /// Tries to find a field with the given name.
+    pub fn field(&self, name: &str) -> Option<&FieldKind> {
+        self.node()
+            .fields
+            .iter()
+            .find(|field| field.name == name)
+            .map(|field| &field.kind)
+    }
+
+    /// Tries to find a field with the given name.
+    pub fn field_mut(&mut self, name: &str) -> Option<&mut FieldKind> {
+        self.node_mut()
+            .fields
+            .iter_mut()
+            .find(|field| field.name == name)
+            .map(|field| &mut field.kind)
+    }
+
+    /// Sets a value of the field with the given name, adding the field if it does not exist.
+    /// Returns the previous value of the field (if any).
+    pub fn set_field(&mut self, name: &str, kind: FieldKind) -> Option<FieldKind> {
+        if let Some(existing) = self.field_mut(name) {
+            Some(std::mem::replace(existing, kind))
+        } else {
+            self.node_mut().fields.push(Field::new(name, kind));
+            None
+        }
+    }
+
+    /// Removes a field with the given name and returns its value (if any).
+    pub fn remove_field(&mut self, name: &str) -> Option<FieldKind> {
+        let fields = &mut self.node_mut().fields;
+        let index = fields.iter().position(|field| field.name == name)?;
+        Some(fields.remove(index).kind)
+    }
+
+    /// Renames a field. Returns `false` if there is no such field or if the new name is taken.
+    pub fn rename_field(&mut self, old_name: &str, new_name: &str) -> bool {
+        if self.has_field(new_name) {
+            return false;
+        }
+        match self
+            .node_mut()
+            .fields
+            .iter_mut()
+            .find(|field| field.name == old_name)
+        {
+            Some(field) => {
+                field.name = new_name.to_owned();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns true if the region has a child region with the given name.
+    pub fn has_region(&self, name: &str) -> bool {
+        self.find_child(name).is_some()
+    }
+
+    /// Returns names of every child region.
+    pub fn region_names(&self) -> Vec<String> {
+        self.node()
+            .children
+            .iter()
+            .map(|child| self.visitor.nodes.borrow(*child).name.clone())
+            .collect()
+    }
+
+    /// Adds a new empty child region. Fails if the region already exists.
+    pub fn add_region(&mut self, name: &str) -> VisitResult {
+        if self.has_region(name) {
+            return Err(VisitError::RegionAlreadyExists(name.to_owned()));
+        }
+        let handle = self.visitor.nodes.spawn(VisitorNode::new(name, self.node));
+        self.node_mut().children.push(handle);
+        Ok(())
+    }
+
+    /// Removes a child region with all its descendants. Returns `false` if there is no such region.
+    pub fn remove_region(&mut self, name: &str) -> bool {
+        let Some(handle) = self.find_child(name) else {
+            return false;
+        };
+        self.node_mut().children.retain(|child| *child != handle);
+        let mut stack = vec![handle];
+        while let Some(handle) = stack.pop() {
+            let node = self.visitor.nodes.free(handle);
+            stack.extend(node.children);
+        }
+        true
+    }
+
+    /// Renames a child region. Returns `false` if there is no such region or if the new name is
+    /// taken.
+    pub fn rename_region(&mut self, old_name: &str, new_name: &str) -> bool {
+        if self.has_region(new_name) {
+            return false;
+        }
+        match self.find_child(old_name) {
+            Some(handle) => {
+                self.visitor.nodes.borrow_mut(handle).name = new_name.to_owned();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Moves a field of this region into the given child region, keeping its name. Returns `false`
+    /// if there is no such field or region, or if the region already has a field with the name.
+    pub fn move_field_to_region(&mut self, field: &str, region: &str) -> bool {
+        let Some(target) = self.find_child(region) else {
+            return false;
+        };
+        if !self.has_field(field)
+            || self
+                .visitor
+                .nodes
+                .borrow(target)
+                .fields
+                .iter()
+                .any(|f| f.name == field)
+        {
+            return false;
+        }
+        let kind = self.remove_field(field).unwrap();
+        self.visitor
+            .nodes
+            .borrow_mut(target)
+            .fields
+            .push(Field::new(field, kind));
+        true
+    }
+
+    /// Moves a field from the given child region into this region, keeping its name. Returns
+    /// `false` if there is no such field or region, or if this region already has a field with
+    /// the name.
+    pub fn move_field_from_region(&mut self, region: &str, field: &str) -> bool {
+        if self.has_field(field) {
+            return false;
+        }
+        let Some(source) = self.find_child(region) else {
+            return false;
+        };
+        let fields = &mut self.visitor.nodes.borrow_mut(source).fields;
+        let Some(index) = fields.iter().position(|f| f.name == field) else {
+            return false;
+        };
+        let field = fields.remove(index);
+        self.node_mut().fields.push(field);
+        true
+    }
+
+    /// Runs the given function on a child region with the given name.
+    pub fn region<F>(&mut self, name: &str, func: F) -> VisitResult
+    where
+        F: FnOnce(&mut MigrationContext) -> VisitResult,
+    {
+        match self.find_child(name) {
+            Some(node) => func(&mut MigrationContext {
+                visitor: self.visitor,
+                node,
+                from_version: self.from_version,
+            }),
+            None => Err(VisitError::RegionDoesNotExist(format!(
+                "{} > {}",
+                self.name(),
+                name
+            ))),
+        }
+    }
+
+    /// Runs the given function on every child region.
+    pub fn for_each_region<F>(&mut self, mut func: F) -> VisitResult
+    where
+        F: FnMut(&mut MigrationContext) -> VisitResult,
+    {
+        for node in self.node().children.clone() {
+            func(&mut MigrationContext {
+                visitor: self.visitor,
+                node,
+                from_version: self.from_version,
+            })?;
+        }
+        Ok(())
+    }
+}
+
+impl Visitor {
+    /// Visits schema version of the type whose region is currently entered. When writing, the
+    /// `current_version` is stored in the region. When reading, the stored version (0 if absent) is
+    /// read and every migration from the stored version up to `current_version` is applied to
+    /// the region in ascending order of versions. Must be called before visiting any field of the
+    /// type. Data with a version newer than `current_version` is rejected with
+    /// [`VisitError::UnsupportedSchemaVersion`].
+    pub fn visit_schema_version(
+        &mut self,
+        current_version: u32,
+        migrations: &[Migration],
+    ) -> VisitResult {
+        if !self.reading {
+            let mut version = current_version;
+            return version.visit(SCHEMA_VERSION_FIELD, self);
+        }
+
+        let stored_version = match self.find_field(SCHEMA_VERSION_FIELD) {
+            Some(Field {
+                kind: FieldKind::U32(version),
+                ..
+            }) => *version,
+            Some(field) => {
+                return Err(VisitError::FieldTypeDoesNotMatch {
+                    expected: "FieldKind::U32",
+                    actual: format!("{:?}", field.kind),
+                })
+            }
+            None => 0,
+        };
+
+        if stored_version > current_version {
+            return Err(VisitError::UnsupportedSchemaVersion {
+                region: self.breadcrumbs(),
+                stored: stored_version,
+                current: current_version,
+            });
+        }
+
+        if stored_version == current_version {
+            return Ok(());
+        }
+
+        let mut migrations = migrations
+            .iter()
+            .filter(|migration| {
+                migration.from_version >= stored_version && migration.from_version < current_version
+            })
+            .collect::<Vec<_>>();
+        // Stable sort, migrations of the same version are applied in the order of declaration.
+        migrations.sort_by_key(|migration| migration.from_version);
+
+        let node = self.current_node;
+        for migration in migrations {
+            (migration.upgrade)(&mut MigrationContext {
+                visitor: self,
+                node,
+                from_version: migration.from_version,
+            })?;
+        }
+
+        let mut ctx = MigrationContext {
+            visitor: self,
+            node,
+            from_version: current_version,
+        };
+        ctx.set_field(SCHEMA_VERSION_FIELD, FieldKind::U32(current_version));
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::visitor::{
+        field::FieldKind,
+        migration::{Migration, MigrationContext, SCHEMA_VERSION_FIELD},
+        prelude::*,
+    };
+
+    #[derive(Visit, Default, Debug, PartialEq)]
+    struct Inner {
+        speed: f32,
+    }
+
+    // Version 0: `Speed` was a field of `Outer`, `Inner` did not exist and `Label` was `Name`.
+    // Version 1: `Name` renamed to `Label`.
+    // Version 2: `Speed` moved into `Inner` region.
+    #[derive(Visit, Default, Debug, PartialEq)]
+    #[visit(schema_version = 2, migrations = "Outer::migrations")]
+    struct Outer {
+        label: String,
+        inner: Inner,
+    }
+
+    impl Outer {
+        fn migrations() -> Vec<Migration> {
+            vec![
+                Migration::new(1, |ctx: &mut MigrationContext| {
+                    ctx.add_region("Inner")?;
+                    assert!(ctx.move_field_to_region("Speed", "Inner"));
+                    Ok(())
+                }),
+                Migration::new(0, |ctx: &mut MigrationContext| {
+                    assert!(ctx.rename_field("Name", "Label"));
+                    Ok(())
+                }),
+            ]
+        }
+    }
+
+    #[test]
+    fn test_schema_migration() {
+        let mut visitor = Visitor::new();
+        {
+            let mut region = visitor.enter_region("Outer").unwrap();
+            "Foo".to_string().visit("Name", &mut region).unwrap();
+            1.5f32.visit("Speed", &mut region).unwrap();
+        }
+        let data = visitor.save_binary_to_vec().unwrap();
+
+        let mut visitor = Visitor::load_from_memory(&data).unwrap();
+        let mut outer = Outer::default();
+        outer.visit("Outer", &mut visitor).unwrap();
+        assert_eq!(
+            outer,
+            Outer {
+                label: "Foo".to_string(),
+                inner: Inner { speed: 1.5 }
+            }
+        );
+
+        // Round trip with current version must not run any migrations.
+        let mut visitor = Visitor::new();
+        outer.visit("Outer", &mut visitor).unwrap();
+        let data = visitor.save_binary_to_vec().unwrap();
+        let mut visitor = Visitor::load_from_memory(&data).unwrap();
+        let mut loaded = Outer::default();
+        loaded.visit("Outer", &mut visitor).unwrap();
+        assert_eq!(loaded, outer);
+    }
+
+    // Version 0: `Velocity` of `Moving` was called `Speed`.
+    #[derive(Visit, Default, Debug, PartialEq)]
+    #[visit(schema_version = 1, migrations = "Motion::migrations")]
+    enum Motion {
+        #[default]
+        Idle,
+        Moving {
+            velocity: f32,
+        },
+    }
+
+    impl Motion {
+        fn migrations() -> Vec<Migration> {
+            vec![Migration::new(0, |ctx: &mut MigrationContext| {
+                ctx.rename_field("Speed", "Velocity");
+                Ok(())
+            })]
+        }
+    }
+
+    #[test]
+    fn test_enum_schema_migration() {
+        let mut visitor = Visitor::new();
+        {
+            let mut region = visitor.enter_region("Motion").unwrap();
+            1u32.visit("Id", &mut region).unwrap();
+            2.5f32.visit("Speed", &mut region).unwrap();
+        }
+        let data = visitor.save_binary_to_vec().unwrap();
+
+        let mut visitor = Visitor::load_from_memory(&data).unwrap();
+        let mut motion = Motion::default();
+        motion.visit("Motion", &mut visitor).unwrap();
+        assert_eq!(motion, Motion::Moving { velocity: 2.5 });
+
+        let mut visitor = Visitor::new();
+        motion.visit("Motion", &mut visitor).unwrap();
+        let data = visitor.save_binary_to_vec().unwrap();
+        let mut visitor = Visitor::load_from_memory(&data).unwrap();
+        {
+            let mut region = visitor.enter_region("Motion").unwrap();
+            assert_eq!(
+                region
+                    .find_field(SCHEMA_VERSION_FIELD)
+                    .map(|field| &field.kind),
+                Some(&FieldKind::U32(1))
+            );
+        }
+        let mut loaded = Motion::default();
+        loaded.visit("Motion", &mut visitor).unwrap();
+        assert_eq!(loaded, motion);
+    }
+
+    #[test]
+    fn test_newer_schema_version_is_rejected() {
+        let mut visitor = Visitor::new();
+        {
+            let mut region = visitor.enter_region("Outer").unwrap();
+            3u32.visit(SCHEMA_VERSION_FIELD, &mut region).unwrap();
+        }
+        let data = visitor.save_binary_to_vec().unwrap();
+        let mut visitor = Visitor::load_from_memory(&data).unwrap();
+        let mut outer = Outer::default();
+        assert!(matches!(
+            outer.visit("Outer", &mut visitor),
+            Err(VisitError::UnsupportedSchemaVersion {
+                stored: 3,
+                current: 2,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_migration_context_editing() {
+        let mut visitor = Visitor::new();
+        {
+            let mut region = visitor.enter_region("Root").unwrap();
+            1u32.visit("A", &mut region).unwrap();
+            {
+                let mut child = region.enter_region("Child").unwrap();
+                2u32.visit("B", &mut child).unwrap();
+            }
+        }
+        let data = visitor.save_binary_to_vec().unwrap();
+        let mut visitor = Visitor::load_from_memory(&data).unwrap();
+        let mut region = visitor.enter_region("Root").unwrap();
+        region
+            .visit_schema_version(
+                1,
+                &[Migration::new(0, |ctx| {
+                    assert_eq!(ctx.from_version(), 0);
+                    assert_eq!(ctx.field("A"), Some(&FieldKind::U32(1)));
+                    assert!(ctx.move_field_from_region("Child", "B"));
+                    assert!(ctx.remove_region("Child"));
+                    assert!(!ctx.has_region("Child"));
+                    assert_eq!(ctx.remove_field("A"), Some(FieldKind::U32(1)));
+                    ctx.set_field("C", FieldKind::U32(3));
+                    Ok(())
+                })],
+            )
+            .unwrap();
+        let mut b = 0u32;
+        b.visit("B", &mut region).unwrap();
+        assert_eq!(b, 2);
+        let mut c = 0u32;
+        c.visit("C", &mut region).unwrap();
+        assert_eq!(c, 3);
+        assert!(region.find_field("A").is_none());
+    }
+}
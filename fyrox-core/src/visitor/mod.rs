@@ -38,15 +38,24 @@
 #![warn(missing_docs)]
 
 pub mod blackboard;
+pub mod diff;
 pub mod error;
 pub mod field;
 mod impls;
+pub mod lazy;
+pub mod migration;
 pub mod pod;
 mod reader;
 mod writer;
 
 pub use fyrox_core_derive::Visit;
 
+pub mod ron {
+    //! Intermediate types of the RON form of a visitor, see [`super::Format::Ron`]. They could be
+    //! used to inspect or generate RON documents with `serde` without going through a [`super::Visitor`].
+    pub use super::writer::ron::{RonDocument, RonField, RonNode, RonValue};
+}
+
 pub mod prelude {
     //! Types to use `#[derive(Visit)]`
     pub use super::{Visit, VisitResult, Visitor};
@@ -59,8 +68,8 @@ use crate::{
     io::{self},
     pool::{Handle, Pool},
     visitor::{
-        reader::{ascii::AsciiReader, binary::BinaryReader, Reader},
-        writer::{ascii::AsciiWriter, binary::BinaryWriter, Writer},
+        reader::{ascii::AsciiReader, binary::BinaryReader, ron::RonReader, Reader},
+        writer::{ascii::AsciiWriter, binary::BinaryWriter, ron::RonWriter, Writer},
     },
 };
 use bitflags::bitflags;
@@ -469,6 +478,15 @@ pub enum Format {
     /// Slow and "fat" format, but changes can be merged by a version control system. It makes this
     /// format ideal for collaborative work.
     Ascii,
+    /// Binary format with an offset table, that allows to load individual subtrees on demand using
+    /// [`lazy::LazyVisitor`]. It is slightly larger than [`Self::Binary`], but it is the best
+    /// choice for large scenes and save files.
+    IndexedBinary,
+    /// Standard [RON](https://github.com/ron-rs/ron) format, that maps every node and field of a
+    /// visitor losslessly. It is the slowest and the largest format, but it can be read and
+    /// generated by external tools (using any serde-compatible library) that know nothing about
+    /// the other formats.
+    Ron,
 }
 
 impl Visitor {
@@ -490,23 +508,53 @@ impl Visitor {
     /// these bytes are not at the beginning of the given slice.
     pub const MAGIC_ASCII_CURRENT: &'static str = "FTAX";
 
+    /// Sequence of bytes that is automatically written at the start when a visitor is encoded into
+    /// the indexed binary form. It is written by [Visitor::save_indexed_binary_to_file],
+    /// [Visitor::save_indexed_binary_to_memory] and [Visitor::save_indexed_binary_to_vec]. See
+    /// [`lazy`] module docs for more info.
+    pub const MAGIC_INDEXED_BINARY_CURRENT: &'static str = "FBAI";
+
+    /// A value of the `magic` member of the top-level structure of a visitor encoded in RON form.
+    /// It is written by [Visitor::save_ron_to_file], [Visitor::save_ron_to_memory] and
+    /// [Visitor::save_ron_to_string].
+    ///
+    /// [Visitor::load_ron_from_file] and [Visitor::load_ron_from_memory] will return an error if
+    /// the member is missing or has a different value.
+    pub const MAGIC_RON_CURRENT: &'static str = "FRON";
+
     /// Checks whether the given reader points to a supported file format or not.
     #[must_use]
     pub fn is_supported(src: &mut dyn Read) -> bool {
         Self::detect_format(src) != Format::Unknown
     }
 
-    /// Tries to extract the information about the file format in the given reader.
+    /// Maximum amount of bytes that is read by [`Self::detect_format`]. RON documents could start
+    /// with comments and attributes, so the format cannot be detected by the first few bytes only.
+    pub const FORMAT_DETECTION_PREFIX_LEN: u64 = 4096;
+
+    /// Tries to extract the information about the file format in the given reader. At most
+    /// [`Self::FORMAT_DETECTION_PREFIX_LEN`] bytes are read from the reader.
     pub fn detect_format(src: &mut dyn Read) -> Format {
-        let mut magic: [u8; 4] = Default::default();
-        if src.read_exact(&mut magic).is_ok() {
-            if magic.eq(Visitor::MAGIC_BINARY_CURRENT.as_bytes()) {
-                return Format::Binary;
-            } else if magic.eq(Visitor::MAGIC_ASCII_CURRENT.as_bytes()) {
-                return Format::Ascii;
-            }
+        let mut prefix = Vec::new();
+        if src
+            .take(Self::FORMAT_DETECTION_PREFIX_LEN)
+            .read_to_end(&mut prefix)
+            .is_err()
+        {
+            return Format::Unknown;
+        }
+        if prefix.starts_with(Visitor::MAGIC_BINARY_CURRENT.as_bytes()) {
+            Format::Binary
+        } else if prefix.starts_with(Visitor::MAGIC_ASCII_CURRENT.as_bytes()) {
+            Format::Ascii
+        } else if prefix.starts_with(Visitor::MAGIC_INDEXED_BINARY_CURRENT.as_bytes()) {
+            Format::IndexedBinary
+        } else if reader::ron::is_ron_document_prefix(&prefix) {
+            // The magic is checked by the reader, since the members could go in any order.
+            Format::Ron
+        } else {
+            Format::Unknown
         }
-        Format::Unknown
     }
 
     /// Tries to extract the information about the file format in the given slice.
@@ -771,6 +819,40 @@ impl Visitor {
         Self::load_ascii_from_memory(&io::load_file(path).await?)
     }
 
+    /// Create a string containing all the data of this Visitor in RON form. See [`Format::Ron`]
+    /// for more info.
+    pub fn save_ron_to_string(&self) -> String {
+        let mut cursor = Cursor::<Vec<u8>>::default();
+        self.save_ron_to_memory(&mut cursor).unwrap();
+        String::from_utf8(cursor.into_inner()).unwrap()
+    }
+
+    /// Write all the data of this Visitor in RON form to a file at the given path.
+    pub fn save_ron_to_file(&self, path: impl AsRef<Path>) -> VisitResult {
+        let writer = BufWriter::new(File::create(path)?);
+        self.save_ron_to_memory(writer)
+    }
+
+    /// Write all the data of this Visitor in RON form to the given writer.
+    pub fn save_ron_to_memory(&self, mut dest: impl Write) -> VisitResult {
+        let writer = RonWriter::default();
+        writer.write(self, &mut dest)
+    }
+
+    /// Tries to create a visitor from the given RON data. The returned instance can then be used
+    /// to deserialize some data.
+    pub fn load_ron_from_memory(data: &[u8]) -> Result<Self, VisitError> {
+        let mut src = Cursor::new(data);
+        let mut reader = RonReader::new(&mut src);
+        reader.read()
+    }
+
+    /// Tries to create a visitor from the given RON file. The returned instance can then be used
+    /// to deserialize some data.
+    pub async fn load_ron_from_file(path: impl AsRef<Path>) -> Result<Self, VisitError> {
+        Self::load_ron_from_memory(&io::load_file(path).await?)
+    }
+
     /// Write the data of this Visitor to the given writer. Begin by writing [Visitor::MAGIC_BINARY_CURRENT].
     pub fn save_binary_to_memory(&self, mut dest: impl Write) -> VisitResult {
         let writer = BinaryWriter::default();
@@ -793,6 +875,26 @@ impl Visitor {
         self.save_binary_to_memory(writer)
     }
 
+    /// Write the data of this Visitor to the given writer in the indexed binary format. See
+    /// [`lazy`] module docs for more info.
+    pub fn save_indexed_binary_to_memory(&self, mut dest: impl Write) -> VisitResult {
+        lazy::write_indexed(self, &mut dest)
+    }
+
+    /// Encode the data of this visitor in the indexed binary format and return the bytes.
+    pub fn save_indexed_binary_to_vec(&self) -> Result<Vec<u8>, VisitError> {
+        let mut writer = Cursor::new(Vec::new());
+        self.save_indexed_binary_to_memory(&mut writer)?;
+        Ok(writer.into_inner())
+    }
+
+    /// Create a file at the given path and write the data of this visitor into that file in the
+    /// indexed binary format. The file could be read partially using [`lazy::LazyVisitor`].
+    pub fn save_indexed_binary_to_file(&self, path: impl AsRef<Path>) -> VisitResult {
+        let writer = BufWriter::new(File::create(path)?);
+        self.save_indexed_binary_to_memory(writer)
+    }
+
     /// Create a visitor by reading data from the file at the given path, assuming that the file was
     /// created using [Visitor::save_binary_to_file]. Return a [VisitError::NotSupportedFormat] if
     /// [Visitor::MAGIC_BINARY_CURRENT] is not the first bytes read from the file.
@@ -823,13 +925,15 @@ impl Visitor {
             Format::Unknown => Err(VisitError::NotSupportedFormat),
             Format::Binary => Self::load_binary_from_memory(data),
             Format::Ascii => Self::load_ascii_from_memory(data),
+            Format::Ron => Self::load_ron_from_memory(data),
+            Format::IndexedBinary => lazy::LazyVisitor::new(Cursor::new(data))?.load_all(),
         }
     }
 }
 
 #[cfg(test)]
 mod test {
-    use crate::visitor::{BinaryBlob, Visit, VisitResult, Visitor};
+    use crate::visitor::{BinaryBlob, Format, Visit, VisitResult, Visitor};
     use nalgebra::{
         Matrix2, Matrix3, Matrix4, UnitComplex, UnitQuaternion, Vector2, Vector3, Vector4,
     };
@@ -1131,4 +1235,136 @@ mod test {
             assert_eq!(objects, expected_objects);
         }
     }
+
+    #[test]
+    fn visitor_test_ron() {
+        let visitor = serialize();
+        let ron = visitor.save_ron_to_string();
+        assert_eq!(
+            Visitor::detect_format_from_slice(ron.as_bytes()),
+            Format::Ron
+        );
+
+        let mut loaded = Visitor::load_from_memory(ron.as_bytes()).unwrap();
+        assert!(crate::visitor::diff::diff(&visitor, &loaded).is_empty());
+
+        let expected_resource = resource();
+        let expected_resource_arc = resource_arc();
+        let expected_objects = objects(expected_resource.clone(), expected_resource_arc.clone());
+
+        let mut resource: Rc<Resource> = Rc::new(Default::default());
+        resource.visit("SharedResource", &mut loaded).unwrap();
+        assert_eq!(resource, expected_resource);
+
+        let mut resource_arc: Arc<Resource> = Arc::new(Default::default());
+        resource_arc
+            .visit("SharedResourceArc", &mut loaded)
+            .unwrap();
+        assert_eq!(resource_arc, expected_resource_arc);
+
+        let mut objects: Vec<Foo> = Vec::new();
+        objects.visit("Objects", &mut loaded).unwrap();
+        assert_eq!(objects, expected_objects);
+    }
+
+    #[test]
+    fn visitor_test_ron_non_finite_floats() {
+        let mut visitor = Visitor::new();
+        let mut nan = f32::NAN;
+        nan.visit("NaN", &mut visitor).unwrap();
+        let mut inf = f64::INFINITY;
+        inf.visit("Inf", &mut visitor).unwrap();
+        let mut neg_inf = -f32::INFINITY;
+        neg_inf.visit("NegInf", &mut visitor).unwrap();
+        let mut short = 0.1f32;
+        short.visit("Short", &mut visitor).unwrap();
+
+        let ron = visitor.save_ron_to_string();
+        // Shortest representation must be used for floats.
+        assert!(ron.contains("F32(0.1)"));
+
+        let mut loaded = Visitor::load_ron_from_memory(ron.as_bytes()).unwrap();
+        let mut nan = 0.0f32;
+        nan.visit("NaN", &mut loaded).unwrap();
+        assert!(nan.is_nan());
+        let mut inf = 0.0f64;
+        inf.visit("Inf", &mut loaded).unwrap();
+        assert_eq!(inf, f64::INFINITY);
+        let mut neg_inf = 0.0f32;
+        neg_inf.visit("NegInf", &mut loaded).unwrap();
+        assert_eq!(neg_inf, -f32::INFINITY);
+        let mut short = 0.0f32;
+        short.visit("Short", &mut loaded).unwrap();
+        assert_eq!(short, 0.1);
+    }
+
+    #[test]
+    fn visitor_test_ron_format_detection() {
+        let mut visitor = Visitor::new();
+        42u32.visit("Value", &mut visitor).unwrap();
+        let ron = visitor.save_ron_to_string();
+
+        for prefix in [
+            "",
+            "  \n\t",
+            "// Comment\n",
+            "/* Block /* nested */ comment */",
+            "#![enable(implicit_some)]\n",
+            "#![enable(implicit_some)] // Comment\nRonDocument ",
+            "RonDocument",
+        ] {
+            let document = format!("{prefix}{ron}");
+            assert_eq!(
+                Visitor::detect_format_from_slice(document.as_bytes()),
+                Format::Ron,
+                "{prefix}"
+            );
+            let mut loaded = Visitor::load_from_memory(document.as_bytes()).unwrap();
+            let mut value = 0u32;
+            value.visit("Value", &mut loaded).unwrap();
+            assert_eq!(value, 42);
+        }
+
+        for data in ["", "// (", "/* ( */", "#![enable(", "Foo(", "RonDocumentX("] {
+            assert_eq!(
+                Visitor::detect_format_from_slice(data.as_bytes()),
+                Format::Unknown,
+                "{data}"
+            );
+        }
+    }
+
+    #[test]
+    fn visitor_test_ron_too_deep_regions() {
+        const DEPTH: usize = 100_000;
+
+        let mut ron = r#"(magic: "FRON", version: 0, root: "#.to_string();
+        for _ in 0..DEPTH {
+            ron.push_str(r#"(name: "Region", children: ["#);
+        }
+        assert!(Visitor::load_ron_from_memory(ron.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn visitor_test_ron_deep_regions() {
+        const DEPTH: usize = 120;
+
+        fn visit_nested(visitor: &mut Visitor, depth: usize, value: &mut u32) {
+            if depth == 0 {
+                value.visit("Value", visitor).unwrap();
+            } else {
+                let mut region = visitor.enter_region("Region").unwrap();
+                visit_nested(&mut region, depth - 1, value);
+            }
+        }
+
+        let mut visitor = Visitor::new();
+        visit_nested(&mut visitor, DEPTH, &mut 123);
+        let ron = visitor.save_ron_to_string();
+
+        let mut loaded = Visitor::load_ron_from_memory(ron.as_bytes()).unwrap();
+        let mut value = 0;
+        visit_nested(&mut loaded, DEPTH, &mut value);
+        assert_eq!(value, 123);
+    }
 }
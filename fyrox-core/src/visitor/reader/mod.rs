@@ -20,6 +20,7 @@
 
 pub mod ascii;
 pub mod binary;
+pub mod ron;
 
 use crate::{
     pool::Handle,
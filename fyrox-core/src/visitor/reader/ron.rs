@@ -0,0 +1,232 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use crate::{
+    pool::{Handle, Pool},
+    visitor::{
+        blackboard::Blackboard,
+        error::VisitError,
+        field::{Field, FieldKind},
+        writer::ron::{ron_options, RonDocument, RonField, RonNode, RonValue},
+        Visitor, VisitorFlags, VisitorNode,
+    },
+};
+use base64::Engine;
+use nalgebra::{
+    Complex, Matrix2, Matrix3, Matrix4, Quaternion, UnitComplex, UnitQuaternion, Vector4,
+};
+use std::{io::Read, str::FromStr};
+use uuid::Uuid;
+
+fn base64(string: &str) -> Result<Vec<u8>, VisitError> {
+    Ok(base64::engine::general_purpose::STANDARD.decode(string)?)
+}
+
+impl TryFrom<RonValue> for FieldKind {
+    type Error = VisitError;
+
+    fn try_from(value: RonValue) -> Result<Self, Self::Error> {
+        Ok(match value {
+            RonValue::Bool(data) => Self::Bool(data),
+            RonValue::U8(data) => Self::U8(data),
+            RonValue::I8(data) => Self::I8(data),
+            RonValue::U16(data) => Self::U16(data),
+            RonValue::I16(data) => Self::I16(data),
+            RonValue::U32(data) => Self::U32(data),
+            RonValue::I32(data) => Self::I32(data),
+            RonValue::U64(data) => Self::U64(data),
+            RonValue::I64(data) => Self::I64(data),
+            RonValue::F32(data) => Self::F32(data),
+            RonValue::F64(data) => Self::F64(data),
+            RonValue::Vec2F32(data) => Self::Vector2F32(data.into()),
+            RonValue::Vec3F32(data) => Self::Vector3F32(data.into()),
+            RonValue::Vec4F32(data) => Self::Vector4F32(data.into()),
+            RonValue::Vec2F64(data) => Self::Vector2F64(data.into()),
+            RonValue::Vec3F64(data) => Self::Vector3F64(data.into()),
+            RonValue::Vec4F64(data) => Self::Vector4F64(data.into()),
+            RonValue::Vec2I8(data) => Self::Vector2I8(data.into()),
+            RonValue::Vec3I8(data) => Self::Vector3I8(data.into()),
+            RonValue::Vec4I8(data) => Self::Vector4I8(data.into()),
+            RonValue::Vec2U8(data) => Self::Vector2U8(data.into()),
+            RonValue::Vec3U8(data) => Self::Vector3U8(data.into()),
+            RonValue::Vec4U8(data) => Self::Vector4U8(data.into()),
+            RonValue::Vec2I16(data) => Self::Vector2I16(data.into()),
+            RonValue::Vec3I16(data) => Self::Vector3I16(data.into()),
+            RonValue::Vec4I16(data) => Self::Vector4I16(data.into()),
+            RonValue::Vec2U16(data) => Self::Vector2U16(data.into()),
+            RonValue::Vec3U16(data) => Self::Vector3U16(data.into()),
+            RonValue::Vec4U16(data) => Self::Vector4U16(data.into()),
+            RonValue::Vec2I32(data) => Self::Vector2I32(data.into()),
+            RonValue::Vec3I32(data) => Self::Vector3I32(data.into()),
+            RonValue::Vec4I32(data) => Self::Vector4I32(data.into()),
+            RonValue::Vec2U32(data) => Self::Vector2U32(data.into()),
+            RonValue::Vec3U32(data) => Self::Vector3U32(data.into()),
+            RonValue::Vec4U32(data) => Self::Vector4U32(data.into()),
+            RonValue::Vec2I64(data) => Self::Vector2I64(data.into()),
+            RonValue::Vec3I64(data) => Self::Vector3I64(data.into()),
+            RonValue::Vec4I64(data) => Self::Vector4I64(data.into()),
+            RonValue::Vec2U64(data) => Self::Vector2U64(data.into()),
+            RonValue::Vec3U64(data) => Self::Vector3U64(data.into()),
+            RonValue::Vec4U64(data) => Self::Vector4U64(data.into()),
+            // Stored as is, without normalization, to make the round trip lossless.
+            RonValue::Quat(data) => Self::UnitQuaternion(UnitQuaternion::new_unchecked(
+                Quaternion::from(Vector4::from(data)),
+            )),
+            RonValue::Mat4(data) => Self::Matrix4(Matrix4::from_column_slice(&data)),
+            RonValue::Mat3(data) => Self::Matrix3(Matrix3::from_column_slice(&data)),
+            RonValue::Mat2(data) => Self::Matrix2(Matrix2::from_column_slice(&data)),
+            RonValue::Data(data) => Self::BinaryBlob(base64(&data)?),
+            RonValue::Uuid(uuid) => Self::Uuid(Uuid::from_str(&uuid)?),
+            RonValue::Complex([re, im]) => {
+                Self::UnitComplex(UnitComplex::new_unchecked(Complex::new(re, im)))
+            }
+            RonValue::PodArray {
+                type_id,
+                element_size,
+                bytes,
+            } => Self::PodArray {
+                type_id,
+                element_size,
+                bytes: base64(&bytes)?,
+            },
+            RonValue::Str(string) => Self::String(string),
+        })
+    }
+}
+
+impl TryFrom<RonField> for Field {
+    type Error = VisitError;
+
+    fn try_from(field: RonField) -> Result<Self, Self::Error> {
+        Ok(Field {
+            name: field.name,
+            kind: field.value.try_into()?,
+        })
+    }
+}
+
+fn read_node(
+    visitor: &mut Visitor,
+    parent: Handle<VisitorNode>,
+    ron_node: RonNode,
+) -> Result<Handle<VisitorNode>, VisitError> {
+    let mut node = VisitorNode::new(&ron_node.name, parent);
+    node.fields = ron_node
+        .fields
+        .into_iter()
+        .map(Field::try_from)
+        .collect::<Result<_, _>>()?;
+    let handle = visitor.nodes.spawn(node);
+    for child in ron_node.children {
+        let child = read_node(visitor, handle, child)?;
+        visitor.nodes[handle].children.push(child);
+    }
+    Ok(handle)
+}
+
+// Skips whitespace, line comments, nested block comments and inner attributes such as
+// `#![enable(implicit_some)]`. Returns `None` if the prefix ends in the middle of a comment or an
+// attribute.
+fn skip_ron_trivia(mut bytes: &[u8]) -> Option<&[u8]> {
+    loop {
+        bytes = bytes.trim_ascii_start();
+        if let Some(rest) = bytes.strip_prefix(b"//") {
+            let end = rest.iter().position(|ch| *ch == b'\n')?;
+            bytes = &rest[end..];
+        } else if bytes.starts_with(b"/*") {
+            let mut depth = 0usize;
+            let mut i = 0;
+            loop {
+                let rest = bytes.get(i..)?;
+                if rest.starts_with(b"/*") {
+                    depth += 1;
+                    i += 2;
+                } else if rest.starts_with(b"*/") {
+                    depth -= 1;
+                    i += 2;
+                    if depth == 0 {
+                        break;
+                    }
+                } else if rest.is_empty() {
+                    return None;
+                } else {
+                    i += 1;
+                }
+            }
+            bytes = &bytes[i..];
+        } else if let Some(rest) = bytes.strip_prefix(b"#!") {
+            let end = rest.iter().position(|ch| *ch == b']')?;
+            bytes = &rest[end + 1..];
+        } else {
+            return Some(bytes);
+        }
+    }
+}
+
+/// Checks whether the given prefix of some data looks like the beginning of a [`RonDocument`]:
+/// an unnamed or `RonDocument`-named struct, optionally preceded by whitespace, comments and
+/// attributes.
+pub(crate) fn is_ron_document_prefix(bytes: &[u8]) -> bool {
+    let Some(mut bytes) = skip_ron_trivia(bytes) else {
+        return false;
+    };
+    if let Some(rest) = bytes.strip_prefix(b"RonDocument") {
+        let Some(rest) = skip_ron_trivia(rest) else {
+            return false;
+        };
+        bytes = rest;
+    }
+    bytes.starts_with(b"(")
+}
+
+pub struct RonReader<'a> {
+    src: &'a mut dyn Read,
+}
+
+impl<'a> RonReader<'a> {
+    pub fn new(src: &'a mut dyn Read) -> Self {
+        Self { src }
+    }
+
+    pub fn read(&mut self) -> Result<Visitor, VisitError> {
+        let document: RonDocument = ron_options().from_reader(&mut self.src)?;
+
+        if document.magic != Visitor::MAGIC_RON_CURRENT {
+            return Err(VisitError::NotSupportedFormat);
+        }
+
+        let mut visitor = Visitor {
+            nodes: Pool::new(),
+            unique_id_counter: 1,
+            type_name_map: Default::default(),
+            rc_map: Default::default(),
+            arc_map: Default::default(),
+            reading: true,
+            current_node: Handle::NONE,
+            root: Handle::NONE,
+            version: document.version,
+            blackboard: Blackboard::new(),
+            flags: VisitorFlags::NONE,
+        };
+        visitor.root = read_node(&mut visitor, Handle::NONE, document.root)?;
+        visitor.current_node = visitor.root;
+        Ok(visitor)
+    }
+}
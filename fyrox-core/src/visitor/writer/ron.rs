@@ -0,0 +1,318 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use crate::visitor::{
+    field::{Field, FieldKind},
+    writer::Writer,
+    VisitResult, Visitor, VisitorNode,
+};
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+
+/// Top-level object of a visitor in RON form.
+#[derive(Serialize, Deserialize)]
+pub struct RonDocument {
+    /// Magic string that identifies the format, must be equal to [`Visitor::MAGIC_RON_CURRENT`].
+    pub magic: String,
+    /// Version of the visitor format that was used to write the document.
+    pub version: u32,
+    /// Root region of the document.
+    pub root: RonNode,
+}
+
+/// A region of a visitor in RON form.
+#[derive(Serialize, Deserialize)]
+pub struct RonNode {
+    /// Name of the region.
+    pub name: String,
+    /// Fields of the region, in the order they were written.
+    #[serde(default)]
+    pub fields: Vec<RonField>,
+    /// Nested regions, in the order they were entered.
+    #[serde(default)]
+    pub children: Vec<RonNode>,
+}
+
+/// A named field of a region in RON form.
+#[derive(Serialize, Deserialize)]
+pub struct RonField {
+    /// Name of the field.
+    pub name: String,
+    /// Value of the field.
+    pub value: RonValue,
+}
+
+/// A value of a field. Vectors are stored as tuples of their components, matrices are stored in
+/// column-major order, quaternions in (i, j, k, w) order, binary data is base64-encoded.
+#[derive(Serialize, Deserialize)]
+pub enum RonValue {
+    /// Boolean value.
+    Bool(bool),
+    /// Unsigned 8-bit integer.
+    U8(u8),
+    /// Signed 8-bit integer.
+    I8(i8),
+    /// Unsigned 16-bit integer.
+    U16(u16),
+    /// Signed 16-bit integer.
+    I16(i16),
+    /// Unsigned 32-bit integer.
+    U32(u32),
+    /// Signed 32-bit integer.
+    I32(i32),
+    /// Unsigned 64-bit integer.
+    U64(u64),
+    /// Signed 64-bit integer.
+    I64(i64),
+    /// 32-bit floating point number.
+    F32(f32),
+    /// 64-bit floating point number.
+    F64(f64),
+    /// 2-dimensional vector of 32-bit floating point components.
+    Vec2F32([f32; 2]),
+    /// 3-dimensional vector of 32-bit floating point components.
+    Vec3F32([f32; 3]),
+    /// 4-dimensional vector of 32-bit floating point components.
+    Vec4F32([f32; 4]),
+    /// 2-dimensional vector of 64-bit floating point components.
+    Vec2F64([f64; 2]),
+    /// 3-dimensional vector of 64-bit floating point components.
+    Vec3F64([f64; 3]),
+    /// 4-dimensional vector of 64-bit floating point components.
+    Vec4F64([f64; 4]),
+    /// 2-dimensional vector of signed 8-bit integer components.
+    Vec2I8([i8; 2]),
+    /// 3-dimensional vector of signed 8-bit integer components.
+    Vec3I8([i8; 3]),
+    /// 4-dimensional vector of signed 8-bit integer components.
+    Vec4I8([i8; 4]),
+    /// 2-dimensional vector of unsigned 8-bit integer components.
+    Vec2U8([u8; 2]),
+    /// 3-dimensional vector of unsigned 8-bit integer components.
+    Vec3U8([u8; 3]),
+    /// 4-dimensional vector of unsigned 8-bit integer components.
+    Vec4U8([u8; 4]),
+    /// 2-dimensional vector of signed 16-bit integer components.
+    Vec2I16([i16; 2]),
+    /// 3-dimensional vector of signed 16-bit integer components.
+    Vec3I16([i16; 3]),
+    /// 4-dimensional vector of signed 16-bit integer components.
+    Vec4I16([i16; 4]),
+    /// 2-dimensional vector of unsigned 16-bit integer components.
+    Vec2U16([u16; 2]),
+    /// 3-dimensional vector of unsigned 16-bit integer components.
+    Vec3U16([u16; 3]),
+    /// 4-dimensional vector of unsigned 16-bit integer components.
+    Vec4U16([u16; 4]),
+    /// 2-dimensional vector of signed 32-bit integer components.
+    Vec2I32([i32; 2]),
+    /// 3-dimensional vector of signed 32-bit integer components.
+    Vec3I32([i32; 3]),
+    /// 4-dimensional vector of signed 32-bit integer components.
+    Vec4I32([i32; 4]),
+    /// 2-dimensional vector of unsigned 32-bit integer components.
+    Vec2U32([u32; 2]),
+    /// 3-dimensional vector of unsigned 32-bit integer components.
+    Vec3U32([u32; 3]),
+    /// 4-dimensional vector of unsigned 32-bit integer components.
+    Vec4U32([u32; 4]),
+    /// 2-dimensional vector of signed 64-bit integer components.
+    Vec2I64([i64; 2]),
+    /// 3-dimensional vector of signed 64-bit integer components.
+    Vec3I64([i64; 3]),
+    /// 4-dimensional vector of signed 64-bit integer components.
+    Vec4I64([i64; 4]),
+    /// 2-dimensional vector of unsigned 64-bit integer components.
+    Vec2U64([u64; 2]),
+    /// 3-dimensional vector of unsigned 64-bit integer components.
+    Vec3U64([u64; 3]),
+    /// 4-dimensional vector of unsigned 64-bit integer components.
+    Vec4U64([u64; 4]),
+    /// Unit quaternion in (i, j, k, w) order.
+    Quat([f32; 4]),
+    /// 4x4 matrix in column-major order.
+    Mat4([f32; 16]),
+    /// 3x3 matrix in column-major order.
+    Mat3([f32; 9]),
+    /// 2x2 matrix in column-major order.
+    Mat2([f32; 4]),
+    /// Arbitrary binary data, base64-encoded.
+    Data(String),
+    /// Uuid in its hyphenated string form.
+    Uuid(String),
+    /// Unit complex number in (re, im) order.
+    Complex([f32; 2]),
+    /// Array of plain-old-data elements.
+    PodArray {
+        /// Type id of the elements, see `Pod::type_id`.
+        type_id: u8,
+        /// Size of a single element in bytes.
+        element_size: u32,
+        /// Raw bytes of the array, base64-encoded.
+        bytes: String,
+    },
+    /// UTF-8 string.
+    Str(String),
+}
+
+fn base64(bytes: &[u8]) -> String {
+    base64::engine::general_purpose::STANDARD.encode(bytes)
+}
+
+impl From<&FieldKind> for RonValue {
+    fn from(kind: &FieldKind) -> Self {
+        match kind {
+            FieldKind::Bool(data) => Self::Bool(*data),
+            FieldKind::U8(data) => Self::U8(*data),
+            FieldKind::I8(data) => Self::I8(*data),
+            FieldKind::U16(data) => Self::U16(*data),
+            FieldKind::I16(data) => Self::I16(*data),
+            FieldKind::U32(data) => Self::U32(*data),
+            FieldKind::I32(data) => Self::I32(*data),
+            FieldKind::U64(data) => Self::U64(*data),
+            FieldKind::I64(data) => Self::I64(*data),
+            FieldKind::F32(data) => Self::F32(*data),
+            FieldKind::F64(data) => Self::F64(*data),
+            FieldKind::Vector2F32(data) => Self::Vec2F32((*data).into()),
+            FieldKind::Vector3F32(data) => Self::Vec3F32((*data).into()),
+            FieldKind::Vector4F32(data) => Self::Vec4F32((*data).into()),
+            FieldKind::Vector2F64(data) => Self::Vec2F64((*data).into()),
+            FieldKind::Vector3F64(data) => Self::Vec3F64((*data).into()),
+            FieldKind::Vector4F64(data) => Self::Vec4F64((*data).into()),
+            FieldKind::Vector2I8(data) => Self::Vec2I8((*data).into()),
+            FieldKind::Vector3I8(data) => Self::Vec3I8((*data).into()),
+            FieldKind::Vector4I8(data) => Self::Vec4I8((*data).into()),
+            FieldKind::Vector2U8(data) => Self::Vec2U8((*data).into()),
+            FieldKind::Vector3U8(data) => Self::Vec3U8((*data).into()),
+            FieldKind::Vector4U8(data) => Self::Vec4U8((*data).into()),
+            FieldKind::Vector2I16(data) => Self::Vec2I16((*data).into()),
+            FieldKind::Vector3I16(data) => Self::Vec3I16((*data).into()),
+            FieldKind::Vector4I16(data) => Self::Vec4I16((*data).into()),
+            FieldKind::Vector2U16(data) => Self::Vec2U16((*data).into()),
+            FieldKind::Vector3U16(data) => Self::Vec3U16((*data).into()),
+            FieldKind::Vector4U16(data) => Self::Vec4U16((*data).into()),
+            FieldKind::Vector2I32(data) => Self::Vec2I32((*data).into()),
+            FieldKind::Vector3I32(data) => Self::Vec3I32((*data).into()),
+            FieldKind::Vector4I32(data) => Self::Vec4I32((*data).into()),
+            FieldKind::Vector2U32(data) => Self::Vec2U32((*data).into()),
+            FieldKind::Vector3U32(data) => Self::Vec3U32((*data).into()),
+            FieldKind::Vector4U32(data) => Self::Vec4U32((*data).into()),
+            FieldKind::Vector2I64(data) => Self::Vec2I64((*data).into()),
+            FieldKind::Vector3I64(data) => Self::Vec3I64((*data).into()),
+            FieldKind::Vector4I64(data) => Self::Vec4I64((*data).into()),
+            FieldKind::Vector2U64(data) => Self::Vec2U64((*data).into()),
+            FieldKind::Vector3U64(data) => Self::Vec3U64((*data).into()),
+            FieldKind::Vector4U64(data) => Self::Vec4U64((*data).into()),
+            FieldKind::UnitQuaternion(data) => Self::Quat(data.coords.into()),
+            FieldKind::Matrix4(data) => Self::Mat4(data.as_slice().try_into().unwrap()),
+            FieldKind::Matrix3(data) => Self::Mat3(data.as_slice().try_into().unwrap()),
+            FieldKind::Matrix2(data) => Self::Mat2(data.as_slice().try_into().unwrap()),
+            FieldKind::BinaryBlob(data) => Self::Data(base64(data)),
+            FieldKind::Uuid(uuid) => Self::Uuid(uuid.to_string()),
+            FieldKind::UnitComplex(data) => Self::Complex([data.re, data.im]),
+            FieldKind::PodArray {
+                type_id,
+                element_size,
+                bytes,
+            } => Self::PodArray {
+                type_id: *type_id,
+                element_size: *element_size,
+                bytes: base64(bytes),
+            },
+            FieldKind::String(string) => Self::Str(string.clone()),
+        }
+    }
+}
+
+impl From<&Field> for RonField {
+    fn from(field: &Field) -> Self {
+        Self {
+            name: field.name.clone(),
+            value: RonValue::from(&field.kind),
+        }
+    }
+}
+
+impl RonNode {
+    /// Converts the given node of the visitor and all its descendant nodes to RON form.
+    pub fn from_visitor_node(visitor: &Visitor, node: &VisitorNode) -> Self {
+        Self {
+            name: node.name.clone(),
+            fields: node.fields.iter().map(RonField::from).collect(),
+            children: node
+                .children
+                .iter()
+                .map(|child| Self::from_visitor_node(visitor, visitor.nodes.borrow(*child)))
+                .collect(),
+        }
+    }
+}
+
+fn pretty_config() -> ron::ser::PrettyConfig {
+    ron::ser::PrettyConfig::default().indentor("\t")
+}
+
+/// Maximum nesting depth of RON data. Every region adds four levels of nesting, so the default
+/// recursion limit of RON is exhausted by 32 nested regions, while this one allows over a hundred.
+/// The limit is still finite, because both (de)serialization and conversion of the regions are
+/// recursive and malformed data must not be able to overflow the stack of a loader thread.
+const RON_RECURSION_LIMIT: usize = 512;
+
+/// Options shared by the RON reader and writer.
+pub(crate) fn ron_options() -> ron::Options {
+    ron::Options::default().with_recursion_limit(RON_RECURSION_LIMIT)
+}
+
+fn write_ron<T: Serialize>(value: &T, dest: &mut dyn Write) -> VisitResult {
+    let string = ron_options().to_string_pretty(value, pretty_config())?;
+    dest.write_all(string.as_bytes())?;
+    Ok(())
+}
+
+#[derive(Default)]
+pub struct RonWriter {}
+
+impl Writer for RonWriter {
+    fn write_field(&self, field: &Field, dest: &mut dyn Write) -> VisitResult {
+        write_ron(&RonField::from(field), dest)
+    }
+
+    fn write_node(
+        &self,
+        visitor: &Visitor,
+        node: &VisitorNode,
+        _hierarchy_level: usize,
+        dest: &mut dyn Write,
+    ) -> VisitResult {
+        write_ron(&RonNode::from_visitor_node(visitor, node), dest)
+    }
+
+    fn write(&self, visitor: &Visitor, dest: &mut dyn Write) -> VisitResult {
+        let document = RonDocument {
+            magic: Visitor::MAGIC_RON_CURRENT.to_string(),
+            version: visitor.version,
+            root: RonNode::from_visitor_node(visitor, &visitor.nodes[visitor.root]),
+        };
+        write_ron(&document, dest)?;
+        writeln!(dest)?;
+        Ok(())
+    }
+}
@@ -40,7 +40,7 @@ use crate::{
 };
 use clap::Parser;
 use fyrox_core::pool::Handle;
-use fyrox_resource::io::FsResourceIo;
+use fyrox_resource::io::{FsResourceIo, ResourceIo};
 use fyrox_ui::constructor::new_widget_constructor_container;
 use std::cell::Cell;
 use std::collections::VecDeque;
@@ -97,7 +97,7 @@ impl Executor {
     ) -> Self {
         let serialization_context = Arc::new(SerializationContext::new());
         let task_pool = Arc::new(TaskPool::new());
-        let io = Arc::new(FsResourceIo);
+        let io = Self::make_resource_io();
         let engine = Engine::new(EngineInitParams {
             graphics_context_params,
             resource_manager: ResourceManager::new(io, task_pool.clone()),
@@ -118,6 +118,31 @@ impl Executor {
         }
     }
 
+    // Exported games ship their assets in a single pack file, use it if it exists. Otherwise, read
+    // the assets directly from the file system.
+    fn make_resource_io() -> Arc<dyn ResourceIo> {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            use fyrox_resource::pack::PackedResourceIo;
+
+            let pack_path = std::path::Path::new(PackedResourceIo::DEFAULT_FILE_NAME);
+            if pack_path.exists() {
+                match PackedResourceIo::open(pack_path) {
+                    Ok(io) => {
+                        Log::info(format!("Using assets from {} pack.", pack_path.display()));
+                        return Arc::new(io);
+                    }
+                    Err(err) => Log::err(format!(
+                        "Unable to open {} pack, loose files will be used instead. Reason: {err:?}",
+                        pack_path.display()
+                    )),
+                }
+            }
+        }
+
+        Arc::new(FsResourceIo)
+    }
+
     /// Creates new game executor using default window and with vsync turned on. For more flexible
     /// way to create an executor see [`Executor::from_params`]. To run the engine in headless mode,
     /// pass [`None`] to the `event_loop` argument.
@@ -41,6 +41,7 @@ use crate::{
     },
     core::{
         algebra::Vector2,
+        arrayvec::ArrayVec,
         err,
         futures::{executor::block_on, future::join_all},
         instant,
@@ -85,12 +86,14 @@ use crate::{
     },
     scene::{
         base::NodeScriptMessage,
-        graph::GraphUpdateSwitches,
+        dim2,
+        graph::{Graph, GraphUpdateSwitches},
         mesh::surface::{self, SurfaceData, SurfaceDataLoader},
         node::{
             constructor::{new_node_constructor_container, NodeConstructorContainer},
             Node,
         },
+        rigidbody::RigidBody,
         skybox::SkyBoxKind,
         sound::SoundEngine,
         tilemap::{
@@ -818,6 +821,110 @@ impl ScriptProcessor {
                 }
             }
 
+            // Collision events of the last physics step are dispatched after the update, so the
+            // events will be received only by initialized and started scripts. Every event is sent
+            // twice - once for each of the colliders.
+            let collision_events = scene
+                .graph
+                .physics
+                .take_collision_events()
+                .into_iter()
+                .chain(scene.graph.physics2d.take_collision_events())
+                .flat_map(|event| [event, event.swapped()])
+                .collect::<Vec<_>>();
+            if !collision_events.is_empty() {
+                let mut context = ScriptContext {
+                    dt,
+                    elapsed_time,
+                    plugins: PluginsRefMut(plugins),
+                    handle: Default::default(),
+                    scene,
+                    scene_handle: scripted_scene.handle,
+                    resource_manager,
+                    message_sender: &scripted_scene.message_sender,
+                    message_dispatcher: &mut scripted_scene.message_dispatcher,
+                    task_pool,
+                    graphics_context,
+                    user_interfaces,
+                    script_index: 0,
+                    input_state,
+                };
+
+                for event in collision_events {
+                    for recipient in
+                        collision_event_recipients(&context.scene.graph, event.collider)
+                    {
+                        context.handle = recipient;
+
+                        process_node_scripts(
+                            "on_collision",
+                            &mut context,
+                            scripted_scene.handle,
+                            error_queue,
+                            &mut |script, context| {
+                                if script.initialized && script.started {
+                                    script.on_collision(&event, context)
+                                } else {
+                                    Ok(())
+                                }
+                            },
+                        );
+                    }
+                }
+            }
+
+            // Joint break events are dispatched the same way as collision events - to the joint
+            // and to both of its rigid bodies.
+            let joint_break_events = scene
+                .graph
+                .physics
+                .take_joint_break_events()
+                .into_iter()
+                .chain(scene.graph.physics2d.take_joint_break_events())
+                .collect::<Vec<_>>();
+            if !joint_break_events.is_empty() {
+                let mut context = ScriptContext {
+                    dt,
+                    elapsed_time,
+                    plugins: PluginsRefMut(plugins),
+                    handle: Default::default(),
+                    scene,
+                    scene_handle: scripted_scene.handle,
+                    resource_manager,
+                    message_sender: &scripted_scene.message_sender,
+                    message_dispatcher: &mut scripted_scene.message_dispatcher,
+                    task_pool,
+                    graphics_context,
+                    user_interfaces,
+                    script_index: 0,
+                    input_state,
+                };
+
+                for event in joint_break_events {
+                    for recipient in [event.joint, event.body1, event.body2] {
+                        if context.scene.graph.try_get_node(recipient).is_err() {
+                            continue;
+                        }
+
+                        context.handle = recipient;
+
+                        process_node_scripts(
+                            "on_joint_break",
+                            &mut context,
+                            scripted_scene.handle,
+                            error_queue,
+                            &mut |script, context| {
+                                if script.initialized && script.started {
+                                    script.on_joint_break(&event, context)
+                                } else {
+                                    Ok(())
+                                }
+                            },
+                        );
+                    }
+                }
+            }
+
             // Dispatch script messages only when everything is initialized and updated. This has to
             // be done this way, because all those methods could spawn new messages. However, if a new
             // message is spawned directly in `on_message` the dispatcher will correctly handle it
@@ -1144,6 +1251,22 @@ where
     true
 }
 
+/// Returns handles of the nodes, that should receive a collision event of the given collider: the
+/// collider itself and its parent rigid body (if any).
+fn collision_event_recipients(graph: &Graph, collider: Handle<Node>) -> ArrayVec<Handle<Node>, 2> {
+    let mut recipients = ArrayVec::new();
+    if let Ok(node) = graph.try_get_node(collider) {
+        recipients.push(collider);
+        let parent = node.parent();
+        if graph.has_component::<RigidBody>(parent)
+            || graph.has_component::<dim2::rigidbody::RigidBody>(parent)
+        {
+            recipients.push(parent);
+        }
+    }
+    recipients
+}
+
 fn process_node_scripts<T, C>(
     caller_name: &'static str,
     context: &mut C,
@@ -1222,11 +1345,14 @@ pub(crate) fn initialize_resource_manager_loaders(
     widget_constructors: Arc<WidgetConstructorContainer>,
     dyn_type_constructors: Arc<DynTypeConstructorContainer>,
 ) {
+    let derived_data_cache = resource_manager.derived_data_cache();
+
     let model_loader = ModelLoader {
         resource_manager: resource_manager.clone(),
-        serialization_context,
+        serialization_context: serialization_context.clone(),
         dyn_type_constructors: dyn_type_constructors.clone(),
         default_import_options: Default::default(),
+        derived_data_cache: Some(derived_data_cache.clone()),
     };
 
     let mut state = resource_manager.state();
@@ -1258,6 +1384,7 @@ pub(crate) fn initialize_resource_manager_loaders(
         &*material::STANDARD_TWOSIDES,
         &*material::STANDARD_PARTICLE_SYSTEM,
         &*material::STANDARD_WIDGET,
+        &*material::STANDARD_FOLIAGE,
     ] {
         state.built_in_resources.add(material.clone());
     }
@@ -1294,11 +1421,15 @@ pub(crate) fn initialize_resource_manager_loaders(
     let gltf_loader = super::resource::gltf::GltfLoader {
         resource_manager: resource_manager.clone(),
         default_import_options: Default::default(),
+        serialization_context: serialization_context.clone(),
+        dyn_type_constructors: dyn_type_constructors.clone(),
+        derived_data_cache: Some(derived_data_cache.clone()),
     };
     loaders.set(gltf_loader);
     loaders.set(model_loader);
     loaders.set(TextureLoader {
         default_import_options: Default::default(),
+        derived_data_cache: Some(derived_data_cache),
     });
     loaders.set(SoundBufferLoader {
         default_import_options: Default::default(),
@@ -1691,11 +1822,13 @@ impl Engine {
                         }
                     });
 
-            scene.update(
-                frame_size,
-                dt,
-                switches.get(&handle).cloned().unwrap_or_default(),
-            );
+            let switches = switches.get(&handle).cloned().unwrap_or_default();
+
+            if switches.streaming && !switches.paused {
+                scene.update_streaming(&self.resource_manager);
+            }
+
+            scene.update(frame_size, dt, switches);
         }
     }
 
@@ -2858,12 +2991,20 @@ mod test {
     use crate::{
         asset::manager::ResourceManager,
         core::{
-            pool::Handle, reflect::prelude::*, task::TaskPool, type_traits::prelude::*,
-            visitor::prelude::*,
+            algebra::Vector2, pool::Handle, reflect::prelude::*, task::TaskPool,
+            type_traits::prelude::*, visitor::prelude::*,
         },
         engine::{task::TaskPoolHandler, GraphicsContext, ScriptProcessor},
         graph::SceneGraph,
-        scene::{base::BaseBuilder, node::Node, pivot::PivotBuilder, Scene, SceneContainer},
+        scene::{
+            base::BaseBuilder,
+            collider::{ColliderBuilder, ColliderShape},
+            graph::physics::{CollisionEvent, CollisionEventKind},
+            node::Node,
+            pivot::PivotBuilder,
+            rigidbody::{RigidBodyBuilder, RigidBodyType},
+            Scene, SceneContainer,
+        },
         script::{
             ScriptContext, ScriptDeinitContext, ScriptMessageContext, ScriptMessagePayload,
             ScriptTrait,
@@ -3282,6 +3423,89 @@ mod test {
         }
     }
 
+    #[derive(Debug, Clone, Reflect, Visit, TypeUuidProvider, ComponentProvider)]
+    #[type_uuid(id = "4f2c8a11-6b7e-4d0a-9c55-2f8e1b3d7a90")]
+    struct CollisionListener {
+        #[reflect(hidden)]
+        #[visit(skip)]
+        sender: Sender<(Handle<Node>, CollisionEvent)>,
+    }
+
+    impl ScriptTrait for CollisionListener {
+        fn on_collision(&mut self, event: &CollisionEvent, ctx: &mut ScriptContext) -> GameResult {
+            self.sender.send((ctx.handle, *event)).unwrap();
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_collision_events() {
+        let resource_manager =
+            ResourceManager::new(Arc::new(FsResourceIo), Arc::new(Default::default()));
+        let mut scene = Scene::new();
+
+        let (tx, rx) = mpsc::channel();
+
+        let sensor = ColliderBuilder::new(BaseBuilder::new())
+            .with_shape(ColliderShape::cuboid(0.5, 0.5, 0.5))
+            .with_sensor(true)
+            .build(&mut scene.graph);
+        RigidBodyBuilder::new(BaseBuilder::new().with_child(sensor))
+            .with_body_type(RigidBodyType::Static)
+            .build(&mut scene.graph);
+
+        let ball = ColliderBuilder::new(BaseBuilder::new())
+            .with_shape(ColliderShape::ball(0.25))
+            .build(&mut scene.graph);
+        let body = RigidBodyBuilder::new(
+            BaseBuilder::new()
+                .with_child(ball)
+                .with_script(CollisionListener { sender: tx }),
+        )
+        .with_body_type(RigidBodyType::Dynamic)
+        .with_gravity_scale(0.0)
+        .build(&mut scene.graph);
+
+        let mut scene_container = SceneContainer::new(Default::default());
+
+        let scene_handle = scene_container.add(scene);
+
+        let mut script_processor = ScriptProcessor::default();
+        let mut task_pool = TaskPoolHandler::new(Arc::new(TaskPool::new()));
+        let mut gc = GraphicsContext::Uninitialized(Default::default());
+        let mut user_interfaces = UiContainer::default();
+
+        script_processor.register_scripted_scene(scene_handle, &resource_manager);
+
+        for _ in 0..4 {
+            script_processor.handle_scripts(
+                &mut scene_container,
+                &mut Vec::new(),
+                &resource_manager,
+                &mut task_pool,
+                &mut gc,
+                &mut user_interfaces,
+                1.0 / 60.0,
+                0.0,
+                &Default::default(),
+                &mut Default::default(),
+            );
+
+            scene_container[scene_handle].update(
+                Vector2::new(800.0, 600.0),
+                1.0 / 60.0,
+                Default::default(),
+            );
+        }
+
+        let (recipient, event) = rx.try_recv().unwrap();
+        assert_eq!(recipient, body.to_base());
+        assert_eq!(event.kind, CollisionEventKind::SensorEntered);
+        assert_eq!(event.collider, ball.to_base());
+        assert_eq!(event.other_collider, sensor.to_base());
+        assert_eq!(rx.try_recv(), Err(TryRecvError::Empty));
+    }
+
     #[derive(Clone, Debug, PartialEq, Reflect, Visit, TypeUuidProvider, ComponentProvider)]
     #[type_uuid(id = "7bcbf9b4-9546-42d3-965a-de055ab85475")]
     pub struct ScriptSpawningAsyncTasks {
@@ -39,6 +39,7 @@ pub mod material;
 pub mod plugin;
 pub mod renderer;
 pub mod resource;
+pub mod savegame;
 pub mod scene;
 pub mod script;
 pub mod utils;
@@ -61,6 +61,7 @@ use crate::{
     },
     resource::texture::TextureResource,
     scene::{
+        base::LodGroup,
         collider::BitMask,
         graph::Graph,
         light::{
@@ -854,6 +855,31 @@ impl Default for RenderDataBundleStorageOptions {
     }
 }
 
+/// Marks the objects of every level of the given LOD group as visible or hidden depending on the
+/// distance from the observer to each object. Hidden objects are skipped together with their
+/// descendants.
+pub(crate) fn apply_lod_group(
+    lod_filter: &mut [bool],
+    graph: &Graph,
+    lod_group: &LodGroup,
+    observer_position: &ObserverPosition,
+) {
+    for level in lod_group.levels.iter() {
+        for &object in level.objects.iter() {
+            if let Ok(object_ref) = graph.try_get_node(object) {
+                let distance = observer_position
+                    .translation
+                    .metric_distance(&object_ref.global_position());
+                let z_range = observer_position.z_far - observer_position.z_near;
+                let normalized_distance = (distance - observer_position.z_near) / z_range;
+                let visible =
+                    normalized_distance >= level.begin() && normalized_distance <= level.end();
+                lod_filter[object.index() as usize] = visible;
+            }
+        }
+    }
+}
+
 impl RenderDataBundleStorage {
     pub fn new_empty(observer_position: ObserverPosition) -> Self {
         Self {
@@ -895,21 +921,7 @@ impl RenderDataBundleStorage {
         let mut lod_filter = vec![true; graph.capacity() as usize];
         for (node_handle, node) in graph.pair_iter() {
             if let Some(lod_group) = node.lod_group() {
-                for level in lod_group.levels.iter() {
-                    for &object in level.objects.iter() {
-                        if let Ok(object_ref) = graph.try_get_node(object) {
-                            let distance = observer_position
-                                .translation
-                                .metric_distance(&object_ref.global_position());
-                            let z_range = observer_position.z_far - observer_position.z_near;
-                            let normalized_distance =
-                                (distance - observer_position.z_near) / z_range;
-                            let visible = normalized_distance >= level.begin()
-                                && normalized_distance <= level.end();
-                            lod_filter[object.index() as usize] = visible;
-                        }
-                    }
-                }
+                apply_lod_group(&mut lod_filter, graph, lod_group, observer_position);
             }
 
             if let Some(reflection_probe) = node.component_ref::<ReflectionProbe>() {
@@ -64,6 +64,7 @@ use crate::{
         graph::Graph,
         mesh::{
             buffer::{VertexAttributeUsage, VertexBuffer, VertexWriteTrait},
+            lod,
             surface::{
                 BlendShape, BlendShapesContainer, InputBlendShapeData, Surface, SurfaceData,
                 SurfaceResource, VertexWeightSet,
@@ -913,6 +914,12 @@ async fn convert(
         }
     }
 
+    // LODs must be generated after the skinning data is written to the vertex buffers, so the
+    // simplified surfaces will have it too.
+    if let Some(lod_generation) = model_import_options.lod_generation.as_ref() {
+        lod::generate_graph_lods(&mut scene.graph, lod_generation);
+    }
+
     Ok(())
 }
 
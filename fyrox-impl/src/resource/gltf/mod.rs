@@ -20,15 +20,18 @@
 
 //! [GltfLoader] enables the importing of *.gltf and *.glb files in the glTF format.
 //! This requires the "gltf" feature.
+use crate::asset::cache::DerivedDataCache;
 use crate::asset::io::ResourceIo;
 use crate::asset::loader;
 use crate::asset::manager::ResourceManager;
 use crate::asset::options;
 use crate::asset::state::LoadError;
 use crate::core::algebra::{Matrix4, Unit};
+use crate::core::dyntype::DynTypeConstructorContainer;
 use crate::core::log::Log;
 use crate::core::pool::Handle;
 use crate::core::TypeUuidProvider;
+use crate::engine::SerializationContext;
 use crate::graph::NodeMapping;
 use crate::graph::SceneGraph;
 use crate::gui::core::io::FileError;
@@ -38,6 +41,7 @@ use crate::resource::texture::{TextureError, TextureResource};
 use crate::scene::animation::{AnimationContainer, AnimationPlayerBuilder};
 use crate::scene::base::BaseBuilder;
 use crate::scene::graph::Graph;
+use crate::scene::mesh::lod;
 use crate::scene::mesh::surface::{BlendShape, Surface, SurfaceResource};
 use crate::scene::mesh::{Mesh, MeshBuilder};
 use crate::scene::node::Node;
@@ -288,6 +292,15 @@ pub struct GltfLoader {
     pub resource_manager: ResourceManager,
     /// Import options control where this loader should search for additional resources.
     pub default_import_options: ModelImportOptions,
+    /// Serialization context is needed to load imported models from the derived-data cache.
+    pub serialization_context: Arc<SerializationContext>,
+    /// A container for dynamic types, it is needed to load imported models from the derived-data
+    /// cache.
+    pub dyn_type_constructors: Arc<DynTypeConstructorContainer>,
+    /// Optional cache for imported models. If set, models imported from self-contained `glb` files
+    /// are stored in the cache and loaded from it while their source files and import options
+    /// remain the same. `gltf` files are not cached, because they could reference external buffers.
+    pub derived_data_cache: Option<DerivedDataCache>,
 }
 
 impl loader::ResourceLoader for GltfLoader {
@@ -302,15 +315,53 @@ impl loader::ResourceLoader for GltfLoader {
     fn load(&self, path: PathBuf, io: Arc<dyn ResourceIo>) -> loader::BoxedLoaderFuture {
         let resource_manager = self.resource_manager.clone();
         let default_import_options = self.default_import_options.clone();
+        let serialization_context = self.serialization_context.clone();
+        let dyn_type_constructors = self.dyn_type_constructors.clone();
+        let derived_data_cache = self.derived_data_cache.clone().filter(|_| {
+            path.extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("glb"))
+        });
 
         Box::pin(async move {
             let import_options = options::try_get_import_settings(&path, io.as_ref())
                 .await
                 .unwrap_or(default_import_options);
 
-            let model = load(path, io, resource_manager, import_options)
+            let Some(cache) =
+                derived_data_cache.filter(|_| Model::is_derived_data_cacheable(&import_options))
+            else {
+                let model = load(path, io, resource_manager, import_options)
+                    .await
+                    .map_err(LoadError::new)?;
+                return Ok(loader::LoaderPayload::new(model));
+            };
+
+            let source = io.load_file(&path).await.map_err(LoadError::new)?;
+            let Some(key) =
+                Model::derived_data_key(&source, "Gltf", &path, &import_options, io.as_ref()).await
+            else {
+                let model = load(path, io, resource_manager, import_options)
+                    .await
+                    .map_err(LoadError::new)?;
+                return Ok(loader::LoaderPayload::new(model));
+            };
+            if let Some(model) = Model::load_from_derived_data(
+                key,
+                &path,
+                &cache,
+                serialization_context,
+                dyn_type_constructors,
+                resource_manager.clone(),
+            )
+            .await
+            {
+                return Ok(loader::LoaderPayload::new(model));
+            }
+
+            let mut model = load(path.clone(), io, resource_manager, import_options)
                 .await
                 .map_err(LoadError::new)?;
+            model.save_to_derived_data(key, &path, &cache).await;
 
             Ok(loader::LoaderPayload::new(model))
         })
@@ -352,6 +403,9 @@ async fn load(
     let root = scene.graph.get_root();
     scene.graph[root].set_name(root_name.clone());
     import_from_path(&mut scene.graph, &context).await?;
+    if let Some(lod_generation) = options.lod_generation.as_ref() {
+        lod::generate_graph_lods(&mut scene.graph, lod_generation);
+    }
     node_names::resolve_name_conflicts(context.model_path.as_path(), &mut scene.graph);
     Ok(Model::new(NodeMapping::UseNames, scene))
 }
@@ -22,6 +22,7 @@
 
 use crate::{
     asset::{
+        cache::DerivedDataCache,
         io::ResourceIo,
         loader::{
             BoxedImportOptionsLoaderFuture, BoxedLoaderFuture, LoaderPayload, ResourceLoader,
@@ -48,6 +49,9 @@ pub struct ModelLoader {
     pub dyn_type_constructors: Arc<DynTypeConstructorContainer>,
     /// Default import options for model resources.
     pub default_import_options: ModelImportOptions,
+    /// Optional cache for imported models. If set, models imported from FBX files are stored in the
+    /// cache and loaded from it while their source files and import options remain the same.
+    pub derived_data_cache: Option<DerivedDataCache>,
 }
 
 impl ResourceLoader for ModelLoader {
@@ -68,6 +72,7 @@ impl ResourceLoader for ModelLoader {
         let node_constructors = self.serialization_context.clone();
         let dyn_type_constructors = self.dyn_type_constructors.clone();
         let default_import_options = self.default_import_options.clone();
+        let derived_data_cache = self.derived_data_cache.clone();
 
         Box::pin(async move {
             let io = io.as_ref();
@@ -83,6 +88,7 @@ impl ResourceLoader for ModelLoader {
                 dyn_type_constructors,
                 resource_manager,
                 import_options,
+                derived_data_cache.as_ref(),
             )
             .await
             .map_err(LoadError::new)?;
@@ -40,7 +40,11 @@
 
 use crate::{
     asset::{
-        io::ResourceIo, manager::ResourceManager, options::ImportOptions, untyped::ResourceKind,
+        cache::{DerivedDataCache, DerivedDataKey},
+        io::ResourceIo,
+        manager::ResourceManager,
+        options::ImportOptions,
+        untyped::ResourceKind,
         Resource, ResourceData,
     },
     core::{
@@ -62,8 +66,8 @@ use crate::{
     graph::{NodeHandleMap, NodeMapping, PrefabData, SceneGraph, SceneGraphNode},
     resource::fbx::{self, error::FbxError},
     scene::{
-        animation::Animation, base::SceneNodeId, graph::Graph, node::Node, transform::Transform,
-        Scene, SceneLoader,
+        animation::Animation, base::SceneNodeId, graph::Graph, mesh::lod::LodGenerationSettings,
+        node::Node, transform::Transform, Scene, SceneLoader,
     },
 };
 use fxhash::FxHashMap;
@@ -740,6 +744,10 @@ pub struct ModelImportOptions {
     /// See [`MaterialSearchOptions`] docs for more info.
     #[serde(default)]
     pub material_search_options: MaterialSearchOptions,
+    /// Optional settings of automatic LOD generation. If set, every mesh of the model will get a LOD
+    /// chain with simplified meshes. See [`LodGenerationSettings`] docs for more info.
+    #[serde(default)]
+    pub lod_generation: Option<LodGenerationSettings>,
 }
 
 impl ImportOptions for ModelImportOptions {}
@@ -789,6 +797,129 @@ impl Model {
         Self { mapping, scene }
     }
 
+    /// Version of the built-in model importers (FBX, glTF). It is a part of derived-data cache keys,
+    /// so it must be bumped every time when the output of the importers changes.
+    pub const IMPORTER_VERSION: u32 = 1;
+
+    /// Checks whether a model, that is imported from a foreign format with the given options, could
+    /// be stored in the derived-data cache. Models that search their textures in the entire working
+    /// directory are never cached, because their textures could be found anywhere in the project
+    /// and the cached data could silently become stale.
+    pub(crate) fn is_derived_data_cacheable(import_options: &ModelImportOptions) -> bool {
+        import_options.material_search_options != MaterialSearchOptions::WorkingDirectory
+    }
+
+    /// Lists the files that could be picked as textures of a model with the given import options.
+    /// Textures are searched by their file names, so adding, removing or renaming a file in one of
+    /// the searched directories could change the textures of the model. `None` means that the
+    /// searched directories cannot be listed.
+    async fn texture_search_space(
+        path: &Path,
+        import_options: &ModelImportOptions,
+        io: &dyn ResourceIo,
+    ) -> Option<Vec<PathBuf>> {
+        match import_options.material_search_options {
+            // Texture paths do not depend on the existing files in these cases.
+            MaterialSearchOptions::MaterialsDirectory(_)
+            | MaterialSearchOptions::UsePathDirectly => Some(Vec::new()),
+            MaterialSearchOptions::RecursiveUp => {
+                if !io.can_read_directories() {
+                    return None;
+                }
+                let mut files = Vec::new();
+                for directory in path.ancestors().skip(1) {
+                    let directory = if directory.as_os_str().is_empty() {
+                        Path::new(".")
+                    } else {
+                        directory
+                    };
+                    files.extend(io.read_directory(directory).await.ok()?);
+                }
+                files.sort();
+                Some(files)
+            }
+            MaterialSearchOptions::WorkingDirectory => None,
+        }
+    }
+
+    /// Creates a derived-data cache key for a model, that is imported from a foreign format. The
+    /// key includes the path of the model, the import options and the files that could be picked
+    /// as the textures of the model, because they define how the textures are resolved. `None`
+    /// means that the textures could not be tracked and the model must not be cached.
+    pub(crate) async fn derived_data_key(
+        source: &[u8],
+        importer: &str,
+        path: &Path,
+        import_options: &ModelImportOptions,
+        io: &dyn ResourceIo,
+    ) -> Option<DerivedDataKey> {
+        let search_space = Self::texture_search_space(path, import_options, io).await?;
+        Some(DerivedDataKey::with_options(
+            source,
+            importer,
+            Self::IMPORTER_VERSION,
+            &(path, import_options, search_space),
+        ))
+    }
+
+    /// Tries to fetch a model, that was imported from a foreign format, from the derived-data cache.
+    pub(crate) async fn load_from_derived_data(
+        key: DerivedDataKey,
+        path: &Path,
+        cache: &DerivedDataCache,
+        serialization_context: Arc<SerializationContext>,
+        dyn_type_constructors: Arc<DynTypeConstructorContainer>,
+        resource_manager: ResourceManager,
+    ) -> Option<Self> {
+        let data = cache.get(key).await?;
+        // Visitor must not be held across await points, because it is not `Send`.
+        let loader = {
+            let mut visitor = Visitor::load_binary_from_memory(&data).ok()?;
+            SceneLoader::load(
+                "Scene",
+                serialization_context,
+                dyn_type_constructors,
+                resource_manager,
+                &mut visitor,
+                Some(path.to_path_buf()),
+            )
+            .ok()?
+        };
+        Log::info(format!(
+            "Model {} was loaded from the derived data cache.",
+            path.display()
+        ));
+        Some(Self {
+            scene: loader.finish().await,
+            mapping: NodeMapping::UseNames,
+        })
+    }
+
+    /// Puts the model, that was imported from a foreign format, to the derived-data cache.
+    pub(crate) async fn save_to_derived_data(
+        &mut self,
+        key: DerivedDataKey,
+        path: &Path,
+        cache: &DerivedDataCache,
+    ) {
+        let data = {
+            let mut visitor = Visitor::new();
+            self.scene
+                .save("Scene", &mut visitor)
+                .and_then(|_| visitor.save_binary_to_vec())
+        };
+        let result = match data {
+            Ok(data) => cache.put(key, &data).await.map_err(|err| err.to_string()),
+            Err(err) => Err(err.to_string()),
+        };
+        if let Err(err) = result {
+            Log::warn(format!(
+                "Unable to put {} model to the derived data cache. Reason: {err}",
+                path.display()
+            ));
+        }
+    }
+
     pub(crate) async fn load<P: AsRef<Path>>(
         path: P,
         io: &dyn ResourceIo,
@@ -796,6 +927,7 @@ impl Model {
         dyn_type_constructors: Arc<DynTypeConstructorContainer>,
         resource_manager: ResourceManager,
         model_import_options: ModelImportOptions,
+        derived_data_cache: Option<&DerivedDataCache>,
     ) -> Result<Self, ModelLoadError> {
         let extension = path
             .as_ref()
@@ -806,6 +938,41 @@ impl Model {
             .to_lowercase();
         let (scene, mapping) = match extension.as_ref() {
             "fbx" => {
+                let derived_data_cache = derived_data_cache
+                    .filter(|_| Self::is_derived_data_cacheable(&model_import_options));
+                let cache_key = match derived_data_cache {
+                    Some(cache) => {
+                        let source = io
+                            .load_file(path.as_ref())
+                            .await
+                            .map_err(VisitError::from)?;
+                        Self::derived_data_key(
+                            &source,
+                            "Fbx",
+                            path.as_ref(),
+                            &model_import_options,
+                            io,
+                        )
+                        .await
+                        .map(|key| (key, cache))
+                    }
+                    None => None,
+                };
+                if let Some((key, cache)) = cache_key {
+                    if let Some(model) = Self::load_from_derived_data(
+                        key,
+                        path.as_ref(),
+                        cache,
+                        serialization_context,
+                        dyn_type_constructors,
+                        resource_manager.clone(),
+                    )
+                    .await
+                    {
+                        return Ok(model);
+                    }
+                }
+
                 let mut scene = Scene::new();
                 if let Some(filename) = path.as_ref().file_name() {
                     let root = scene.graph.get_root();
@@ -821,7 +988,11 @@ impl Model {
                 .await?;
                 // Set NodeMapping::UseNames as mapping here because FBX does not have
                 // any persistent unique ids, and we have to use names.
-                (scene, NodeMapping::UseNames)
+                let mut model = Self::new(NodeMapping::UseNames, scene);
+                if let Some((key, cache)) = cache_key {
+                    model.save_to_derived_data(key, path.as_ref(), cache).await;
+                }
+                return Ok(model);
             }
             // Scene can be used directly as model resource. Such scenes can be created in
             // Fyroxed.
@@ -0,0 +1,931 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Save game subsystem built on top of [`Visitor`].
+//!
+//! A [`SaveGame`] contains a snapshot of a selected set of scene nodes and arbitrary named states
+//! (usually plugin state). A typical save game stores only the things that can change during a
+//! play session - transforms of dynamic objects, script state, runtime-spawned prefab instances,
+//! etc. Everything else is taken from the scene file itself: on load, the scene is loaded as usual
+//! and then the snapshot is restored into it using [`SceneSnapshot::restore`].
+//!
+//! ```rust,no_run
+//! # use fyrox_impl::{
+//! #     plugin::PluginContext,
+//! #     savegame::{tagged, SaveGame, SaveGameContext, SaveGameMetadata, SceneSnapshot},
+//! #     scene::Scene,
+//! # };
+//! fn save(scene: &Scene) {
+//!     let mut save_game = SaveGame::new(SaveGameMetadata::new("Quick Save"));
+//!     save_game.scene = SceneSnapshot::capture(scene, tagged("Persistent"));
+//!     save_game.save_to_file("quick.save").unwrap();
+//! }
+//!
+//! fn load(fresh_scene: &mut Scene, ctx: &PluginContext) {
+//!     let context = SaveGameContext::from_plugin_context(ctx);
+//!     let save_game = SaveGame::load_from_file("quick.save", &context).unwrap();
+//!     save_game.scene.restore(fresh_scene, tagged("Persistent"));
+//! }
+//! ```
+//!
+//! Save files are stored in the indexed binary format, so [`SaveGameSlots::list`] is able to read
+//! metadata of every save without loading the entire files.
+
+use crate::{
+    asset::{manager::ResourceManager, untyped::UntypedResource},
+    core::{
+        dyntype::DynTypeConstructorContainer,
+        log::Log,
+        pool::Handle,
+        reflect::prelude::*,
+        visitor::{lazy::LazyVisitor, pod::PodVecView, prelude::*},
+    },
+    engine::SerializationContext,
+    fxhash::{FxHashMap, FxHashSet},
+    graph::{NodeHandleMap, SceneGraph},
+    plugin::{Plugin, PluginContainer, PluginContext},
+    resource::model::{ModelResource, ModelResourceExtension},
+    scene::{
+        base::{SceneNodeId, ScriptRecord},
+        node::Node,
+        transform::Transform,
+        Scene,
+    },
+};
+use std::{
+    any::TypeId,
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Name of the region that contains the save game data.
+const SAVE_GAME_REGION: &str = "SaveGame";
+/// Name of the region that contains the metadata of a save game.
+const METADATA_REGION: &str = "Metadata";
+
+/// Everything that is needed to deserialize a save game. Scripts require [`SerializationContext`]
+/// to be created, resources (for example prefabs of spawned objects) require [`ResourceManager`].
+#[derive(Clone)]
+pub struct SaveGameContext {
+    /// A context with constructors of scripts and scene nodes.
+    pub serialization_context: Arc<SerializationContext>,
+    /// A container with constructors of dynamic types.
+    pub dyn_type_constructors: Arc<DynTypeConstructorContainer>,
+    /// A resource manager, that will be used to request resources.
+    pub resource_manager: ResourceManager,
+}
+
+impl SaveGameContext {
+    /// Creates a new save game context.
+    pub fn new(
+        serialization_context: Arc<SerializationContext>,
+        dyn_type_constructors: Arc<DynTypeConstructorContainer>,
+        resource_manager: ResourceManager,
+    ) -> Self {
+        Self {
+            serialization_context,
+            dyn_type_constructors,
+            resource_manager,
+        }
+    }
+
+    /// Creates a new save game context from the respective parts of the plugin context.
+    pub fn from_plugin_context(ctx: &PluginContext) -> Self {
+        Self::new(
+            ctx.serialization_context.clone(),
+            ctx.dyn_type_constructors.clone(),
+            ctx.resource_manager.clone(),
+        )
+    }
+
+    fn register(&self, visitor: &mut Visitor) {
+        visitor
+            .blackboard
+            .register(self.serialization_context.clone());
+        visitor
+            .blackboard
+            .register(self.dyn_type_constructors.clone());
+        visitor
+            .blackboard
+            .register(Arc::new(self.resource_manager.clone()));
+    }
+}
+
+/// Descriptive information about a save game. It is stored separately from the rest of the data
+/// and can be read without loading the entire save game (see [`SaveGameSlots::list`]).
+#[derive(Visit, Clone, Debug, Default, PartialEq)]
+pub struct SaveGameMetadata {
+    /// A name of the save game, that could be shown to a player.
+    pub name: String,
+    /// Time of creation of the save game in seconds since Unix epoch.
+    pub timestamp: u64,
+    /// Total play time in seconds.
+    pub play_time: f64,
+    /// A path to the scene the snapshot was taken from.
+    pub scene_path: PathBuf,
+    /// Version of the game, that created the save game. Could be used to reject incompatible
+    /// saves.
+    pub game_version: String,
+}
+
+impl SaveGameMetadata {
+    /// Creates new metadata with the given name and the current time as the timestamp.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default(),
+            ..Default::default()
+        }
+    }
+
+    /// Sets the total play time in seconds.
+    pub fn with_play_time(mut self, play_time: f64) -> Self {
+        self.play_time = play_time;
+        self
+    }
+
+    /// Sets the path to the scene of the save game.
+    pub fn with_scene_path(mut self, scene_path: impl Into<PathBuf>) -> Self {
+        self.scene_path = scene_path.into();
+        self
+    }
+
+    /// Sets the version of the game.
+    pub fn with_game_version(mut self, game_version: impl Into<String>) -> Self {
+        self.game_version = game_version.into();
+        self
+    }
+}
+
+/// A small image, that could be shown in a list of save games.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SaveGameThumbnail {
+    /// Width of the image in pixels.
+    pub width: u32,
+    /// Height of the image in pixels.
+    pub height: u32,
+    /// Pixels of the image in RGBA8 format.
+    pub pixels: Vec<u8>,
+}
+
+impl Visit for SaveGameThumbnail {
+    fn visit(&mut self, name: &str, visitor: &mut Visitor) -> VisitResult {
+        let mut region = visitor.enter_region(name)?;
+
+        self.width.visit("Width", &mut region)?;
+        self.height.visit("Height", &mut region)?;
+        PodVecView::from_pod_vec(&mut self.pixels).visit("Pixels", &mut region)?;
+
+        Ok(())
+    }
+}
+
+/// Information required to re-create a prefab instance, that was spawned at runtime and thus does
+/// not exist in the scene file.
+#[derive(Visit, Clone, Debug, Default)]
+pub struct SpawnRecord {
+    /// A prefab the instance was created from.
+    pub resource: ModelResource,
+    /// Ids of the instance nodes, mapped by the handles of their originals in the prefab.
+    pub ids: FxHashMap<Handle<Node>, SceneNodeId>,
+}
+
+/// Saved state of a single scene node.
+#[derive(Visit, Clone, Debug, Default)]
+pub struct NodeSnapshot {
+    /// Id of the node, it is used to find the node on restoration.
+    pub id: SceneNodeId,
+    /// Id of the parent node. Nil id means that the node is attached to the root of the graph.
+    pub parent: SceneNodeId,
+    /// Name of the node.
+    pub name: String,
+    /// Local transform of the node.
+    pub local_transform: Transform,
+    /// Visibility flag of the node.
+    pub visibility: bool,
+    /// Enabled flag of the node.
+    pub enabled: bool,
+    /// Scripts of the node.
+    pub scripts: Vec<ScriptRecord>,
+    /// Prefab instantiation info, present only for prefab instance roots.
+    pub spawn: Option<SpawnRecord>,
+}
+
+/// Creates a node filter, that selects the nodes with the given tag.
+pub fn tagged(tag: &str) -> impl Fn(&Node) -> bool + '_ {
+    move |node| node.tag() == tag
+}
+
+/// Result of [`SceneSnapshot::restore`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RestoreReport {
+    /// Amount of nodes, whose state was restored.
+    pub restored: usize,
+    /// Amount of prefab instances, that were re-created.
+    pub spawned: usize,
+    /// Amount of nodes, that were removed from the scene because they did not exist at the moment
+    /// when the snapshot was taken.
+    pub removed: usize,
+    /// Ids of the nodes, that could not be found in the scene nor re-created.
+    pub missing: Vec<SceneNodeId>,
+}
+
+/// A snapshot of a selected set of scene nodes.
+#[derive(Visit, Clone, Debug, Default)]
+pub struct SceneSnapshot {
+    /// States of the selected nodes.
+    pub nodes: Vec<NodeSnapshot>,
+    /// Ids of every node of the graph at the moment of capture, mapped by their handles. It is used
+    /// to remap handles stored in scripts, since handles of the nodes most likely will be different
+    /// after the restoration.
+    pub handles: FxHashMap<Handle<Node>, SceneNodeId>,
+}
+
+impl SceneSnapshot {
+    /// Takes a snapshot of every node of the scene, that passes the given filter. The root node of
+    /// the graph is never included in the snapshot.
+    pub fn capture(scene: &Scene, filter: impl Fn(&Node) -> bool) -> Self {
+        let graph = &scene.graph;
+        let root = graph.get_root();
+
+        let mut snapshot = Self::default();
+
+        for (handle, node) in graph.pair_iter() {
+            snapshot.handles.insert(handle, node.instance_id());
+
+            if handle == root || !filter(node) {
+                continue;
+            }
+
+            let parent = graph
+                .try_get_node(node.parent())
+                .ok()
+                .filter(|_| node.parent() != root)
+                .map(|parent| parent.instance_id())
+                .unwrap_or_default();
+
+            let spawn = node
+                .resource()
+                .filter(|_| node.is_resource_instance_root())
+                .map(|resource| {
+                    let mut ids = FxHashMap::default();
+                    for (_, descendant) in graph.traverse_iter(handle) {
+                        if descendant.resource().as_ref() == Some(&resource) {
+                            ids.insert(
+                                descendant.original_handle_in_resource(),
+                                descendant.instance_id(),
+                            );
+                        }
+                    }
+                    SpawnRecord { resource, ids }
+                });
+
+            snapshot.nodes.push(NodeSnapshot {
+                id: node.instance_id(),
+                parent,
+                name: node.name().to_owned(),
+                local_transform: node.local_transform().clone(),
+                visibility: node.visibility(),
+                enabled: node.is_enabled(),
+                scripts: node
+                    .scripts
+                    .iter()
+                    .filter(|record| record.script.is_some() && !record.should_be_deleted)
+                    .cloned()
+                    .collect(),
+                spawn,
+            });
+        }
+
+        snapshot
+    }
+
+    /// Waits until every prefab required for re-creation of spawned instances is loaded. Prefabs
+    /// must be loaded before [`Self::restore`] is called, otherwise the instances will not be
+    /// re-created.
+    pub async fn wait_for_resources(&self) {
+        for spawn in self.nodes.iter().filter_map(|node| node.spawn.as_ref()) {
+            if let Err(err) = spawn.resource.clone().await {
+                Log::err(format!(
+                    "Unable to load a prefab of a saved instance: {err:?}"
+                ));
+            }
+        }
+    }
+
+    /// Restores the snapshot into the given scene, which should be a freshly loaded copy of the
+    /// scene the snapshot was taken from. The filter must be the same as the one used in
+    /// [`Self::capture`]. The method does the following:
+    ///
+    /// 1. Removes every node, that passes the filter, but does not exist in the snapshot (for
+    /// example a destroyed enemy or a picked up item).
+    /// 2. Re-creates missing prefab instances (for example spawned at runtime) with the same ids.
+    /// 3. Restores saved state of the nodes and re-links them to their saved parents.
+    /// 4. Remaps handles in the restored scripts, so they point to the correct nodes.
+    ///
+    /// The restoration should be done before the scene is added to the engine, so the restored
+    /// scripts will be initialized as usual.
+    pub fn restore(&self, scene: &mut Scene, filter: impl Fn(&Node) -> bool) -> RestoreReport {
+        let mut report = RestoreReport::default();
+
+        let saved = self
+            .nodes
+            .iter()
+            .map(|node| node.id)
+            .collect::<FxHashSet<_>>();
+
+        let root = scene.graph.get_root();
+        let to_remove = scene
+            .graph
+            .pair_iter()
+            .filter(|(handle, node)| {
+                *handle != root && filter(node) && !saved.contains(&node.instance_id())
+            })
+            .map(|(handle, _)| handle)
+            .collect::<Vec<_>>();
+        for handle in to_remove {
+            // The node could be already removed together with its parent.
+            if scene.graph.is_valid_handle(handle) {
+                scene.graph.remove_node(handle);
+                report.removed += 1;
+            }
+        }
+
+        for snapshot in self.nodes.iter() {
+            if scene.graph.id_to_node_handle(snapshot.id).is_some() {
+                continue;
+            }
+
+            if let Some(spawn) = snapshot.spawn.as_ref() {
+                if spawn.resource.is_ok() {
+                    spawn
+                        .resource
+                        .begin_instantiation(scene)
+                        .with_ids(&spawn.ids)
+                        .finish();
+                    report.spawned += 1;
+                } else {
+                    Log::err(format!(
+                        "Unable to re-create {} node, because its prefab is not loaded!",
+                        snapshot.name
+                    ));
+                }
+            }
+        }
+
+        let mut restored = Vec::new();
+        for snapshot in self.nodes.iter() {
+            let Some(handle) = scene.graph.id_to_node_handle(snapshot.id).cloned() else {
+                report.missing.push(snapshot.id);
+                continue;
+            };
+
+            let parent = scene
+                .graph
+                .id_to_node_handle(snapshot.parent)
+                .cloned()
+                .unwrap_or(root);
+            if scene.graph[handle].parent() != parent {
+                scene.graph.link_nodes(handle, parent);
+            }
+
+            let node = &mut scene.graph[handle];
+            node.set_name(&snapshot.name);
+            *node.local_transform_mut() = snapshot.local_transform.clone();
+            node.set_visibility(snapshot.visibility);
+            node.set_enabled(snapshot.enabled);
+            node.scripts = snapshot.scripts.clone();
+
+            restored.push(handle);
+            report.restored += 1;
+        }
+
+        let mut handle_map = NodeHandleMap::default();
+        for (old, id) in self.handles.iter() {
+            let new = scene
+                .graph
+                .id_to_node_handle(*id)
+                .cloned()
+                .unwrap_or_default();
+            handle_map.insert(*old, new);
+        }
+
+        for handle in restored {
+            let node = &mut scene.graph[handle];
+            let name = node.name().to_owned();
+            for record in node.scripts.iter_mut() {
+                if let Some(script) = record.script.as_mut() {
+                    handle_map.remap_handles_any(script, &name, &[TypeId::of::<UntypedResource>()]);
+                }
+            }
+        }
+
+        report
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+struct StateBlob(Vec<u8>);
+
+impl Visit for StateBlob {
+    fn visit(&mut self, name: &str, visitor: &mut Visitor) -> VisitResult {
+        PodVecView::from_pod_vec(&mut self.0).visit(name, visitor)
+    }
+}
+
+/// A save game, that contains metadata, an optional thumbnail, a snapshot of a scene and a set of
+/// named states.
+#[derive(Visit, Clone, Debug, Default)]
+pub struct SaveGame {
+    /// Metadata of the save game.
+    pub metadata: SaveGameMetadata,
+    /// An optional thumbnail of the save game.
+    pub thumbnail: Option<SaveGameThumbnail>,
+    /// A snapshot of a scene.
+    pub scene: SceneSnapshot,
+    states: FxHashMap<String, StateBlob>,
+}
+
+impl SaveGame {
+    /// Creates a new empty save game with the given metadata.
+    pub fn new(metadata: SaveGameMetadata) -> Self {
+        Self {
+            metadata,
+            ..Default::default()
+        }
+    }
+
+    /// Sets the thumbnail of the save game.
+    pub fn with_thumbnail(mut self, thumbnail: SaveGameThumbnail) -> Self {
+        self.thumbnail = Some(thumbnail);
+        self
+    }
+
+    /// Stores a state of an arbitrary object under the given name. Any previous state with the
+    /// same name will be replaced.
+    pub fn write_state<T>(&mut self, name: &str, state: &mut T) -> VisitResult
+    where
+        T: Visit + ?Sized,
+    {
+        let mut visitor = Visitor::new();
+        state.visit(name, &mut visitor)?;
+        self.states
+            .insert(name.to_owned(), StateBlob(visitor.save_binary_to_vec()?));
+        Ok(())
+    }
+
+    /// Reads a state with the given name into the given object. Returns an error if there is no
+    /// such state.
+    pub fn read_state<T>(&self, name: &str, state: &mut T, context: &SaveGameContext) -> VisitResult
+    where
+        T: Visit + ?Sized,
+    {
+        let blob = self
+            .states
+            .get(name)
+            .ok_or_else(|| VisitError::User(format!("There is no {name} state!")))?;
+        let mut visitor = Visitor::load_binary_from_memory(&blob.0)?;
+        context.register(&mut visitor);
+        state.visit(name, &mut visitor)
+    }
+
+    /// Checks whether the save game has a state with the given name.
+    pub fn has_state(&self, name: &str) -> bool {
+        self.states.contains_key(name)
+    }
+
+    /// Returns an iterator over the names of every stored state.
+    pub fn state_names(&self) -> impl Iterator<Item = &str> {
+        self.states.keys().map(|name| name.as_str())
+    }
+
+    /// Removes a state with the given name. Returns `true` if the state existed.
+    pub fn remove_state(&mut self, name: &str) -> bool {
+        self.states.remove(name).is_some()
+    }
+
+    fn plugin_state_name(plugin: &dyn Plugin) -> String {
+        format!("Plugin:{}", plugin.type_name())
+    }
+
+    /// Stores the state of every given plugin using its [`Visit`] implementation. The states are
+    /// keyed by type names of the plugins.
+    pub fn write_plugins(&mut self, plugins: &mut [PluginContainer]) -> VisitResult {
+        for plugin in plugins {
+            let name = Self::plugin_state_name(&**plugin);
+            self.write_state(&name, &mut **plugin)?;
+        }
+        Ok(())
+    }
+
+    /// Restores the state of every given plugin, that has a saved state. Plugins without a saved
+    /// state are left untouched.
+    pub fn read_plugins(
+        &self,
+        plugins: &mut [PluginContainer],
+        context: &SaveGameContext,
+    ) -> VisitResult {
+        for plugin in plugins {
+            let name = Self::plugin_state_name(&**plugin);
+            if self.has_state(&name) {
+                self.read_state(&name, &mut **plugin, context)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Serializes the save game into memory.
+    pub fn save_to_memory(&mut self) -> Result<Vec<u8>, VisitError> {
+        let mut visitor = Visitor::new();
+        self.visit(SAVE_GAME_REGION, &mut visitor)?;
+        visitor.save_indexed_binary_to_vec()
+    }
+
+    /// Serializes the save game into a file.
+    pub fn save_to_file(&mut self, path: impl AsRef<Path>) -> VisitResult {
+        let mut visitor = Visitor::new();
+        self.visit(SAVE_GAME_REGION, &mut visitor)?;
+        visitor.save_indexed_binary_to_file(path)
+    }
+
+    /// Deserializes a save game from memory.
+    pub fn load_from_memory(data: &[u8], context: &SaveGameContext) -> Result<Self, VisitError> {
+        let mut visitor = Visitor::load_from_memory(data)?;
+        context.register(&mut visitor);
+        let mut save_game = Self::default();
+        save_game.visit(SAVE_GAME_REGION, &mut visitor)?;
+        Ok(save_game)
+    }
+
+    /// Deserializes a save game from a file.
+    pub fn load_from_file(
+        path: impl AsRef<Path>,
+        context: &SaveGameContext,
+    ) -> Result<Self, VisitError> {
+        Self::load_from_memory(&fs::read(path)?, context)
+    }
+
+    /// Reads only the metadata of a save game file.
+    pub fn read_metadata(path: impl AsRef<Path>) -> Result<SaveGameMetadata, VisitError> {
+        let mut lazy = LazyVisitor::open_file(path)?;
+        let index = lazy
+            .find_path([SAVE_GAME_REGION, METADATA_REGION])?
+            .ok_or_else(|| VisitError::RegionDoesNotExist(METADATA_REGION.to_owned()))?;
+        let mut visitor = lazy.load_subtree(index)?;
+        let mut metadata = SaveGameMetadata::default();
+        metadata.visit(METADATA_REGION, &mut visitor)?;
+        Ok(metadata)
+    }
+}
+
+/// Information about a save game slot.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SaveGameSlotInfo {
+    /// Name of the slot.
+    pub name: String,
+    /// A path to the save file of the slot.
+    pub path: PathBuf,
+    /// Metadata of the save game in the slot.
+    pub metadata: SaveGameMetadata,
+}
+
+/// A set of named save game slots stored as files in a directory.
+#[derive(Clone, Debug)]
+pub struct SaveGameSlots {
+    directory: PathBuf,
+}
+
+impl SaveGameSlots {
+    /// Extension of save game files.
+    pub const EXTENSION: &'static str = "save";
+
+    /// Creates a new set of slots in the given directory. The directory will be created on first
+    /// save, if it does not exist.
+    pub fn new(directory: impl Into<PathBuf>) -> Self {
+        Self {
+            directory: directory.into(),
+        }
+    }
+
+    /// Returns the directory of the slots.
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    /// Returns a path to the file of the slot with the given name. The name must be a plain file
+    /// name: empty names and names with path separators (`/`, `\`, `:`) or `..` are rejected, so
+    /// a slot cannot point outside of the directory of the slots.
+    pub fn slot_path(&self, name: &str) -> Result<PathBuf, VisitError> {
+        if name.is_empty() || name.contains(['/', '\\', ':', '\0']) || name.contains("..") {
+            return Err(VisitError::User(format!(
+                "{name:?} is not a valid save game slot name"
+            )));
+        }
+        // The extension is appended instead of being replaced, so names with dots (`v1.2`) do not
+        // lose their last part.
+        Ok(self.directory.join(format!("{name}.{}", Self::EXTENSION)))
+    }
+
+    /// Saves the given save game into the slot with the given name, overwriting the previous save
+    /// in the slot.
+    pub fn save(&self, name: &str, save_game: &mut SaveGame) -> VisitResult {
+        let path = self.slot_path(name)?;
+        fs::create_dir_all(&self.directory)?;
+        save_game.save_to_file(path)
+    }
+
+    /// Loads a save game from the slot with the given name.
+    pub fn load(&self, name: &str, context: &SaveGameContext) -> Result<SaveGame, VisitError> {
+        SaveGame::load_from_file(self.slot_path(name)?, context)
+    }
+
+    /// Checks whether the slot with the given name contains a save. Always `false` for invalid
+    /// slot names.
+    pub fn exists(&self, name: &str) -> bool {
+        self.slot_path(name).is_ok_and(|path| path.exists())
+    }
+
+    /// Deletes the save in the slot with the given name.
+    pub fn delete(&self, name: &str) -> VisitResult {
+        fs::remove_file(self.slot_path(name)?)?;
+        Ok(())
+    }
+
+    /// Returns a list of every slot in the directory, sorted by the time of creation of the saves
+    /// (newest first). Files, that could not be read, are skipped.
+    pub fn list(&self) -> Vec<SaveGameSlotInfo> {
+        let Ok(entries) = fs::read_dir(&self.directory) else {
+            return Vec::new();
+        };
+
+        let mut slots = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == Self::EXTENSION))
+            .filter_map(|path| {
+                let name = path.file_stem()?.to_string_lossy().to_string();
+                match SaveGame::read_metadata(&path) {
+                    Ok(metadata) => Some(SaveGameSlotInfo {
+                        name,
+                        path,
+                        metadata,
+                    }),
+                    Err(err) => {
+                        Log::warn(format!(
+                            "Unable to read save game {}. Reason: {err}",
+                            path.display()
+                        ));
+                        None
+                    }
+                }
+            })
+            .collect::<Vec<_>>();
+
+        slots.sort_by(|a, b| b.metadata.timestamp.cmp(&a.metadata.timestamp));
+        slots
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        asset::{io::FsResourceIo, manager::ResourceManager},
+        core::{
+            algebra::Vector3, impl_component_provider, pool::Handle, reflect::prelude::*,
+            uuid::Uuid, uuid_provider, visitor::prelude::*,
+        },
+        engine::SerializationContext,
+        graph::SceneGraph,
+        savegame::{
+            tagged, SaveGame, SaveGameContext, SaveGameMetadata, SaveGameSlots, SaveGameThumbnail,
+            SceneSnapshot,
+        },
+        scene::{
+            base::{BaseBuilder, SceneNodeId},
+            node::Node,
+            pivot::PivotBuilder,
+            Scene,
+        },
+        script::ScriptTrait,
+    };
+    use std::sync::Arc;
+
+    #[derive(Reflect, Visit, Debug, Clone, Default)]
+    struct Follower {
+        target: Handle<Node>,
+        counter: u32,
+    }
+
+    impl_component_provider!(Follower);
+    uuid_provider!(Follower = "7a4f1f3c-2d4a-4bd4-9b0e-8d7f5bb2a6e1");
+
+    impl ScriptTrait for Follower {}
+
+    #[derive(Visit, Debug, Default, PartialEq)]
+    struct GameState {
+        score: u32,
+        level: String,
+    }
+
+    const PERSISTENT: &str = "Persistent";
+
+    fn id(n: u128) -> SceneNodeId {
+        SceneNodeId(Uuid::from_u128(n))
+    }
+
+    fn persistent(name: &str, n: u128) -> BaseBuilder {
+        BaseBuilder::new()
+            .with_name(name)
+            .with_tag(PERSISTENT.to_string())
+            .with_instance_id(id(n))
+    }
+
+    // Builds the same level every time, `padding` shifts the handles of the nodes to make sure
+    // that the handles are remapped on restoration.
+    fn make_level(padding: usize) -> Scene {
+        let mut scene = Scene::new();
+        for _ in 0..padding {
+            PivotBuilder::new(BaseBuilder::new()).build(&mut scene.graph);
+        }
+        PivotBuilder::new(persistent("Target", 1)).build(&mut scene.graph);
+        PivotBuilder::new(persistent("Player", 2).with_script(Follower::default()))
+            .build(&mut scene.graph);
+        PivotBuilder::new(persistent("Crate", 3)).build(&mut scene.graph);
+        PivotBuilder::new(
+            BaseBuilder::new()
+                .with_name("Static")
+                .with_instance_id(id(4)),
+        )
+        .build(&mut scene.graph);
+        scene
+    }
+
+    fn handle_of(scene: &Scene, n: u128) -> Handle<Node> {
+        *scene.graph.id_to_node_handle(id(n)).unwrap()
+    }
+
+    fn make_context() -> SaveGameContext {
+        let serialization_context = SerializationContext::new();
+        serialization_context
+            .script_constructors
+            .add::<Follower>("Follower");
+        SaveGameContext::new(
+            Arc::new(serialization_context),
+            Default::default(),
+            ResourceManager::new(Arc::new(FsResourceIo), Arc::new(Default::default())),
+        )
+    }
+
+    #[test]
+    fn test_save_game_round_trip() {
+        let mut played = make_level(0);
+
+        let target = handle_of(&played, 1);
+        let player = handle_of(&played, 2);
+        played.graph[player]
+            .local_transform_mut()
+            .set_position(Vector3::new(1.0, 2.0, 3.0));
+        played.graph[player].set_visibility(false);
+        let follower = played.graph[player]
+            .try_get_script_mut::<Follower>()
+            .unwrap();
+        follower.target = target;
+        follower.counter = 42;
+        let crate_node = handle_of(&played, 3);
+        played.graph.remove_node(crate_node);
+        let static_node = handle_of(&played, 4);
+        played.graph[static_node]
+            .local_transform_mut()
+            .set_position(Vector3::new(5.0, 5.0, 5.0));
+
+        let mut save_game = SaveGame::new(
+            SaveGameMetadata::new("Test")
+                .with_play_time(12.5)
+                .with_game_version("1.0"),
+        )
+        .with_thumbnail(SaveGameThumbnail {
+            width: 1,
+            height: 1,
+            pixels: vec![1, 2, 3, 4],
+        });
+        save_game.scene = SceneSnapshot::capture(&played, tagged(PERSISTENT));
+        assert_eq!(save_game.scene.nodes.len(), 2);
+        let mut state = GameState {
+            score: 123,
+            level: "Level1".to_string(),
+        };
+        save_game.write_state("GameState", &mut state).unwrap();
+
+        let data = save_game.save_to_memory().unwrap();
+
+        let context = make_context();
+        let loaded = SaveGame::load_from_memory(&data, &context).unwrap();
+        assert_eq!(loaded.metadata, save_game.metadata);
+        assert_eq!(loaded.thumbnail, save_game.thumbnail);
+
+        let mut loaded_state = GameState::default();
+        loaded
+            .read_state("GameState", &mut loaded_state, &context)
+            .unwrap();
+        assert_eq!(loaded_state, state);
+        assert!(loaded
+            .read_state("Missing", &mut loaded_state, &context)
+            .is_err());
+
+        let mut fresh = make_level(3);
+        let report = loaded.scene.restore(&mut fresh, tagged(PERSISTENT));
+        assert_eq!(report.restored, 2);
+        assert_eq!(report.removed, 1);
+        assert!(report.missing.is_empty());
+
+        assert!(fresh.graph.id_to_node_handle(id(3)).is_none());
+
+        let player = handle_of(&fresh, 2);
+        assert_eq!(
+            **fresh.graph[player].local_transform().position(),
+            Vector3::new(1.0, 2.0, 3.0)
+        );
+        assert!(!fresh.graph[player].visibility());
+        let follower = fresh.graph[player].try_get_script::<Follower>().unwrap();
+        assert_eq!(follower.counter, 42);
+        assert_ne!(follower.target, target);
+        assert_eq!(follower.target, handle_of(&fresh, 1));
+
+        // Non-persistent nodes are taken from the scene as is.
+        let static_node = handle_of(&fresh, 4);
+        assert_eq!(
+            **fresh.graph[static_node].local_transform().position(),
+            Vector3::default()
+        );
+    }
+
+    #[test]
+    fn test_save_game_slots() {
+        let directory = std::env::temp_dir().join(format!("fyrox-save-slots-{}", Uuid::new_v4()));
+        let slots = SaveGameSlots::new(&directory);
+        assert!(slots.list().is_empty());
+
+        let mut old = SaveGame::new(SaveGameMetadata {
+            name: "Old".to_string(),
+            timestamp: 1,
+            ..Default::default()
+        });
+        let mut new = SaveGame::new(SaveGameMetadata {
+            name: "New".to_string(),
+            timestamp: 2,
+            ..Default::default()
+        });
+        new.scene = SceneSnapshot::capture(&make_level(0), tagged(PERSISTENT));
+        slots.save("slot1", &mut old).unwrap();
+        slots.save("slot2", &mut new).unwrap();
+        assert!(slots.exists("slot1"));
+
+        let list = slots.list();
+        assert_eq!(list.len(), 2);
+        assert_eq!(list[0].name, "slot2");
+        assert_eq!(list[0].metadata, new.metadata);
+        assert_eq!(list[1].metadata, old.metadata);
+
+        let loaded = slots.load("slot2", &make_context()).unwrap();
+        assert_eq!(loaded.scene.nodes.len(), 3);
+
+        slots.delete("slot1").unwrap();
+        assert!(!slots.exists("slot1"));
+        assert_eq!(slots.list().len(), 1);
+
+        // Dots in names are preserved.
+        slots.save("v1.2", &mut old).unwrap();
+        assert!(slots.exists("v1.2"));
+        assert!(!slots.exists("v1"));
+        assert!(slots.list().iter().any(|slot| slot.name == "v1.2"));
+
+        for name in ["", "../escape", "a/b", "a\\b", "..", "C:save"] {
+            assert!(slots.slot_path(name).is_err());
+            assert!(slots.save(name, &mut old).is_err());
+        }
+
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+}
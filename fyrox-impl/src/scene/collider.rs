@@ -1209,7 +1209,7 @@ mod test {
     use crate::scene::{
         base::BaseBuilder,
         collider::{ColliderBuilder, ColliderShape},
-        graph::Graph,
+        graph::{physics::CollisionEventKind, Graph},
         rigidbody::{RigidBodyBuilder, RigidBodyType},
     };
 
@@ -1258,6 +1258,45 @@ mod test {
                 .count()
         );
     }
+
+    #[test]
+    fn test_collision_events() {
+        let mut graph = Graph::new();
+
+        let sensor = ColliderBuilder::new(BaseBuilder::new())
+            .with_shape(ColliderShape::cuboid(0.5, 0.5, 0.5))
+            .with_sensor(true)
+            .build(&mut graph);
+        RigidBodyBuilder::new(BaseBuilder::new().with_child(sensor))
+            .with_body_type(RigidBodyType::Static)
+            .build(&mut graph);
+
+        let ball = ColliderBuilder::new(BaseBuilder::new())
+            .with_shape(ColliderShape::ball(0.25))
+            .build(&mut graph);
+        RigidBodyBuilder::new(BaseBuilder::new().with_child(ball))
+            .with_body_type(RigidBodyType::Dynamic)
+            .with_gravity_scale(0.0)
+            .build(&mut graph);
+
+        let mut events = Vec::new();
+        for _ in 0..3 {
+            graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+            events.extend_from_slice(graph.physics.collision_events());
+        }
+
+        assert_eq!(events.len(), 1);
+        let event = events[0];
+        assert_eq!(event.kind, CollisionEventKind::SensorEntered);
+        let sensor: Handle<Node> = sensor.to_base();
+        let ball: Handle<Node> = ball.to_base();
+        assert!(
+            (event.collider, event.other_collider) == (sensor, ball)
+                || (event.collider, event.other_collider) == (ball, sensor)
+        );
+        assert_eq!(event.swapped().swapped(), event);
+    }
+
     #[test]
     fn test_bitmask_display() {
         assert_eq!(
@@ -0,0 +1,806 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Kinematic character controller is a node that moves a capsule through the 2D physics world,
+//! taking slopes, steps and obstacles into account. See [`CharacterController`] docs for more info.
+
+use crate::scene::node::constructor::NodeConstructor;
+use crate::{
+    core::{
+        algebra::{Matrix4, Point3, Vector2, Vector3},
+        log::Log,
+        math::{aabb::AxisAlignedBoundingBox, m4x4_approx_eq},
+        pool::Handle,
+        reflect::prelude::*,
+        type_traits::prelude::*,
+        uuid::{uuid, Uuid},
+        variable::InheritableVariable,
+        visitor::prelude::*,
+    },
+    scene::{
+        base::{Base, BaseBuilder},
+        collider::InteractionGroups,
+        graph::Graph,
+        node::{Node, NodeTrait, SyncContext, UpdateContext},
+    },
+};
+use fyrox_graph::constructor::ConstructorProvider;
+use fyrox_graph::SceneGraph;
+use rapier2d::prelude::{ColliderHandle, RigidBodyHandle};
+use std::{
+    cell::Cell,
+    f32::consts::FRAC_PI_4,
+    ops::{Deref, DerefMut},
+};
+
+/// A collision of a character controller with some collider, that happened during the last
+/// movement.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CharacterCollision {
+    /// A handle of the collider the character collided with.
+    pub collider: Handle<Node>,
+    /// World-space contact point on the collider.
+    pub position: Vector2<f32>,
+    /// World-space contact normal on the collider.
+    pub normal: Vector2<f32>,
+}
+
+/// Result of a single movement of a character controller.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct CharacterMovement {
+    pub translation: Vector2<f32>,
+    pub grounded: bool,
+    pub sliding_down_slope: bool,
+    pub collisions: Vec<CharacterCollision>,
+    pub ground: Handle<Node>,
+    pub ground_native: ColliderHandle,
+}
+
+/// Kinematic character controller moves a capsule through the 2D physics world, instead of
+/// relying on the simulation of a dynamic rigid body. It is a 2D counterpart of
+/// [`crate::scene::character_controller::CharacterController`] and supports the same features:
+/// slope limits, step climbing, snapping to the ground, pushing of dynamic rigid bodies and moving
+/// platforms.
+///
+/// The movement is requested by [`CharacterController::move_by`] and is performed on the next
+/// update of the scene. Gravity is not applied automatically, it is up to the game to add it to
+/// the desired translation.
+///
+/// ```rust
+/// use fyrox_impl::{
+///     core::{algebra::Vector2, pool::Handle},
+///     scene::{dim2::character_controller::CharacterController, graph::Graph},
+/// };
+///
+/// fn move_character(graph: &mut Graph, character: Handle<CharacterController>, dt: f32) {
+///     let controller = &mut graph[character];
+///     let gravity = if controller.is_grounded() { 0.0 } else { -9.81 };
+///     controller.move_by(Vector2::new(1.0, gravity) * dt);
+/// }
+/// ```
+#[derive(Reflect, Visit, Debug, ComponentProvider)]
+#[reflect(derived_type = "Node")]
+pub struct CharacterController {
+    base: Base,
+
+    #[reflect(min_value = 0.0, step = 0.05)]
+    #[reflect(setter = "set_radius")]
+    pub(crate) radius: InheritableVariable<f32>,
+
+    #[reflect(min_value = 0.0, step = 0.05)]
+    #[reflect(setter = "set_half_height")]
+    pub(crate) half_height: InheritableVariable<f32>,
+
+    #[reflect(min_value = 0.0, step = 0.005)]
+    #[reflect(setter = "set_offset")]
+    pub(crate) offset: InheritableVariable<f32>,
+
+    #[reflect(setter = "set_up")]
+    pub(crate) up: InheritableVariable<Vector2<f32>>,
+
+    #[reflect(min_value = 0.0, max_value = 1.5708, step = 0.01)]
+    #[reflect(setter = "set_max_slope_climb_angle")]
+    pub(crate) max_slope_climb_angle: InheritableVariable<f32>,
+
+    #[reflect(min_value = 0.0, max_value = 1.5708, step = 0.01)]
+    #[reflect(setter = "set_min_slope_slide_angle")]
+    pub(crate) min_slope_slide_angle: InheritableVariable<f32>,
+
+    #[reflect(setter = "set_slide")]
+    pub(crate) slide: InheritableVariable<bool>,
+
+    #[reflect(min_value = 0.0, step = 0.05)]
+    #[reflect(setter = "set_step_height")]
+    pub(crate) step_height: InheritableVariable<f32>,
+
+    #[reflect(min_value = 0.0, step = 0.05)]
+    #[reflect(setter = "set_step_min_width")]
+    pub(crate) step_min_width: InheritableVariable<f32>,
+
+    #[reflect(setter = "set_step_on_dynamic_bodies")]
+    pub(crate) step_on_dynamic_bodies: InheritableVariable<bool>,
+
+    #[reflect(min_value = 0.0, step = 0.05)]
+    #[reflect(setter = "set_snap_to_ground")]
+    pub(crate) snap_to_ground: InheritableVariable<f32>,
+
+    #[reflect(setter = "set_push_dynamic_bodies")]
+    pub(crate) push_dynamic_bodies: InheritableVariable<bool>,
+
+    #[reflect(min_value = 0.0, step = 0.5)]
+    #[reflect(setter = "set_mass")]
+    pub(crate) mass: InheritableVariable<f32>,
+
+    #[reflect(setter = "set_follow_platforms")]
+    pub(crate) follow_platforms: InheritableVariable<bool>,
+
+    #[reflect(setter = "set_collision_groups")]
+    pub(crate) collision_groups: InheritableVariable<InteractionGroups>,
+
+    #[visit(skip)]
+    #[reflect(hidden)]
+    pub(crate) desired_translation: Vector2<f32>,
+    #[visit(skip)]
+    #[reflect(hidden)]
+    pub(crate) movement: CharacterMovement,
+    #[visit(skip)]
+    #[reflect(hidden)]
+    pub(crate) native_body: Cell<RigidBodyHandle>,
+    #[visit(skip)]
+    #[reflect(hidden)]
+    pub(crate) native_collider: Cell<ColliderHandle>,
+}
+
+impl Default for CharacterController {
+    fn default() -> Self {
+        Self {
+            base: Default::default(),
+            radius: InheritableVariable::new_modified(0.3),
+            half_height: InheritableVariable::new_modified(0.6),
+            offset: InheritableVariable::new_modified(0.01),
+            up: InheritableVariable::new_modified(Vector2::y()),
+            max_slope_climb_angle: InheritableVariable::new_modified(FRAC_PI_4),
+            min_slope_slide_angle: InheritableVariable::new_modified(30.0f32.to_radians()),
+            slide: InheritableVariable::new_modified(true),
+            step_height: InheritableVariable::new_modified(0.3),
+            step_min_width: InheritableVariable::new_modified(0.2),
+            step_on_dynamic_bodies: InheritableVariable::new_modified(true),
+            snap_to_ground: InheritableVariable::new_modified(0.2),
+            push_dynamic_bodies: InheritableVariable::new_modified(true),
+            mass: InheritableVariable::new_modified(80.0),
+            follow_platforms: InheritableVariable::new_modified(true),
+            collision_groups: Default::default(),
+            desired_translation: Default::default(),
+            movement: Default::default(),
+            native_body: Cell::new(RigidBodyHandle::invalid()),
+            native_collider: Cell::new(ColliderHandle::invalid()),
+        }
+    }
+}
+
+impl Clone for CharacterController {
+    fn clone(&self) -> Self {
+        Self {
+            base: self.base.clone(),
+            radius: self.radius.clone(),
+            half_height: self.half_height.clone(),
+            offset: self.offset.clone(),
+            up: self.up.clone(),
+            max_slope_climb_angle: self.max_slope_climb_angle.clone(),
+            min_slope_slide_angle: self.min_slope_slide_angle.clone(),
+            slide: self.slide.clone(),
+            step_height: self.step_height.clone(),
+            step_min_width: self.step_min_width.clone(),
+            step_on_dynamic_bodies: self.step_on_dynamic_bodies.clone(),
+            snap_to_ground: self.snap_to_ground.clone(),
+            push_dynamic_bodies: self.push_dynamic_bodies.clone(),
+            mass: self.mass.clone(),
+            follow_platforms: self.follow_platforms.clone(),
+            collision_groups: self.collision_groups.clone(),
+            desired_translation: Default::default(),
+            movement: Default::default(),
+            // Do not copy. The copy will have its own native representation.
+            native_body: Cell::new(RigidBodyHandle::invalid()),
+            native_collider: Cell::new(ColliderHandle::invalid()),
+        }
+    }
+}
+
+impl Deref for CharacterController {
+    type Target = Base;
+
+    fn deref(&self) -> &Self::Target {
+        &self.base
+    }
+}
+
+impl DerefMut for CharacterController {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.base
+    }
+}
+
+impl TypeUuidProvider for CharacterController {
+    fn type_uuid() -> Uuid {
+        uuid!("b1e0c6a4-2f3d-4e8a-9c71-6d5e4f3a2b10")
+    }
+}
+
+impl CharacterController {
+    /// Requests the character to move by the given world-space translation. The movement is
+    /// performed on the next update of the scene, multiple calls before the update are accumulated.
+    pub fn move_by(&mut self, translation: Vector2<f32>) {
+        self.desired_translation += translation;
+    }
+
+    /// Returns the translation, that was requested but not yet performed.
+    pub fn desired_translation(&self) -> Vector2<f32> {
+        self.desired_translation
+    }
+
+    /// Returns the translation, that was actually performed by the last movement.
+    pub fn last_translation(&self) -> Vector2<f32> {
+        self.movement.translation
+    }
+
+    /// Returns `true` if the character was standing on the ground after the last movement.
+    pub fn is_grounded(&self) -> bool {
+        self.movement.grounded
+    }
+
+    /// Returns `true` if the character was sliding down a slope steeper than
+    /// [`Self::min_slope_slide_angle`] during the last movement.
+    pub fn is_sliding_down_slope(&self) -> bool {
+        self.movement.sliding_down_slope
+    }
+
+    /// Returns a handle of the collider the character was standing on after the last movement, or
+    /// [`Handle::NONE`] if the character is in the air.
+    pub fn ground(&self) -> Handle<Node> {
+        self.movement.ground
+    }
+
+    /// Returns all the collisions, that happened during the last movement.
+    pub fn collisions(&self) -> &[CharacterCollision] {
+        &self.movement.collisions
+    }
+
+    /// Sets the radius of the capsule of the character.
+    pub fn set_radius(&mut self, radius: f32) -> f32 {
+        self.radius.set_value_and_mark_modified(radius.max(0.0))
+    }
+
+    /// Returns the radius of the capsule of the character.
+    pub fn radius(&self) -> f32 {
+        *self.radius
+    }
+
+    /// Sets the half height of the cylindrical part of the capsule of the character. The total
+    /// height of the capsule is `2.0 * (half_height + radius)`.
+    pub fn set_half_height(&mut self, half_height: f32) -> f32 {
+        self.half_height
+            .set_value_and_mark_modified(half_height.max(0.0))
+    }
+
+    /// Returns the half height of the cylindrical part of the capsule of the character.
+    pub fn half_height(&self) -> f32 {
+        *self.half_height
+    }
+
+    /// Sets the small gap, that is kept between the character and the obstacles. Too small values
+    /// may cause the character to get stuck in the obstacles because of floating-point errors.
+    pub fn set_offset(&mut self, offset: f32) -> f32 {
+        self.offset.set_value_and_mark_modified(offset.max(0.0))
+    }
+
+    /// Returns the gap, that is kept between the character and the obstacles.
+    pub fn offset(&self) -> f32 {
+        *self.offset
+    }
+
+    /// Sets the up direction of the character. It is used to determine what is a ground, a slope
+    /// and a step.
+    pub fn set_up(&mut self, up: Vector2<f32>) -> Vector2<f32> {
+        self.up.set_value_and_mark_modified(up)
+    }
+
+    /// Returns the up direction of the character.
+    pub fn up(&self) -> Vector2<f32> {
+        *self.up
+    }
+
+    /// Sets the maximum angle (in radians) of a slope the character can climb.
+    pub fn set_max_slope_climb_angle(&mut self, angle: f32) -> f32 {
+        self.max_slope_climb_angle
+            .set_value_and_mark_modified(angle)
+    }
+
+    /// Returns the maximum angle (in radians) of a slope the character can climb.
+    pub fn max_slope_climb_angle(&self) -> f32 {
+        *self.max_slope_climb_angle
+    }
+
+    /// Sets the minimum angle (in radians) of a slope, starting from which the character will
+    /// slide down.
+    pub fn set_min_slope_slide_angle(&mut self, angle: f32) -> f32 {
+        self.min_slope_slide_angle
+            .set_value_and_mark_modified(angle)
+    }
+
+    /// Returns the minimum angle (in radians) of a slope, starting from which the character will
+    /// slide down.
+    pub fn min_slope_slide_angle(&self) -> f32 {
+        *self.min_slope_slide_angle
+    }
+
+    /// Defines whether the character should slide along the obstacles or stop when hits them.
+    pub fn set_slide(&mut self, slide: bool) -> bool {
+        self.slide.set_value_and_mark_modified(slide)
+    }
+
+    /// Returns `true` if the character slides along the obstacles.
+    pub fn is_slide(&self) -> bool {
+        *self.slide
+    }
+
+    /// Sets the maximum height of a step the character can climb automatically. Zero disables
+    /// step climbing.
+    pub fn set_step_height(&mut self, height: f32) -> f32 {
+        self.step_height
+            .set_value_and_mark_modified(height.max(0.0))
+    }
+
+    /// Returns the maximum height of a step the character can climb automatically.
+    pub fn step_height(&self) -> f32 {
+        *self.step_height
+    }
+
+    /// Sets the minimum width of free space, that must be present on top of a step to climb it.
+    pub fn set_step_min_width(&mut self, width: f32) -> f32 {
+        self.step_min_width
+            .set_value_and_mark_modified(width.max(0.0))
+    }
+
+    /// Returns the minimum width of free space, that must be present on top of a step to climb it.
+    pub fn step_min_width(&self) -> f32 {
+        *self.step_min_width
+    }
+
+    /// Defines whether the character can climb on dynamic rigid bodies as on steps.
+    pub fn set_step_on_dynamic_bodies(&mut self, enabled: bool) -> bool {
+        self.step_on_dynamic_bodies
+            .set_value_and_mark_modified(enabled)
+    }
+
+    /// Returns `true` if the character can climb on dynamic rigid bodies as on steps.
+    pub fn is_step_on_dynamic_bodies(&self) -> bool {
+        *self.step_on_dynamic_bodies
+    }
+
+    /// Sets the maximum distance to the ground, at which the character will be snapped to the
+    /// ground. Zero disables snapping.
+    pub fn set_snap_to_ground(&mut self, distance: f32) -> f32 {
+        self.snap_to_ground
+            .set_value_and_mark_modified(distance.max(0.0))
+    }
+
+    /// Returns the maximum distance to the ground, at which the character will be snapped to the
+    /// ground.
+    pub fn snap_to_ground(&self) -> f32 {
+        *self.snap_to_ground
+    }
+
+    /// Defines whether the character should push dynamic rigid bodies it collides with.
+    pub fn set_push_dynamic_bodies(&mut self, enabled: bool) -> bool {
+        self.push_dynamic_bodies
+            .set_value_and_mark_modified(enabled)
+    }
+
+    /// Returns `true` if the character pushes dynamic rigid bodies it collides with.
+    pub fn is_push_dynamic_bodies(&self) -> bool {
+        *self.push_dynamic_bodies
+    }
+
+    /// Sets the mass of the character, that is used to calculate impulses applied to the pushed
+    /// dynamic rigid bodies.
+    pub fn set_mass(&mut self, mass: f32) -> f32 {
+        self.mass.set_value_and_mark_modified(mass.max(0.0))
+    }
+
+    /// Returns the mass of the character.
+    pub fn mass(&self) -> f32 {
+        *self.mass
+    }
+
+    /// Defines whether the character should move together with the rigid body it stands on.
+    pub fn set_follow_platforms(&mut self, enabled: bool) -> bool {
+        self.follow_platforms.set_value_and_mark_modified(enabled)
+    }
+
+    /// Returns `true` if the character moves together with the rigid body it stands on.
+    pub fn is_follow_platforms(&self) -> bool {
+        *self.follow_platforms
+    }
+
+    /// Sets the collision groups of the character. They're used both for the collider of the
+    /// character and for the filtering of the obstacles.
+    pub fn set_collision_groups(&mut self, groups: InteractionGroups) -> InteractionGroups {
+        self.collision_groups.set_value_and_mark_modified(groups)
+    }
+
+    /// Returns the collision groups of the character.
+    pub fn collision_groups(&self) -> InteractionGroups {
+        *self.collision_groups
+    }
+}
+
+impl ConstructorProvider<Node, Graph> for CharacterController {
+    fn constructor() -> NodeConstructor {
+        NodeConstructor::new::<Self>()
+            .with_variant("Character Controller 2D", |_| {
+                CharacterControllerBuilder::new(
+                    BaseBuilder::new().with_name("Character Controller 2D"),
+                )
+                .build_node()
+                .into()
+            })
+            .with_group("Physics 2D")
+    }
+}
+
+impl NodeTrait for CharacterController {
+    fn local_bounding_box(&self) -> AxisAlignedBoundingBox {
+        let half_extents = Vector3::new(*self.radius, *self.half_height + *self.radius, 0.0);
+        AxisAlignedBoundingBox::from_min_max(-half_extents, half_extents)
+    }
+
+    fn world_bounding_box(&self) -> AxisAlignedBoundingBox {
+        self.local_bounding_box()
+            .transform(&self.global_transform())
+    }
+
+    fn id(&self) -> Uuid {
+        Self::type_uuid()
+    }
+
+    fn on_removed_from_graph(&mut self, graph: &mut Graph) {
+        // Removes the collider as well.
+        graph.physics2d.remove_body(self.native_body.get());
+        self.native_body.set(RigidBodyHandle::invalid());
+        self.native_collider.set(ColliderHandle::invalid());
+
+        Log::info(format!(
+            "Native character controller was removed for node: {}",
+            self.name()
+        ));
+    }
+
+    fn sync_native(&self, self_handle: Handle<Node>, context: &mut SyncContext) {
+        context
+            .physics2d
+            .sync_to_character_controller_node(self_handle, self);
+    }
+
+    fn on_global_transform_changed(
+        &self,
+        new_global_transform: &Matrix4<f32>,
+        context: &mut SyncContext,
+    ) {
+        if !m4x4_approx_eq(new_global_transform, &self.global_transform()) {
+            context
+                .physics2d
+                .set_character_controller_position(self, new_global_transform);
+        }
+    }
+
+    fn update(&mut self, context: &mut UpdateContext) {
+        let desired_translation = std::mem::take(&mut self.desired_translation);
+        let dt = context.physics2d.controller_dt(context.dt);
+
+        let Some(movement) = context
+            .physics2d
+            .move_character(self, desired_translation, dt)
+        else {
+            // The native body is not created yet, keep the translation for the next update.
+            self.desired_translation = desired_translation;
+            return;
+        };
+
+        if movement.translation != Vector2::default() {
+            // Character controller can be root node of a scene, in this case it does not have a
+            // parent.
+            let parent_transform = context
+                .nodes
+                .try_borrow(self.parent())
+                .ok()
+                .map(|p| p.global_transform())
+                .unwrap_or_else(Matrix4::identity);
+            let new_global_position =
+                self.global_position() + movement.translation.to_homogeneous();
+            let new_local_position = parent_transform
+                .try_inverse()
+                .unwrap_or_else(Matrix4::identity)
+                .transform_point(&Point3::from(new_global_position))
+                .coords;
+            self.local_transform_mut().set_position(new_local_position);
+        }
+
+        self.movement = movement;
+    }
+}
+
+/// Allows you to create character controller in declarative manner.
+pub struct CharacterControllerBuilder {
+    base_builder: BaseBuilder,
+    radius: f32,
+    half_height: f32,
+    offset: f32,
+    up: Vector2<f32>,
+    max_slope_climb_angle: f32,
+    min_slope_slide_angle: f32,
+    slide: bool,
+    step_height: f32,
+    step_min_width: f32,
+    step_on_dynamic_bodies: bool,
+    snap_to_ground: f32,
+    push_dynamic_bodies: bool,
+    mass: f32,
+    follow_platforms: bool,
+    collision_groups: InteractionGroups,
+}
+
+impl CharacterControllerBuilder {
+    /// Creates new character controller builder.
+    pub fn new(base_builder: BaseBuilder) -> Self {
+        let default = CharacterController::default();
+        Self {
+            base_builder,
+            radius: *default.radius,
+            half_height: *default.half_height,
+            offset: *default.offset,
+            up: *default.up,
+            max_slope_climb_angle: *default.max_slope_climb_angle,
+            min_slope_slide_angle: *default.min_slope_slide_angle,
+            slide: *default.slide,
+            step_height: *default.step_height,
+            step_min_width: *default.step_min_width,
+            step_on_dynamic_bodies: *default.step_on_dynamic_bodies,
+            snap_to_ground: *default.snap_to_ground,
+            push_dynamic_bodies: *default.push_dynamic_bodies,
+            mass: *default.mass,
+            follow_platforms: *default.follow_platforms,
+            collision_groups: *default.collision_groups,
+        }
+    }
+
+    /// Sets the desired radius and half height of the capsule of the character.
+    pub fn with_capsule(mut self, radius: f32, half_height: f32) -> Self {
+        self.radius = radius;
+        self.half_height = half_height;
+        self
+    }
+
+    /// Sets the desired gap between the character and the obstacles.
+    pub fn with_offset(mut self, offset: f32) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    /// Sets the desired up direction of the character.
+    pub fn with_up(mut self, up: Vector2<f32>) -> Self {
+        self.up = up;
+        self
+    }
+
+    /// Sets the desired maximum angle (in radians) of a slope the character can climb.
+    pub fn with_max_slope_climb_angle(mut self, angle: f32) -> Self {
+        self.max_slope_climb_angle = angle;
+        self
+    }
+
+    /// Sets the desired minimum angle (in radians) of a slope, starting from which the character
+    /// will slide down.
+    pub fn with_min_slope_slide_angle(mut self, angle: f32) -> Self {
+        self.min_slope_slide_angle = angle;
+        self
+    }
+
+    /// Sets whether the character should slide along the obstacles or not.
+    pub fn with_slide(mut self, slide: bool) -> Self {
+        self.slide = slide;
+        self
+    }
+
+    /// Sets the desired maximum height and minimum width of steps the character can climb. Zero
+    /// height disables step climbing.
+    pub fn with_step(mut self, height: f32, min_width: f32) -> Self {
+        self.step_height = height;
+        self.step_min_width = min_width;
+        self
+    }
+
+    /// Sets whether the character can climb on dynamic rigid bodies as on steps.
+    pub fn with_step_on_dynamic_bodies(mut self, enabled: bool) -> Self {
+        self.step_on_dynamic_bodies = enabled;
+        self
+    }
+
+    /// Sets the desired snap-to-ground distance. Zero disables snapping.
+    pub fn with_snap_to_ground(mut self, distance: f32) -> Self {
+        self.snap_to_ground = distance;
+        self
+    }
+
+    /// Sets whether the character should push dynamic rigid bodies.
+    pub fn with_push_dynamic_bodies(mut self, enabled: bool) -> Self {
+        self.push_dynamic_bodies = enabled;
+        self
+    }
+
+    /// Sets the desired mass of the character.
+    pub fn with_mass(mut self, mass: f32) -> Self {
+        self.mass = mass;
+        self
+    }
+
+    /// Sets whether the character should move together with the rigid body it stands on.
+    pub fn with_follow_platforms(mut self, enabled: bool) -> Self {
+        self.follow_platforms = enabled;
+        self
+    }
+
+    /// Sets the desired collision groups of the character.
+    pub fn with_collision_groups(mut self, groups: InteractionGroups) -> Self {
+        self.collision_groups = groups;
+        self
+    }
+
+    /// Creates character controller node, but does not add it to a graph.
+    pub fn build_character_controller(self) -> CharacterController {
+        CharacterController {
+            base: self.base_builder.build_base(),
+            radius: self.radius.into(),
+            half_height: self.half_height.into(),
+            offset: self.offset.into(),
+            up: self.up.into(),
+            max_slope_climb_angle: self.max_slope_climb_angle.into(),
+            min_slope_slide_angle: self.min_slope_slide_angle.into(),
+            slide: self.slide.into(),
+            step_height: self.step_height.into(),
+            step_min_width: self.step_min_width.into(),
+            step_on_dynamic_bodies: self.step_on_dynamic_bodies.into(),
+            snap_to_ground: self.snap_to_ground.into(),
+            push_dynamic_bodies: self.push_dynamic_bodies.into(),
+            mass: self.mass.into(),
+            follow_platforms: self.follow_platforms.into(),
+            collision_groups: self.collision_groups.into(),
+            desired_translation: Default::default(),
+            movement: Default::default(),
+            native_body: Cell::new(RigidBodyHandle::invalid()),
+            native_collider: Cell::new(ColliderHandle::invalid()),
+        }
+    }
+
+    /// Creates character controller node, but does not add it to a graph.
+    pub fn build_node(self) -> Node {
+        Node::new(self.build_character_controller())
+    }
+
+    /// Creates character controller node and adds it to the graph.
+    pub fn build(self, graph: &mut Graph) -> Handle<CharacterController> {
+        graph.add_node(self.build_node()).to_variant()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::algebra::{UnitQuaternion, Vector2, Vector3},
+        scene::{
+            base::BaseBuilder,
+            dim2::{
+                character_controller::CharacterControllerBuilder,
+                collider::{ColliderBuilder, ColliderShape},
+                rigidbody::RigidBodyBuilder,
+            },
+            graph::Graph,
+            rigidbody::RigidBodyType,
+            transform::TransformBuilder,
+        },
+    };
+
+    #[test]
+    fn test_character_controller_stands_on_ground() {
+        let mut graph = Graph::new();
+
+        let ground = ColliderBuilder::new(BaseBuilder::new())
+            .with_shape(ColliderShape::cuboid(10.0, 0.5))
+            .build(&mut graph);
+        RigidBodyBuilder::new(BaseBuilder::new().with_child(ground))
+            .with_body_type(RigidBodyType::Static)
+            .build(&mut graph);
+
+        let character = CharacterControllerBuilder::new(
+            BaseBuilder::new().with_local_transform(
+                TransformBuilder::new()
+                    .with_local_position(Vector3::new(0.0, 2.0, 0.0))
+                    .build(),
+            ),
+        )
+        .with_capsule(0.3, 0.5)
+        .build(&mut graph);
+
+        for _ in 0..120 {
+            graph[character].move_by(Vector2::new(0.0, -0.1));
+            graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+        }
+
+        let controller = &graph[character];
+        assert!(controller.is_grounded());
+        assert_eq!(controller.ground(), ground.to_base());
+        // The bottom of the capsule must stay on top of the ground.
+        let bottom = controller.global_position().y - 0.8;
+        assert!(bottom > 0.45 && bottom < 0.6, "{bottom}");
+    }
+
+    #[test]
+    fn test_character_controller_rotated_ground() {
+        let mut graph = Graph::new();
+
+        // The top side of the ground is the bottom side of the box in local space.
+        let ground = ColliderBuilder::new(BaseBuilder::new())
+            .with_shape(ColliderShape::cuboid(10.0, 0.5))
+            .build(&mut graph);
+        RigidBodyBuilder::new(
+            BaseBuilder::new().with_child(ground).with_local_transform(
+                TransformBuilder::new()
+                    .with_local_rotation(UnitQuaternion::from_axis_angle(
+                        &Vector3::z_axis(),
+                        std::f32::consts::PI,
+                    ))
+                    .build(),
+            ),
+        )
+        .with_body_type(RigidBodyType::Static)
+        .build(&mut graph);
+
+        let character = CharacterControllerBuilder::new(
+            BaseBuilder::new().with_local_transform(
+                TransformBuilder::new()
+                    .with_local_position(Vector3::new(0.0, 2.0, 0.0))
+                    .build(),
+            ),
+        )
+        .with_capsule(0.3, 0.5)
+        .build(&mut graph);
+
+        for _ in 0..120 {
+            graph[character].move_by(Vector2::new(0.0, -0.1));
+            graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+        }
+
+        let controller = &graph[character];
+        assert!(controller.is_grounded());
+        assert_eq!(controller.ground(), ground.to_base());
+        for collision in controller.collisions() {
+            assert!(collision.normal.y > 0.99, "{:?}", collision.normal);
+        }
+    }
+}
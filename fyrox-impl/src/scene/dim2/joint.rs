@@ -37,7 +37,8 @@ use crate::{
         base::{Base, BaseBuilder},
         dim2::rigidbody::RigidBody,
         graph::Graph,
-        node::{Node, NodeTrait, SyncContext},
+        joint::JointFeedback,
+        node::{Node, NodeTrait, SyncContext, UpdateContext},
         Scene,
     },
 };
@@ -188,6 +189,22 @@ pub struct Joint {
     #[reflect(hidden)]
     pub(crate) local_frames: RefCell<Option<JointLocalFrames>>,
 
+    #[visit(optional)]
+    #[reflect(setter = "set_break_force")]
+    pub(crate) break_force: InheritableVariable<Option<f32>>,
+
+    #[visit(optional)]
+    #[reflect(setter = "set_break_torque")]
+    pub(crate) break_torque: InheritableVariable<Option<f32>>,
+
+    #[visit(optional)]
+    #[reflect(hidden)]
+    pub(crate) broken: bool,
+
+    #[visit(skip)]
+    #[reflect(hidden)]
+    pub(crate) feedback: JointFeedback,
+
     #[visit(skip)]
     #[reflect(hidden)]
     pub(crate) native: Cell<ImpulseJointHandle>,
@@ -203,6 +220,10 @@ impl Default for Joint {
             body2: Default::default(),
             local_frames: Default::default(),
             contacts_enabled: InheritableVariable::new_modified(true),
+            break_force: Default::default(),
+            break_torque: Default::default(),
+            broken: false,
+            feedback: Default::default(),
             // Do not copy. The copy will have its own native representation.
             native: Cell::new(ImpulseJointHandle::invalid()),
         }
@@ -233,6 +254,10 @@ impl Clone for Joint {
             body2: self.body2.clone(),
             local_frames: self.local_frames.clone(),
             contacts_enabled: self.contacts_enabled.clone(),
+            break_force: self.break_force.clone(),
+            break_torque: self.break_torque.clone(),
+            broken: self.broken,
+            feedback: Default::default(),
             native: Cell::new(ImpulseJointHandle::invalid()),
         }
     }
@@ -326,6 +351,67 @@ impl Joint {
         *self.contacts_enabled
     }
 
+    /// Sets the maximum force (in Newtons) the joint can apply to the connected bodies. If the
+    /// force exceeds the threshold, the joint breaks: it stops affecting the bodies and sends
+    /// [`crate::scene::graph::physics::JointBreakEvent`] to the scripts of the joint and its bodies.
+    /// `None` means that the joint cannot be broken by a force. Default is `None`.
+    pub fn set_break_force(&mut self, force: Option<f32>) -> Option<f32> {
+        self.break_force.set_value_and_mark_modified(force)
+    }
+
+    /// Returns current break force threshold of the joint. See [`Self::set_break_force`] for more
+    /// info.
+    pub fn break_force(&self) -> Option<f32> {
+        *self.break_force
+    }
+
+    /// Sets the maximum torque (in Newton-meters) the joint can apply to the connected bodies. Works
+    /// the same as [`Self::set_break_force`], but for the angular part of the joint. Default is
+    /// `None`.
+    pub fn set_break_torque(&mut self, torque: Option<f32>) -> Option<f32> {
+        self.break_torque.set_value_and_mark_modified(torque)
+    }
+
+    /// Returns current break torque threshold of the joint. See [`Self::set_break_torque`] for more
+    /// info.
+    pub fn break_torque(&self) -> Option<f32> {
+        *self.break_torque
+    }
+
+    /// Returns `true` if the joint was broken, `false` - otherwise. A broken joint does not affect
+    /// the connected bodies until it is repaired using [`Self::repair`].
+    pub fn is_broken(&self) -> bool {
+        self.broken
+    }
+
+    /// Restores a broken joint. The joint will connect the bodies again (using the same local
+    /// frames it had before it was broken) on the next update of the scene.
+    pub fn repair(&mut self) {
+        self.broken = false;
+    }
+
+    /// Returns forces and impulses, that were applied by the joint at the last physics step. A
+    /// broken or disabled joint has zero feedback. Linear values use X and Y axes, angular values
+    /// use Z axis only.
+    pub fn feedback(&self) -> &JointFeedback {
+        &self.feedback
+    }
+
+    /// Returns the linear impulse, that was applied by the joint at the last physics step.
+    pub fn impulse(&self) -> Vector2<f32> {
+        self.feedback.linear_impulse.xy()
+    }
+
+    /// Returns the force, that was applied by the joint at the last physics step.
+    pub fn force(&self) -> Vector2<f32> {
+        self.feedback.force.xy()
+    }
+
+    /// Returns the torque, that was applied by the joint at the last physics step.
+    pub fn torque(&self) -> f32 {
+        self.feedback.torque.z
+    }
+
     /// Sets the motor force of the joint assuming it is a [`PrismaticJoint`].
     ///
     /// Call [`Self::disable_motor`] to properly stop the motor and set the joint free.
@@ -571,6 +657,22 @@ impl NodeTrait for Joint {
         ));
     }
 
+    fn update(&mut self, context: &mut UpdateContext) {
+        let native = self.native.get();
+        let Some(feedback) = context.physics2d.joint_feedback(native) else {
+            self.feedback = Default::default();
+            return;
+        };
+
+        self.feedback = feedback;
+
+        if !self.broken && feedback.exceeds(*self.break_force, *self.break_torque) {
+            self.broken = true;
+            context.physics2d.break_joint(native, feedback);
+            self.native.set(ImpulseJointHandle::invalid());
+        }
+    }
+
     fn sync_native(&self, self_handle: Handle<Node>, context: &mut SyncContext) {
         context
             .physics2d
@@ -612,6 +714,8 @@ pub struct JointBuilder {
     body1: Handle<RigidBody>,
     body2: Handle<RigidBody>,
     contacts_enabled: bool,
+    break_force: Option<f32>,
+    break_torque: Option<f32>,
 }
 
 impl JointBuilder {
@@ -624,6 +728,8 @@ impl JointBuilder {
             body1: Default::default(),
             body2: Default::default(),
             contacts_enabled: true,
+            break_force: None,
+            break_torque: None,
         }
     }
 
@@ -659,6 +765,20 @@ impl JointBuilder {
         self
     }
 
+    /// Sets the maximum force the joint can apply before it breaks. See [`Joint::set_break_force`]
+    /// for more info.
+    pub fn with_break_force(mut self, force: Option<f32>) -> Self {
+        self.break_force = force;
+        self
+    }
+
+    /// Sets the maximum torque the joint can apply before it breaks. See
+    /// [`Joint::set_break_torque`] for more info.
+    pub fn with_break_torque(mut self, torque: Option<f32>) -> Self {
+        self.break_torque = torque;
+        self
+    }
+
     /// Creates new Joint node, but does not add it to the graph.
     pub fn build_joint(self) -> Joint {
         Joint {
@@ -669,6 +789,10 @@ impl JointBuilder {
             body2: self.body2.into(),
             local_frames: Default::default(),
             contacts_enabled: self.contacts_enabled.into(),
+            break_force: self.break_force.into(),
+            break_torque: self.break_torque.into(),
+            broken: false,
+            feedback: Default::default(),
             native: Cell::new(ImpulseJointHandle::invalid()),
         }
     }
@@ -683,3 +807,60 @@ impl JointBuilder {
         graph.add_node(self.build_node()).to_variant()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::algebra::{Vector2, Vector3},
+        scene::{
+            base::BaseBuilder,
+            dim2::{
+                collider::{ColliderBuilder, ColliderShape},
+                joint::{JointBuilder, JointParams},
+                rigidbody::RigidBodyBuilder,
+            },
+            graph::Graph,
+            rigidbody::RigidBodyType,
+            transform::TransformBuilder,
+        },
+    };
+
+    #[test]
+    fn test_joint_2d_breaks_under_load() {
+        let mut graph = Graph::new();
+
+        let anchor = RigidBodyBuilder::new(BaseBuilder::new())
+            .with_body_type(RigidBodyType::Static)
+            .build(&mut graph);
+        let collider = ColliderBuilder::new(BaseBuilder::new())
+            .with_shape(ColliderShape::cuboid(0.5, 0.5))
+            .build(&mut graph);
+        let load = RigidBodyBuilder::new(
+            BaseBuilder::new()
+                .with_child(collider)
+                .with_local_transform(
+                    TransformBuilder::new()
+                        .with_local_position(Vector3::new(0.0, -1.0, 0.0))
+                        .build(),
+                ),
+        )
+        .build(&mut graph);
+        let joint = JointBuilder::new(BaseBuilder::new())
+            .with_params(JointParams::FixedJoint(Default::default()))
+            .with_body1(anchor)
+            .with_body2(load)
+            .with_break_force(Some(1.0))
+            .build(&mut graph);
+
+        for _ in 0..10 {
+            graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+        }
+
+        assert!(graph[joint].is_broken());
+        let events = graph.physics2d.joint_break_events();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].joint, joint.to_base());
+        assert_eq!(events[0].body1, anchor.to_base());
+        assert_eq!(events[0].body2, load.to_base());
+    }
+}
@@ -45,15 +45,20 @@ use crate::{
         debug::SceneDrawingContext,
         dim2::{
             self,
+            character_controller::{CharacterCollision, CharacterMovement},
             collider::{ColliderShape, TileMapShape},
             joint::{JointLocalFrames, JointMotorParams, JointParams},
             rigidbody::ApplyAction,
         },
         graph::{
             isometric_global_transform,
-            physics::{FeatureId, IntegrationParameters, PhysicsPerformanceStatistics},
+            physics::{
+                CollisionEvent, CollisionEventKind, DeterminismSettings, FeatureId,
+                IntegrationParameters, JointBreakEvent, PhysicsPerformanceStatistics,
+            },
             Graph, NodePool,
         },
+        joint::JointFeedback,
         node::{Node, NodeTrait},
         tilemap::TileMap,
     },
@@ -62,6 +67,7 @@ pub use rapier2d::geometry::shape::*;
 use rapier2d::math::{Pose2, Vec2};
 use rapier2d::parry::query::DefaultQueryDispatcher;
 use rapier2d::{
+    control::{CharacterAutostep, CharacterLength, KinematicCharacterController},
     dynamics::{
         CCDSolver, GenericJoint, GenericJointBuilder, ImpulseJointHandle, ImpulseJointSet,
         IslandManager, JointAxesMask, JointAxis, MultibodyJointHandle, MultibodyJointSet,
@@ -73,7 +79,7 @@ use rapier2d::{
         InteractionGroups, NarrowPhase, Ray, SharedShape,
     },
     parry::query::ShapeCastOptions,
-    pipeline::{DebugRenderPipeline, EventHandler, PhysicsPipeline},
+    pipeline::{ActiveEvents, DebugRenderPipeline, EventHandler, PhysicsPipeline},
 };
 use std::{
     cmp::Ordering,
@@ -318,6 +324,45 @@ impl IntersectionPair {
     }
 }
 
+#[derive(Default)]
+struct CollisionEventCollector {
+    events: Mutex<Vec<CollisionEvent>>,
+}
+
+impl EventHandler for CollisionEventCollector {
+    fn handle_collision_event(
+        &self,
+        _bodies: &RigidBodySet,
+        colliders: &ColliderSet,
+        event: rapier2d::geometry::CollisionEvent,
+        _contact_pair: Option<&rapier2d::geometry::ContactPair>,
+    ) {
+        let node_handle = |collider| {
+            colliders
+                .get(collider)
+                .map(|c| Handle::decode_from_u128(c.user_data))
+                .unwrap_or_default()
+        };
+
+        self.events.safe_lock().push(CollisionEvent {
+            kind: CollisionEventKind::new(event.started(), event.sensor()),
+            collider: node_handle(event.collider1()),
+            other_collider: node_handle(event.collider2()),
+        });
+    }
+
+    fn handle_contact_force_event(
+        &self,
+        _dt: f32,
+        _bodies: &RigidBodySet,
+        _colliders: &ColliderSet,
+        _contact_pair: &rapier2d::geometry::ContactPair,
+        _total_force_magnitude: f32,
+    ) {
+    }
+}
+
+#[derive(Clone)]
 pub(super) struct Container<S, A>
 where
     A: Hash + Eq + Clone,
@@ -468,6 +513,11 @@ pub struct PhysicsWorld {
     /// Current gravity vector. Default is (0.0, -9.81)
     pub gravity: InheritableVariable<Vector2<f32>>,
 
+    /// Settings of the deterministic simulation mode. See [`DeterminismSettings`] docs for more
+    /// info.
+    #[visit(optional)]
+    pub determinism: InheritableVariable<DeterminismSettings>,
+
     /// Performance statistics of a single simulation step.
     #[visit(skip)]
     #[reflect(hidden)]
@@ -513,7 +563,27 @@ pub struct PhysicsWorld {
     // Event handler collects info about contacts and proximity events.
     #[visit(skip)]
     #[reflect(hidden)]
-    event_handler: Box<dyn EventHandler>,
+    event_collector: CollisionEventCollector,
+    // Collision events generated by the last step.
+    #[visit(skip)]
+    #[reflect(hidden)]
+    collision_events: Vec<CollisionEvent>,
+    // Joints, that were broken since the last dispatch of the events.
+    #[visit(skip)]
+    #[reflect(hidden)]
+    joint_break_events: Vec<JointBreakEvent>,
+    // Time step of the last simulated physics step, used to convert joint impulses to forces.
+    #[visit(skip)]
+    #[reflect(hidden)]
+    last_step_dt: f32,
+    // Total time, that was simulated by the last update (all the steps together).
+    #[visit(skip)]
+    #[reflect(hidden)]
+    last_update_dt: f32,
+    // Time, that was not yet simulated in deterministic mode.
+    #[visit(skip)]
+    #[reflect(hidden)]
+    time_accumulator: f32,
     #[visit(skip)]
     #[reflect(hidden)]
     debug_render_pipeline: Mutex<DebugRenderPipeline>,
@@ -525,11 +595,41 @@ impl Clone for PhysicsWorld {
             enabled: self.enabled.clone(),
             integration_parameters: self.integration_parameters.clone(),
             gravity: self.gravity.clone(),
+            determinism: self.determinism.clone(),
             ..Default::default()
         }
     }
 }
 
+/// A copy of the complete internal state of a [`PhysicsWorld`]: rigid bodies, colliders, joints,
+/// contacts (including the impulses, that are used to warm-start the solver), broad phase, islands
+/// and CCD state. Restoring the snapshot brings the world to exactly the same state it was at the
+/// moment of capture, so the simulation can be re-run from that moment.
+#[derive(Clone)]
+pub struct PhysicsWorldSnapshot {
+    gravity: Vector2<f32>,
+    time_accumulator: f32,
+    broad_phase: DefaultBroadPhase,
+    narrow_phase: NarrowPhase,
+    ccd_solver: CCDSolver,
+    islands: IslandManager,
+    bodies: RigidBodySet,
+    colliders: ColliderSet,
+    joints: Container<ImpulseJointSet, ImpulseJointHandle>,
+    multibody_joints: Container<MultibodyJointSet, MultibodyJointHandle>,
+}
+
+impl Debug for PhysicsWorldSnapshot {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "PhysicsWorldSnapshot {{ bodies: {}, colliders: {} }}",
+            self.bodies.len(),
+            self.colliders.len()
+        )
+    }
+}
+
 fn isometry_from_global_transform(transform: &Matrix4<f32>) -> Isometry2<f32> {
     Isometry2 {
         translation: Translation2::new(transform[12], transform[13]),
@@ -622,7 +722,13 @@ impl PhysicsWorld {
                 set: MultibodyJointSet::new(),
                 map: Default::default(),
             },
-            event_handler: Box::new(()),
+            event_collector: Default::default(),
+            collision_events: Default::default(),
+            joint_break_events: Default::default(),
+            last_step_dt: 0.0,
+            last_update_dt: 0.0,
+            time_accumulator: 0.0,
+            determinism: Default::default(),
             performance_statistics: Default::default(),
             debug_render_pipeline: Default::default(),
         }
@@ -652,7 +758,19 @@ impl PhysicsWorld {
             0.0
         };
 
+        self.last_update_dt = 0.0;
+
         if *self.enabled {
+            let determinism = *self.determinism;
+            let (steps, dt) = if determinism.enabled {
+                (
+                    determinism.advance(&mut self.time_accumulator, dt),
+                    determinism.fixed_timestep,
+                )
+            } else {
+                (1, dt)
+            };
+
             let integration_parameters = rapier2d::dynamics::IntegrationParameters {
                 dt,
                 min_ccd_dt: self.integration_parameters.min_ccd_dt,
@@ -675,22 +793,31 @@ impl PhysicsWorld {
                 max_ccd_substeps: self.integration_parameters.max_ccd_substeps as usize,
             };
 
-            self.pipeline.step(
-                (*self.gravity).into(),
-                &integration_parameters,
-                &mut self.islands,
-                &mut self.broad_phase,
-                &mut self.narrow_phase,
-                &mut self.bodies,
-                &mut self.colliders,
-                &mut self.joints.set,
-                &mut self.multibody_joints.set,
-                &mut self.ccd_solver,
-                &(),
-                &*self.event_handler,
-            );
+            if steps > 0 {
+                self.last_step_dt = dt;
+            }
+            self.last_update_dt = steps as f32 * dt;
+
+            for _ in 0..steps {
+                self.pipeline.step(
+                    (*self.gravity).into(),
+                    &integration_parameters,
+                    &mut self.islands,
+                    &mut self.broad_phase,
+                    &mut self.narrow_phase,
+                    &mut self.bodies,
+                    &mut self.colliders,
+                    &mut self.joints.set,
+                    &mut self.multibody_joints.set,
+                    &mut self.ccd_solver,
+                    &(),
+                    &self.event_collector,
+                );
+            }
         }
 
+        self.collision_events = std::mem::take(&mut *self.event_collector.events.safe_lock());
+
         self.performance_statistics.step_time += instant::Instant::now() - time;
     }
 
@@ -1218,7 +1345,8 @@ impl PhysicsWorld {
                             u32_to_group(collider_node.solver_groups().filter.0),
                             Default::default(),
                         ))
-                        .sensor(collider_node.is_sensor());
+                        .sensor(collider_node.is_sensor())
+                        .active_events(ActiveEvents::COLLISION_EVENTS);
 
                     if let Some(density) = collider_node.density() {
                         builder = builder.density(density);
@@ -1247,7 +1375,7 @@ impl PhysicsWorld {
         handle: Handle<Node>,
         joint: &scene::dim2::joint::Joint,
     ) {
-        if !joint.is_globally_enabled() {
+        if !joint.is_globally_enabled() || joint.is_broken() {
             self.remove_joint(joint.native.get());
             joint.native.set(ImpulseJointHandle(Default::default()));
             return;
@@ -1376,6 +1504,354 @@ impl PhysicsWorld {
         }
     }
 
+    pub(crate) fn set_character_controller_position(
+        &mut self,
+        controller: &scene::dim2::character_controller::CharacterController,
+        new_global_transform: &Matrix4<f32>,
+    ) {
+        if let Some(native) = self.bodies.get_mut(controller.native_body.get()) {
+            native.set_next_kinematic_translation(
+                Vector2::new(new_global_transform[12], new_global_transform[13]).into(),
+            );
+        }
+    }
+
+    pub(crate) fn sync_to_character_controller_node(
+        &mut self,
+        handle: Handle<Node>,
+        controller: &scene::dim2::character_controller::CharacterController,
+    ) {
+        if !controller.is_globally_enabled() {
+            self.remove_body(controller.native_body.get());
+            controller.native_body.set(Default::default());
+            controller.native_collider.set(Default::default());
+            return;
+        }
+
+        if self.bodies.get(controller.native_body.get()).is_some() {
+            let needs_sync = controller.radius.need_sync()
+                || controller.half_height.need_sync()
+                || controller.collision_groups.need_sync();
+            if needs_sync {
+                if let Some(native) = self.colliders.get_mut(controller.native_collider.get()) {
+                    // Both values must be synced, do not short-circuit.
+                    let radius_changed = controller.radius.try_sync_model(|_| {});
+                    let half_height_changed = controller.half_height.try_sync_model(|_| {});
+                    if radius_changed || half_height_changed {
+                        native.set_shape(SharedShape::capsule_y(
+                            controller.half_height(),
+                            controller.radius(),
+                        ));
+                    }
+                    controller.collision_groups.try_sync_model(|v| {
+                        native.set_collision_groups(InteractionGroups::new(
+                            u32_to_group(v.memberships.0),
+                            u32_to_group(v.filter.0),
+                            Default::default(),
+                        ))
+                    });
+                }
+            }
+        } else {
+            let position = controller.global_position();
+            let body = RigidBodyBuilder::kinematic_position_based()
+                .translation(position.xy().into())
+                .lock_rotations()
+                .build();
+            let body_handle = self.add_body(handle, body);
+
+            let groups = controller.collision_groups();
+            let collider = ColliderBuilder::new(SharedShape::capsule_y(
+                controller.half_height(),
+                controller.radius(),
+            ))
+            .collision_groups(InteractionGroups::new(
+                u32_to_group(groups.memberships.0),
+                u32_to_group(groups.filter.0),
+                Default::default(),
+            ))
+            .active_events(ActiveEvents::COLLISION_EVENTS)
+            .build();
+            let collider_handle = self.add_collider(handle, body_handle, collider);
+
+            controller.native_body.set(body_handle);
+            controller.native_collider.set(collider_handle);
+
+            Log::writeln(
+                MessageKind::Information,
+                format!(
+                    "Native character controller was created for node {}",
+                    controller.name()
+                ),
+            );
+        }
+    }
+
+    /// Moves the given character controller by the desired translation, taking obstacles, slopes
+    /// and steps into account. Returns [`None`] if the controller has no native representation.
+    pub(crate) fn move_character(
+        &mut self,
+        controller: &scene::dim2::character_controller::CharacterController,
+        desired_translation: Vector2<f32>,
+        dt: f32,
+    ) -> Option<CharacterMovement> {
+        let body_handle = controller.native_body.get();
+        self.bodies.get(body_handle)?;
+        let shape = self
+            .colliders
+            .get(controller.native_collider.get())?
+            .shared_shape()
+            .clone();
+        let pose = Pose2::from(isometry_from_global_transform(
+            &controller.global_transform(),
+        ));
+
+        // Moving platforms carry the character with them.
+        let mut desired_translation = desired_translation;
+        let previous_ground = controller.movement.ground_native;
+        if controller.is_follow_platforms() && controller.movement.grounded {
+            if let Some(platform) = self
+                .colliders
+                .get(previous_ground)
+                .and_then(|c| c.parent())
+                .and_then(|b| self.bodies.get(b))
+            {
+                if platform.is_dynamic() || platform.is_kinematic() {
+                    desired_translation +=
+                        Vector2::from(platform.velocity_at_point(pose.translation)) * dt;
+                }
+            }
+        }
+
+        let up = controller
+            .up()
+            .try_normalize(f32::EPSILON)
+            .unwrap_or_else(Vector2::y);
+        let native_controller = KinematicCharacterController {
+            up: up.into(),
+            offset: CharacterLength::Absolute(controller.offset()),
+            slide: controller.is_slide(),
+            autostep: (controller.step_height() > 0.0).then(|| CharacterAutostep {
+                max_height: CharacterLength::Absolute(controller.step_height()),
+                min_width: CharacterLength::Absolute(controller.step_min_width()),
+                include_dynamic_bodies: controller.is_step_on_dynamic_bodies(),
+            }),
+            max_slope_climb_angle: controller.max_slope_climb_angle(),
+            min_slope_slide_angle: controller.min_slope_slide_angle(),
+            snap_to_ground: (controller.snap_to_ground() > 0.0)
+                .then(|| CharacterLength::Absolute(controller.snap_to_ground())),
+            ..Default::default()
+        };
+
+        let groups = controller.collision_groups();
+        let filter = rapier2d::pipeline::QueryFilter::new()
+            .exclude_rigid_body(body_handle)
+            .exclude_sensors()
+            .groups(InteractionGroups::new(
+                u32_to_group(groups.memberships.0),
+                u32_to_group(groups.filter.0),
+                Default::default(),
+            ));
+
+        let mut native_collisions = Vec::new();
+        let query = self.broad_phase.as_query_pipeline(
+            &DefaultQueryDispatcher,
+            &self.bodies,
+            &self.colliders,
+            filter,
+        );
+        let movement = native_controller.move_shape(
+            dt,
+            &query,
+            &*shape,
+            &pose,
+            desired_translation.into(),
+            |collision| native_collisions.push(collision),
+        );
+
+        let min_ground_dot = controller.max_slope_climb_angle().cos();
+        let mut result = CharacterMovement {
+            translation: movement.translation.into(),
+            grounded: movement.grounded,
+            sliding_down_slope: movement.is_sliding_down_slope,
+            ..Default::default()
+        };
+        for collision in native_collisions.iter() {
+            let Some(collider) = self.colliders.get(collision.handle) else {
+                continue;
+            };
+            // Witness and normal of the hit are in the local space of the collider.
+            let collider_position = Isometry2::from(*collider.position());
+            let normal = collider_position.rotation * Vector2::from(collision.hit.normal1);
+            let position = collider_position
+                .transform_point(&Point2::from(Vector2::from(collision.hit.witness1)))
+                .coords;
+            if normal.dot(&up) >= min_ground_dot {
+                result.ground = Handle::decode_from_u128(collider.user_data);
+                result.ground_native = collision.handle;
+            }
+            result.collisions.push(CharacterCollision {
+                collider: Handle::decode_from_u128(collider.user_data),
+                position,
+                normal,
+            });
+        }
+        if result.grounded && result.ground.is_none() {
+            // Snapping to the ground does not generate collisions, keep the previous ground.
+            result.ground = controller.movement.ground;
+            result.ground_native = previous_ground;
+        } else if !result.grounded {
+            result.ground = Handle::NONE;
+            result.ground_native = ColliderHandle::invalid();
+        }
+
+        if controller.is_push_dynamic_bodies() && !native_collisions.is_empty() {
+            let mut query = self.broad_phase.as_query_pipeline_mut(
+                &DefaultQueryDispatcher,
+                &mut self.bodies,
+                &mut self.colliders,
+                filter,
+            );
+            native_controller.solve_character_collision_impulses(
+                dt,
+                &mut query,
+                &*shape,
+                controller.mass(),
+                native_collisions.iter(),
+            );
+        }
+
+        Some(result)
+    }
+
+    /// Captures the complete internal state of the physics world. See [`PhysicsWorldSnapshot`] docs
+    /// for more info.
+    pub fn snapshot(&self) -> PhysicsWorldSnapshot {
+        PhysicsWorldSnapshot {
+            gravity: *self.gravity,
+            time_accumulator: self.time_accumulator,
+            broad_phase: self.broad_phase.clone(),
+            narrow_phase: self.narrow_phase.clone(),
+            ccd_solver: self.ccd_solver.clone(),
+            islands: self.islands.clone(),
+            bodies: self.bodies.clone(),
+            colliders: self.colliders.clone(),
+            joints: self.joints.clone(),
+            multibody_joints: self.multibody_joints.clone(),
+        }
+    }
+
+    /// Restores the internal state of the physics world from the given snapshot. Collision events
+    /// of the last step are discarded, because they belong to the "future" of the snapshot.
+    ///
+    /// # Important notes
+    ///
+    /// The scene nodes keep handles of their native physics entities, so the snapshot must be
+    /// restored in the same graph it was captured from. Use [`crate::scene::graph::snapshot::PhysicsStateSnapshot`]
+    /// to restore physics together with scene transforms, it also reconciles the physics entities
+    /// with the nodes, that were added or removed after the capture.
+    pub fn restore_snapshot(&mut self, snapshot: &PhysicsWorldSnapshot) {
+        self.gravity.set_value_silent(snapshot.gravity);
+        self.time_accumulator = snapshot.time_accumulator;
+        self.broad_phase.clone_from(&snapshot.broad_phase);
+        self.narrow_phase.clone_from(&snapshot.narrow_phase);
+        self.ccd_solver.clone_from(&snapshot.ccd_solver);
+        self.islands.clone_from(&snapshot.islands);
+        self.bodies.clone_from(&snapshot.bodies);
+        self.colliders.clone_from(&snapshot.colliders);
+        self.joints.clone_from(&snapshot.joints);
+        self.multibody_joints.clone_from(&snapshot.multibody_joints);
+        self.collision_events.clear();
+        self.joint_break_events.clear();
+        self.event_collector.events.safe_lock().clear();
+    }
+
+    /// Makes physics entities consistent with scene nodes after a snapshot was restored: removes
+    /// native entities, whose nodes do not exist anymore, and resets native handles of nodes, whose
+    /// entities do not exist in the restored state, so they will be re-created on next sync.
+    pub(crate) fn reconcile_with_nodes(&mut self, nodes: &NodePool) {
+        let orphan_bodies = self
+            .bodies
+            .iter()
+            .filter(|(handle, body)| {
+                let owner = nodes.try_borrow(Handle::decode_from_u128(body.user_data));
+                !owner.is_ok_and(|owner| {
+                    owner
+                        .component_ref::<dim2::rigidbody::RigidBody>()
+                        .is_some_and(|b| b.native.get() == *handle)
+                        || owner
+                            .component_ref::<dim2::character_controller::CharacterController>()
+                            .is_some_and(|c| c.native_body.get() == *handle)
+                })
+            })
+            .map(|(handle, _)| handle)
+            .collect::<Vec<_>>();
+        for handle in orphan_bodies {
+            self.remove_body(handle);
+        }
+
+        let orphan_colliders = self
+            .colliders
+            .iter()
+            .filter(|(handle, collider)| {
+                let owner = nodes.try_borrow(Handle::decode_from_u128(collider.user_data));
+                !owner.is_ok_and(|owner| {
+                    owner
+                        .component_ref::<dim2::collider::Collider>()
+                        .is_some_and(|c| c.native.get() == *handle)
+                        || owner
+                            .component_ref::<dim2::character_controller::CharacterController>()
+                            .is_some_and(|c| c.native_collider.get() == *handle)
+                })
+            })
+            .map(|(handle, _)| handle)
+            .collect::<Vec<_>>();
+        for handle in orphan_colliders {
+            self.remove_collider(handle);
+        }
+
+        let orphan_joints = self
+            .joints
+            .map
+            .forward_map()
+            .iter()
+            .filter(|(handle, owner)| {
+                !nodes
+                    .try_borrow(**owner)
+                    .ok()
+                    .and_then(|owner| owner.component_ref::<dim2::joint::Joint>())
+                    .is_some_and(|j| j.native.get() == **handle)
+            })
+            .map(|(handle, _)| *handle)
+            .collect::<Vec<_>>();
+        for handle in orphan_joints {
+            self.remove_joint(handle);
+        }
+
+        for node in nodes.iter() {
+            if let Some(body) = node.component_ref::<dim2::rigidbody::RigidBody>() {
+                if self.bodies.get(body.native.get()).is_none() {
+                    body.native.set(RigidBodyHandle::invalid());
+                }
+            } else if let Some(collider) = node.component_ref::<dim2::collider::Collider>() {
+                if self.colliders.get(collider.native.get()).is_none() {
+                    collider.native.set(ColliderHandle::invalid());
+                }
+            } else if let Some(joint) = node.component_ref::<dim2::joint::Joint>() {
+                if self.joints.set.get(joint.native.get()).is_none() {
+                    joint.native.set(ImpulseJointHandle::invalid());
+                }
+            } else if let Some(controller) =
+                node.component_ref::<dim2::character_controller::CharacterController>()
+            {
+                if self.bodies.get(controller.native_body.get()).is_none() {
+                    controller.native_body.set(RigidBodyHandle::invalid());
+                    controller.native_collider.set(ColliderHandle::invalid());
+                }
+            }
+        }
+    }
+
     /// Intersections checks between regular colliders and sensor colliders
     pub(crate) fn intersections_with(
         &self,
@@ -1408,6 +1884,75 @@ impl PhysicsWorld {
             .contact_pairs()
             .filter_map(|c| ContactPair::from_native(c, self))
     }
+
+    /// Returns collision events generated by the last physics step. The events are removed from
+    /// the world when they're dispatched to scripts.
+    pub fn collision_events(&self) -> &[CollisionEvent] {
+        &self.collision_events
+    }
+
+    pub(crate) fn take_collision_events(&mut self) -> Vec<CollisionEvent> {
+        std::mem::take(&mut self.collision_events)
+    }
+
+    /// Returns joint break events, that were generated since the last dispatch of the events to
+    /// scripts.
+    pub fn joint_break_events(&self) -> &[JointBreakEvent] {
+        &self.joint_break_events
+    }
+
+    /// Returns a time step for the controllers (characters, vehicles), that are updated after the
+    /// physics. In deterministic mode it is the time simulated by the fixed steps of the last
+    /// update, so the controllers do not depend on the frame rate. Otherwise it is the frame time.
+    pub(crate) fn controller_dt(&self, frame_dt: f32) -> f32 {
+        if self.determinism.enabled {
+            self.last_update_dt
+        } else {
+            frame_dt
+        }
+    }
+
+    pub(crate) fn take_joint_break_events(&mut self) -> Vec<JointBreakEvent> {
+        std::mem::take(&mut self.joint_break_events)
+    }
+
+    /// Returns forces and impulses, that were applied by the given native joint at the last
+    /// physics step. Linear values use X and Y axes, angular values use Z axis only.
+    pub(crate) fn joint_feedback(&self, handle: ImpulseJointHandle) -> Option<JointFeedback> {
+        let native = self.joints.set.get(handle)?;
+        let impulses = native.impulses;
+        Some(JointFeedback::from_impulses(
+            Vector3::new(impulses[0], impulses[1], 0.0),
+            Vector3::new(0.0, 0.0, impulses[2]),
+            self.last_step_dt,
+        ))
+    }
+
+    /// Removes the given native joint and generates a break event for the joint node, that owns it.
+    pub(crate) fn break_joint(&mut self, handle: ImpulseJointHandle, feedback: JointFeedback) {
+        let Some(native) = self.joints.set.get(handle) else {
+            return;
+        };
+        let body_node = |body| {
+            self.bodies
+                .get(body)
+                .map(|b| Handle::decode_from_u128(b.user_data))
+                .unwrap_or_default()
+        };
+        let event = JointBreakEvent {
+            joint: self
+                .joints
+                .map
+                .value_of(&handle)
+                .cloned()
+                .unwrap_or_default(),
+            body1: body_node(native.body1),
+            body2: body_node(native.body2),
+            feedback,
+        };
+        self.remove_joint(handle);
+        self.joint_break_events.push(event);
+    }
 }
 
 impl Default for PhysicsWorld {
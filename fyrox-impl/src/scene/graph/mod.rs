@@ -87,6 +87,7 @@ use std::{
 
 pub mod event;
 pub mod physics;
+pub mod snapshot;
 
 /// Graph performance statistics. Allows you to find out "hot" parts of the scene graph, which
 /// parts takes the most time to update.
@@ -314,6 +315,9 @@ pub struct GraphUpdateSwitches {
     /// Whether the graph update is paused or not. Paused graphs won't be updated and their sound content will be also paused
     /// so it won't emit any sounds.
     pub paused: bool,
+    /// Enables or disables world streaming of the scene. See [`crate::scene::streaming::WorldStreamer`]
+    /// docs for more info.
+    pub streaming: bool,
 }
 
 impl Default for GraphUpdateSwitches {
@@ -325,6 +329,7 @@ impl Default for GraphUpdateSwitches {
             node_overrides: Default::default(),
             delete_dead_nodes: true,
             paused: false,
+            streaming: true,
         }
     }
 }
@@ -1496,6 +1501,8 @@ impl Graph {
                 );
             }
         }
+
+        self.sound_context.apply_reverb_zones();
     }
 
     /// Returns capacity of internal pool. Can be used to iterate over all **potentially**
@@ -40,10 +40,11 @@ use crate::{
     },
     scene::{
         self,
+        character_controller::{CharacterCollision, CharacterMovement},
         collider::{self, ColliderShape, GeometrySource},
         debug::SceneDrawingContext,
         graph::{isometric_global_transform, Graph, NodePool},
-        joint::{JointLocalFrames, JointMotorParams, JointParams},
+        joint::{JointFeedback, JointLocalFrames, JointMotorParams, JointParams},
         mesh::{
             buffer::{VertexAttributeUsage, VertexReadTrait},
             Mesh,
@@ -51,11 +52,16 @@ use crate::{
         node::{Node, NodeTrait},
         rigidbody::{self, ApplyAction, RigidBodyMassPropertiesType},
         terrain::{Chunk, Terrain},
+        vehicle::VehicleWheelState,
     },
     utils::raw_mesh::{RawMeshBuilder, RawVertex},
 };
 use rapier3d::geometry::Array2;
 use rapier3d::{
+    control::{
+        CharacterAutostep, CharacterLength, DynamicRayCastVehicleController,
+        KinematicCharacterController,
+    },
     dynamics::{
         CCDSolver, GenericJoint, GenericJointBuilder, ImpulseJointHandle, ImpulseJointSet,
         IslandManager, JointAxesMask, MultibodyJointHandle, MultibodyJointSet, RigidBody,
@@ -66,7 +72,7 @@ use rapier3d::{
         InteractionGroups, NarrowPhase, Ray, SharedShape,
     },
     parry::{query::ShapeCastOptions, shape::HeightField},
-    pipeline::{DebugRenderPipeline, EventHandler, PhysicsPipeline},
+    pipeline::{ActiveEvents, DebugRenderPipeline, EventHandler, PhysicsPipeline},
     prelude::{HeightFieldCellStatus, JointAxis, MassProperties},
 };
 use std::{
@@ -445,6 +451,127 @@ impl IntersectionPair {
     }
 }
 
+/// Kind of a collision event.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum CollisionEventKind {
+    /// Two colliders started touching each other.
+    ContactStarted,
+    /// Two colliders stopped touching each other.
+    ContactStopped,
+    /// A collider entered a sensor (or a sensor entered a collider).
+    SensorEntered,
+    /// A collider left a sensor (or a sensor left a collider).
+    SensorExited,
+}
+
+impl CollisionEventKind {
+    pub(crate) fn new(started: bool, sensor: bool) -> Self {
+        match (started, sensor) {
+            (true, false) => Self::ContactStarted,
+            (false, false) => Self::ContactStopped,
+            (true, true) => Self::SensorEntered,
+            (false, true) => Self::SensorExited,
+        }
+    }
+
+    /// Returns `true` if the event signals that the colliders started touching each other.
+    pub fn is_started(self) -> bool {
+        matches!(self, Self::ContactStarted | Self::SensorEntered)
+    }
+
+    /// Returns `true` if at least one of the colliders is a sensor.
+    pub fn is_sensor(self) -> bool {
+        matches!(self, Self::SensorEntered | Self::SensorExited)
+    }
+}
+
+/// A collision event between two colliders. The events are generated by a physics step and then
+/// dispatched to the scripts of the involved colliders and their rigid bodies (see
+/// [`crate::script::ScriptTrait::on_collision`]). The same structure is used for 2D and 3D
+/// physics.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct CollisionEvent {
+    /// Kind of the event.
+    pub kind: CollisionEventKind,
+    /// The first collider involved in the event. When the event is passed to a script, this is
+    /// the collider that belongs to the node of the script (either the node itself or its child
+    /// collider).
+    pub collider: Handle<Node>,
+    /// The second collider involved in the event. Could be [`Handle::NONE`], if the collider was
+    /// removed.
+    pub other_collider: Handle<Node>,
+}
+
+impl CollisionEvent {
+    /// Returns the same event, but with the colliders swapped.
+    #[inline]
+    pub fn swapped(self) -> Self {
+        Self {
+            kind: self.kind,
+            collider: self.other_collider,
+            other_collider: self.collider,
+        }
+    }
+}
+
+/// An event, that is generated when a joint breaks, because the force or the torque applied by it
+/// exceeded the break threshold of the joint (see [`scene::joint::Joint::set_break_force`] and
+/// [`scene::dim2::joint::Joint::set_break_force`]). The
+/// event is dispatched to the scripts of the joint and both of its rigid bodies (see
+/// [`crate::script::ScriptTrait::on_joint_break`]).
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct JointBreakEvent {
+    /// A handle of the joint node, that was broken.
+    pub joint: Handle<Node>,
+    /// A handle of the first rigid body of the joint. Could be [`Handle::NONE`], if the body was
+    /// removed.
+    pub body1: Handle<Node>,
+    /// A handle of the second rigid body of the joint. Could be [`Handle::NONE`], if the body was
+    /// removed.
+    pub body2: Handle<Node>,
+    /// Forces and impulses of the joint at the physics step, at which it was broken.
+    pub feedback: JointFeedback,
+}
+
+#[derive(Default)]
+struct CollisionEventCollector {
+    events: Mutex<Vec<CollisionEvent>>,
+}
+
+impl EventHandler for CollisionEventCollector {
+    fn handle_collision_event(
+        &self,
+        _bodies: &RigidBodySet,
+        colliders: &ColliderSet,
+        event: rapier3d::geometry::CollisionEvent,
+        _contact_pair: Option<&rapier3d::geometry::ContactPair>,
+    ) {
+        let node_handle = |collider| {
+            colliders
+                .get(collider)
+                .map(|c| Handle::decode_from_u128(c.user_data))
+                .unwrap_or_default()
+        };
+
+        self.events.safe_lock().push(CollisionEvent {
+            kind: CollisionEventKind::new(event.started(), event.sensor()),
+            collider: node_handle(event.collider1()),
+            other_collider: node_handle(event.collider2()),
+        });
+    }
+
+    fn handle_contact_force_event(
+        &self,
+        _dt: f32,
+        _bodies: &RigidBodySet,
+        _colliders: &ColliderSet,
+        _contact_pair: &rapier3d::geometry::ContactPair,
+        _total_force_magnitude: f32,
+    ) {
+    }
+}
+
+#[derive(Clone)]
 pub(super) struct Container<S, A>
 where
     A: Hash + Eq + Clone,
@@ -463,6 +590,7 @@ fn convert_joint_params(
         JointParams::FixedJoint(_) => JointAxesMask::LOCKED_FIXED_AXES,
         JointParams::PrismaticJoint(_) => JointAxesMask::LOCKED_PRISMATIC_AXES,
         JointParams::RevoluteJoint(_) => JointAxesMask::LOCKED_REVOLUTE_AXES,
+        JointParams::SpringJoint(_) => JointAxesMask::empty(),
     };
 
     let mut joint = GenericJointBuilder::new(locked_axis)
@@ -502,6 +630,13 @@ fn convert_joint_params(
                 joint.set_limits(JointAxis::AngX, [v.limits.start, v.limits.end]);
             }
         }
+        scene::joint::JointParams::SpringJoint(v) => {
+            // The distance between the anchors is driven by a force-based position motor on the
+            // coupled linear axes, which effectively makes it a damped spring.
+            joint.coupled_axes = JointAxesMask::LIN_AXES;
+            joint.set_motor_model(JointAxis::LinX, rapier3d::prelude::MotorModel::ForceBased);
+            joint.set_motor_position(JointAxis::LinX, v.rest_length, v.stiffness, v.damping);
+        }
     }
 
     joint
@@ -909,6 +1044,67 @@ impl Default for IntegrationParameters {
     }
 }
 
+/// Settings of the deterministic simulation mode of a physics world.
+///
+/// # Determinism guarantee
+///
+/// When the mode is enabled, the physics world is advanced only by whole steps of
+/// [`Self::fixed_timestep`] length, the frame time is accumulated between updates. Given the same
+/// binary (same build of the game for the same target), the same initial state and the same
+/// sequence of changes made by the game (forces, velocities, spawned and removed nodes, etc.),
+/// the simulation produces bit-identical results. Floating-point results are **not** guaranteed
+/// to be identical across different builds, compilers or CPU architectures, unless the engine is
+/// compiled with `enhanced_determinism` feature, that enables cross-platform determinism of the
+/// physics engine at the cost of some performance.
+///
+/// It is recommended to update the scene with the time step equal to [`Self::fixed_timestep`], so
+/// every update advances the simulation exactly by one step. This mode together with
+/// [`crate::scene::graph::snapshot::PhysicsStateSnapshot`] is the basis for rollback networking.
+#[derive(Copy, Clone, Visit, Reflect, Debug, PartialEq)]
+pub struct DeterminismSettings {
+    /// Whether the deterministic mode is enabled or not. Default is `false`.
+    pub enabled: bool,
+
+    /// Length of a single simulation step in seconds. Default is `1.0 / 60.0`.
+    #[reflect(min_value = 0.0001, step = 0.001)]
+    pub fixed_timestep: f32,
+
+    /// Maximum amount of steps, that can be performed in a single update. If the accumulated
+    /// time requires more steps, the rest of it is dropped, which prevents the simulation from
+    /// falling behind forever on slow machines. Default is `8`.
+    #[reflect(min_value = 1.0)]
+    pub max_steps_per_update: u32,
+}
+
+impl Default for DeterminismSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            fixed_timestep: 1.0 / 60.0,
+            max_steps_per_update: 8,
+        }
+    }
+}
+
+impl DeterminismSettings {
+    /// Adds the given time to the accumulator and returns the amount of fixed steps, that must
+    /// be performed.
+    pub(crate) fn advance(&self, accumulator: &mut f32, dt: f32) -> u32 {
+        let fixed_timestep = self.fixed_timestep.max(f32::EPSILON);
+        let max_steps = self.max_steps_per_update.max(1);
+        *accumulator += dt;
+        let mut steps = 0;
+        while *accumulator >= fixed_timestep && steps < max_steps {
+            *accumulator -= fixed_timestep;
+            steps += 1;
+        }
+        if steps == max_steps {
+            *accumulator = accumulator.min(fixed_timestep);
+        }
+        steps
+    }
+}
+
 /// Physics world is responsible for physics simulation in the engine. There is a very few public
 /// methods, mostly for ray casting. You should add physical entities using scene graph nodes, such
 /// as RigidBody, Collider, Joint.
@@ -924,6 +1120,11 @@ pub struct PhysicsWorld {
     /// Current gravity vector. Default is (0.0, -9.81, 0.0)
     pub gravity: InheritableVariable<Vector3<f32>>,
 
+    /// Settings of the deterministic simulation mode. See [`DeterminismSettings`] docs for more
+    /// info.
+    #[visit(optional)]
+    pub determinism: InheritableVariable<DeterminismSettings>,
+
     /// Performance statistics of a single simulation step.
     #[visit(skip)]
     #[reflect(hidden)]
@@ -969,7 +1170,27 @@ pub struct PhysicsWorld {
     // Event handler collects info about contacts and proximity events.
     #[visit(skip)]
     #[reflect(hidden)]
-    event_handler: Box<dyn EventHandler>,
+    event_collector: CollisionEventCollector,
+    // Collision events generated by the last step.
+    #[visit(skip)]
+    #[reflect(hidden)]
+    collision_events: Vec<CollisionEvent>,
+    // Joints, that were broken since the last dispatch of the events.
+    #[visit(skip)]
+    #[reflect(hidden)]
+    joint_break_events: Vec<JointBreakEvent>,
+    // Time step of the last simulated physics step, used to convert joint impulses to forces.
+    #[visit(skip)]
+    #[reflect(hidden)]
+    last_step_dt: f32,
+    // Total time, that was simulated by the last update (all the steps together).
+    #[visit(skip)]
+    #[reflect(hidden)]
+    last_update_dt: f32,
+    // Time, that was not yet simulated in deterministic mode.
+    #[visit(skip)]
+    #[reflect(hidden)]
+    time_accumulator: f32,
     #[visit(skip)]
     #[reflect(hidden)]
     debug_render_pipeline: Mutex<DebugRenderPipeline>,
@@ -981,11 +1202,41 @@ impl Clone for PhysicsWorld {
             enabled: self.enabled.clone(),
             integration_parameters: self.integration_parameters.clone(),
             gravity: self.gravity.clone(),
+            determinism: self.determinism.clone(),
             ..Default::default()
         }
     }
 }
 
+/// A copy of the complete internal state of a [`PhysicsWorld`]: rigid bodies, colliders, joints,
+/// contacts (including the impulses, that are used to warm-start the solver), broad phase, islands
+/// and CCD state. Restoring the snapshot brings the world to exactly the same state it was at the
+/// moment of capture, so the simulation can be re-run from that moment.
+#[derive(Clone)]
+pub struct PhysicsWorldSnapshot {
+    gravity: Vector3<f32>,
+    time_accumulator: f32,
+    broad_phase: DefaultBroadPhase,
+    narrow_phase: NarrowPhase,
+    ccd_solver: CCDSolver,
+    islands: IslandManager,
+    bodies: RigidBodySet,
+    colliders: ColliderSet,
+    joints: Container<ImpulseJointSet, ImpulseJointHandle>,
+    multibody_joints: Container<MultibodyJointSet, MultibodyJointHandle>,
+}
+
+impl Debug for PhysicsWorldSnapshot {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "PhysicsWorldSnapshot {{ bodies: {}, colliders: {} }}",
+            self.bodies.len(),
+            self.colliders.len()
+        )
+    }
+}
+
 fn isometry_from_global_transform(transform: &Matrix4<f32>) -> Isometry3<f32> {
     Isometry3 {
         translation: Translation3::new(transform[12], transform[13], transform[14]),
@@ -1079,7 +1330,13 @@ impl PhysicsWorld {
                 set: MultibodyJointSet::new(),
                 map: Default::default(),
             },
-            event_handler: Box::new(()),
+            event_collector: Default::default(),
+            collision_events: Default::default(),
+            joint_break_events: Default::default(),
+            last_step_dt: 0.0,
+            last_update_dt: 0.0,
+            time_accumulator: 0.0,
+            determinism: Default::default(),
             performance_statistics: Default::default(),
             debug_render_pipeline: Default::default(),
         }
@@ -1109,7 +1366,19 @@ impl PhysicsWorld {
             0.0
         };
 
+        self.last_update_dt = 0.0;
+
         if *self.enabled {
+            let determinism = *self.determinism;
+            let (steps, dt) = if determinism.enabled {
+                (
+                    determinism.advance(&mut self.time_accumulator, dt),
+                    determinism.fixed_timestep,
+                )
+            } else {
+                (1, dt)
+            };
+
             let integration_parameters = rapier3d::dynamics::IntegrationParameters {
                 dt,
                 min_ccd_dt: self.integration_parameters.min_ccd_dt,
@@ -1133,22 +1402,31 @@ impl PhysicsWorld {
                 friction_model: FrictionModel::default(),
             };
 
-            self.pipeline.step(
-                (*self.gravity).into(),
-                &integration_parameters,
-                &mut self.islands,
-                &mut self.broad_phase,
-                &mut self.narrow_phase,
-                &mut self.bodies,
-                &mut self.colliders,
-                &mut self.joints.set,
-                &mut self.multibody_joints.set,
-                &mut self.ccd_solver,
-                &(),
-                &*self.event_handler,
-            );
+            if steps > 0 {
+                self.last_step_dt = dt;
+            }
+            self.last_update_dt = steps as f32 * dt;
+
+            for _ in 0..steps {
+                self.pipeline.step(
+                    (*self.gravity).into(),
+                    &integration_parameters,
+                    &mut self.islands,
+                    &mut self.broad_phase,
+                    &mut self.narrow_phase,
+                    &mut self.bodies,
+                    &mut self.colliders,
+                    &mut self.joints.set,
+                    &mut self.multibody_joints.set,
+                    &mut self.ccd_solver,
+                    &(),
+                    &self.event_collector,
+                );
+            }
         }
 
+        self.collision_events = std::mem::take(&mut *self.event_collector.events.safe_lock());
+
         self.performance_statistics.step_time += instant::Instant::now() - time;
     }
 
@@ -1749,7 +2027,8 @@ impl PhysicsWorld {
                             u32_to_group(collider_node.solver_groups().filter.0),
                             Default::default(),
                         ))
-                        .sensor(collider_node.is_sensor());
+                        .sensor(collider_node.is_sensor())
+                        .active_events(ActiveEvents::COLLISION_EVENTS);
 
                     if let Some(density) = collider_node.density() {
                         builder = builder.density(density);
@@ -1778,7 +2057,7 @@ impl PhysicsWorld {
         handle: Handle<Node>,
         joint: &scene::joint::Joint,
     ) {
-        if !joint.is_globally_enabled() {
+        if !joint.is_globally_enabled() || joint.is_broken() {
             self.remove_joint(joint.native.get());
             joint.native.set(ImpulseJointHandle(Default::default()));
             return;
@@ -1919,6 +2198,404 @@ impl PhysicsWorld {
         }
     }
 
+    pub(crate) fn set_character_controller_position(
+        &mut self,
+        controller: &scene::character_controller::CharacterController,
+        new_global_transform: &Matrix4<f32>,
+    ) {
+        if let Some(native) = self.bodies.get_mut(controller.native_body.get()) {
+            native.set_next_kinematic_translation(
+                Vector3::new(
+                    new_global_transform[12],
+                    new_global_transform[13],
+                    new_global_transform[14],
+                )
+                .into(),
+            );
+        }
+    }
+
+    pub(crate) fn sync_to_character_controller_node(
+        &mut self,
+        handle: Handle<Node>,
+        controller: &scene::character_controller::CharacterController,
+    ) {
+        if !controller.is_globally_enabled() {
+            self.remove_body(controller.native_body.get());
+            controller.native_body.set(Default::default());
+            controller.native_collider.set(Default::default());
+            return;
+        }
+
+        if self.bodies.get(controller.native_body.get()).is_some() {
+            let needs_sync = controller.radius.need_sync()
+                || controller.half_height.need_sync()
+                || controller.collision_groups.need_sync();
+            if needs_sync {
+                if let Some(native) = self.colliders.get_mut(controller.native_collider.get()) {
+                    // Both values must be synced, do not short-circuit.
+                    let radius_changed = controller.radius.try_sync_model(|_| {});
+                    let half_height_changed = controller.half_height.try_sync_model(|_| {});
+                    if radius_changed || half_height_changed {
+                        native.set_shape(SharedShape::capsule_y(
+                            controller.half_height(),
+                            controller.radius(),
+                        ));
+                    }
+                    controller.collision_groups.try_sync_model(|v| {
+                        native.set_collision_groups(InteractionGroups::new(
+                            u32_to_group(v.memberships.0),
+                            u32_to_group(v.filter.0),
+                            Default::default(),
+                        ))
+                    });
+                }
+            }
+        } else {
+            let position = controller.global_position();
+            let body = RigidBodyBuilder::kinematic_position_based()
+                .translation(position.into())
+                .lock_rotations()
+                .build();
+            let body_handle = self.add_body(handle, body);
+
+            let groups = controller.collision_groups();
+            let collider = ColliderBuilder::new(SharedShape::capsule_y(
+                controller.half_height(),
+                controller.radius(),
+            ))
+            .collision_groups(InteractionGroups::new(
+                u32_to_group(groups.memberships.0),
+                u32_to_group(groups.filter.0),
+                Default::default(),
+            ))
+            .active_events(ActiveEvents::COLLISION_EVENTS)
+            .build();
+            let collider_handle = self.add_collider(handle, body_handle, collider);
+
+            controller.native_body.set(body_handle);
+            controller.native_collider.set(collider_handle);
+
+            Log::writeln(
+                MessageKind::Information,
+                format!(
+                    "Native character controller was created for node {}",
+                    controller.name()
+                ),
+            );
+        }
+    }
+
+    /// Moves the given character controller by the desired translation, taking obstacles, slopes
+    /// and steps into account. Returns [`None`] if the controller has no native representation.
+    pub(crate) fn move_character(
+        &mut self,
+        controller: &scene::character_controller::CharacterController,
+        desired_translation: Vector3<f32>,
+        dt: f32,
+    ) -> Option<CharacterMovement> {
+        let body_handle = controller.native_body.get();
+        self.bodies.get(body_handle)?;
+        let shape = self
+            .colliders
+            .get(controller.native_collider.get())?
+            .shared_shape()
+            .clone();
+        let pose = Pose3::from(isometry_from_global_transform(
+            &controller.global_transform(),
+        ));
+
+        // Moving platforms carry the character with them.
+        let mut desired_translation = desired_translation;
+        let previous_ground = controller.movement.ground_native;
+        if controller.is_follow_platforms() && controller.movement.grounded {
+            if let Some(platform) = self
+                .colliders
+                .get(previous_ground)
+                .and_then(|c| c.parent())
+                .and_then(|b| self.bodies.get(b))
+            {
+                if platform.is_dynamic() || platform.is_kinematic() {
+                    desired_translation +=
+                        Vector3::from(platform.velocity_at_point(pose.translation)) * dt;
+                }
+            }
+        }
+
+        let up = controller
+            .up()
+            .try_normalize(f32::EPSILON)
+            .unwrap_or_else(Vector3::y);
+        let native_controller = KinematicCharacterController {
+            up: up.into(),
+            offset: CharacterLength::Absolute(controller.offset()),
+            slide: controller.is_slide(),
+            autostep: (controller.step_height() > 0.0).then(|| CharacterAutostep {
+                max_height: CharacterLength::Absolute(controller.step_height()),
+                min_width: CharacterLength::Absolute(controller.step_min_width()),
+                include_dynamic_bodies: controller.is_step_on_dynamic_bodies(),
+            }),
+            max_slope_climb_angle: controller.max_slope_climb_angle(),
+            min_slope_slide_angle: controller.min_slope_slide_angle(),
+            snap_to_ground: (controller.snap_to_ground() > 0.0)
+                .then(|| CharacterLength::Absolute(controller.snap_to_ground())),
+            ..Default::default()
+        };
+
+        let groups = controller.collision_groups();
+        let filter = rapier3d::pipeline::QueryFilter::new()
+            .exclude_rigid_body(body_handle)
+            .exclude_sensors()
+            .groups(InteractionGroups::new(
+                u32_to_group(groups.memberships.0),
+                u32_to_group(groups.filter.0),
+                Default::default(),
+            ));
+
+        let mut native_collisions = Vec::new();
+        let query = self.broad_phase.as_query_pipeline(
+            &DefaultQueryDispatcher,
+            &self.bodies,
+            &self.colliders,
+            filter,
+        );
+        let movement = native_controller.move_shape(
+            dt,
+            &query,
+            &*shape,
+            &pose,
+            desired_translation.into(),
+            |collision| native_collisions.push(collision),
+        );
+
+        let min_ground_dot = controller.max_slope_climb_angle().cos();
+        let mut result = CharacterMovement {
+            translation: movement.translation.into(),
+            grounded: movement.grounded,
+            sliding_down_slope: movement.is_sliding_down_slope,
+            ..Default::default()
+        };
+        for collision in native_collisions.iter() {
+            let Some(collider) = self.colliders.get(collision.handle) else {
+                continue;
+            };
+            // Witness and normal of the hit are in the local space of the collider.
+            let collider_position = Isometry3::from(*collider.position());
+            let normal = collider_position.rotation * Vector3::from(collision.hit.normal1);
+            let position = collider_position
+                .transform_point(&Point3::from(Vector3::from(collision.hit.witness1)))
+                .coords;
+            if normal.dot(&up) >= min_ground_dot {
+                result.ground = Handle::decode_from_u128(collider.user_data);
+                result.ground_native = collision.handle;
+            }
+            result.collisions.push(CharacterCollision {
+                collider: Handle::decode_from_u128(collider.user_data),
+                position,
+                normal,
+            });
+        }
+        if result.grounded && result.ground.is_none() {
+            // Snapping to the ground does not generate collisions, keep the previous ground.
+            result.ground = controller.movement.ground;
+            result.ground_native = previous_ground;
+        } else if !result.grounded {
+            result.ground = Handle::NONE;
+            result.ground_native = ColliderHandle::invalid();
+        }
+
+        if controller.is_push_dynamic_bodies() && !native_collisions.is_empty() {
+            let mut query = self.broad_phase.as_query_pipeline_mut(
+                &DefaultQueryDispatcher,
+                &mut self.bodies,
+                &mut self.colliders,
+                filter,
+            );
+            native_controller.solve_character_collision_impulses(
+                dt,
+                &mut query,
+                &*shape,
+                controller.mass(),
+                native_collisions.iter(),
+            );
+        }
+
+        Some(result)
+    }
+
+    /// Updates the given vehicle controller and writes the states of its wheels into the given
+    /// storage. Returns the current forward speed of the vehicle.
+    pub(crate) fn update_vehicle(
+        &mut self,
+        controller: &mut DynamicRayCastVehicleController,
+        dt: f32,
+        wheel_states: &mut Vec<VehicleWheelState>,
+    ) -> f32 {
+        wheel_states.clear();
+
+        if self.bodies.get(controller.chassis).is_none() {
+            return 0.0;
+        }
+
+        let filter = rapier3d::pipeline::QueryFilter::new()
+            .exclude_rigid_body(controller.chassis)
+            .exclude_sensors();
+        let query = self.broad_phase.as_query_pipeline_mut(
+            &DefaultQueryDispatcher,
+            &mut self.bodies,
+            &mut self.colliders,
+            filter,
+        );
+        controller.update_vehicle(dt, query);
+
+        for wheel in controller.wheels() {
+            let info = wheel.raycast_info();
+            wheel_states.push(VehicleWheelState {
+                in_contact: info.is_in_contact,
+                ground: info
+                    .ground_object
+                    .and_then(|h| self.colliders.get(h))
+                    .map(|c| Handle::decode_from_u128(c.user_data))
+                    .unwrap_or_default(),
+                contact_point: info.contact_point_ws.into(),
+                contact_normal: info.contact_normal_ws.into(),
+                suspension_length: info.suspension_length,
+                steering: wheel.steering,
+                rotation: wheel.rotation,
+            });
+        }
+
+        controller.current_vehicle_speed()
+    }
+
+    /// Captures the complete internal state of the physics world. See [`PhysicsWorldSnapshot`] docs
+    /// for more info.
+    pub fn snapshot(&self) -> PhysicsWorldSnapshot {
+        PhysicsWorldSnapshot {
+            gravity: *self.gravity,
+            time_accumulator: self.time_accumulator,
+            broad_phase: self.broad_phase.clone(),
+            narrow_phase: self.narrow_phase.clone(),
+            ccd_solver: self.ccd_solver.clone(),
+            islands: self.islands.clone(),
+            bodies: self.bodies.clone(),
+            colliders: self.colliders.clone(),
+            joints: self.joints.clone(),
+            multibody_joints: self.multibody_joints.clone(),
+        }
+    }
+
+    /// Restores the internal state of the physics world from the given snapshot. Collision events
+    /// of the last step are discarded, because they belong to the "future" of the snapshot.
+    ///
+    /// # Important notes
+    ///
+    /// The scene nodes keep handles of their native physics entities, so the snapshot must be
+    /// restored in the same graph it was captured from. Use [`crate::scene::graph::snapshot::PhysicsStateSnapshot`]
+    /// to restore physics together with scene transforms, it also reconciles the physics entities
+    /// with the nodes, that were added or removed after the capture.
+    pub fn restore_snapshot(&mut self, snapshot: &PhysicsWorldSnapshot) {
+        self.gravity.set_value_silent(snapshot.gravity);
+        self.time_accumulator = snapshot.time_accumulator;
+        self.broad_phase.clone_from(&snapshot.broad_phase);
+        self.narrow_phase.clone_from(&snapshot.narrow_phase);
+        self.ccd_solver.clone_from(&snapshot.ccd_solver);
+        self.islands.clone_from(&snapshot.islands);
+        self.bodies.clone_from(&snapshot.bodies);
+        self.colliders.clone_from(&snapshot.colliders);
+        self.joints.clone_from(&snapshot.joints);
+        self.multibody_joints.clone_from(&snapshot.multibody_joints);
+        self.collision_events.clear();
+        self.joint_break_events.clear();
+        self.event_collector.events.safe_lock().clear();
+    }
+
+    /// Makes physics entities consistent with scene nodes after a snapshot was restored: removes
+    /// native entities, whose nodes do not exist anymore, and resets native handles of nodes, whose
+    /// entities do not exist in the restored state, so they will be re-created on next sync.
+    pub(crate) fn reconcile_with_nodes(&mut self, nodes: &NodePool) {
+        let orphan_bodies = self
+            .bodies
+            .iter()
+            .filter(|(handle, body)| {
+                let owner = nodes.try_borrow(Handle::decode_from_u128(body.user_data));
+                !owner.is_ok_and(|owner| {
+                    owner
+                        .component_ref::<rigidbody::RigidBody>()
+                        .is_some_and(|b| b.native.get() == *handle)
+                        || owner
+                            .component_ref::<scene::character_controller::CharacterController>()
+                            .is_some_and(|c| c.native_body.get() == *handle)
+                })
+            })
+            .map(|(handle, _)| handle)
+            .collect::<Vec<_>>();
+        for handle in orphan_bodies {
+            self.remove_body(handle);
+        }
+
+        let orphan_colliders = self
+            .colliders
+            .iter()
+            .filter(|(handle, collider)| {
+                let owner = nodes.try_borrow(Handle::decode_from_u128(collider.user_data));
+                !owner.is_ok_and(|owner| {
+                    owner
+                        .component_ref::<collider::Collider>()
+                        .is_some_and(|c| c.native.get() == *handle)
+                        || owner
+                            .component_ref::<scene::character_controller::CharacterController>()
+                            .is_some_and(|c| c.native_collider.get() == *handle)
+                })
+            })
+            .map(|(handle, _)| handle)
+            .collect::<Vec<_>>();
+        for handle in orphan_colliders {
+            self.remove_collider(handle);
+        }
+
+        let orphan_joints = self
+            .joints
+            .map
+            .forward_map()
+            .iter()
+            .filter(|(handle, owner)| {
+                !nodes
+                    .try_borrow(**owner)
+                    .ok()
+                    .and_then(|owner| owner.component_ref::<scene::joint::Joint>())
+                    .is_some_and(|j| j.native.get() == **handle)
+            })
+            .map(|(handle, _)| *handle)
+            .collect::<Vec<_>>();
+        for handle in orphan_joints {
+            self.remove_joint(handle);
+        }
+
+        for node in nodes.iter() {
+            if let Some(body) = node.component_ref::<rigidbody::RigidBody>() {
+                if self.bodies.get(body.native.get()).is_none() {
+                    body.native.set(RigidBodyHandle::invalid());
+                }
+            } else if let Some(collider) = node.component_ref::<collider::Collider>() {
+                if self.colliders.get(collider.native.get()).is_none() {
+                    collider.native.set(ColliderHandle::invalid());
+                }
+            } else if let Some(joint) = node.component_ref::<scene::joint::Joint>() {
+                if self.joints.set.get(joint.native.get()).is_none() {
+                    joint.native.set(ImpulseJointHandle::invalid());
+                }
+            } else if let Some(controller) =
+                node.component_ref::<scene::character_controller::CharacterController>()
+            {
+                if self.bodies.get(controller.native_body.get()).is_none() {
+                    controller.native_body.set(RigidBodyHandle::invalid());
+                    controller.native_collider.set(ColliderHandle::invalid());
+                }
+            }
+        }
+    }
+
     /// Intersections checks between regular colliders and sensor colliders
     pub(crate) fn intersections_with(
         &self,
@@ -1953,6 +2630,75 @@ impl PhysicsWorld {
             .contact_pairs()
             .filter_map(|c| ContactPair::from_native(c, self))
     }
+
+    /// Returns collision events generated by the last physics step. The events are removed from
+    /// the world when they're dispatched to scripts.
+    pub fn collision_events(&self) -> &[CollisionEvent] {
+        &self.collision_events
+    }
+
+    pub(crate) fn take_collision_events(&mut self) -> Vec<CollisionEvent> {
+        std::mem::take(&mut self.collision_events)
+    }
+
+    /// Returns joint break events, that were generated since the last dispatch of the events to
+    /// scripts.
+    pub fn joint_break_events(&self) -> &[JointBreakEvent] {
+        &self.joint_break_events
+    }
+
+    /// Returns a time step for the controllers (characters, vehicles), that are updated after the
+    /// physics. In deterministic mode it is the time simulated by the fixed steps of the last
+    /// update, so the controllers do not depend on the frame rate. Otherwise it is the frame time.
+    pub(crate) fn controller_dt(&self, frame_dt: f32) -> f32 {
+        if self.determinism.enabled {
+            self.last_update_dt
+        } else {
+            frame_dt
+        }
+    }
+
+    pub(crate) fn take_joint_break_events(&mut self) -> Vec<JointBreakEvent> {
+        std::mem::take(&mut self.joint_break_events)
+    }
+
+    /// Returns forces and impulses, that were applied by the given native joint at the last
+    /// physics step.
+    pub(crate) fn joint_feedback(&self, handle: ImpulseJointHandle) -> Option<JointFeedback> {
+        let native = self.joints.set.get(handle)?;
+        let impulses = native.impulses;
+        Some(JointFeedback::from_impulses(
+            Vector3::new(impulses[0], impulses[1], impulses[2]),
+            Vector3::new(impulses[3], impulses[4], impulses[5]),
+            self.last_step_dt,
+        ))
+    }
+
+    /// Removes the given native joint and generates a break event for the joint node, that owns it.
+    pub(crate) fn break_joint(&mut self, handle: ImpulseJointHandle, feedback: JointFeedback) {
+        let Some(native) = self.joints.set.get(handle) else {
+            return;
+        };
+        let body_node = |body| {
+            self.bodies
+                .get(body)
+                .map(|b| Handle::decode_from_u128(b.user_data))
+                .unwrap_or_default()
+        };
+        let event = JointBreakEvent {
+            joint: self
+                .joints
+                .map
+                .value_of(&handle)
+                .cloned()
+                .unwrap_or_default(),
+            body1: body_node(native.body1),
+            body2: body_node(native.body2),
+            feedback,
+        };
+        self.remove_joint(handle);
+        self.joint_break_events.push(event);
+    }
 }
 
 impl Default for PhysicsWorld {
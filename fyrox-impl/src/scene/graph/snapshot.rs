@@ -0,0 +1,444 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Physics state snapshots allow you to capture and restore the state of the physics simulation
+//! of a graph. See [`PhysicsStateSnapshot`] docs for more info.
+
+use crate::{
+    core::{
+        algebra::{Matrix4, Vector2, Vector3},
+        pool::Handle,
+    },
+    graph::SceneGraph,
+    scene::{
+        character_controller::{CharacterController, CharacterMovement},
+        dim2,
+        graph::{physics::PhysicsWorldSnapshot, Graph},
+        joint::{Joint, JointFeedback},
+        node::Node,
+        transform::Transform,
+        vehicle::{Vehicle, VehicleWheelState},
+    },
+};
+use rapier3d::control::Wheel;
+
+#[derive(Clone, Debug)]
+struct TransformRecord {
+    node: Handle<Node>,
+    local_transform: Transform,
+    global_transform: Matrix4<f32>,
+}
+
+// State of a joint node, that is changed by the simulation. `H` is a handle of the native joint.
+#[derive(Clone, Debug)]
+struct JointRecord<H> {
+    node: Handle<Node>,
+    native: H,
+    broken: bool,
+    feedback: JointFeedback,
+}
+
+// State of a character controller node, that is changed by its movement. It includes whether the
+// character is grounded and the platform it follows. `V` is a translation vector, `M` is a result of
+// the movement.
+#[derive(Clone, Debug)]
+struct CharacterControllerRecord<V, M> {
+    node: Handle<Node>,
+    desired_translation: V,
+    movement: M,
+}
+
+// State of a vehicle node and its native wheels (rotation, suspension, contacts).
+#[derive(Clone, Debug)]
+struct VehicleRecord {
+    node: Handle<Node>,
+    native_wheels: Vec<Wheel>,
+    speed: f32,
+    wheel_states: Vec<VehicleWheelState>,
+}
+
+/// A snapshot of the complete physics state of a graph: both physics worlds (3D and 2D), transforms
+/// of the scene nodes, the state of the joints (whether a joint is broken and its force feedback),
+/// the state of the character controllers (whether a character is grounded and the platform it
+/// follows) and the state of the vehicles. It is intended to be captured every frame and restored
+/// when a frame must be re-simulated, for example after a late input arrived from a remote player.
+///
+/// The snapshot does not include the state of scripts, animations and other non-physical
+/// entities, it is up to the game to save and restore them (if needed).
+///
+/// # Determinism
+///
+/// Re-simulation produces the same results as the original simulation only if the physics worlds
+/// run in the deterministic mode (see [`crate::scene::graph::physics::DeterminismSettings`]) and
+/// the game applies the same inputs in the same order.
+///
+/// # Example
+///
+/// ```rust
+/// use fyrox_impl::{
+///     core::algebra::Vector2,
+///     scene::graph::{snapshot::PhysicsStateSnapshot, Graph},
+/// };
+///
+/// fn resimulate(graph: &mut Graph, snapshot: &PhysicsStateSnapshot, frames: usize, dt: f32) {
+///     snapshot.restore(graph);
+///     for _ in 0..frames {
+///         // Apply the inputs of the frame here.
+///         graph.update(Vector2::new(800.0, 600.0), dt, Default::default());
+///     }
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct PhysicsStateSnapshot {
+    physics: PhysicsWorldSnapshot,
+    physics2d: dim2::physics::PhysicsWorldSnapshot,
+    transforms: Vec<TransformRecord>,
+    joints: Vec<JointRecord<rapier3d::dynamics::ImpulseJointHandle>>,
+    joints2d: Vec<JointRecord<rapier2d::dynamics::ImpulseJointHandle>>,
+    character_controllers: Vec<CharacterControllerRecord<Vector3<f32>, CharacterMovement>>,
+    character_controllers2d:
+        Vec<CharacterControllerRecord<Vector2<f32>, dim2::character_controller::CharacterMovement>>,
+    vehicles: Vec<VehicleRecord>,
+}
+
+impl PhysicsStateSnapshot {
+    /// Captures the physics state of the given graph.
+    pub fn capture(graph: &Graph) -> Self {
+        Self {
+            physics: graph.physics.snapshot(),
+            physics2d: graph.physics2d.snapshot(),
+            transforms: graph
+                .pair_iter()
+                .map(|(node, node_ref)| TransformRecord {
+                    node,
+                    local_transform: node_ref.local_transform().clone(),
+                    global_transform: node_ref.global_transform(),
+                })
+                .collect(),
+            joints: graph
+                .pair_iter()
+                .filter_map(|(node, node_ref)| {
+                    let joint = node_ref.cast::<Joint>()?;
+                    Some(JointRecord {
+                        node,
+                        native: joint.native.get(),
+                        broken: joint.broken,
+                        feedback: joint.feedback,
+                    })
+                })
+                .collect(),
+            joints2d: graph
+                .pair_iter()
+                .filter_map(|(node, node_ref)| {
+                    let joint = node_ref.cast::<dim2::joint::Joint>()?;
+                    Some(JointRecord {
+                        node,
+                        native: joint.native.get(),
+                        broken: joint.broken,
+                        feedback: joint.feedback,
+                    })
+                })
+                .collect(),
+            character_controllers: graph
+                .pair_iter()
+                .filter_map(|(node, node_ref)| {
+                    let controller = node_ref.cast::<CharacterController>()?;
+                    Some(CharacterControllerRecord {
+                        node,
+                        desired_translation: controller.desired_translation,
+                        movement: controller.movement.clone(),
+                    })
+                })
+                .collect(),
+            character_controllers2d: graph
+                .pair_iter()
+                .filter_map(|(node, node_ref)| {
+                    let controller =
+                        node_ref.cast::<dim2::character_controller::CharacterController>()?;
+                    Some(CharacterControllerRecord {
+                        node,
+                        desired_translation: controller.desired_translation,
+                        movement: controller.movement.clone(),
+                    })
+                })
+                .collect(),
+            vehicles: graph
+                .pair_iter()
+                .filter_map(|(node, node_ref)| {
+                    let vehicle = node_ref.cast::<Vehicle>()?;
+                    Some(VehicleRecord {
+                        node,
+                        native_wheels: vehicle
+                            .native
+                            .controller
+                            .as_ref()
+                            .map(|c| c.wheels().to_vec())
+                            .unwrap_or_default(),
+                        speed: vehicle.speed,
+                        wheel_states: vehicle.wheel_states.clone(),
+                    })
+                })
+                .collect(),
+        }
+    }
+
+    /// Restores the physics state of the given graph. The graph must be the same graph the snapshot
+    /// was captured from. Nodes, that were added after the capture keep their current state, physics
+    /// entities of nodes, that were removed after the capture are removed from the physics worlds.
+    pub fn restore(&self, graph: &mut Graph) {
+        graph.physics.restore_snapshot(&self.physics);
+        graph.physics2d.restore_snapshot(&self.physics2d);
+
+        for record in self.transforms.iter() {
+            if let Ok(node) = graph.try_get_mut(record.node) {
+                // Global transform is restored as well, so the comparisons with previous global
+                // transform made on the next update will behave exactly as they did originally.
+                node.set_local_transform(record.local_transform.clone());
+                node.global_transform.set(record.global_transform);
+            }
+        }
+
+        // Joints could be broken (and their native joints removed) after the capture, native
+        // handles are restored as well, so the restored native joints will not be treated as
+        // orphans.
+        for record in self.joints.iter() {
+            if let Ok(joint) = graph.try_get_mut_of_type::<Joint>(record.node) {
+                joint.native.set(record.native);
+                joint.broken = record.broken;
+                joint.feedback = record.feedback;
+            }
+        }
+        for record in self.joints2d.iter() {
+            if let Ok(joint) = graph.try_get_mut_of_type::<dim2::joint::Joint>(record.node) {
+                joint.native.set(record.native);
+                joint.broken = record.broken;
+                joint.feedback = record.feedback;
+            }
+        }
+
+        // The movement of a character depends on the result of the previous movement: grounded
+        // characters are carried by the platforms they stand on.
+        for record in self.character_controllers.iter() {
+            if let Ok(controller) = graph.try_get_mut_of_type::<CharacterController>(record.node) {
+                controller.desired_translation = record.desired_translation;
+                controller.movement = record.movement.clone();
+            }
+        }
+        for record in self.character_controllers2d.iter() {
+            if let Ok(controller) = graph
+                .try_get_mut_of_type::<dim2::character_controller::CharacterController>(record.node)
+            {
+                controller.desired_translation = record.desired_translation;
+                controller.movement = record.movement.clone();
+            }
+        }
+
+        for record in self.vehicles.iter() {
+            if let Ok(vehicle) = graph.try_get_mut_of_type::<Vehicle>(record.node) {
+                if let Some(controller) = vehicle.native.controller.as_mut() {
+                    // The native vehicle could be rebuilt after the capture, the wheels are restored
+                    // only if they still match.
+                    if controller.wheels().len() == record.native_wheels.len() {
+                        controller
+                            .wheels_mut()
+                            .clone_from_slice(&record.native_wheels);
+                    }
+                }
+                vehicle.speed = record.speed;
+                vehicle.wheel_states.clone_from(&record.wheel_states);
+            }
+        }
+
+        graph.physics.reconcile_with_nodes(&graph.pool);
+        graph.physics2d.reconcile_with_nodes(&graph.pool);
+    }
+
+    /// Returns the snapshot of the 3D physics world.
+    pub fn physics(&self) -> &PhysicsWorldSnapshot {
+        &self.physics
+    }
+
+    /// Returns the snapshot of the 2D physics world.
+    pub fn physics2d(&self) -> &dim2::physics::PhysicsWorldSnapshot {
+        &self.physics2d
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::algebra::{Vector2, Vector3},
+        scene::{
+            base::BaseBuilder,
+            character_controller::CharacterControllerBuilder,
+            collider::{ColliderBuilder, ColliderShape},
+            graph::{snapshot::PhysicsStateSnapshot, Graph},
+            joint::{JointBuilder, JointParams},
+            rigidbody::{RigidBodyBuilder, RigidBodyType},
+            transform::TransformBuilder,
+        },
+    };
+
+    fn simulate(graph: &mut Graph, frames: usize) -> Vec<Vector3<f32>> {
+        (0..frames)
+            .map(|_| {
+                graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+                graph
+                    .linear_iter()
+                    .map(|n| n.global_position())
+                    .fold(Vector3::default(), |acc, p| acc + p)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_physics_state_rollback() {
+        let mut graph = Graph::new();
+        graph.physics.determinism.get_value_mut_silent().enabled = true;
+
+        let ground = ColliderBuilder::new(BaseBuilder::new())
+            .with_shape(ColliderShape::cuboid(10.0, 0.5, 10.0))
+            .build(&mut graph);
+        RigidBodyBuilder::new(BaseBuilder::new().with_child(ground))
+            .with_body_type(RigidBodyType::Static)
+            .build(&mut graph);
+
+        for i in 0..4 {
+            let collider = ColliderBuilder::new(BaseBuilder::new())
+                .with_shape(ColliderShape::cuboid(0.5, 0.5, 0.5))
+                .build(&mut graph);
+            RigidBodyBuilder::new(
+                BaseBuilder::new()
+                    .with_child(collider)
+                    .with_local_transform(
+                        TransformBuilder::new()
+                            .with_local_position(Vector3::new(0.1 * i as f32, 1.0 + i as f32, 0.0))
+                            .build(),
+                    ),
+            )
+            .build(&mut graph);
+        }
+
+        simulate(&mut graph, 10);
+        let snapshot = PhysicsStateSnapshot::capture(&graph);
+        let original = simulate(&mut graph, 60);
+
+        snapshot.restore(&mut graph);
+        let resimulated = simulate(&mut graph, 60);
+
+        // Bit-exact match is expected.
+        assert_eq!(original, resimulated);
+    }
+
+    #[test]
+    fn test_physics_state_rollback_over_joint_break() {
+        let mut graph = Graph::new();
+        graph.physics.determinism.get_value_mut_silent().enabled = true;
+
+        let anchor = RigidBodyBuilder::new(BaseBuilder::new())
+            .with_body_type(RigidBodyType::Static)
+            .build(&mut graph);
+        let collider = ColliderBuilder::new(BaseBuilder::new())
+            .with_shape(ColliderShape::cuboid(0.5, 0.5, 0.5))
+            .build(&mut graph);
+        let load = RigidBodyBuilder::new(
+            BaseBuilder::new()
+                .with_child(collider)
+                .with_local_transform(
+                    TransformBuilder::new()
+                        .with_local_position(Vector3::new(0.0, -1.0, 0.0))
+                        .build(),
+                ),
+        )
+        .build(&mut graph);
+        let joint = JointBuilder::new(BaseBuilder::new())
+            .with_params(JointParams::FixedJoint(Default::default()))
+            .with_body1(anchor)
+            .with_body2(load)
+            .with_break_force(Some(1.0))
+            .build(&mut graph);
+
+        simulate(&mut graph, 1);
+        assert!(!graph[joint].is_broken());
+        let snapshot = PhysicsStateSnapshot::capture(&graph);
+        let original = simulate(&mut graph, 30);
+        assert!(graph[joint].is_broken());
+
+        snapshot.restore(&mut graph);
+        assert!(!graph[joint].is_broken());
+        let resimulated = simulate(&mut graph, 30);
+
+        // The joint must break at the same step, so the load falls exactly the same way.
+        assert!(graph[joint].is_broken());
+        assert_eq!(original, resimulated);
+    }
+
+    #[test]
+    fn test_physics_state_rollback_with_character_on_platform() {
+        let mut graph = Graph::new();
+        graph.physics.determinism.get_value_mut_silent().enabled = true;
+
+        let platform = ColliderBuilder::new(BaseBuilder::new())
+            .with_shape(ColliderShape::cuboid(5.0, 0.5, 5.0))
+            .build(&mut graph);
+        RigidBodyBuilder::new(BaseBuilder::new().with_child(platform))
+            .with_body_type(RigidBodyType::KinematicVelocityBased)
+            .with_lin_vel(Vector3::new(1.0, 0.0, 0.0))
+            .build(&mut graph);
+
+        let character = CharacterControllerBuilder::new(
+            BaseBuilder::new().with_local_transform(
+                TransformBuilder::new()
+                    .with_local_position(Vector3::new(0.0, 1.5, 0.0))
+                    .build(),
+            ),
+        )
+        .with_capsule(0.3, 0.5)
+        .with_follow_platforms(true)
+        .build(&mut graph);
+
+        // Frame time does not match the fixed step, so some frames have no physics steps at all
+        // and some have two of them.
+        let run = |graph: &mut Graph, frames: usize| {
+            (0..frames)
+                .map(|_| {
+                    graph[character].move_by(Vector3::new(0.0, -0.05, 0.0));
+                    graph.update(Vector2::new(800.0, 600.0), 1.0 / 45.0, Default::default());
+                    (
+                        graph[character].global_position(),
+                        graph[character].is_grounded(),
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+
+        run(&mut graph, 30);
+        assert!(graph[character].is_grounded());
+        let snapshot = PhysicsStateSnapshot::capture(&graph);
+        let original = run(&mut graph, 30);
+
+        snapshot.restore(&mut graph);
+        let resimulated = run(&mut graph, 30);
+
+        // The character must be carried by the platform exactly the same way.
+        assert!(original.last().unwrap().0.x > 1.0);
+        assert_eq!(original, resimulated);
+    }
+}
@@ -36,7 +36,7 @@ use crate::{
     scene::{
         base::{Base, BaseBuilder},
         graph::Graph,
-        node::{Node, NodeTrait, SyncContext},
+        node::{Node, NodeTrait, SyncContext, UpdateContext},
         rigidbody::RigidBody,
         Scene,
     },
@@ -137,6 +137,34 @@ impl Default for RevoluteJoint {
     }
 }
 
+/// Spring joint keeps two rigid bodies at the given distance from each other, but unlike other
+/// joints it is not rigid - it pulls (or pushes) the bodies using a damped spring force. Relative
+/// rotations of the bodies are not restricted. The real world example is a car suspension,
+/// a bungee rope, etc.
+#[derive(Clone, Debug, Visit, PartialEq, Reflect)]
+pub struct SpringJoint {
+    /// The distance between the anchors of the bodies at which the spring does not apply any force.
+    pub rest_length: f32,
+
+    /// The stiffness coefficient of the spring. The higher the value, the stronger the spring
+    /// pulls the bodies to the rest length.
+    pub stiffness: f32,
+
+    /// The damping coefficient of the spring. It damps relative velocity of the bodies and
+    /// prevents infinite oscillations.
+    pub damping: f32,
+}
+
+impl Default for SpringJoint {
+    fn default() -> Self {
+        Self {
+            rest_length: 1.0,
+            stiffness: 100.0,
+            damping: 1.0,
+        }
+    }
+}
+
 /// Parameters that define how the joint motor will behave.
 #[derive(Default, Clone, Debug, PartialEq, Visit, Reflect)]
 pub struct JointMotorParams {
@@ -152,6 +180,44 @@ pub struct JointMotorParams {
     pub max_force: f32,
 }
 
+/// Forces and impulses, that were applied by a joint to the connected rigid bodies at the last
+/// physics step to satisfy the constraints of the joint. All the values are expressed in the local
+/// frame of the joint on the first body.
+#[derive(Default, Copy, Clone, Debug, PartialEq)]
+pub struct JointFeedback {
+    /// Linear impulse applied by the joint.
+    pub linear_impulse: Vector3<f32>,
+    /// Angular impulse applied by the joint.
+    pub angular_impulse: Vector3<f32>,
+    /// Force applied by the joint. It is the linear impulse divided by the time step.
+    pub force: Vector3<f32>,
+    /// Torque applied by the joint. It is the angular impulse divided by the time step.
+    pub torque: Vector3<f32>,
+}
+
+impl JointFeedback {
+    pub(crate) fn from_impulses(
+        linear_impulse: Vector3<f32>,
+        angular_impulse: Vector3<f32>,
+        dt: f32,
+    ) -> Self {
+        let inv_dt = if dt > 0.0 { 1.0 / dt } else { 0.0 };
+        Self {
+            linear_impulse,
+            angular_impulse,
+            force: linear_impulse.scale(inv_dt),
+            torque: angular_impulse.scale(inv_dt),
+        }
+    }
+
+    /// Returns `true` if the magnitude of the force or the torque exceeds the respective
+    /// threshold. `None` threshold is never exceeded.
+    pub fn exceeds(&self, break_force: Option<f32>, break_torque: Option<f32>) -> bool {
+        break_force.is_some_and(|max| self.force.norm() > max)
+            || break_torque.is_some_and(|max| self.torque.norm() > max)
+    }
+}
+
 /// The exact kind of the joint.
 #[derive(Clone, Debug, PartialEq, Visit, Reflect, AsRefStr, EnumString, VariantNames)]
 pub enum JointParams {
@@ -163,6 +229,8 @@ pub enum JointParams {
     PrismaticJoint(PrismaticJoint),
     /// See [`RevoluteJoint`] for more info.
     RevoluteJoint(RevoluteJoint),
+    /// See [`SpringJoint`] for more info.
+    SpringJoint(SpringJoint),
 }
 
 uuid_provider!(JointParams = "a3e09303-9de4-4123-9492-05e27f29aaa3");
@@ -228,10 +296,26 @@ pub struct Joint {
     #[reflect(setter = "set_auto_rebinding")]
     pub(crate) auto_rebind: InheritableVariable<bool>,
 
+    #[visit(optional)]
+    #[reflect(setter = "set_break_force")]
+    pub(crate) break_force: InheritableVariable<Option<f32>>,
+
+    #[visit(optional)]
+    #[reflect(setter = "set_break_torque")]
+    pub(crate) break_torque: InheritableVariable<Option<f32>>,
+
+    #[visit(optional)]
+    #[reflect(hidden)]
+    pub(crate) broken: bool,
+
     #[visit(optional)]
     #[reflect(hidden)]
     pub(crate) local_frames: RefCell<Option<JointLocalFrames>>,
 
+    #[visit(skip)]
+    #[reflect(hidden)]
+    pub(crate) feedback: JointFeedback,
+
     #[visit(skip)]
     #[reflect(hidden)]
     pub(crate) native: Cell<ImpulseJointHandle>,
@@ -247,7 +331,11 @@ impl Default for Joint {
             body2: Default::default(),
             contacts_enabled: InheritableVariable::new_modified(true),
             auto_rebind: true.into(),
+            break_force: Default::default(),
+            break_torque: Default::default(),
+            broken: false,
             local_frames: Default::default(),
+            feedback: Default::default(),
             native: Cell::new(ImpulseJointHandle::invalid()),
         }
     }
@@ -279,6 +367,10 @@ impl Clone for Joint {
             local_frames: self.local_frames.clone(),
             // Do not copy. The copy will have its own native representation.
             auto_rebind: self.auto_rebind.clone(),
+            break_force: self.break_force.clone(),
+            break_torque: self.break_torque.clone(),
+            broken: self.broken,
+            feedback: Default::default(),
             native: Cell::new(ImpulseJointHandle::invalid()),
         }
     }
@@ -383,6 +475,66 @@ impl Joint {
         *self.auto_rebind
     }
 
+    /// Sets the maximum force (in Newtons) the joint can apply to the connected bodies. If the
+    /// force exceeds the threshold, the joint breaks: it stops affecting the bodies and sends
+    /// [`crate::scene::graph::physics::JointBreakEvent`] to the scripts of the joint and its bodies.
+    /// `None` means that the joint cannot be broken by a force. Default is `None`.
+    pub fn set_break_force(&mut self, force: Option<f32>) -> Option<f32> {
+        self.break_force.set_value_and_mark_modified(force)
+    }
+
+    /// Returns current break force threshold of the joint. See [`Self::set_break_force`] for more
+    /// info.
+    pub fn break_force(&self) -> Option<f32> {
+        *self.break_force
+    }
+
+    /// Sets the maximum torque (in Newton-meters) the joint can apply to the connected bodies. Works
+    /// the same as [`Self::set_break_force`], but for the angular part of the joint. Default is
+    /// `None`.
+    pub fn set_break_torque(&mut self, torque: Option<f32>) -> Option<f32> {
+        self.break_torque.set_value_and_mark_modified(torque)
+    }
+
+    /// Returns current break torque threshold of the joint. See [`Self::set_break_torque`] for more
+    /// info.
+    pub fn break_torque(&self) -> Option<f32> {
+        *self.break_torque
+    }
+
+    /// Returns `true` if the joint was broken, `false` - otherwise. A broken joint does not affect
+    /// the connected bodies until it is repaired using [`Self::repair`].
+    pub fn is_broken(&self) -> bool {
+        self.broken
+    }
+
+    /// Restores a broken joint. The joint will connect the bodies again (using the same local
+    /// frames it had before it was broken) on the next update of the scene.
+    pub fn repair(&mut self) {
+        self.broken = false;
+    }
+
+    /// Returns forces and impulses, that were applied by the joint at the last physics step. A
+    /// broken or disabled joint has zero feedback.
+    pub fn feedback(&self) -> &JointFeedback {
+        &self.feedback
+    }
+
+    /// Returns the linear impulse, that was applied by the joint at the last physics step.
+    pub fn impulse(&self) -> Vector3<f32> {
+        self.feedback.linear_impulse
+    }
+
+    /// Returns the force, that was applied by the joint at the last physics step.
+    pub fn force(&self) -> Vector3<f32> {
+        self.feedback.force
+    }
+
+    /// Returns the torque, that was applied by the joint at the last physics step.
+    pub fn torque(&self) -> Vector3<f32> {
+        self.feedback.torque
+    }
+
     /// Sets the motor force of the joint assuming it is a [`PrismaticJoint`].
     ///
     /// Call [`Self::disable_motor`] to properly stop the motor and set the joint free.
@@ -622,6 +774,12 @@ impl ConstructorProvider<Node, Graph> for Joint {
                     .build_node()
                     .into()
             })
+            .with_variant("Spring Joint", |_| {
+                JointBuilder::new(BaseBuilder::new().with_name("Spring Joint"))
+                    .with_params(JointParams::SpringJoint(Default::default()))
+                    .build_node()
+                    .into()
+            })
             .with_group("Physics")
     }
 }
@@ -665,6 +823,22 @@ impl NodeTrait for Joint {
         }
     }
 
+    fn update(&mut self, context: &mut UpdateContext) {
+        let native = self.native.get();
+        let Some(feedback) = context.physics.joint_feedback(native) else {
+            self.feedback = Default::default();
+            return;
+        };
+
+        self.feedback = feedback;
+
+        if !self.broken && feedback.exceeds(*self.break_force, *self.break_torque) {
+            self.broken = true;
+            context.physics.break_joint(native, feedback);
+            self.native.set(ImpulseJointHandle::invalid());
+        }
+    }
+
     fn validate(&self, scene: &Scene) -> Result<(), String> {
         if scene.graph.try_get(self.body1()).is_err() {
             return Err("3D Joint has invalid or unassigned handle to a \
@@ -691,6 +865,8 @@ pub struct JointBuilder {
     body2: Handle<RigidBody>,
     contacts_enabled: bool,
     auto_rebind: bool,
+    break_force: Option<f32>,
+    break_torque: Option<f32>,
 }
 
 impl JointBuilder {
@@ -704,6 +880,8 @@ impl JointBuilder {
             body2: Default::default(),
             contacts_enabled: true,
             auto_rebind: true,
+            break_force: None,
+            break_torque: None,
         }
     }
 
@@ -746,6 +924,20 @@ impl JointBuilder {
         self
     }
 
+    /// Sets the maximum force the joint can apply before it breaks. See [`Joint::set_break_force`]
+    /// for more info.
+    pub fn with_break_force(mut self, force: Option<f32>) -> Self {
+        self.break_force = force;
+        self
+    }
+
+    /// Sets the maximum torque the joint can apply before it breaks. See
+    /// [`Joint::set_break_torque`] for more info.
+    pub fn with_break_torque(mut self, torque: Option<f32>) -> Self {
+        self.break_torque = torque;
+        self
+    }
+
     /// Creates new Joint node, but does not add it to the graph.
     pub fn build_joint(self) -> Joint {
         Joint {
@@ -756,7 +948,11 @@ impl JointBuilder {
             body2: self.body2.into(),
             contacts_enabled: self.contacts_enabled.into(),
             auto_rebind: self.auto_rebind.into(),
+            break_force: self.break_force.into(),
+            break_torque: self.break_torque.into(),
+            broken: false,
             local_frames: Default::default(),
+            feedback: Default::default(),
             native: Cell::new(ImpulseJointHandle::invalid()),
         }
     }
@@ -771,3 +967,67 @@ impl JointBuilder {
         graph.add_node(self.build_node()).to_variant()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::algebra::{Vector2, Vector3},
+        scene::{
+            base::BaseBuilder,
+            collider::{ColliderBuilder, ColliderShape},
+            graph::Graph,
+            joint::{JointBuilder, JointFeedback, JointParams},
+            rigidbody::{RigidBodyBuilder, RigidBodyType},
+            transform::TransformBuilder,
+        },
+    };
+
+    #[test]
+    fn test_joint_feedback_thresholds() {
+        let feedback =
+            JointFeedback::from_impulses(Vector3::new(0.0, 1.0, 0.0), Vector3::default(), 0.5);
+        assert_eq!(feedback.force, Vector3::new(0.0, 2.0, 0.0));
+        assert!(!feedback.exceeds(None, None));
+        assert!(!feedback.exceeds(Some(3.0), Some(0.0)));
+        assert!(feedback.exceeds(Some(1.0), None));
+    }
+
+    #[test]
+    fn test_joint_breaks_under_load() {
+        let mut graph = Graph::new();
+
+        let anchor = RigidBodyBuilder::new(BaseBuilder::new())
+            .with_body_type(RigidBodyType::Static)
+            .build(&mut graph);
+        let collider = ColliderBuilder::new(BaseBuilder::new())
+            .with_shape(ColliderShape::cuboid(0.5, 0.5, 0.5))
+            .build(&mut graph);
+        let load = RigidBodyBuilder::new(
+            BaseBuilder::new()
+                .with_child(collider)
+                .with_local_transform(
+                    TransformBuilder::new()
+                        .with_local_position(Vector3::new(0.0, -1.0, 0.0))
+                        .build(),
+                ),
+        )
+        .build(&mut graph);
+        let joint = JointBuilder::new(BaseBuilder::new())
+            .with_params(JointParams::FixedJoint(Default::default()))
+            .with_body1(anchor)
+            .with_body2(load)
+            .with_break_force(Some(1.0))
+            .build(&mut graph);
+
+        for _ in 0..10 {
+            graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+        }
+
+        assert!(graph[joint].is_broken());
+        let events = graph.physics.joint_break_events();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].joint, joint.to_base());
+        assert_eq!(events[0].body1, anchor.to_base());
+        assert_eq!(events[0].body2, load.to_base());
+    }
+}
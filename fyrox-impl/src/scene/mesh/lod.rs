@@ -0,0 +1,537 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Automatic generation of LOD (level-of-detail) chains for meshes. See [`generate_lods`] docs for
+//! more info.
+
+use crate::{
+    core::{pool::Handle, reflect::prelude::*, visitor::prelude::*},
+    graph::SceneGraph,
+    scene::{
+        base::{BaseBuilder, LevelOfDetail, LodGroup},
+        camera::PerspectiveProjection,
+        graph::Graph,
+        mesh::{
+            surface::{SurfaceBuilder, SurfaceResource},
+            Mesh, MeshBuilder,
+        },
+        node::{Node, NodeTrait},
+        pivot::PivotBuilder,
+    },
+    utils::simplify::{simplify, SimplificationOptions},
+};
+use fxhash::FxHashSet;
+use fyrox_core::uuid_provider;
+use serde::{Deserialize, Serialize};
+
+/// Settings of a single level of an automatically generated LOD chain.
+#[derive(Clone, Debug, Serialize, Deserialize, Reflect, Visit)]
+pub struct LodLevelSettings {
+    /// Ratio of triangles of the level to the triangles of the source mesh. 1.0 means that the
+    /// source mesh will be used as is. The ratio of the first level is ignored, the first level always
+    /// uses the source mesh.
+    #[reflect(min_value = 0.0, max_value = 1.0)]
+    pub triangle_ratio: f32,
+    /// Minimum size of the mesh on screen (as a fraction of the screen height) at which the level is
+    /// still used. When the mesh becomes smaller, the next level is used. Zero means that the level
+    /// will be used at any distance.
+    #[reflect(min_value = 0.0, max_value = 1.0)]
+    pub screen_size: f32,
+}
+
+uuid_provider!(LodLevelSettings = "3f5d7b19-6c2e-4a8d-b0e4-71c9a2d5f836");
+
+// Settings are compared by bits, so they could be a part of import options, that must implement `Eq`.
+impl PartialEq for LodLevelSettings {
+    fn eq(&self, other: &Self) -> bool {
+        self.triangle_ratio.to_bits() == other.triangle_ratio.to_bits()
+            && self.screen_size.to_bits() == other.screen_size.to_bits()
+    }
+}
+
+impl Eq for LodLevelSettings {}
+
+impl Default for LodLevelSettings {
+    fn default() -> Self {
+        Self {
+            triangle_ratio: 1.0,
+            screen_size: 0.0,
+        }
+    }
+}
+
+/// Settings of automatic LOD chain generation. Levels must be sorted by the screen size in descending
+/// order, i.e. from the most detailed level to the least detailed one.
+#[derive(Clone, Debug, Serialize, Deserialize, Reflect, Visit)]
+pub struct LodGenerationSettings {
+    /// A set of levels of the chain.
+    #[serde(default)]
+    pub levels: Vec<LodLevelSettings>,
+    /// Maximum allowed geometric error of the simplification expressed as a fraction of the radius of
+    /// the mesh bounds. See [`SimplificationOptions::max_error`] docs for more info.
+    #[serde(default = "default_max_error")]
+    #[reflect(min_value = 0.0)]
+    pub max_error: f32,
+}
+
+uuid_provider!(LodGenerationSettings = "a81c4e6f-2d9b-4f37-8e15-c6b0d3a7e924");
+
+impl PartialEq for LodGenerationSettings {
+    fn eq(&self, other: &Self) -> bool {
+        self.levels == other.levels && self.max_error.to_bits() == other.max_error.to_bits()
+    }
+}
+
+impl Eq for LodGenerationSettings {}
+
+fn default_max_error() -> f32 {
+    0.05
+}
+
+impl Default for LodGenerationSettings {
+    fn default() -> Self {
+        Self {
+            levels: vec![
+                LodLevelSettings {
+                    triangle_ratio: 1.0,
+                    screen_size: 0.25,
+                },
+                LodLevelSettings {
+                    triangle_ratio: 0.5,
+                    screen_size: 0.1,
+                },
+                LodLevelSettings {
+                    triangle_ratio: 0.25,
+                    screen_size: 0.04,
+                },
+                LodLevelSettings {
+                    triangle_ratio: 0.1,
+                    screen_size: 0.0,
+                },
+            ],
+            max_error: default_max_error(),
+        }
+    }
+}
+
+impl LodGenerationSettings {
+    /// Converts the screen size of an object with the given radius to the normalized distance used by
+    /// [`LevelOfDetail`]. The conversion uses default perspective projection parameters (see
+    /// [`PerspectiveProjection`]), since the actual camera is unknown at generation time.
+    pub fn screen_size_to_normalized_distance(screen_size: f32, radius: f32) -> f32 {
+        if screen_size <= 0.0 {
+            return 1.0;
+        }
+        let projection = PerspectiveProjection::default();
+        let distance = radius / (screen_size * (projection.fov * 0.5).tan());
+        ((distance - projection.z_near) / (projection.z_far - projection.z_near)).clamp(0.0, 1.0)
+    }
+}
+
+/// Result of [`generate_lods`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GeneratedLods {
+    /// A node that holds the [`LodGroup`]. It is the parent of the source mesh and of all the
+    /// generated levels.
+    pub group: Handle<Node>,
+    /// `true` if the group node was created by the generation, `false` if an existing group node
+    /// (created by a previous generation) was reused.
+    pub group_created: bool,
+    /// A list of handles of the created meshes.
+    pub levels: Vec<Handle<Node>>,
+}
+
+/// Returns a handle of the node that holds the LOD group created by [`generate_lods`] for the given
+/// mesh, or [`Handle::NONE`] if there's no such group. The group node is the parent of the mesh,
+/// whose LOD group includes the mesh.
+pub fn generated_lod_group(graph: &Graph, mesh_handle: Handle<Node>) -> Handle<Node> {
+    let Ok(mesh) = graph.try_get_node(mesh_handle) else {
+        return Handle::NONE;
+    };
+    let parent = mesh.parent();
+    let is_group = graph
+        .try_get_node(parent)
+        .ok()
+        .and_then(|parent| parent.lod_group())
+        .is_some_and(|lod_group| {
+            lod_group
+                .levels
+                .iter()
+                .any(|level| level.objects.contains(&mesh_handle))
+        });
+    if is_group {
+        parent
+    } else {
+        Handle::NONE
+    }
+}
+
+/// Returns a list of objects of the LOD group, that was previously generated for the given mesh (see
+/// [`generated_lod_group`]). These objects are siblings of the mesh and they are removed by
+/// [`generate_lods`], when it replaces the LOD group.
+pub fn replaced_lod_objects(graph: &Graph, mesh_handle: Handle<Node>) -> Vec<Handle<Node>> {
+    let group = generated_lod_group(graph, mesh_handle);
+    let Some(lod_group) = graph.try_get_node(group).ok().and_then(|n| n.lod_group()) else {
+        return Default::default();
+    };
+    let mut objects = Vec::new();
+    for level in lod_group.levels.iter() {
+        for &object in level.objects.iter() {
+            if object != mesh_handle
+                && !objects.contains(&object)
+                && graph
+                    .try_get_node(object)
+                    .is_ok_and(|node| node.parent() == group)
+            {
+                objects.push(object);
+            }
+        }
+    }
+    objects
+}
+
+/// Generates a LOD chain for the given mesh using the given settings. The first level and every
+/// level with the triangle ratio of 1.0 use the source mesh, every other level is created as a new
+/// mesh with simplified surfaces (see [`simplify`]). All the levels are attached to a group node,
+/// that takes the place of the source mesh in the hierarchy and holds a [`LodGroup`] with all the
+/// levels. The levels must be siblings, because the renderer skips the descendants of every hidden
+/// level.
+///
+/// If the mesh already has a group node made by a previous generation, the group node is reused and
+/// its levels are replaced, see [`replaced_lod_objects`] for more info.
+///
+/// Make sure that the global transform of the mesh is up-to-date before calling this method, it is
+/// used to calculate the size of the mesh.
+pub fn generate_lods(
+    graph: &mut Graph,
+    mesh_handle: Handle<Node>,
+    settings: &LodGenerationSettings,
+) -> GeneratedLods {
+    if graph
+        .try_get_node(mesh_handle)
+        .ok()
+        .and_then(|n| n.cast::<Mesh>())
+        .is_none()
+    {
+        return Default::default();
+    }
+
+    for object in replaced_lod_objects(graph, mesh_handle) {
+        graph.remove_node(object);
+    }
+
+    let mut group = generated_lod_group(graph, mesh_handle);
+    let group_created = group.is_none();
+
+    let mesh = graph[mesh_handle].as_mesh();
+    let parent = mesh.parent();
+    let name = mesh.name().to_owned();
+
+    let radius = mesh
+        .local_bounding_box()
+        .transform(&mesh.global_transform())
+        .half_extents()
+        .norm();
+
+    let mut nodes = Vec::new();
+    let mut levels = Vec::new();
+    let mut begin = 0.0;
+    for (index, level) in settings.levels.iter().enumerate() {
+        let end =
+            LodGenerationSettings::screen_size_to_normalized_distance(level.screen_size, radius)
+                .max(begin);
+
+        // The source mesh must always be in a level, otherwise it will be rendered on top of every
+        // level.
+        if index == 0 || level.triangle_ratio >= 1.0 {
+            levels.push((begin, end, None));
+        } else {
+            let options = SimplificationOptions {
+                target_ratio: level.triangle_ratio,
+                max_error: settings.max_error,
+            };
+            let surfaces = mesh
+                .surfaces()
+                .iter()
+                .map(|surface| {
+                    let data = surface.data();
+                    let data = data.data_ref();
+                    SurfaceBuilder::new(SurfaceResource::new_embedded(simplify(&data, &options)))
+                        .with_material(surface.material().clone())
+                        .with_bones(surface.bones().to_vec())
+                        .build()
+                })
+                .collect();
+            let node = MeshBuilder::new(
+                BaseBuilder::new()
+                    .with_name(format!("{name}_LOD{index}"))
+                    .with_local_transform(mesh.local_transform().clone())
+                    .with_cast_shadows(mesh.cast_shadows()),
+            )
+            .with_surfaces(surfaces)
+            .with_render_path(mesh.render_path())
+            .build_node();
+            levels.push((begin, end, Some(nodes.len())));
+            nodes.push(node);
+        }
+
+        begin = end;
+    }
+
+    if group_created {
+        group = graph.add_node(
+            PivotBuilder::new(BaseBuilder::new().with_name(format!("{name}_LODGroup")))
+                .build_node(),
+        );
+        graph.link_nodes(group, parent);
+        graph.link_nodes(mesh_handle, group);
+    }
+
+    let handles = nodes
+        .into_iter()
+        .map(|node| {
+            let handle = graph.add_node(node);
+            graph.link_nodes(handle, group);
+            handle
+        })
+        .collect::<Vec<_>>();
+
+    let lod_group = LodGroup {
+        levels: levels
+            .into_iter()
+            .map(|(begin, end, node)| {
+                let object = node.map_or(mesh_handle, |i| handles[i]);
+                LevelOfDetail::new(begin, end, vec![object])
+            })
+            .collect(),
+    };
+    graph[group].set_lod_group(Some(lod_group));
+
+    GeneratedLods {
+        group,
+        group_created,
+        levels: handles,
+    }
+}
+
+/// Generates LOD chains (see [`generate_lods`]) for every mesh in the graph, that does not have a LOD
+/// group yet and is not a part of an existing LOD group.
+pub fn generate_graph_lods(graph: &mut Graph, settings: &LodGenerationSettings) {
+    graph.update_hierarchical_data();
+
+    let lod_objects = graph
+        .linear_iter()
+        .filter_map(|node| node.lod_group())
+        .flat_map(|lod_group| lod_group.levels.iter())
+        .flat_map(|level| level.objects.iter().cloned())
+        .collect::<FxHashSet<_>>();
+
+    let meshes = graph
+        .pair_iter()
+        .filter(|(handle, node)| {
+            node.cast::<Mesh>().is_some()
+                && node.lod_group().is_none()
+                && !lod_objects.contains(handle)
+        })
+        .map(|(handle, _)| handle)
+        .collect::<Vec<_>>();
+
+    for mesh in meshes {
+        generate_lods(graph, mesh, settings);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::{
+            algebra::{Matrix4, Vector3},
+            pool::Handle,
+        },
+        graph::SceneGraph,
+        renderer::{bundle::apply_lod_group, observer::ObserverPosition},
+        scene::{
+            base::BaseBuilder,
+            camera::PerspectiveProjection,
+            graph::Graph,
+            mesh::{
+                lod::{generate_lods, LodGenerationSettings, LodLevelSettings},
+                surface::{SurfaceBuilder, SurfaceData, SurfaceResource},
+                Mesh, MeshBuilder,
+            },
+            node::Node,
+        },
+    };
+
+    fn make_sphere(graph: &mut Graph, detail: usize) -> Handle<Node> {
+        let mesh = MeshBuilder::new(BaseBuilder::new().with_name("Sphere"))
+            .with_surfaces(vec![SurfaceBuilder::new(SurfaceResource::new_embedded(
+                SurfaceData::make_sphere(detail, detail, 1.0, &Matrix4::identity()),
+            ))
+            .build()])
+            .build(graph)
+            .to_base();
+        graph.update_hierarchical_data();
+        mesh
+    }
+
+    // Collects the meshes that will be rendered by an observer at the given distance from the origin,
+    // the same way as the renderer does it.
+    fn rendered_meshes(graph: &Graph, distance: f32) -> Vec<Handle<Node>> {
+        let projection = PerspectiveProjection::default();
+        let observer_position = ObserverPosition {
+            translation: Vector3::new(0.0, 0.0, distance),
+            z_near: projection.z_near,
+            z_far: projection.z_far,
+            view_matrix: Matrix4::identity(),
+            projection_matrix: Matrix4::identity(),
+            view_projection_matrix: Matrix4::identity(),
+        };
+
+        let mut lod_filter = vec![true; graph.capacity() as usize];
+        for node in graph.linear_iter() {
+            if let Some(lod_group) = node.lod_group() {
+                apply_lod_group(&mut lod_filter, graph, lod_group, &observer_position);
+            }
+        }
+
+        let mut meshes = Vec::new();
+        let mut stack = vec![graph.root()];
+        while let Some(handle) = stack.pop() {
+            if lod_filter[handle.index() as usize] {
+                if graph[handle].cast::<Mesh>().is_some() {
+                    meshes.push(handle);
+                }
+                stack.extend_from_slice(graph[handle].children());
+            }
+        }
+        meshes
+    }
+
+    #[test]
+    fn test_generate_lods() {
+        let mut graph = Graph::new();
+        let mesh = make_sphere(&mut graph, 32);
+
+        let settings = LodGenerationSettings {
+            max_error: 1.0,
+            ..Default::default()
+        };
+        let lods = generate_lods(&mut graph, mesh, &settings);
+
+        // The first level uses the source mesh.
+        assert_eq!(lods.levels.len(), settings.levels.len() - 1);
+        assert!(lods.group_created);
+        assert_eq!(graph[lods.group].parent(), graph.root());
+        assert_eq!(graph[mesh].parent(), lods.group);
+
+        let source_triangles = graph[mesh].as_mesh().surfaces()[0]
+            .data()
+            .data_ref()
+            .geometry_buffer
+            .len();
+        let mut prev_triangles = source_triangles;
+        for &lod in lods.levels.iter() {
+            assert_eq!(graph[lod].parent(), lods.group);
+            let triangles = graph[lod].cast::<Mesh>().unwrap().surfaces()[0]
+                .data()
+                .data_ref()
+                .geometry_buffer
+                .len();
+            assert!(triangles < prev_triangles);
+            prev_triangles = triangles;
+        }
+
+        assert!(graph[mesh].lod_group().is_none());
+        let lod_group = graph[lods.group].lod_group().unwrap();
+        assert_eq!(lod_group.levels.len(), settings.levels.len());
+        assert_eq!(lod_group.levels[0].objects, vec![mesh]);
+        assert_eq!(lod_group.levels.last().unwrap().end(), 1.0);
+        for pair in lod_group.levels.windows(2) {
+            assert_eq!(pair[0].end(), pair[1].begin());
+            assert!(pair[0].begin() <= pair[0].end());
+        }
+    }
+
+    #[test]
+    fn test_regenerate_lods() {
+        let mut graph = Graph::new();
+        let mesh = make_sphere(&mut graph, 16);
+
+        let settings = LodGenerationSettings::default();
+        let old_lods = generate_lods(&mut graph, mesh, &settings);
+        let new_lods = generate_lods(&mut graph, mesh, &settings);
+
+        // Previous levels must be replaced, not accumulated, and the group node must be reused.
+        assert!(!new_lods.group_created);
+        assert_eq!(old_lods.group, new_lods.group);
+        assert!(old_lods
+            .levels
+            .iter()
+            .all(|lod| !graph.is_valid_handle(*lod)));
+        let mut expected = vec![mesh];
+        expected.extend_from_slice(&new_lods.levels);
+        assert_eq!(graph[new_lods.group].children(), expected.as_slice());
+    }
+
+    #[test]
+    fn test_lods_visibility() {
+        let mut graph = Graph::new();
+        let mesh = make_sphere(&mut graph, 16);
+
+        let settings = LodGenerationSettings::default();
+        let lods = generate_lods(&mut graph, mesh, &settings);
+        graph.update_hierarchical_data();
+
+        let z_far = PerspectiveProjection::default().z_far;
+        assert_eq!(rendered_meshes(&graph, 1.0), vec![mesh]);
+        assert_eq!(
+            rendered_meshes(&graph, z_far * 0.99),
+            vec![*lods.levels.last().unwrap()]
+        );
+    }
+
+    #[test]
+    fn test_lods_visibility_without_full_detail_level() {
+        let mut graph = Graph::new();
+        let mesh = make_sphere(&mut graph, 16);
+
+        let settings = LodGenerationSettings {
+            levels: vec![
+                LodLevelSettings {
+                    triangle_ratio: 0.5,
+                    screen_size: 0.1,
+                },
+                LodLevelSettings {
+                    triangle_ratio: 0.25,
+                    screen_size: 0.0,
+                },
+            ],
+            ..Default::default()
+        };
+        let lods = generate_lods(&mut graph, mesh, &settings);
+        graph.update_hierarchical_data();
+
+        // The source mesh must never be rendered together with another level.
+        let z_far = PerspectiveProjection::default().z_far;
+        assert_eq!(rendered_meshes(&graph, 1.0), vec![mesh]);
+        assert_eq!(rendered_meshes(&graph, z_far * 0.99), lods.levels);
+    }
+}
@@ -74,6 +74,7 @@ use std::{
 use strum_macros::{AsRefStr, EnumString, VariantNames};
 
 pub mod buffer;
+pub mod lod;
 pub mod surface;
 pub mod vertex;
 
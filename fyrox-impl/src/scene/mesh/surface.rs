@@ -255,6 +255,11 @@ impl ResourceData for SurfaceData {
     fn try_clone_box(&self) -> Option<Box<dyn ResourceData>> {
         Some(Box::new(self.clone()))
     }
+
+    fn memory_usage(&self) -> usize {
+        self.vertex_buffer.raw_data().len()
+            + std::mem::size_of_val(self.geometry_buffer.triangles_ref())
+    }
 }
 
 impl SurfaceData {
@@ -28,6 +28,7 @@ pub mod accel;
 pub mod animation;
 pub mod base;
 pub mod camera;
+pub mod character_controller;
 pub mod collider;
 pub mod debug;
 pub mod decal;
@@ -46,9 +47,11 @@ pub mod rigidbody;
 pub mod skybox;
 pub mod sound;
 pub mod sprite;
+pub mod streaming;
 pub mod terrain;
 pub mod tilemap;
 pub mod transform;
+pub mod vehicle;
 
 use crate::{
     asset::{self, io::ResourceIo, manager::ResourceManager, untyped::UntypedResource},
@@ -74,6 +77,7 @@ use crate::{
         node::Node,
         skybox::{SkyBox, SkyBoxKind},
         sound::SoundEngine,
+        streaming::WorldStreamer,
     },
     utils::navmesh::Navmesh,
 };
@@ -278,6 +282,10 @@ pub struct Scene {
     /// to false for menu's scene and when you need to open a menu - set it to true and
     /// set `enabled` flag to false for level's scene.
     pub enabled: InheritableVariable<bool>,
+
+    /// World streamer of the scene, that loads and unloads parts of the world around the observers.
+    /// See [`WorldStreamer`] docs for more info.
+    pub streaming: WorldStreamer,
 }
 
 impl Clone for Scene {
@@ -295,6 +303,7 @@ impl Default for Scene {
             performance_statistics: Default::default(),
             enabled: true.into(),
             sky_box: Some(SkyBoxKind::built_in_skybox().clone()).into(),
+            streaming: Default::default(),
         }
     }
 }
@@ -533,6 +542,15 @@ impl Scene {
         self.performance_statistics.graph = self.graph.performance_statistics.clone();
     }
 
+    /// Updates the world streamer of the scene, loading and unloading the cells around the observers.
+    /// See [`WorldStreamer`] docs for more info. In most cases there is no need to call it directly,
+    /// engine automatically updates the streamers of all available scenes.
+    pub fn update_streaming(&mut self, resource_manager: &ResourceManager) {
+        let mut streaming = std::mem::take(&mut self.streaming);
+        streaming.update(self, resource_manager);
+        self.streaming = streaming;
+    }
+
     /// Creates deep copy of a scene, filter predicate allows you to filter out nodes
     /// by your criteria.
     pub fn clone_ex<F, Pre, Post>(
@@ -556,6 +574,9 @@ impl Scene {
             post_process_callback,
         );
 
+        let mut streaming = self.streaming.clone();
+        streaming.remap_handles(&old_new_map);
+
         (
             Self {
                 graph,
@@ -564,6 +585,7 @@ impl Scene {
                 performance_statistics: Default::default(),
                 enabled: self.enabled.clone(),
                 sky_box: self.sky_box.clone(),
+                streaming,
             },
             old_new_map,
         )
@@ -588,6 +610,7 @@ impl Scene {
         self.rendering_options
             .visit("RenderingOptions", &mut region)?;
         self.sky_box.visit("SkyBox", &mut region)?;
+        let _ = self.streaming.visit("Streaming", &mut region);
 
         Ok(())
     }
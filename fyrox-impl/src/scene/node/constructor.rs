@@ -37,10 +37,11 @@ use crate::{
         pivot::Pivot,
         probe::ReflectionProbe,
         ragdoll::Ragdoll,
-        sound::{listener::Listener, Sound},
+        sound::{listener::Listener, reverb_zone::ReverbZone, Sound},
         sprite::Sprite,
         terrain::Terrain,
         tilemap::TileMap,
+        vehicle::Vehicle,
     },
 };
 
@@ -54,6 +55,7 @@ pub type NodeConstructorContainer = GraphNodeConstructorContainer<Node, Graph>;
 pub fn new_node_constructor_container() -> NodeConstructorContainer {
     let container = NodeConstructorContainer::default();
 
+    container.add::<dim2::character_controller::CharacterController>();
     container.add::<dim2::collider::Collider>();
     container.add::<dim2::joint::Joint>();
     container.add::<Rectangle>();
@@ -65,7 +67,9 @@ pub fn new_node_constructor_container() -> NodeConstructorContainer {
     container.add::<ParticleSystem>();
     container.add::<Sound>();
     container.add::<Listener>();
+    container.add::<ReverbZone>();
     container.add::<Camera>();
+    container.add::<scene::character_controller::CharacterController>();
     container.add::<scene::collider::Collider>();
     container.add::<Decal>();
     container.add::<scene::joint::Joint>();
@@ -79,6 +83,7 @@ pub fn new_node_constructor_container() -> NodeConstructorContainer {
     container.add::<Ragdoll>();
     container.add::<TileMap>();
     container.add::<ReflectionProbe>();
+    container.add::<Vehicle>();
 
     container
 }
@@ -28,20 +28,82 @@ use crate::{
     },
     scene::{node::Node, sound::Sound},
 };
-use fxhash::FxHashSet;
+use fxhash::{FxHashMap, FxHashSet};
 use fyrox_sound::{
-    bus::AudioBusGraph,
+    bus::{AudioBus, AudioBusGraph},
     context::DistanceModel,
+    effects::{
+        reverb::{Reverb, ReverbParameters},
+        Effect,
+    },
     renderer::Renderer,
     source::{SoundSource, SoundSourceBuilder, Status},
 };
 use std::{sync::MutexGuard, time::Duration};
 
 /// Sound context.
-#[derive(Debug, Visit)]
+#[derive(Debug)]
 pub struct SoundContext {
-    #[visit(optional)]
     pub(crate) native: fyrox_sound::context::SoundContext,
+    reverb_zone_influences: Vec<ReverbZoneInfluence>,
+    // Original state of reverb effects of the buses affected by reverb zones.
+    reverb_zone_bases: FxHashMap<String, ReverbZoneBase>,
+}
+
+// Original state of a reverb effect of an audio bus, that is affected by reverb zones.
+#[derive(Debug, Clone)]
+struct ReverbZoneBase {
+    parameters: ReverbParameters,
+    // True if the effect was added by reverb zones, such effect is removed when the bus is no longer
+    // affected by any zone.
+    added_effect: bool,
+}
+
+// A change of an audio bus made by reverb zones, that was temporarily reverted.
+enum RevertedReverbZone {
+    Parameters(ReverbParameters),
+    Effect(Effect),
+}
+
+/// Influence of a reverb zone on a reverb effect of an audio bus.
+#[derive(Debug, Clone)]
+pub(crate) struct ReverbZoneInfluence {
+    pub audio_bus: String,
+    pub weight: f32,
+    pub parameters: ReverbParameters,
+}
+
+fn find_reverb_mut(bus: &mut AudioBus) -> Option<&mut Reverb> {
+    bus.effects_mut().find_map(|effect| match effect {
+        Effect::Reverb(reverb) => Some(reverb),
+        _ => None,
+    })
+}
+
+fn find_reverb_index(bus: &AudioBus) -> Option<usize> {
+    bus.effects()
+        .position(|effect| matches!(effect, Effect::Reverb(_)))
+}
+
+fn find_bus_mut<'a>(bus_graph: &'a mut AudioBusGraph, name: &str) -> Option<&'a mut AudioBus> {
+    bus_graph.buses_iter_mut().find(|bus| bus.name() == name)
+}
+
+impl Visit for SoundContext {
+    fn visit(&mut self, name: &str, visitor: &mut Visitor) -> VisitResult {
+        let mut region = visitor.enter_region(name)?;
+
+        if region.is_reading() {
+            self.native.visit("Native", &mut region).ok();
+        } else {
+            // Reverb zones change the audio buses every frame, these changes must not be saved.
+            let reverted = self.revert_reverb_zones();
+            self.native.visit("Native", &mut region).ok();
+            self.reapply_reverb_zones(reverted);
+        }
+
+        Ok(())
+    }
 }
 
 /// Proxy for guarded access to the sound context.
@@ -80,6 +142,26 @@ impl SoundContextGuard<'_> {
         self.guard.distance_model()
     }
 
+    /// Sets new Doppler factor. 0.0 disables Doppler effect, 1.0 gives physically correct pitch shift.
+    pub fn set_doppler_factor(&mut self, doppler_factor: f32) {
+        self.guard.set_doppler_factor(doppler_factor);
+    }
+
+    /// Returns current Doppler factor.
+    pub fn doppler_factor(&self) -> f32 {
+        self.guard.doppler_factor()
+    }
+
+    /// Sets new speed of sound (in units per second), that is used to calculate Doppler effect.
+    pub fn set_speed_of_sound(&mut self, speed_of_sound: f32) {
+        self.guard.set_speed_of_sound(speed_of_sound);
+    }
+
+    /// Returns current speed of sound.
+    pub fn speed_of_sound(&self) -> f32 {
+        self.guard.speed_of_sound()
+    }
+
     /// Returns amount of time context spent on rendering all sound sources.
     pub fn full_render_duration(&self) -> Duration {
         self.guard.full_render_duration()
@@ -118,7 +200,11 @@ impl Default for SoundContext {
         // There's no need to serialize native sources, because they'll be re-created automatically.
         state.serialization_options.skip_sources = true;
         drop(state);
-        Self { native }
+        Self {
+            native,
+            reverb_zone_influences: Default::default(),
+            reverb_zone_bases: Default::default(),
+        }
     }
 }
 
@@ -131,6 +217,8 @@ impl SoundContext {
     pub fn deep_clone(&self) -> Self {
         Self {
             native: self.native.deep_clone(),
+            reverb_zone_influences: Default::default(),
+            reverb_zone_bases: self.reverb_zone_bases.clone(),
         }
     }
 
@@ -141,6 +229,116 @@ impl SoundContext {
         }
     }
 
+    pub(crate) fn add_reverb_zone_influence(&mut self, influence: ReverbZoneInfluence) {
+        self.reverb_zone_influences.push(influence);
+    }
+
+    // Blends parameters of the reverb zones, that were collected during the update of the graph, and
+    // applies them to the reverb effects of respective audio buses. Buses that are no longer affected
+    // by any zone get their original state back.
+    pub(crate) fn apply_reverb_zones(&mut self) {
+        let influences = std::mem::take(&mut self.reverb_zone_influences);
+        if influences.is_empty() && self.reverb_zone_bases.is_empty() {
+            return;
+        }
+
+        let bus_names = influences
+            .iter()
+            .map(|influence| influence.audio_bus.clone())
+            .chain(self.reverb_zone_bases.keys().cloned())
+            .collect::<FxHashSet<_>>();
+
+        let mut state = self.native.state();
+        for bus_name in bus_names {
+            let Some(bus) = find_bus_mut(state.bus_graph_mut(), &bus_name) else {
+                self.reverb_zone_bases.remove(&bus_name);
+                continue;
+            };
+
+            let mut zones = influences
+                .iter()
+                .filter(|influence| influence.audio_bus == bus_name)
+                .peekable();
+
+            if zones.peek().is_none() {
+                let Some(base) = self.reverb_zone_bases.remove(&bus_name) else {
+                    continue;
+                };
+                if base.added_effect {
+                    if let Some(index) = find_reverb_index(bus) {
+                        bus.remove_effect(index);
+                    }
+                } else if let Some(reverb) = find_reverb_mut(bus) {
+                    reverb.set_parameters(&base.parameters);
+                }
+                continue;
+            }
+
+            let mut added_effect = false;
+            if find_reverb_mut(bus).is_none() {
+                bus.add_effect(Effect::Reverb(Reverb::new()));
+                added_effect = true;
+            }
+            let Some(reverb) = find_reverb_mut(bus) else {
+                continue;
+            };
+
+            let base = self
+                .reverb_zone_bases
+                .entry(bus_name.clone())
+                .or_insert_with(|| ReverbZoneBase {
+                    parameters: reverb.parameters(),
+                    added_effect,
+                })
+                .parameters;
+            reverb.set_parameters(
+                &base.blend(zones.map(|influence| (influence.weight, &influence.parameters))),
+            );
+        }
+    }
+
+    // Temporarily reverts the changes made by reverb zones, the changes could be put back by
+    // `reapply_reverb_zones`.
+    fn revert_reverb_zones(&mut self) -> Vec<(String, RevertedReverbZone)> {
+        let mut reverted = Vec::new();
+        let mut state = self.native.state();
+        for (bus_name, base) in self.reverb_zone_bases.iter() {
+            let Some(bus) = find_bus_mut(state.bus_graph_mut(), bus_name) else {
+                continue;
+            };
+            if base.added_effect {
+                if let Some(index) = find_reverb_index(bus) {
+                    if let Some(effect) = bus.effect(index).cloned() {
+                        bus.remove_effect(index);
+                        reverted.push((bus_name.clone(), RevertedReverbZone::Effect(effect)));
+                    }
+                }
+            } else if let Some(reverb) = find_reverb_mut(bus) {
+                let parameters = reverb.parameters();
+                reverb.set_parameters(&base.parameters);
+                reverted.push((bus_name.clone(), RevertedReverbZone::Parameters(parameters)));
+            }
+        }
+        reverted
+    }
+
+    fn reapply_reverb_zones(&mut self, reverted: Vec<(String, RevertedReverbZone)>) {
+        let mut state = self.native.state();
+        for (bus_name, change) in reverted {
+            let Some(bus) = find_bus_mut(state.bus_graph_mut(), &bus_name) else {
+                continue;
+            };
+            match change {
+                RevertedReverbZone::Parameters(parameters) => {
+                    if let Some(reverb) = find_reverb_mut(bus) {
+                        reverb.set_parameters(&parameters);
+                    }
+                }
+                RevertedReverbZone::Effect(effect) => bus.add_effect(effect),
+            }
+        }
+    }
+
     pub(crate) fn remove_sound(&mut self, sound: Handle<SoundSource>, name: &str) {
         let mut state = self.native.state();
         if state.is_valid_handle(sound) {
@@ -153,11 +351,14 @@ impl SoundContext {
     pub(crate) fn set_sound_position(&mut self, sound: &Sound) {
         if let Ok(source) = self.native.state().try_get_source_mut(sound.native.get()) {
             source.set_position(sound.global_position());
+            source.set_direction(sound.look_vector());
         }
     }
 
     pub(crate) fn sync_with_sound(&self, sound: &mut Sound) {
         if let Ok(source) = self.native.state().try_get_source_mut(sound.native.get()) {
+            source.set_velocity(sound.velocity());
+
             // Sync back.
             sound.status.set_value_silent(source.status());
             sound
@@ -194,6 +395,15 @@ impl SoundContext {
             sound.radius.try_sync_model(|v| {
                 source.set_radius(v);
             });
+            sound.inner_cone_angle.try_sync_model(|v| {
+                source.set_inner_cone_angle(v);
+            });
+            sound.outer_cone_angle.try_sync_model(|v| {
+                source.set_outer_cone_angle(v);
+            });
+            sound.outer_cone_gain.try_sync_model(|v| {
+                source.set_outer_cone_gain(v);
+            });
             sound.playback_time.try_sync_model(|v| {
                 source.set_playback_time(Duration::from_secs_f32(v));
             });
@@ -236,7 +446,12 @@ impl SoundContext {
                 .with_status(sound.status())
                 .with_playback_time(Duration::from_secs_f32(sound.playback_time()))
                 .with_position(sound.global_position())
+                .with_direction(sound.look_vector())
+                .with_velocity(sound.velocity())
                 .with_radius(sound.radius())
+                .with_inner_cone_angle(sound.inner_cone_angle())
+                .with_outer_cone_angle(sound.outer_cone_angle())
+                .with_outer_cone_gain(sound.outer_cone_gain())
                 .with_max_distance(sound.max_distance())
                 .with_bus(sound.audio_bus())
                 .with_rolloff_factor(sound.rolloff_factor())
@@ -264,3 +479,100 @@ impl SoundContext {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::visitor::prelude::*,
+        scene::sound::context::{find_reverb_mut, ReverbZoneInfluence, SoundContext},
+    };
+    use fyrox_sound::{
+        bus::AudioBusGraph,
+        effects::{
+            reverb::{Reverb, ReverbParameters},
+            Effect,
+        },
+    };
+
+    fn zone_parameters() -> ReverbParameters {
+        ReverbParameters {
+            dry: 0.5,
+            wet: 0.25,
+            decay_time: 5.0,
+            fc: 0.5,
+        }
+    }
+
+    fn enter_zone(context: &mut SoundContext) {
+        context.add_reverb_zone_influence(ReverbZoneInfluence {
+            audio_bus: AudioBusGraph::PRIMARY_BUS.to_string(),
+            weight: 1.0,
+            parameters: zone_parameters(),
+        });
+        context.apply_reverb_zones();
+    }
+
+    fn primary_reverb_parameters(context: &SoundContext) -> Option<ReverbParameters> {
+        let mut state = context.state();
+        find_reverb_mut(state.bus_graph_mut().primary_bus_mut()).map(|reverb| reverb.parameters())
+    }
+
+    #[test]
+    fn test_leave_reverb_zone_removes_added_effect() {
+        let mut context = SoundContext::new();
+        assert_eq!(primary_reverb_parameters(&context), None);
+
+        enter_zone(&mut context);
+        assert_eq!(primary_reverb_parameters(&context), Some(zone_parameters()));
+
+        // No influences - the listener has left the zone.
+        context.apply_reverb_zones();
+        assert_eq!(primary_reverb_parameters(&context), None);
+        assert_eq!(
+            context
+                .state()
+                .bus_graph_ref()
+                .primary_bus_ref()
+                .effects()
+                .count(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_leave_reverb_zone_restores_parameters() {
+        let base = ReverbParameters::default();
+        let mut context = SoundContext::new();
+        let mut reverb = Reverb::new();
+        reverb.set_parameters(&base);
+        context
+            .state()
+            .bus_graph_mut()
+            .primary_bus_mut()
+            .add_effect(Effect::Reverb(reverb));
+
+        enter_zone(&mut context);
+        assert_eq!(primary_reverb_parameters(&context), Some(zone_parameters()));
+
+        context.apply_reverb_zones();
+        assert_eq!(primary_reverb_parameters(&context), Some(base));
+    }
+
+    #[test]
+    fn test_reverb_zones_are_not_saved() {
+        let mut context = SoundContext::new();
+        enter_zone(&mut context);
+
+        let mut visitor = Visitor::new();
+        context.visit("Context", &mut visitor).unwrap();
+        let data = visitor.save_binary_to_vec().unwrap();
+
+        // Zone changes must stay in effect after saving.
+        assert_eq!(primary_reverb_parameters(&context), Some(zone_parameters()));
+
+        let mut loaded = SoundContext::new();
+        let mut visitor = Visitor::load_binary_from_memory(&data).unwrap();
+        loaded.visit("Context", &mut visitor).unwrap();
+        assert_eq!(primary_reverb_parameters(&loaded), None);
+    }
+}
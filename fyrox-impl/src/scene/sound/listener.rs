@@ -26,6 +26,7 @@
 use crate::scene::node::constructor::NodeConstructor;
 use crate::{
     core::{
+        algebra::Vector3,
         math::aabb::AxisAlignedBoundingBox,
         pool::Handle,
         reflect::prelude::*,
@@ -36,7 +37,7 @@ use crate::{
     scene::{
         base::{Base, BaseBuilder},
         graph::Graph,
-        node::{Node, NodeTrait, SyncContext},
+        node::{Node, NodeTrait, SyncContext, UpdateContext},
     },
 };
 use fyrox_graph::constructor::ConstructorProvider;
@@ -61,6 +62,14 @@ use std::ops::{Deref, DerefMut};
 #[reflect(derived_type = "Node")]
 pub struct Listener {
     base: Base,
+
+    #[reflect(hidden)]
+    #[visit(skip)]
+    last_position: Option<Vector3<f32>>,
+
+    #[reflect(hidden)]
+    #[visit(skip)]
+    velocity: Vector3<f32>,
 }
 
 impl Deref for Listener {
@@ -77,6 +86,14 @@ impl DerefMut for Listener {
     }
 }
 
+impl Listener {
+    /// Returns velocity of the listener in world space. Velocity is calculated automatically from
+    /// the changes of global position of the node and it is used to simulate Doppler effect.
+    pub fn velocity(&self) -> Vector3<f32> {
+        self.velocity
+    }
+}
+
 impl TypeUuidProvider for Listener {
     fn type_uuid() -> Uuid {
         uuid!("2c7dabc1-5666-4256-b020-01532701e4c6")
@@ -123,6 +140,25 @@ impl NodeTrait for Listener {
         native.set_position(self.global_position());
         native.set_orientation_lh(self.look_vector(), self.up_vector());
     }
+
+    fn update(&mut self, context: &mut UpdateContext) {
+        let position = self.global_position();
+        self.velocity = match self.last_position.replace(position) {
+            Some(last_position) if context.dt > 0.0 => {
+                (position - last_position).scale(1.0 / context.dt)
+            }
+            _ => Default::default(),
+        };
+
+        if self.is_globally_enabled() {
+            context
+                .sound_context
+                .native
+                .state()
+                .listener_mut()
+                .set_velocity(self.velocity);
+        }
+    }
 }
 
 /// Allows you to create listener in declarative manner.
@@ -140,6 +176,8 @@ impl ListenerBuilder {
     pub fn build_listener(self) -> Listener {
         Listener {
             base: self.base_builder.build_base(),
+            last_position: None,
+            velocity: Default::default(),
         }
     }
 
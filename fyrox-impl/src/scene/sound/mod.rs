@@ -22,7 +22,7 @@
 
 use crate::{
     core::{
-        algebra::Matrix4,
+        algebra::{Matrix4, Vector3},
         math::{aabb::AxisAlignedBoundingBox, m4x4_approx_eq},
         pool::Handle,
         reflect::prelude::*,
@@ -71,6 +71,7 @@ use std::{
 
 pub mod context;
 pub mod listener;
+pub mod reverb_zone;
 
 /// Sound source.
 #[derive(Visit, Reflect, Debug, ComponentProvider)]
@@ -114,6 +115,21 @@ pub struct Sound {
     #[reflect(setter = "set_rolloff_factor")]
     rolloff_factor: InheritableVariable<f32>,
 
+    #[visit(optional)]
+    #[reflect(min_value = 0.0, step = 0.05)]
+    #[reflect(setter = "set_inner_cone_angle")]
+    inner_cone_angle: InheritableVariable<f32>,
+
+    #[visit(optional)]
+    #[reflect(min_value = 0.0, step = 0.05)]
+    #[reflect(setter = "set_outer_cone_angle")]
+    outer_cone_angle: InheritableVariable<f32>,
+
+    #[visit(optional)]
+    #[reflect(min_value = 0.0, max_value = 1.0, step = 0.05)]
+    #[reflect(setter = "set_outer_cone_gain")]
+    outer_cone_gain: InheritableVariable<f32>,
+
     #[visit(optional)]
     #[reflect(setter = "set_playback_time", min_value = 0.0)]
     playback_time: InheritableVariable<f32>,
@@ -128,6 +144,14 @@ pub struct Sound {
     #[reflect(hidden)]
     #[visit(skip)]
     pub(crate) native: Cell<Handle<SoundSource>>,
+
+    #[reflect(hidden)]
+    #[visit(skip)]
+    last_position: Option<Vector3<f32>>,
+
+    #[reflect(hidden)]
+    #[visit(skip)]
+    velocity: Vector3<f32>,
 }
 
 impl Deref for Sound {
@@ -158,10 +182,15 @@ impl Default for Sound {
             radius: InheritableVariable::new_modified(10.0),
             max_distance: InheritableVariable::new_modified(f32::MAX),
             rolloff_factor: InheritableVariable::new_modified(1.0),
+            inner_cone_angle: InheritableVariable::new_modified(std::f32::consts::TAU),
+            outer_cone_angle: InheritableVariable::new_modified(std::f32::consts::TAU),
+            outer_cone_gain: InheritableVariable::new_modified(0.0),
             playback_time: Default::default(),
             spatial_blend: InheritableVariable::new_modified(1.0),
             audio_bus: InheritableVariable::new_modified(AudioBusGraph::PRIMARY_BUS.to_string()),
             native: Default::default(),
+            last_position: None,
+            velocity: Default::default(),
         }
     }
 }
@@ -180,11 +209,16 @@ impl Clone for Sound {
             radius: self.radius.clone(),
             max_distance: self.max_distance.clone(),
             rolloff_factor: self.rolloff_factor.clone(),
+            inner_cone_angle: self.inner_cone_angle.clone(),
+            outer_cone_angle: self.outer_cone_angle.clone(),
+            outer_cone_gain: self.outer_cone_gain.clone(),
             playback_time: self.playback_time.clone(),
             spatial_blend: self.spatial_blend.clone(),
             audio_bus: self.audio_bus.clone(),
             // Do not copy. The copy will have its own native representation.
             native: Default::default(),
+            last_position: None,
+            velocity: Default::default(),
         }
     }
 }
@@ -376,6 +410,49 @@ impl Sound {
         *self.max_distance
     }
 
+    /// Sets full angle (in radians) of inner sound cone. The cone is directed along the look vector
+    /// of the node, the listener inside of it hears the sound with no cone attenuation. Default value
+    /// is `2*pi`, which means that the sound is omnidirectional.
+    pub fn set_inner_cone_angle(&mut self, angle: f32) -> f32 {
+        self.inner_cone_angle
+            .set_value_and_mark_modified(angle.clamp(0.0, std::f32::consts::TAU))
+    }
+
+    /// Returns full angle (in radians) of inner sound cone.
+    pub fn inner_cone_angle(&self) -> f32 {
+        *self.inner_cone_angle
+    }
+
+    /// Sets full angle (in radians) of outer sound cone. The listener outside of the outer cone hears
+    /// the sound with [`Self::outer_cone_gain`], between inner and outer cones the gain is linearly
+    /// interpolated. Default value is `2*pi`.
+    pub fn set_outer_cone_angle(&mut self, angle: f32) -> f32 {
+        self.outer_cone_angle
+            .set_value_and_mark_modified(angle.clamp(0.0, std::f32::consts::TAU))
+    }
+
+    /// Returns full angle (in radians) of outer sound cone.
+    pub fn outer_cone_angle(&self) -> f32 {
+        *self.outer_cone_angle
+    }
+
+    /// Sets gain that will be applied to the sound when the listener is outside of outer sound cone.
+    pub fn set_outer_cone_gain(&mut self, gain: f32) -> f32 {
+        self.outer_cone_gain
+            .set_value_and_mark_modified(gain.clamp(0.0, 1.0))
+    }
+
+    /// Returns gain of the sound outside of outer sound cone.
+    pub fn outer_cone_gain(&self) -> f32 {
+        *self.outer_cone_gain
+    }
+
+    /// Returns velocity of the sound in world space. Velocity is calculated automatically from the
+    /// changes of global position of the node and it is used to simulate Doppler effect.
+    pub fn velocity(&self) -> Vector3<f32> {
+        self.velocity
+    }
+
     /// Sets new audio bus name to which the sound will be attached.
     pub fn set_audio_bus(&mut self, name: String) {
         self.audio_bus.set_value_and_mark_modified(name);
@@ -447,6 +524,13 @@ impl NodeTrait for Sound {
     }
 
     fn update(&mut self, context: &mut UpdateContext) {
+        let position = self.global_position();
+        self.velocity = match self.last_position.replace(position) {
+            Some(last_position) if context.dt > 0.0 => {
+                (position - last_position).scale(1.0 / context.dt)
+            }
+            _ => Default::default(),
+        };
         context.sound_context.sync_with_sound(self);
     }
 
@@ -486,6 +570,9 @@ pub struct SoundBuilder {
     radius: f32,
     max_distance: f32,
     rolloff_factor: f32,
+    inner_cone_angle: f32,
+    outer_cone_angle: f32,
+    outer_cone_gain: f32,
     playback_time: Duration,
     spatial_blend: f32,
     audio_bus: String,
@@ -506,6 +593,9 @@ impl SoundBuilder {
             radius: 10.0,
             max_distance: f32::MAX,
             rolloff_factor: 1.0,
+            inner_cone_angle: std::f32::consts::TAU,
+            outer_cone_angle: std::f32::consts::TAU,
+            outer_cone_gain: 0.0,
             spatial_blend: 1.0,
             playback_time: Default::default(),
             audio_bus: AudioBusGraph::PRIMARY_BUS.to_string(),
@@ -562,6 +652,21 @@ impl SoundBuilder {
         fn with_rolloff_factor(rolloff_factor: f32)
     );
 
+    define_with!(
+        /// Sets desired inner cone angle. See [`Sound::set_inner_cone_angle`] for more info.
+        fn with_inner_cone_angle(inner_cone_angle: f32)
+    );
+
+    define_with!(
+        /// Sets desired outer cone angle. See [`Sound::set_outer_cone_angle`] for more info.
+        fn with_outer_cone_angle(outer_cone_angle: f32)
+    );
+
+    define_with!(
+        /// Sets desired outer cone gain. See [`Sound::set_outer_cone_gain`] for more info.
+        fn with_outer_cone_gain(outer_cone_gain: f32)
+    );
+
     define_with!(
         /// Sets desired spatial blend factor. See [`Sound::set_spatial_blend`] for more info.
         fn with_spatial_blend_factor(spatial_blend: f32)
@@ -592,10 +697,15 @@ impl SoundBuilder {
             radius: self.radius.into(),
             max_distance: self.max_distance.into(),
             rolloff_factor: self.rolloff_factor.into(),
+            inner_cone_angle: self.inner_cone_angle.into(),
+            outer_cone_angle: self.outer_cone_angle.into(),
+            outer_cone_gain: self.outer_cone_gain.into(),
             playback_time: self.playback_time.as_secs_f32().into(),
             spatial_blend: self.spatial_blend.into(),
             audio_bus: self.audio_bus.into(),
             native: Default::default(),
+            last_position: None,
+            velocity: Default::default(),
         }
     }
 
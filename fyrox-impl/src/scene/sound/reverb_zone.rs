@@ -0,0 +1,340 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Reverb zone is a volume in a scene, that defines acoustics of an environment.
+//!
+//! See [`ReverbZone`] docs for more info.
+
+use crate::{
+    core::{
+        algebra::Vector3,
+        math::aabb::AxisAlignedBoundingBox,
+        pool::Handle,
+        reflect::prelude::*,
+        type_traits::prelude::*,
+        uuid::{uuid, Uuid},
+        variable::InheritableVariable,
+        visitor::prelude::*,
+    },
+    graph::{constructor::ConstructorProvider, SceneGraph},
+    scene::{
+        base::{Base, BaseBuilder},
+        graph::Graph,
+        node::{constructor::NodeConstructor, Node, NodeTrait, UpdateContext},
+        sound::{context::ReverbZoneInfluence, AudioBusGraph},
+    },
+};
+use fyrox_sound::effects::reverb::ReverbPreset;
+use std::ops::{Deref, DerefMut};
+use strum_macros::{AsRefStr, EnumString, VariantNames};
+
+/// Shape of a reverb zone. All sizes are given in local coordinates of the zone.
+#[derive(
+    Clone, Debug, PartialEq, Visit, Reflect, TypeUuidProvider, AsRefStr, EnumString, VariantNames,
+)]
+#[type_uuid(id = "f5b7f4a2-2b9e-4f0c-9d35-3c0f8e6a8d11")]
+pub enum ReverbZoneShape {
+    /// Oriented box.
+    Box {
+        /// Half of the size of the box along each axis.
+        half_extents: Vector3<f32>,
+    },
+    /// Sphere.
+    Sphere {
+        /// Radius of the sphere.
+        radius: f32,
+    },
+}
+
+impl Default for ReverbZoneShape {
+    fn default() -> Self {
+        Self::Box {
+            half_extents: Vector3::repeat(0.5),
+        }
+    }
+}
+
+impl ReverbZoneShape {
+    /// Returns distance from the given point (in local coordinates of the zone) to the surface of
+    /// the shape. The distance is zero if the point is inside the shape.
+    pub fn distance(&self, point: Vector3<f32>) -> f32 {
+        match self {
+            ReverbZoneShape::Box { half_extents } => point
+                .abs()
+                .zip_map(half_extents, |p, e| (p - e).max(0.0))
+                .norm(),
+            ReverbZoneShape::Sphere { radius } => (point.norm() - radius).max(0.0),
+        }
+    }
+}
+
+/// Reverb zone is a volume (box or sphere) that defines acoustics of an environment, for example a
+/// small room, a large hall or a cave. When the listener is inside the zone, the engine automatically
+/// changes the parameters of the reverb effect on the audio bus of the zone to the ones of the zone.
+/// Level designers can set up acoustics of a level without any scripting.
+///
+/// ## Blending
+///
+/// Reverb zone has blend distance, that defines a distance outside the zone at which the zone starts
+/// to influence the reverb. The influence grows linearly while the listener approaches the zone and
+/// reaches its maximum on the surface of the zone. This way the reverb parameters are smoothly
+/// interpolated when the listener moves from one zone to another. Overlapping zones are mixed
+/// proportionally to their influence. When the listener is outside of any zone, the reverb has its
+/// original parameters.
+///
+/// ## Audio bus
+///
+/// Each zone drives the reverb effect of an audio bus with a specific name (primary bus by default).
+/// If the bus has no reverb effect, it will be added automatically. Sounds, that should not be
+/// affected by environment acoustics (for example, music or UI sounds) should be put on a different
+/// bus.
+///
+/// ## Example
+///
+/// ```rust
+/// # use fyrox_impl::{
+/// #     core::{algebra::Vector3, pool::Handle},
+/// #     scene::{
+/// #         base::BaseBuilder,
+/// #         graph::Graph,
+/// #         sound::{
+/// #             reverb_zone::{ReverbZone, ReverbZoneBuilder, ReverbZoneShape},
+/// #             reverb::ReverbPreset,
+/// #         },
+/// #     },
+/// # };
+/// fn create_cave_zone(graph: &mut Graph) -> Handle<ReverbZone> {
+///     ReverbZoneBuilder::new(BaseBuilder::new())
+///         .with_shape(ReverbZoneShape::Sphere { radius: 20.0 })
+///         .with_preset(ReverbPreset::Cave)
+///         .with_blend_distance(5.0)
+///         .build(graph)
+/// }
+/// ```
+#[derive(Clone, Reflect, Debug, Visit, ComponentProvider)]
+#[reflect(derived_type = "Node")]
+#[visit(optional)]
+pub struct ReverbZone {
+    base: Base,
+
+    /// Shape of the zone. See [`ReverbZoneShape`] docs for more info.
+    pub shape: InheritableVariable<ReverbZoneShape>,
+
+    /// A set of reverb parameters of the zone. See [`ReverbPreset`] docs for more info.
+    pub preset: InheritableVariable<ReverbPreset>,
+
+    /// A distance outside the zone at which the zone starts to influence the reverb. See
+    /// [`ReverbZone`] docs for more info.
+    #[reflect(min_value = 0.0, step = 0.1)]
+    pub blend_distance: InheritableVariable<f32>,
+
+    /// A name of an audio bus, whose reverb effect is controlled by the zone.
+    pub audio_bus: InheritableVariable<String>,
+}
+
+impl Default for ReverbZone {
+    fn default() -> Self {
+        Self {
+            base: Default::default(),
+            shape: Default::default(),
+            preset: Default::default(),
+            blend_distance: 1.0.into(),
+            audio_bus: AudioBusGraph::PRIMARY_BUS.to_string().into(),
+        }
+    }
+}
+
+impl Deref for ReverbZone {
+    type Target = Base;
+
+    fn deref(&self) -> &Self::Target {
+        &self.base
+    }
+}
+
+impl DerefMut for ReverbZone {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.base
+    }
+}
+
+impl TypeUuidProvider for ReverbZone {
+    fn type_uuid() -> Uuid {
+        uuid!("4b0c8d3e-71a4-4b43-9b42-2f3a6f9e5c27")
+    }
+}
+
+impl ReverbZone {
+    /// Calculates influence of the zone in `[0; 1]` range for a listener at the given position (in
+    /// world coordinates). See [`ReverbZone`] docs for more info.
+    pub fn influence(&self, listener_position: Vector3<f32>) -> f32 {
+        let local_position = self
+            .global_transform()
+            .try_inverse()
+            .map(|inv| inv.transform_point(&listener_position.into()).coords)
+            .unwrap_or_default();
+        let distance = self.shape.distance(local_position);
+        if distance <= 0.0 {
+            1.0
+        } else if *self.blend_distance > 0.0 {
+            (1.0 - distance / *self.blend_distance).max(0.0)
+        } else {
+            0.0
+        }
+    }
+}
+
+impl ConstructorProvider<Node, Graph> for ReverbZone {
+    fn constructor() -> NodeConstructor {
+        NodeConstructor::new::<Self>()
+            .with_variant("Reverb Zone", |_| {
+                ReverbZoneBuilder::new(BaseBuilder::new().with_name("Reverb Zone"))
+                    .build_node()
+                    .into()
+            })
+            .with_group("Sound")
+    }
+}
+
+impl NodeTrait for ReverbZone {
+    fn local_bounding_box(&self) -> AxisAlignedBoundingBox {
+        match *self.shape {
+            ReverbZoneShape::Box { half_extents } => {
+                AxisAlignedBoundingBox::from_min_max(-half_extents, half_extents)
+            }
+            ReverbZoneShape::Sphere { radius } => AxisAlignedBoundingBox::from_radius(radius),
+        }
+    }
+
+    fn world_bounding_box(&self) -> AxisAlignedBoundingBox {
+        self.local_bounding_box()
+            .transform(&self.global_transform())
+    }
+
+    fn id(&self) -> Uuid {
+        Self::type_uuid()
+    }
+
+    fn update(&mut self, context: &mut UpdateContext) {
+        let listener_position = context.sound_context.native.state().listener().position();
+        let weight = self.influence(listener_position);
+        if weight > 0.0 {
+            context
+                .sound_context
+                .add_reverb_zone_influence(ReverbZoneInfluence {
+                    audio_bus: (*self.audio_bus).clone(),
+                    weight,
+                    parameters: self.preset.parameters(),
+                });
+        }
+    }
+}
+
+/// Allows you to create a reverb zone node declaratively.
+pub struct ReverbZoneBuilder {
+    base_builder: BaseBuilder,
+    shape: ReverbZoneShape,
+    preset: ReverbPreset,
+    blend_distance: f32,
+    audio_bus: String,
+}
+
+impl ReverbZoneBuilder {
+    /// Creates a new reverb zone builder.
+    pub fn new(base_builder: BaseBuilder) -> Self {
+        Self {
+            base_builder,
+            shape: Default::default(),
+            preset: Default::default(),
+            blend_distance: 1.0,
+            audio_bus: AudioBusGraph::PRIMARY_BUS.to_string(),
+        }
+    }
+
+    /// Sets the desired shape of the zone.
+    pub fn with_shape(mut self, shape: ReverbZoneShape) -> Self {
+        self.shape = shape;
+        self
+    }
+
+    /// Sets the desired reverb preset of the zone.
+    pub fn with_preset(mut self, preset: ReverbPreset) -> Self {
+        self.preset = preset;
+        self
+    }
+
+    /// Sets the desired blend distance of the zone.
+    pub fn with_blend_distance(mut self, blend_distance: f32) -> Self {
+        self.blend_distance = blend_distance;
+        self
+    }
+
+    /// Sets the name of the audio bus, whose reverb effect will be controlled by the zone.
+    pub fn with_audio_bus(mut self, audio_bus: String) -> Self {
+        self.audio_bus = audio_bus;
+        self
+    }
+
+    /// Creates a new reverb zone.
+    pub fn build_reverb_zone(self) -> ReverbZone {
+        ReverbZone {
+            base: self.base_builder.build_base(),
+            shape: self.shape.into(),
+            preset: self.preset.into(),
+            blend_distance: self.blend_distance.into(),
+            audio_bus: self.audio_bus.into(),
+        }
+    }
+
+    /// Creates a new reverb zone node.
+    pub fn build_node(self) -> Node {
+        Node::new(self.build_reverb_zone())
+    }
+
+    /// Creates a new reverb zone node and adds it to the graph.
+    pub fn build(self, graph: &mut Graph) -> Handle<ReverbZone> {
+        graph.add_node(self.build_node()).to_variant()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::algebra::Vector3,
+        scene::{
+            base::BaseBuilder,
+            sound::reverb_zone::{ReverbZoneBuilder, ReverbZoneShape},
+        },
+    };
+
+    #[test]
+    fn test_reverb_zone_influence() {
+        let zone = ReverbZoneBuilder::new(BaseBuilder::new())
+            .with_shape(ReverbZoneShape::Box {
+                half_extents: Vector3::repeat(1.0),
+            })
+            .with_blend_distance(2.0)
+            .build_reverb_zone();
+
+        assert_eq!(zone.influence(Vector3::new(0.5, 0.0, 0.0)), 1.0);
+        assert_eq!(zone.influence(Vector3::new(2.0, 0.0, 0.0)), 0.5);
+        assert_eq!(zone.influence(Vector3::new(5.0, 0.0, 0.0)), 0.0);
+    }
+}
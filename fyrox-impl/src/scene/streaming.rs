@@ -0,0 +1,694 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! World streaming allows you to split a large world into a set of cells (sub-scenes or prefabs)
+//! that are loaded and instantiated in the scene only when an observer (a camera or any other
+//! node) comes close to them, and unloaded when it moves away. See [`WorldStreamer`] docs for
+//! more info.
+
+use crate::{
+    asset::{manager::ResourceManager, queue::LoadPriority},
+    core::{
+        algebra::Vector3, log::Log, math::aabb::AxisAlignedBoundingBox, pool::Handle,
+        reflect::prelude::*, visitor::prelude::*,
+    },
+    graph::{NodeHandleMap, SceneGraph},
+    resource::model::{Model, ModelResource, ModelResourceExtension},
+    scene::{camera::Camera, node::Node, Scene},
+};
+use fyrox_core::uuid_provider;
+use std::{cmp::Ordering, path::PathBuf};
+
+/// Runtime state of a streaming cell.
+#[derive(Default, Clone, Debug, PartialEq)]
+pub enum StreamingCellState {
+    /// The cell is not loaded.
+    #[default]
+    Unloaded,
+    /// The resource of the cell was requested and is being loaded.
+    Loading(ModelResource),
+    /// The cell is loaded and instantiated in the scene.
+    Loaded {
+        /// The resource of the cell. It is held to prevent the resource manager from unloading it.
+        resource: ModelResource,
+        /// A handle of the root node of the instance.
+        root: Handle<Node>,
+    },
+    /// The resource of the cell has failed to load. The cell won't be requested again until the
+    /// observers leave its unload radius.
+    Failed,
+    /// The cell was loaded when the scene was saved, so its instance was saved as a part of the
+    /// scene. The instance with the given root will be taken over by the streamer on the next
+    /// update.
+    Restored(Handle<Node>),
+}
+
+impl Visit for StreamingCellState {
+    fn visit(&mut self, name: &str, visitor: &mut Visitor) -> VisitResult {
+        let mut region = visitor.enter_region(name)?;
+
+        // Only the instances of the loaded cells are stored, everything else is loaded again when
+        // needed.
+        let mut root = match self {
+            StreamingCellState::Loaded { root, .. } | StreamingCellState::Restored(root) => *root,
+            _ => Handle::NONE,
+        };
+        root.visit("Root", &mut region)?;
+
+        if region.is_reading() {
+            *self = if root.is_some() {
+                StreamingCellState::Restored(root)
+            } else {
+                StreamingCellState::Unloaded
+            };
+        }
+
+        Ok(())
+    }
+}
+
+/// A part of the world, that is loaded on demand. The cell is defined by a path to a model resource
+/// (a prefab or a scene) with the content of the cell and by its world-space bounds, that are used to
+/// calculate the distance from the observers.
+#[derive(Visit, Reflect, Clone, Debug)]
+pub struct StreamingCell {
+    /// Name of the cell. It is used for debugging purposes only.
+    pub name: String,
+    /// A path to a model resource with the content of the cell. The path is stored instead of the
+    /// resource itself, to prevent the resource from being loaded together with the scene.
+    pub path: PathBuf,
+    /// Minimum point of the world-space bounds of the cell.
+    pub min: Vector3<f32>,
+    /// Maximum point of the world-space bounds of the cell.
+    pub max: Vector3<f32>,
+    /// Distance from the bounds of the cell at which the cell will be loaded.
+    #[reflect(min_value = 0.0)]
+    pub load_distance: f32,
+    /// Distance from the bounds of the cell at which the cell will be unloaded. It should be larger
+    /// than the load distance, the difference between the two distances defines the hysteresis that
+    /// prevents the cell from being loaded and unloaded repeatedly when an observer moves along the
+    /// load boundary.
+    #[reflect(min_value = 0.0)]
+    pub unload_distance: f32,
+    #[visit(optional)]
+    #[reflect(hidden)]
+    state: StreamingCellState,
+}
+
+uuid_provider!(StreamingCell = "8b0c2d6e-4f1a-4e3b-9c7d-2a5e8f1b3c60");
+
+impl Default for StreamingCell {
+    fn default() -> Self {
+        Self {
+            name: Default::default(),
+            path: Default::default(),
+            min: Vector3::repeat(-50.0),
+            max: Vector3::repeat(50.0),
+            load_distance: 100.0,
+            unload_distance: 150.0,
+            state: Default::default(),
+        }
+    }
+}
+
+impl PartialEq for StreamingCell {
+    fn eq(&self, other: &Self) -> bool {
+        // Runtime state is intentionally ignored.
+        self.name == other.name
+            && self.path == other.path
+            && self.min == other.min
+            && self.max == other.max
+            && self.load_distance == other.load_distance
+            && self.unload_distance == other.unload_distance
+    }
+}
+
+impl StreamingCell {
+    /// Creates a new cell with the given path and world-space bounds.
+    pub fn new(path: impl Into<PathBuf>, bounds: AxisAlignedBoundingBox) -> Self {
+        Self {
+            path: path.into(),
+            min: bounds.min,
+            max: bounds.max,
+            ..Default::default()
+        }
+    }
+
+    /// Sets the name of the cell.
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+
+    /// Sets the load and unload distances of the cell. The unload distance will be clamped so it
+    /// is never less than the load distance.
+    pub fn with_distances(mut self, load_distance: f32, unload_distance: f32) -> Self {
+        self.load_distance = load_distance.max(0.0);
+        self.unload_distance = unload_distance.max(self.load_distance);
+        self
+    }
+
+    /// Returns world-space bounds of the cell.
+    pub fn bounds(&self) -> AxisAlignedBoundingBox {
+        AxisAlignedBoundingBox::from_min_max(self.min, self.max)
+    }
+
+    /// Returns the current runtime state of the cell.
+    pub fn state(&self) -> &StreamingCellState {
+        &self.state
+    }
+
+    /// Returns a handle of the root node of the cell instance, if the cell is loaded.
+    pub fn root(&self) -> Handle<Node> {
+        match self.state {
+            StreamingCellState::Loaded { root, .. } | StreamingCellState::Restored(root) => root,
+            _ => Handle::NONE,
+        }
+    }
+
+    /// Returns the distance from the given point to the bounds of the cell. The distance is zero
+    /// if the point is inside the bounds.
+    pub fn distance_to(&self, point: Vector3<f32>) -> f32 {
+        let closest = point.sup(&self.min).inf(&self.max);
+        (point - closest).norm()
+    }
+}
+
+/// An event, that is produced by [`WorldStreamer`] when the state of a cell changes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum StreamingEvent {
+    /// A cell was loaded and instantiated in the scene.
+    CellLoaded {
+        /// Index of the cell.
+        cell: usize,
+        /// A handle of the root node of the cell instance.
+        root: Handle<Node>,
+    },
+    /// A cell was unloaded and its instance was removed from the scene.
+    CellUnloaded {
+        /// Index of the cell.
+        cell: usize,
+    },
+    /// The resource of a cell has failed to load.
+    CellFailed {
+        /// Index of the cell.
+        cell: usize,
+    },
+}
+
+/// An action, that should be performed on a cell during the current update.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum CellAction {
+    Request { cell: usize, distance: f32 },
+    Cancel { cell: usize },
+    Instantiate { cell: usize, distance: f32 },
+    Unload { cell: usize, distance: f32 },
+    Fail { cell: usize },
+    Reset { cell: usize },
+}
+
+/// World streamer loads and unloads [`StreamingCell`]s depending on the distance from the observers.
+/// A cell is requested from the resource manager when the distance from an observer to its bounds
+/// becomes less than [`StreamingCell::load_distance`], then it is instantiated in the scene as soon as
+/// its resource is loaded. The instance is removed from the scene when the distance from the closest
+/// observer to the cell becomes larger than [`StreamingCell::unload_distance`].
+///
+/// Loading is asynchronous, but instantiation and node removal are done on the main thread, so the
+/// amount of work done per frame is limited by the budgets of the streamer. Cells closer to the
+/// observers are always processed first.
+///
+/// The streamer is a part of every scene (see [`Scene::streaming`]) and it is updated by the engine
+/// automatically, but it could also be updated manually by calling [`WorldStreamer::update`].
+///
+/// Instances of the loaded cells are saved as a part of the scene. The streamer remembers their roots
+/// and takes them over after the scene is loaded, so the cells are not instantiated twice. Call
+/// [`WorldStreamer::unload_all`] before saving, if the streamed content should not be saved.
+#[derive(Visit, Reflect, Clone, Debug, PartialEq)]
+pub struct WorldStreamer {
+    /// Whether the streamer is enabled or not. Disabled streamer does not load or unload any cells.
+    pub enabled: bool,
+    /// A set of cells of the world.
+    pub cells: Vec<StreamingCell>,
+    /// A set of nodes, around which the cells will be loaded. If empty, all enabled cameras of the
+    /// scene will be used as observers.
+    pub observers: Vec<Handle<Node>>,
+    /// A node to which the instances of the cells will be attached. If not set, the instances will
+    /// be attached to the root of the graph.
+    pub parent: Handle<Node>,
+    /// Maximum amount of resource requests per frame.
+    pub max_requests_per_frame: usize,
+    /// Maximum amount of cells instantiated per frame.
+    pub max_instantiations_per_frame: usize,
+    /// Maximum amount of cells unloaded per frame.
+    pub max_unloads_per_frame: usize,
+    #[visit(skip)]
+    #[reflect(hidden)]
+    events: Vec<StreamingEvent>,
+}
+
+uuid_provider!(WorldStreamer = "e4a7c1f3-9b2d-4c8e-a6f0-3d5b7e9a1c24");
+
+impl Default for WorldStreamer {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            cells: Default::default(),
+            observers: Default::default(),
+            parent: Default::default(),
+            max_requests_per_frame: 4,
+            max_instantiations_per_frame: 1,
+            max_unloads_per_frame: 2,
+            events: Default::default(),
+        }
+    }
+}
+
+impl WorldStreamer {
+    /// Adds a new cell to the streamer and returns its index.
+    pub fn add_cell(&mut self, cell: StreamingCell) -> usize {
+        self.cells.push(cell);
+        self.cells.len() - 1
+    }
+
+    /// Sets new observers of the streamer.
+    pub fn with_observers(mut self, observers: Vec<Handle<Node>>) -> Self {
+        self.observers = observers;
+        self
+    }
+
+    /// Sets new cells of the streamer.
+    pub fn with_cells(mut self, cells: Vec<StreamingCell>) -> Self {
+        self.cells = cells;
+        self
+    }
+
+    /// Returns `true` if the streamer has any cells, that are loading at the moment.
+    pub fn is_loading(&self) -> bool {
+        self.cells
+            .iter()
+            .any(|c| matches!(c.state, StreamingCellState::Loading(_)))
+    }
+
+    /// Maps the handles of the streamer using the given map, that was produced by cloning of a scene.
+    /// Cells with instances that were not cloned become unloaded.
+    pub(crate) fn remap_handles(&mut self, old_new_map: &NodeHandleMap<Node>) {
+        for cell in self.cells.iter_mut() {
+            let is_mapped = match cell.state {
+                StreamingCellState::Loaded { ref mut root, .. }
+                | StreamingCellState::Restored(ref mut root) => old_new_map.try_map(root),
+                _ => true,
+            };
+            if !is_mapped {
+                cell.state = StreamingCellState::Unloaded;
+            }
+        }
+        self.observers
+            .retain_mut(|observer| old_new_map.try_map(observer));
+        if !old_new_map.try_map(&mut self.parent) {
+            self.parent = Handle::NONE;
+        }
+    }
+
+    /// Returns and clears the events produced since the last call of this method.
+    pub fn take_events(&mut self) -> Vec<StreamingEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    fn observer_positions(&self, scene: &Scene) -> Vec<Vector3<f32>> {
+        if self.observers.is_empty() {
+            scene
+                .graph
+                .linear_iter()
+                .filter(|n| n.is_globally_enabled())
+                .filter_map(|n| n.cast::<Camera>())
+                .filter(|c| c.is_enabled())
+                .map(|c| c.global_position())
+                .collect()
+        } else {
+            self.observers
+                .iter()
+                .filter_map(|h| scene.graph.try_get(*h).ok())
+                .map(|n| n.global_position())
+                .collect()
+        }
+    }
+
+    /// Decides what should be done with every cell, the actions are sorted in the order of
+    /// priority and limited by the budgets of the streamer.
+    fn plan(&self, observers: &[Vector3<f32>]) -> Vec<CellAction> {
+        let mut requests = Vec::new();
+        let mut instantiations = Vec::new();
+        let mut unloads = Vec::new();
+        let mut actions = Vec::new();
+
+        for (cell, data) in self.cells.iter().enumerate() {
+            let distance = observers
+                .iter()
+                .map(|p| data.distance_to(*p))
+                .fold(f32::MAX, f32::min);
+            let outside = distance > data.unload_distance.max(data.load_distance);
+
+            match data.state {
+                StreamingCellState::Unloaded => {
+                    if distance <= data.load_distance {
+                        requests.push(CellAction::Request { cell, distance });
+                    }
+                }
+                StreamingCellState::Loading(ref resource) => {
+                    if outside {
+                        actions.push(CellAction::Cancel { cell });
+                    } else if resource.is_ok() {
+                        instantiations.push(CellAction::Instantiate { cell, distance });
+                    } else if resource.is_failed_to_load() {
+                        actions.push(CellAction::Fail { cell });
+                    }
+                }
+                StreamingCellState::Loaded { .. } => {
+                    if outside {
+                        unloads.push(CellAction::Unload { cell, distance });
+                    }
+                }
+                StreamingCellState::Failed => {
+                    if outside {
+                        actions.push(CellAction::Reset { cell });
+                    }
+                }
+                // Restored cells are taken over before planning.
+                StreamingCellState::Restored(_) => (),
+            }
+        }
+
+        fn sort_by_distance(actions: &mut [CellAction], farthest_first: bool) {
+            let key = |a: &CellAction| match *a {
+                CellAction::Request { distance, .. }
+                | CellAction::Instantiate { distance, .. }
+                | CellAction::Unload { distance, .. } => distance,
+                _ => 0.0,
+            };
+            actions.sort_by(|a, b| {
+                let ordering = key(a).partial_cmp(&key(b)).unwrap_or(Ordering::Equal);
+                if farthest_first {
+                    ordering.reverse()
+                } else {
+                    ordering
+                }
+            });
+        }
+
+        sort_by_distance(&mut requests, false);
+        sort_by_distance(&mut instantiations, false);
+        sort_by_distance(&mut unloads, true);
+
+        actions.extend(unloads.into_iter().take(self.max_unloads_per_frame));
+        actions.extend(
+            instantiations
+                .into_iter()
+                .take(self.max_instantiations_per_frame),
+        );
+        actions.extend(requests.into_iter().take(self.max_requests_per_frame));
+        actions
+    }
+
+    fn unload_cell(&mut self, cell: usize, scene: &mut Scene) {
+        if let StreamingCellState::Loaded { root, .. } = std::mem::take(&mut self.cells[cell].state)
+        {
+            if scene.graph.is_valid_handle(root) {
+                scene.graph.remove_node(root);
+            }
+            self.events.push(StreamingEvent::CellUnloaded { cell });
+        }
+    }
+
+    /// Unloads all loaded cells and cancels all pending requests.
+    pub fn unload_all(&mut self, scene: &mut Scene) {
+        for cell in 0..self.cells.len() {
+            self.unload_cell(cell, scene);
+            self.cells[cell].state = StreamingCellState::Unloaded;
+        }
+    }
+
+    /// Updates the state of the cells. Requests the cells that are close to the observers, instantiates
+    /// the loaded ones and removes the instances of the distant cells from the scene. This method is
+    /// called by the engine automatically for every enabled scene.
+    pub fn update(&mut self, scene: &mut Scene, resource_manager: &ResourceManager) {
+        // Searching for the observers requires a full scan of the graph, when there are no
+        // explicit observers. It is pointless without any cells.
+        if !self.enabled || self.cells.is_empty() {
+            return;
+        }
+
+        for (cell, data) in self.cells.iter_mut().enumerate() {
+            match data.state {
+                // The instance could be deleted by some other code, in this case the cell must be
+                // loaded again when needed.
+                StreamingCellState::Loaded { root, .. } => {
+                    if !scene.graph.is_valid_handle(root) {
+                        data.state = StreamingCellState::Unloaded;
+                        self.events.push(StreamingEvent::CellUnloaded { cell });
+                    }
+                }
+                StreamingCellState::Restored(root) => {
+                    data.state = if scene.graph.is_valid_handle(root) {
+                        // The resource is already loaded, since the instance references it.
+                        let resource = resource_manager.request::<Model>(&data.path);
+                        StreamingCellState::Loaded { resource, root }
+                    } else {
+                        StreamingCellState::Unloaded
+                    };
+                }
+                _ => (),
+            }
+        }
+
+        let observers = self.observer_positions(scene);
+        if observers.is_empty() {
+            return;
+        }
+
+        for action in self.plan(&observers) {
+            match action {
+                CellAction::Request { cell, distance } => {
+                    let priority = if distance <= 0.0 {
+                        LoadPriority::High
+                    } else {
+                        LoadPriority::Normal
+                    };
+                    let resource = resource_manager
+                        .request_with_priority::<Model>(&self.cells[cell].path, priority);
+                    self.cells[cell].state = StreamingCellState::Loading(resource);
+                }
+                CellAction::Cancel { cell } | CellAction::Reset { cell } => {
+                    self.cells[cell].state = StreamingCellState::Unloaded;
+                }
+                CellAction::Instantiate { cell, .. } => {
+                    if let StreamingCellState::Loading(resource) =
+                        std::mem::take(&mut self.cells[cell].state)
+                    {
+                        let root = resource.instantiate(scene);
+                        if scene.graph.is_valid_handle(self.parent) {
+                            scene.graph.link_nodes(root, self.parent);
+                        }
+                        self.cells[cell].state = StreamingCellState::Loaded { resource, root };
+                        self.events.push(StreamingEvent::CellLoaded { cell, root });
+                    }
+                }
+                CellAction::Unload { cell, .. } => self.unload_cell(cell, scene),
+                CellAction::Fail { cell } => {
+                    Log::err(format!(
+                        "Unable to load streaming cell {} ({}).",
+                        self.cells[cell].name,
+                        self.cells[cell].path.display()
+                    ));
+                    self.cells[cell].state = StreamingCellState::Failed;
+                    self.events.push(StreamingEvent::CellFailed { cell });
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        asset::io::FsResourceIo,
+        core::futures::executor::block_on,
+        engine::{self, SerializationContext},
+        scene::{base::BaseBuilder, pivot::PivotBuilder, transform::TransformBuilder},
+    };
+    use std::{fs, path::Path, sync::Arc};
+
+    fn cell(x: f32) -> StreamingCell {
+        StreamingCell::new(
+            "cell.rgs",
+            AxisAlignedBoundingBox::from_min_max(
+                Vector3::new(x, 0.0, 0.0),
+                Vector3::new(x + 10.0, 10.0, 10.0),
+            ),
+        )
+        .with_distances(20.0, 40.0)
+    }
+
+    #[test]
+    fn test_cell_distance() {
+        let cell = cell(0.0);
+        assert_eq!(cell.distance_to(Vector3::new(5.0, 5.0, 5.0)), 0.0);
+        assert_eq!(cell.distance_to(Vector3::new(-3.0, 5.0, 5.0)), 3.0);
+        assert_eq!(cell.distance_to(Vector3::new(5.0, 14.0, 5.0)), 4.0);
+    }
+
+    #[test]
+    fn test_streaming_plan() {
+        let mut streamer = WorldStreamer {
+            max_requests_per_frame: 2,
+            ..Default::default()
+        }
+        .with_cells(vec![cell(100.0), cell(0.0), cell(20.0), cell(30.0)]);
+
+        // Nearest cells are requested first, the amount of requests is limited by the budget.
+        let actions = streamer.plan(&[Vector3::new(5.0, 5.0, 5.0)]);
+        assert_eq!(
+            actions,
+            vec![
+                CellAction::Request {
+                    cell: 1,
+                    distance: 0.0
+                },
+                CellAction::Request {
+                    cell: 2,
+                    distance: 15.0
+                }
+            ]
+        );
+
+        // Failed cells stay failed until the observer leaves their unload radius (hysteresis).
+        streamer.cells[1].state = StreamingCellState::Failed;
+        let actions = streamer.plan(&[Vector3::new(-30.0, 5.0, 5.0)]);
+        assert!(actions.is_empty());
+        let actions = streamer.plan(&[Vector3::new(-50.0, 5.0, 5.0)]);
+        assert_eq!(actions, vec![CellAction::Reset { cell: 1 }]);
+    }
+
+    fn make_resource_manager(root: &Path) -> ResourceManager {
+        let resource_manager =
+            ResourceManager::new(Arc::new(FsResourceIo), Arc::new(Default::default()));
+        resource_manager
+            .state()
+            .resource_registry
+            .lock()
+            .set_path(root.join("resources.registry"));
+        engine::initialize_resource_manager_loaders(
+            &resource_manager,
+            Arc::new(SerializationContext::new()),
+            Default::default(),
+            Default::default(),
+        );
+        resource_manager.update_or_load_registry();
+        resource_manager
+    }
+
+    #[test]
+    fn test_streaming_update() {
+        let root = Path::new("test_streaming_update");
+        if !root.exists() {
+            fs::create_dir_all(root).unwrap();
+        }
+
+        let cell_path = root.join("cell.rgs");
+        {
+            let mut cell_scene = Scene::new();
+            PivotBuilder::new(BaseBuilder::new().with_name("CellContent"))
+                .build(&mut cell_scene.graph);
+            let mut visitor = Visitor::new();
+            cell_scene.save("Scene", &mut visitor).unwrap();
+            visitor.save_ascii_to_file(&cell_path).unwrap();
+        }
+
+        let resource_manager = make_resource_manager(root);
+        let mut scene = Scene::new();
+        let observer = PivotBuilder::new(
+            BaseBuilder::new().with_local_transform(
+                TransformBuilder::new()
+                    .with_local_position(Vector3::new(5.0, 5.0, 5.0))
+                    .build(),
+            ),
+        )
+        .build(&mut scene.graph)
+        .to_base();
+        scene.graph.update_hierarchical_data();
+        scene.streaming = WorldStreamer::default()
+            .with_observers(vec![observer])
+            .with_cells(vec![StreamingCell::new(
+                cell_path,
+                AxisAlignedBoundingBox::from_min_max(Vector3::default(), Vector3::repeat(10.0)),
+            )
+            .with_distances(20.0, 40.0)]);
+
+        // The observer is inside the cell, it must be requested.
+        scene.update_streaming(&resource_manager);
+        let StreamingCellState::Loading(resource) = scene.streaming.cells[0].state().clone() else {
+            panic!("The cell must be loading!")
+        };
+        block_on(resource).unwrap();
+
+        // The resource is loaded, the cell must be instantiated.
+        scene.update_streaming(&resource_manager);
+        let cell_root = scene.streaming.cells[0].root();
+        assert!(scene.graph.is_valid_handle(cell_root));
+        assert_eq!(
+            scene.streaming.take_events(),
+            vec![StreamingEvent::CellLoaded {
+                cell: 0,
+                root: cell_root
+            }]
+        );
+
+        // Saved instances are taken over after loading instead of being instantiated again.
+        let mut visitor = Visitor::new();
+        scene.streaming.visit("Streaming", &mut visitor).unwrap();
+        let data = visitor.save_binary_to_vec().unwrap();
+        let mut visitor = Visitor::load_binary_from_memory(&data).unwrap();
+        let mut restored = WorldStreamer::default();
+        restored.visit("Streaming", &mut visitor).unwrap();
+        assert_eq!(
+            restored.cells[0].state(),
+            &StreamingCellState::Restored(cell_root)
+        );
+        let node_count = scene.graph.node_count();
+        std::mem::swap(&mut scene.streaming, &mut restored);
+        scene.update_streaming(&resource_manager);
+        assert_eq!(scene.streaming.cells[0].root(), cell_root);
+        assert_eq!(scene.graph.node_count(), node_count);
+
+        // The observer is outside the unload distance, the instance must be removed.
+        scene.graph[observer].set_position(Vector3::new(100.0, 5.0, 5.0));
+        scene.graph.update_hierarchical_data();
+        scene.update_streaming(&resource_manager);
+        assert!(!scene.graph.is_valid_handle(cell_root));
+        assert_eq!(
+            scene.streaming.cells[0].state(),
+            &StreamingCellState::Unloaded
+        );
+        assert_eq!(
+            scene.streaming.take_events(),
+            vec![StreamingEvent::CellUnloaded { cell: 0 }]
+        );
+    }
+}
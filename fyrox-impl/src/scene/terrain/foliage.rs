@@ -0,0 +1,759 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Foliage is a set of mesh instances (grass, bushes, flowers, small rocks, etc.) scattered over
+//! a terrain. See [`FoliageLayer`] docs for more info.
+
+use crate::{
+    asset::ResourceDataRef,
+    core::{
+        algebra::{Matrix4, UnitQuaternion, Vector2, Vector3},
+        math::aabb::AxisAlignedBoundingBox,
+        parking_lot::Mutex,
+        reflect::prelude::*,
+        type_traits::prelude::*,
+        uuid::Uuid,
+        visitor::prelude::*,
+        SafeLock,
+    },
+    graphics::ElementRange,
+    material::{Material, MaterialProperty, MaterialResource},
+    rand::{rngs::StdRng, Rng, SeedableRng},
+    renderer::{
+        self,
+        bundle::{RenderContext, SurfaceInstanceData},
+    },
+    resource::texture::{Texture, TextureResource},
+    scene::{
+        mesh::{surface::Surface, RenderPath},
+        terrain::{Chunk, Terrain},
+    },
+};
+use fxhash::{FxHashMap, FxHasher};
+use fyrox_core::uuid_provider;
+use std::{
+    fmt::{Debug, Formatter},
+    hash::Hasher,
+    ops::Range,
+};
+
+/// Maximum amount of instances of a single foliage layer per terrain chunk. It is a safety limit
+/// that prevents the engine from freezing when a too high density is set.
+pub const MAX_FOLIAGE_INSTANCES_PER_CHUNK: usize = 65536;
+
+/// A level of detail of a foliage layer. Instances, that are closer to the observer than
+/// [`Self::max_distance`] (and further than the max distance of the previous level) are rendered
+/// using the surfaces of this level.
+#[derive(Debug, Clone, Visit, Reflect, PartialEq)]
+pub struct FoliageLod {
+    /// Maximum distance (in meters) from the observer at which this level of detail is used.
+    #[reflect(min_value = 0.0)]
+    pub max_distance: f32,
+
+    /// A set of surfaces, that will be rendered for each instance. It is advised to use materials
+    /// with the standard foliage shader (see [`Material::standard_foliage`]), which supports
+    /// wind animation and distance fading.
+    pub surfaces: Vec<Surface>,
+}
+
+uuid_provider!(FoliageLod = "0f6b6c2e-8d5e-4f43-9b8c-5c1e7a4d2f90");
+
+impl Default for FoliageLod {
+    fn default() -> Self {
+        Self {
+            max_distance: 50.0,
+            surfaces: Default::default(),
+        }
+    }
+}
+
+/// Wind parameters of a foliage layer. They're passed to the material of the layer and used by the
+/// standard foliage shader to animate vertices of the instances.
+#[derive(Debug, Clone, Visit, Reflect, PartialEq)]
+pub struct FoliageWind {
+    /// Direction of the wind in XZ plane.
+    pub direction: Vector2<f32>,
+
+    /// Horizontal displacement (in meters) of a vertex per each meter of its height above the
+    /// root of an instance.
+    #[reflect(min_value = 0.0)]
+    pub strength: f32,
+
+    /// Frequency of the swaying.
+    #[reflect(min_value = 0.0)]
+    pub frequency: f32,
+}
+
+uuid_provider!(FoliageWind = "5a3e7d91-2c4b-4f0e-a6d8-9b1c3e5f7a20");
+
+impl Default for FoliageWind {
+    fn default() -> Self {
+        Self {
+            direction: Vector2::new(1.0, 0.0),
+            strength: 0.05,
+            frequency: 1.5,
+        }
+    }
+}
+
+/// Foliage layer defines a kind of vegetation (or any other small objects), that is automatically
+/// scattered over a terrain. Instances are placed randomly (but deterministically, using
+/// [`Self::seed`]) with the given [`Self::density`], which is modulated by the blending mask of
+/// a terrain layer (see [`Self::terrain_layer`]) and by the density mask of the foliage layer,
+/// that can be painted using [`crate::scene::terrain::brushstroke::BrushTarget::FoliageMask`].
+///
+/// Foliage is rendered using GPU instancing, instances further than the max distance of the
+/// last level of detail are culled. Instances are faded out smoothly in the last
+/// [`Self::fade_distance`] meters of the visible range, when the material uses the standard foliage
+/// shader.
+#[derive(Debug, Clone, Visit, Reflect, PartialEq)]
+pub struct FoliageLayer {
+    /// Name of the layer.
+    pub name: String,
+
+    /// Levels of detail of the layer, sorted by the max distance in ascending order.
+    pub lods: Vec<FoliageLod>,
+
+    /// Amount of instances per square meter at full density.
+    #[reflect(min_value = 0.0)]
+    pub density: f32,
+
+    /// Index of a terrain layer, whose blending mask defines where the foliage could grow. `None`
+    /// means that the foliage grows everywhere.
+    pub terrain_layer: Option<u32>,
+
+    /// Minimum value of the terrain layer mask (in `[0; 1]` range) at which the foliage could grow.
+    #[reflect(min_value = 0.0, max_value = 1.0)]
+    pub terrain_layer_threshold: f32,
+
+    /// Range of random uniform scale of instances.
+    pub scale: Range<f32>,
+
+    /// Whether instances should be randomly rotated around their vertical axis or not.
+    pub random_rotation: bool,
+
+    /// Whether instances should be aligned with the normal of the terrain or not.
+    pub align_to_normal: bool,
+
+    /// A seed for the random number generator, that is used to place instances.
+    pub seed: u64,
+
+    /// Approximate radius (in meters) of a single instance with scale 1.0. It is used for culling.
+    #[reflect(min_value = 0.0)]
+    pub bounding_radius: f32,
+
+    /// Length (in meters) of the range at the end of the visible distance, where instances are
+    /// faded out.
+    #[reflect(min_value = 0.0)]
+    pub fade_distance: f32,
+
+    /// Whether the instances should cast shadows or not.
+    pub cast_shadows: bool,
+
+    /// Wind parameters of the layer.
+    pub wind: FoliageWind,
+}
+
+uuid_provider!(FoliageLayer = "c2d4e6f8-1a3b-4c5d-8e7f-9a0b1c2d3e4f");
+
+impl Default for FoliageLayer {
+    fn default() -> Self {
+        Self {
+            name: "Foliage".to_string(),
+            lods: vec![FoliageLod::default()],
+            density: 1.0,
+            terrain_layer: None,
+            terrain_layer_threshold: 0.5,
+            scale: 0.8..1.2,
+            random_rotation: true,
+            align_to_normal: false,
+            seed: 0,
+            bounding_radius: 1.0,
+            fade_distance: 10.0,
+            cast_shadows: false,
+            wind: Default::default(),
+        }
+    }
+}
+
+impl FoliageLayer {
+    /// Returns the max distance at which the instances of the layer are visible.
+    pub fn draw_distance(&self) -> f32 {
+        self.lods.last().map_or(0.0, |lod| lod.max_distance)
+    }
+
+    /// Returns an index of the level of detail, that should be used for an instance at the given
+    /// distance from the observer. `None` means that the instance should be culled.
+    pub fn lod_index(&self, distance: f32) -> Option<usize> {
+        self.lods
+            .iter()
+            .position(|lod| distance <= lod.max_distance)
+    }
+
+    fn placement_hash(&self, hasher: &mut FxHasher) {
+        hasher.write_u32(self.density.to_bits());
+        hasher.write_u32(self.terrain_layer.unwrap_or(u32::MAX));
+        hasher.write_u32(self.terrain_layer_threshold.to_bits());
+        hasher.write_u32(self.scale.start.to_bits());
+        hasher.write_u32(self.scale.end.to_bits());
+        hasher.write_u8(self.random_rotation as u8);
+        hasher.write_u8(self.align_to_normal as u8);
+        hasher.write_u64(self.seed);
+    }
+
+    fn apply_material_properties(&self, material: &mut Material, time: f32) {
+        let fade_end = self.draw_distance();
+        material.set_property("time", MaterialProperty::Float(time));
+        material.set_property(
+            "windDirection",
+            MaterialProperty::Vector2(
+                self.wind
+                    .direction
+                    .try_normalize(f32::EPSILON)
+                    .unwrap_or_default(),
+            ),
+        );
+        material.set_property("windStrength", MaterialProperty::Float(self.wind.strength));
+        material.set_property(
+            "windFrequency",
+            MaterialProperty::Float(self.wind.frequency),
+        );
+        material.set_property(
+            "fadeStart",
+            MaterialProperty::Float((fade_end - self.fade_distance).max(0.0)),
+        );
+        material.set_property("fadeEnd", MaterialProperty::Float(fade_end));
+    }
+}
+
+/// A view of a single-channel 8-bit mask of a chunk.
+#[derive(Copy, Clone)]
+pub(super) struct MaskView<'a> {
+    pub data: &'a [u8],
+    pub size: Vector2<u32>,
+}
+
+impl MaskView<'_> {
+    /// Returns the value of the mask in `[0; 1]` range at the given normalized chunk position.
+    fn sample(&self, uv: Vector2<f32>) -> f32 {
+        let x = ((uv.x * self.size.x as f32) as u32).min(self.size.x.saturating_sub(1));
+        let y = ((uv.y * self.size.y as f32) as u32).min(self.size.y.saturating_sub(1));
+        self.data
+            .get((y * self.size.x + x) as usize)
+            .map_or(1.0, |v| *v as f32 / 255.0)
+    }
+}
+
+/// Input data for instance placement within a single chunk.
+pub(super) struct ScatterInput<'a> {
+    pub chunk_position: Vector3<f32>,
+    pub chunk_size: Vector2<f32>,
+    pub grid_position: Vector2<i32>,
+    pub height_map: &'a [f32],
+    pub height_map_size: Vector2<u32>,
+    pub layer_mask: Option<MaskView<'a>>,
+    pub density_mask: Option<MaskView<'a>>,
+    pub hole_mask: Option<MaskView<'a>>,
+}
+
+impl ScatterInput<'_> {
+    fn height_at_pixel(&self, x: usize, y: usize) -> f32 {
+        let x = x.min((self.height_map_size.x as usize).saturating_sub(1));
+        let y = y.min((self.height_map_size.y as usize).saturating_sub(1));
+        self.height_map
+            .get(y * self.height_map_size.x as usize + x)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Bilinearly interpolates the height at the given normalized chunk position. Height maps have
+    /// one pixel margin, so the pixel (1, 1) is at the origin of the chunk.
+    fn height(&self, uv: Vector2<f32>) -> f32 {
+        let px = 1.0 + uv.x * self.height_map_size.x.saturating_sub(3) as f32;
+        let py = 1.0 + uv.y * self.height_map_size.y.saturating_sub(3) as f32;
+        let (x, y) = (px.floor(), py.floor());
+        let (kx, ky) = (px - x, py - y);
+        let (x, y) = (x as usize, y as usize);
+        let h00 = self.height_at_pixel(x, y);
+        let h10 = self.height_at_pixel(x + 1, y);
+        let h01 = self.height_at_pixel(x, y + 1);
+        let h11 = self.height_at_pixel(x + 1, y + 1);
+        let h0 = h00 + (h10 - h00) * kx;
+        let h1 = h01 + (h11 - h01) * kx;
+        h0 + (h1 - h0) * ky
+    }
+
+    fn normal(&self, uv: Vector2<f32>) -> Vector3<f32> {
+        let du = 1.0 / self.height_map_size.x.saturating_sub(3).max(1) as f32;
+        let dv = 1.0 / self.height_map_size.y.saturating_sub(3).max(1) as f32;
+        let dx =
+            self.height(Vector2::new(uv.x + du, uv.y)) - self.height(Vector2::new(uv.x - du, uv.y));
+        let dz =
+            self.height(Vector2::new(uv.x, uv.y + dv)) - self.height(Vector2::new(uv.x, uv.y - dv));
+        Vector3::new(
+            -dx / (2.0 * du * self.chunk_size.x),
+            1.0,
+            -dz / (2.0 * dv * self.chunk_size.y),
+        )
+        .try_normalize(f32::EPSILON)
+        .unwrap_or_else(Vector3::y)
+    }
+}
+
+/// Generates local (relative to the terrain) transforms of the instances of the given foliage layer
+/// within a chunk. The result is fully defined by the input data, so the same input always produces
+/// the same set of instances.
+pub(super) fn scatter_instances(layer: &FoliageLayer, input: &ScatterInput) -> Vec<Matrix4<f32>> {
+    let area = input.chunk_size.x * input.chunk_size.y;
+    let count =
+        ((layer.density.max(0.0) * area).round() as usize).min(MAX_FOLIAGE_INSTANCES_PER_CHUNK);
+    // Height maps must have at least one pixel margin on each side.
+    let size = input.height_map_size;
+    if count == 0
+        || size.x < 3
+        || size.y < 3
+        || input.height_map.len() < size.x as usize * size.y as usize
+    {
+        return Vec::new();
+    }
+
+    // Each chunk has its own sequence of random numbers, so modification of one chunk does not
+    // affect the others.
+    let mut hasher = FxHasher::default();
+    hasher.write_u64(layer.seed);
+    hasher.write_i32(input.grid_position.x);
+    hasher.write_i32(input.grid_position.y);
+    let mut rng = StdRng::seed_from_u64(hasher.finish());
+
+    let scale_range = if layer.scale.start < layer.scale.end {
+        layer.scale.clone()
+    } else {
+        layer.scale.start..layer.scale.start + f32::EPSILON
+    };
+
+    let mut instances = Vec::new();
+    for _ in 0..count {
+        // Generate every random value up-front, so the sequence does not depend on the masks.
+        let uv = Vector2::new(rng.gen::<f32>(), rng.gen::<f32>());
+        let probability = rng.gen::<f32>();
+        let angle = rng.gen_range(0.0..std::f32::consts::TAU);
+        let scale = rng.gen_range(scale_range.clone());
+
+        if let Some(layer_mask) = input.layer_mask {
+            if layer_mask.sample(uv) < layer.terrain_layer_threshold {
+                continue;
+            }
+        }
+
+        if let Some(hole_mask) = input.hole_mask {
+            if hole_mask.sample(uv) < 0.5 {
+                continue;
+            }
+        }
+
+        let density = input.density_mask.map_or(1.0, |mask| mask.sample(uv));
+        if probability >= density {
+            continue;
+        }
+
+        let position = input.chunk_position
+            + Vector3::new(
+                uv.x * input.chunk_size.x,
+                input.height(uv),
+                uv.y * input.chunk_size.y,
+            );
+
+        let alignment = if layer.align_to_normal {
+            UnitQuaternion::rotation_between(&Vector3::y(), &input.normal(uv))
+                .unwrap_or_else(UnitQuaternion::identity)
+        } else {
+            UnitQuaternion::identity()
+        };
+
+        let yaw = if layer.random_rotation {
+            UnitQuaternion::from_axis_angle(&Vector3::y_axis(), angle)
+        } else {
+            UnitQuaternion::identity()
+        };
+
+        instances.push(
+            Matrix4::new_translation(&position)
+                * (alignment * yaw).to_homogeneous()
+                * Matrix4::new_scaling(scale),
+        );
+    }
+
+    instances
+}
+
+struct CachedInstances {
+    key: u64,
+    transforms: Vec<Matrix4<f32>>,
+    // Bounds of the instances in the local coordinates of the terrain.
+    bounds: AxisAlignedBoundingBox,
+}
+
+struct CachedMaterial {
+    source: MaterialResource,
+    material: MaterialResource,
+}
+
+impl CachedMaterial {
+    fn new(source: &MaterialResource) -> Self {
+        Self {
+            source: source.clone(),
+            material: MaterialResource::new_ok(
+                Uuid::new_v4(),
+                Default::default(),
+                source.data_ref().clone(),
+            ),
+        }
+    }
+}
+
+/// Cache of generated foliage instances per each chunk and foliage layer. Instances are
+/// regenerated only when the data they depend on is changed. It also holds a copy of the material
+/// of each surface of each level of detail, that stores the properties of the layer (wind, fading,
+/// etc.). Such copy is created once and then only its properties are updated.
+#[derive(Default)]
+pub(super) struct FoliageCache {
+    entries: Mutex<FxHashMap<(usize, Vector2<i32>), CachedInstances>>,
+    materials: Mutex<FxHashMap<(usize, usize, usize), CachedMaterial>>,
+}
+
+impl Clone for FoliageCache {
+    fn clone(&self) -> Self {
+        // The cache will be filled again on demand.
+        Self::default()
+    }
+}
+
+impl Debug for FoliageCache {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "FoliageCache")
+    }
+}
+
+impl FoliageCache {
+    /// Invalidates the entire cache.
+    pub(super) fn clear(&self) {
+        self.entries.safe_lock().clear();
+        self.materials.safe_lock().clear();
+    }
+
+    /// Returns the material of the surface with the given indices (layer, level of detail, surface)
+    /// with the up-to-date properties of the layer. The material is re-created only if the source
+    /// material of the surface was replaced.
+    fn material(
+        &self,
+        layer: &FoliageLayer,
+        indices: (usize, usize, usize),
+        source: &MaterialResource,
+        time: f32,
+    ) -> Option<MaterialResource> {
+        if !source.is_ok() {
+            return None;
+        }
+
+        let mut materials = self.materials.safe_lock();
+        let cached = materials
+            .entry(indices)
+            .or_insert_with(|| CachedMaterial::new(source));
+        if cached.source != *source {
+            *cached = CachedMaterial::new(source);
+        }
+        layer.apply_material_properties(&mut cached.material.data_ref(), time);
+        Some(cached.material.clone())
+    }
+}
+
+fn hash_texture(hasher: &mut FxHasher, texture: Option<&TextureResource>) {
+    match texture {
+        Some(texture) => {
+            hasher.write_u64(texture.key());
+            if texture.is_ok() {
+                hasher.write_u64(texture.data_ref().modifications_count());
+            }
+        }
+        None => hasher.write_u64(0),
+    }
+}
+
+fn mask_view<'a>(
+    data: &'a Option<ResourceDataRef<'_, Texture>>,
+    size: Vector2<u32>,
+) -> Option<MaskView<'a>> {
+    data.as_ref().map(|data| MaskView {
+        data: data.data(),
+        size,
+    })
+}
+
+impl Terrain {
+    fn foliage_cache_key(&self, layer: &FoliageLayer, layer_index: usize, chunk: &Chunk) -> u64 {
+        let mut hasher = FxHasher::default();
+        layer.placement_hash(&mut hasher);
+        hasher.write_u32(self.chunk_size.x.to_bits());
+        hasher.write_u32(self.chunk_size.y.to_bits());
+        hash_texture(&mut hasher, chunk.heightmap.as_ref());
+        hash_texture(
+            &mut hasher,
+            layer
+                .terrain_layer
+                .and_then(|i| chunk.layer_masks.get(i as usize)),
+        );
+        hash_texture(&mut hasher, chunk.foliage_masks.get(layer_index));
+        hash_texture(
+            &mut hasher,
+            chunk.hole_mask.as_ref().filter(|_| self.holes_enabled),
+        );
+        hasher.finish()
+    }
+
+    fn scatter_chunk_instances(
+        &self,
+        layer: &FoliageLayer,
+        layer_index: usize,
+        chunk: &Chunk,
+    ) -> Vec<Matrix4<f32>> {
+        let Some(heightmap) = chunk.heightmap.as_ref().filter(|h| h.is_ok()) else {
+            return Vec::new();
+        };
+        let heightmap = heightmap.data_ref();
+        let Some(height_map) = heightmap.data_of_type::<f32>() else {
+            return Vec::new();
+        };
+
+        let layer_mask = layer
+            .terrain_layer
+            .and_then(|i| chunk.layer_masks.get(i as usize))
+            .filter(|m| m.is_ok())
+            .map(|m| m.data_ref());
+        let density_mask = chunk
+            .foliage_masks
+            .get(layer_index)
+            .filter(|m| m.is_ok())
+            .map(|m| m.data_ref());
+        let hole_mask = chunk
+            .hole_mask
+            .as_ref()
+            .filter(|m| self.holes_enabled && m.is_ok())
+            .map(|m| m.data_ref());
+
+        scatter_instances(
+            layer,
+            &ScatterInput {
+                chunk_position: chunk.position(),
+                chunk_size: chunk.physical_size(),
+                grid_position: chunk.grid_position(),
+                height_map,
+                height_map_size: chunk.height_map_size(),
+                layer_mask: mask_view(&layer_mask, *self.mask_size),
+                density_mask: mask_view(&density_mask, *self.mask_size),
+                hole_mask: mask_view(&hole_mask, chunk.hole_mask_size()),
+            },
+        )
+    }
+
+    /// Renders foliage of the terrain. Instances of the same surface share the same material, which
+    /// makes the renderer to draw them in a single instanced draw call.
+    pub(super) fn collect_foliage_render_data(&self, ctx: &mut RenderContext) {
+        if self.foliage_layers.is_empty() {
+            return;
+        }
+
+        let is_shadow_pass = renderer::is_shadow_pass(ctx.render_pass_name);
+        let global_transform = self.global_transform();
+        let observer_position = ctx.observer_position.translation;
+        let mut cache = self.foliage_cache.entries.safe_lock();
+
+        for (layer_index, layer) in self.foliage_layers.iter().enumerate() {
+            if layer.lods.is_empty() || (is_shadow_pass && !layer.cast_shadows) {
+                continue;
+            }
+
+            let draw_distance = layer.draw_distance();
+            let mut materials = FxHashMap::<(usize, usize), Option<MaterialResource>>::default();
+
+            for chunk in self.chunks.iter() {
+                let key = self.foliage_cache_key(layer, layer_index, chunk);
+                let entry = cache
+                    .entry((layer_index, chunk.grid_position()))
+                    .or_insert_with(|| CachedInstances {
+                        // Force the generation.
+                        key: !key,
+                        transforms: Default::default(),
+                        bounds: Default::default(),
+                    });
+
+                if entry.key != key {
+                    entry.key = key;
+                    entry.transforms = self.scatter_chunk_instances(layer, layer_index, chunk);
+                    entry.bounds = AxisAlignedBoundingBox::default();
+                    let radius = layer.bounding_radius * layer.scale.start.max(layer.scale.end);
+                    for transform in entry.transforms.iter() {
+                        let position = transform.column(3).xyz();
+                        entry.bounds.add_point(position - Vector3::repeat(radius));
+                        entry.bounds.add_point(position + Vector3::repeat(radius));
+                    }
+                }
+
+                if entry.transforms.is_empty() {
+                    continue;
+                }
+
+                let world_bounds = entry.bounds.transform(&global_transform);
+                let closest_point = observer_position
+                    .sup(&world_bounds.min)
+                    .inf(&world_bounds.max);
+                if (closest_point - observer_position).norm() > draw_distance
+                    || !ctx
+                        .frustum
+                        .is_none_or(|f| f.is_intersects_aabb(&world_bounds))
+                {
+                    continue;
+                }
+
+                for local_transform in entry.transforms.iter() {
+                    let world_transform = global_transform * local_transform;
+                    let position = world_transform.column(3).xyz();
+                    let Some(lod_index) = layer.lod_index((position - observer_position).norm())
+                    else {
+                        continue;
+                    };
+
+                    for (surface_index, surface) in
+                        layer.lods[lod_index].surfaces.iter().enumerate()
+                    {
+                        let Some(material) = materials
+                            .entry((lod_index, surface_index))
+                            .or_insert_with(|| {
+                                self.foliage_cache.material(
+                                    layer,
+                                    (layer_index, lod_index, surface_index),
+                                    surface.material(),
+                                    ctx.elapsed_time,
+                                )
+                            })
+                        else {
+                            continue;
+                        };
+
+                        ctx.storage.push(
+                            surface.data_ref(),
+                            material,
+                            RenderPath::Deferred,
+                            0,
+                            SurfaceInstanceData {
+                                world_transform,
+                                bone_matrices: Default::default(),
+                                blend_shapes_weights: Default::default(),
+                                element_range: ElementRange::Full,
+                                node_handle: self.handle(),
+                            },
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn input<'a>(height_map: &'a [f32], density_mask: Option<MaskView<'a>>) -> ScatterInput<'a> {
+        ScatterInput {
+            chunk_position: Vector3::new(16.0, 0.0, 0.0),
+            chunk_size: Vector2::new(16.0, 16.0),
+            grid_position: Vector2::new(1, 0),
+            height_map,
+            height_map_size: Vector2::new(7, 7),
+            layer_mask: None,
+            density_mask,
+            hole_mask: None,
+        }
+    }
+
+    #[test]
+    fn test_foliage_scatter() {
+        let height_map = vec![2.0; 7 * 7];
+        let layer = FoliageLayer {
+            density: 0.5,
+            ..Default::default()
+        };
+
+        let instances = scatter_instances(&layer, &input(&height_map, None));
+        assert_eq!(instances.len(), 128);
+        // Placement is deterministic.
+        assert_eq!(
+            instances,
+            scatter_instances(&layer, &input(&height_map, None))
+        );
+        for instance in instances {
+            let position = instance.column(3).xyz();
+            assert!((16.0..=32.0).contains(&position.x));
+            assert!((0.0..=16.0).contains(&position.z));
+            assert_eq!(position.y, 2.0);
+        }
+
+        let empty = [0u8; 4];
+        let mask = MaskView {
+            data: &empty,
+            size: Vector2::new(2, 2),
+        };
+        assert!(scatter_instances(&layer, &input(&height_map, Some(mask))).is_empty());
+    }
+
+    #[test]
+    fn test_foliage_scatter_invalid_height_map() {
+        let layer = FoliageLayer::default();
+        for size in [Vector2::new(0, 0), Vector2::new(2, 2), Vector2::new(7, 7)] {
+            let height_map = vec![0.0; 4];
+            let input = ScatterInput {
+                height_map_size: size,
+                ..input(&height_map, None)
+            };
+            assert!(scatter_instances(&layer, &input).is_empty());
+        }
+    }
+
+    #[test]
+    fn test_foliage_lod_selection() {
+        let layer = FoliageLayer {
+            lods: vec![
+                FoliageLod {
+                    max_distance: 10.0,
+                    surfaces: Default::default(),
+                },
+                FoliageLod {
+                    max_distance: 30.0,
+                    surfaces: Default::default(),
+                },
+            ],
+            ..Default::default()
+        };
+        assert_eq!(layer.draw_distance(), 30.0);
+        assert_eq!(layer.lod_index(5.0), Some(0));
+        assert_eq!(layer.lod_index(20.0), Some(1));
+        assert_eq!(layer.lod_index(31.0), None);
+    }
+}
@@ -0,0 +1,939 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Procedural generation and erosion of terrain height maps. See [`TerrainGenerator`] docs for
+//! more info.
+
+use crate::{
+    core::{algebra::Vector2, reflect::prelude::*, type_traits::prelude::*, SafeLock},
+    rand::{rngs::StdRng, Rng, SeedableRng},
+    scene::terrain::{make_quad_tree, Terrain},
+};
+use fyrox_core::uuid_provider;
+use std::ops::Range;
+use strum_macros::{AsRefStr, EnumString, VariantNames};
+
+/// A rectangular grid of heights, that covers the entire terrain. Unlike height maps of chunks,
+/// it has no margins and no duplicated pixels along the edges of the chunks, which makes it
+/// convenient for the operations that require information about the neighbour pixels.
+#[derive(Clone, Debug, PartialEq)]
+pub struct HeightField {
+    size: Vector2<u32>,
+    cell_size: Vector2<f32>,
+    data: Vec<f32>,
+}
+
+impl HeightField {
+    /// Creates a new flat height field of the given size (in pixels). `cell_size` defines the
+    /// distance (in meters) between two adjacent pixels.
+    pub fn new(size: Vector2<u32>, cell_size: Vector2<f32>) -> Self {
+        let size = size.sup(&Vector2::repeat(1));
+        Self {
+            size,
+            cell_size,
+            data: vec![0.0; (size.x * size.y) as usize],
+        }
+    }
+
+    /// Creates a new height field from the given heights in row-major order. Returns `None` if the
+    /// amount of heights does not match the size.
+    pub fn from_raw(size: Vector2<u32>, cell_size: Vector2<f32>, data: Vec<f32>) -> Option<Self> {
+        if size.x == 0 || size.y == 0 || data.len() != (size.x * size.y) as usize {
+            return None;
+        }
+        Some(Self {
+            size,
+            cell_size,
+            data,
+        })
+    }
+
+    /// Returns size of the height field in pixels.
+    pub fn size(&self) -> Vector2<u32> {
+        self.size
+    }
+
+    /// Returns the distance (in meters) between two adjacent pixels.
+    pub fn cell_size(&self) -> Vector2<f32> {
+        self.cell_size
+    }
+
+    /// Returns a reference to raw heights of the field in row-major order.
+    pub fn data(&self) -> &[f32] {
+        &self.data
+    }
+
+    /// Returns a mutable reference to raw heights of the field in row-major order.
+    pub fn data_mut(&mut self) -> &mut [f32] {
+        &mut self.data
+    }
+
+    #[inline]
+    fn index(&self, x: u32, y: u32) -> usize {
+        (y * self.size.x + x) as usize
+    }
+
+    /// Returns a height at the given pixel. Positions outside of the field are clamped to the
+    /// nearest edge.
+    #[inline]
+    pub fn get(&self, x: i32, y: i32) -> f32 {
+        let x = x.clamp(0, self.size.x as i32 - 1) as u32;
+        let y = y.clamp(0, self.size.y as i32 - 1) as u32;
+        self.data[self.index(x, y)]
+    }
+
+    /// Sets a height at the given pixel. Positions outside of the field are ignored.
+    #[inline]
+    pub fn set(&mut self, x: i32, y: i32, height: f32) {
+        if x >= 0 && y >= 0 && (x as u32) < self.size.x && (y as u32) < self.size.y {
+            let index = self.index(x as u32, y as u32);
+            self.data[index] = height;
+        }
+    }
+
+    /// Bilinearly interpolates a height at the given position in pixels.
+    pub fn sample(&self, position: Vector2<f32>) -> f32 {
+        let x = position.x.floor();
+        let y = position.y.floor();
+        let (kx, ky) = (position.x - x, position.y - y);
+        let (x, y) = (x as i32, y as i32);
+        let h0 = self.get(x, y) + (self.get(x + 1, y) - self.get(x, y)) * kx;
+        let h1 = self.get(x, y + 1) + (self.get(x + 1, y + 1) - self.get(x, y + 1)) * kx;
+        h0 + (h1 - h0) * ky
+    }
+
+    /// Returns the gradient of heights (in meters per meter) at the given pixel.
+    pub fn gradient(&self, x: i32, y: i32) -> Vector2<f32> {
+        Vector2::new(
+            (self.get(x + 1, y) - self.get(x - 1, y)) / (2.0 * self.cell_size.x),
+            (self.get(x, y + 1) - self.get(x, y - 1)) / (2.0 * self.cell_size.y),
+        )
+    }
+
+    /// Returns the slope angle (in degrees) at the given pixel.
+    pub fn slope(&self, x: i32, y: i32) -> f32 {
+        self.gradient(x, y).norm().atan().to_degrees()
+    }
+
+    /// Adds (or assigns, depending on [`NoiseSettings::blend`]) fractal noise to the height field.
+    /// Ridged noise produces sharp crests, that look like mountain ridges.
+    pub fn apply_noise(&mut self, settings: &NoiseSettings, ridged: bool) {
+        for y in 0..self.size.y {
+            for x in 0..self.size.x {
+                let position =
+                    Vector2::new(x as f32 * self.cell_size.x, y as f32 * self.cell_size.y)
+                        / settings.scale.max(f32::EPSILON);
+                let value = if ridged {
+                    ridged_noise(settings, position)
+                } else {
+                    fractal_noise(settings, position)
+                } * settings.amplitude;
+                let index = self.index(x, y);
+                let height = &mut self.data[index];
+                match settings.blend {
+                    HeightBlend::Add => *height += value,
+                    HeightBlend::Replace => *height = value,
+                }
+            }
+        }
+    }
+
+    /// Quantizes the heights into a number of flat steps, like terraces on a hill side.
+    pub fn terrace(&mut self, settings: &TerraceSettings) {
+        if settings.step_height <= 0.0 {
+            return;
+        }
+        // Zero smoothness gives perfectly flat steps, one - unmodified heights.
+        let power = 1.0 / settings.smoothness.clamp(0.001, 1.0);
+        for height in self.data.iter_mut() {
+            let steps = *height / settings.step_height;
+            let floor = steps.floor();
+            *height = (floor + (steps - floor).powf(power)) * settings.step_height;
+        }
+    }
+
+    /// Simulates thermal erosion: material crumbles down from the slopes, that are steeper than the
+    /// talus angle, and piles up at their feet.
+    pub fn thermal_erosion(&mut self, settings: &ThermalErosionSettings) {
+        const NEIGHBOURS: [(i32, i32); 8] = [
+            (-1, -1),
+            (0, -1),
+            (1, -1),
+            (-1, 0),
+            (1, 0),
+            (-1, 1),
+            (0, 1),
+            (1, 1),
+        ];
+
+        let talus = settings.talus_angle.clamp(0.0, 89.9).to_radians().tan();
+        let strength = settings.strength.clamp(0.0, 1.0);
+        let distances = NEIGHBOURS.map(|(dx, dy)| {
+            Vector2::new(dx as f32 * self.cell_size.x, dy as f32 * self.cell_size.y).norm()
+        });
+        let mut delta = vec![0.0; self.data.len()];
+
+        for _ in 0..settings.iterations {
+            delta.iter_mut().for_each(|d| *d = 0.0);
+
+            for y in 0..self.size.y as i32 {
+                for x in 0..self.size.x as i32 {
+                    let height = self.get(x, y);
+                    let mut excess = [0.0; 8];
+                    let mut total_excess = 0.0;
+                    let mut max_excess = 0.0f32;
+                    for (i, ((dx, dy), distance)) in NEIGHBOURS.iter().zip(distances).enumerate() {
+                        let (nx, ny) = (x + dx, y + dy);
+                        if nx < 0 || ny < 0 || nx >= self.size.x as i32 || ny >= self.size.y as i32
+                        {
+                            continue;
+                        }
+                        let difference = height - self.get(nx, ny) - talus * distance;
+                        if difference > 0.0 {
+                            excess[i] = difference;
+                            total_excess += difference;
+                            max_excess = max_excess.max(difference);
+                        }
+                    }
+
+                    if total_excess <= 0.0 {
+                        continue;
+                    }
+
+                    // Move half of the largest excess, so the slope ends up at the talus angle,
+                    // instead of being flipped over.
+                    let amount = 0.5 * max_excess * strength;
+                    delta[self.index(x as u32, y as u32)] -= amount;
+                    for (i, (dx, dy)) in NEIGHBOURS.iter().enumerate() {
+                        if excess[i] > 0.0 {
+                            let index = self.index((x + dx) as u32, (y + dy) as u32);
+                            delta[index] += amount * excess[i] / total_excess;
+                        }
+                    }
+                }
+            }
+
+            for (height, delta) in self.data.iter_mut().zip(delta.iter()) {
+                *height += *delta;
+            }
+        }
+    }
+
+    /// Simulates hydraulic erosion using a set of water droplets. Each droplet flows down the slope,
+    /// picks up sediment on steep parts and deposits it when it slows down or evaporates. It carves
+    /// gullies and river beds and forms smooth alluvial fans.
+    pub fn hydraulic_erosion(&mut self, settings: &HydraulicErosionSettings) {
+        let mut rng = StdRng::seed_from_u64(settings.seed);
+
+        // Precompute weights of the erosion brush.
+        let radius = settings.radius as i32;
+        let mut brush = Vec::new();
+        let mut weight_sum = 0.0;
+        for dy in -radius..=radius {
+            for dx in -radius..=radius {
+                let distance = ((dx * dx + dy * dy) as f32).sqrt();
+                if distance <= radius as f32 {
+                    let weight = 1.0 - distance / (radius as f32 + 1.0);
+                    weight_sum += weight;
+                    brush.push((dx, dy, weight));
+                }
+            }
+        }
+        for (_, _, weight) in brush.iter_mut() {
+            *weight /= weight_sum;
+        }
+
+        let max_x = (self.size.x - 1) as f32;
+        let max_y = (self.size.y - 1) as f32;
+        if max_x < 1.0 || max_y < 1.0 {
+            return;
+        }
+
+        for _ in 0..settings.droplets {
+            let mut position = Vector2::new(rng.gen_range(0.0..max_x), rng.gen_range(0.0..max_y));
+            let mut direction = Vector2::<f32>::zeros();
+            let mut speed = 1.0;
+            let mut water = 1.0;
+            let mut sediment = 0.0;
+
+            for _ in 0..settings.max_lifetime {
+                let cell = position.map(|c| c.floor() as i32);
+                let height = self.sample(position);
+
+                // Gradient in the local cell.
+                let offset = position - cell.map(|c| c as f32);
+                let h00 = self.get(cell.x, cell.y);
+                let h10 = self.get(cell.x + 1, cell.y);
+                let h01 = self.get(cell.x, cell.y + 1);
+                let h11 = self.get(cell.x + 1, cell.y + 1);
+                let gradient = Vector2::new(
+                    (h10 - h00) * (1.0 - offset.y) + (h11 - h01) * offset.y,
+                    (h01 - h00) * (1.0 - offset.x) + (h11 - h10) * offset.x,
+                );
+
+                direction = direction * settings.inertia - gradient * (1.0 - settings.inertia);
+                let Some(normalized) = direction.try_normalize(f32::EPSILON) else {
+                    break;
+                };
+                direction = normalized;
+                position += direction;
+
+                if position.x < 0.0 || position.y < 0.0 || position.x > max_x || position.y > max_y
+                {
+                    break;
+                }
+
+                let delta_height = self.sample(position) - height;
+                let capacity = (-delta_height * speed * water * settings.sediment_capacity)
+                    .max(settings.min_sediment_capacity);
+
+                if sediment > capacity || delta_height > 0.0 {
+                    // Fill the pit the droplet has flown into, or drop the excess of the sediment.
+                    let amount = if delta_height > 0.0 {
+                        delta_height.min(sediment)
+                    } else {
+                        (sediment - capacity) * settings.deposit_speed
+                    };
+                    sediment -= amount;
+                    self.deposit(cell, offset, amount);
+                } else {
+                    let amount = ((capacity - sediment) * settings.erode_speed).min(-delta_height);
+                    for (dx, dy, weight) in brush.iter() {
+                        let (x, y) = (cell.x + dx, cell.y + dy);
+                        if x < 0 || y < 0 || x >= self.size.x as i32 || y >= self.size.y as i32 {
+                            continue;
+                        }
+                        let index = self.index(x as u32, y as u32);
+                        let eroded = amount * weight;
+                        self.data[index] -= eroded;
+                        sediment += eroded;
+                    }
+                }
+
+                speed = (speed * speed + delta_height.abs() * settings.gravity)
+                    .max(0.0)
+                    .sqrt();
+                water *= 1.0 - settings.evaporate_speed;
+            }
+        }
+    }
+
+    fn deposit(&mut self, cell: Vector2<i32>, offset: Vector2<f32>, amount: f32) {
+        for (dx, dy, weight) in [
+            (0, 0, (1.0 - offset.x) * (1.0 - offset.y)),
+            (1, 0, offset.x * (1.0 - offset.y)),
+            (0, 1, (1.0 - offset.x) * offset.y),
+            (1, 1, offset.x * offset.y),
+        ] {
+            let (x, y) = (cell.x + dx, cell.y + dy);
+            if x >= 0 && y >= 0 && (x as u32) < self.size.x && (y as u32) < self.size.y {
+                let index = self.index(x as u32, y as u32);
+                self.data[index] += amount * weight;
+            }
+        }
+    }
+}
+
+/// Defines how a generated height is combined with the existing one.
+#[derive(
+    Copy, Clone, Default, Debug, PartialEq, Eq, Reflect, AsRefStr, EnumString, VariantNames,
+)]
+pub enum HeightBlend {
+    /// The generated height is added to the existing one.
+    #[default]
+    Add,
+    /// The generated height replaces the existing one.
+    Replace,
+}
+
+uuid_provider!(HeightBlend = "3c0bd5a4-27d8-4f1b-9d5e-6a2f8e1c7b93");
+
+/// Parameters of fractal noise.
+#[derive(Clone, Debug, PartialEq, Reflect)]
+pub struct NoiseSettings {
+    /// A seed of the noise. Different seeds produce different patterns.
+    pub seed: u32,
+    /// Size (in meters) of the largest features of the noise.
+    #[reflect(min_value = 0.001)]
+    pub scale: f32,
+    /// Amount of noise layers with increasing frequency and decreasing amplitude.
+    #[reflect(min_value = 1.0, max_value = 16.0, step = 1.0)]
+    pub octaves: u32,
+    /// Frequency multiplier of each next octave.
+    #[reflect(min_value = 1.0)]
+    pub lacunarity: f32,
+    /// Amplitude multiplier of each next octave.
+    #[reflect(min_value = 0.0, max_value = 1.0)]
+    pub gain: f32,
+    /// Max height (in meters) of the noise.
+    pub amplitude: f32,
+    /// Defines how the noise is combined with the existing heights.
+    pub blend: HeightBlend,
+}
+
+uuid_provider!(NoiseSettings = "a5e1f6d2-8b3c-4e7a-9f0d-2c4b6a8e1d35");
+
+impl Default for NoiseSettings {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            scale: 64.0,
+            octaves: 5,
+            lacunarity: 2.0,
+            gain: 0.5,
+            amplitude: 10.0,
+            blend: Default::default(),
+        }
+    }
+}
+
+/// Parameters of terracing.
+#[derive(Clone, Debug, PartialEq, Reflect)]
+pub struct TerraceSettings {
+    /// Height (in meters) of a single step.
+    #[reflect(min_value = 0.001)]
+    pub step_height: f32,
+    /// Smoothness of the steps. Zero gives perfectly flat steps with vertical walls, one leaves the
+    /// heights unmodified.
+    #[reflect(min_value = 0.0, max_value = 1.0)]
+    pub smoothness: f32,
+}
+
+uuid_provider!(TerraceSettings = "6b9d2e4f-1a7c-4d3e-8b5f-0e2a4c6d8f17");
+
+impl Default for TerraceSettings {
+    fn default() -> Self {
+        Self {
+            step_height: 2.0,
+            smoothness: 0.3,
+        }
+    }
+}
+
+/// Parameters of thermal erosion.
+#[derive(Clone, Debug, PartialEq, Reflect)]
+pub struct ThermalErosionSettings {
+    /// Amount of simulation steps.
+    #[reflect(min_value = 0.0, step = 1.0)]
+    pub iterations: u32,
+    /// Max angle (in degrees) of a stable slope.
+    #[reflect(min_value = 0.0, max_value = 89.0)]
+    pub talus_angle: f32,
+    /// Fraction of the unstable material, that is moved per each step.
+    #[reflect(min_value = 0.0, max_value = 1.0)]
+    pub strength: f32,
+}
+
+uuid_provider!(ThermalErosionSettings = "d7f3a1c9-4e2b-4a6d-b8e0-5c1f9a3d7e62");
+
+impl Default for ThermalErosionSettings {
+    fn default() -> Self {
+        Self {
+            iterations: 50,
+            talus_angle: 35.0,
+            strength: 0.5,
+        }
+    }
+}
+
+/// Parameters of droplet-based hydraulic erosion.
+#[derive(Clone, Debug, PartialEq, Reflect)]
+pub struct HydraulicErosionSettings {
+    /// A seed for the random number generator, that is used to spawn droplets.
+    pub seed: u64,
+    /// Total amount of droplets.
+    #[reflect(min_value = 0.0, step = 1.0)]
+    pub droplets: u32,
+    /// Max amount of steps (in pixels) of a single droplet.
+    #[reflect(min_value = 1.0, step = 1.0)]
+    pub max_lifetime: u32,
+    /// How much a droplet keeps its direction. Zero means that droplets always flow down the
+    /// steepest slope.
+    #[reflect(min_value = 0.0, max_value = 1.0)]
+    pub inertia: f32,
+    /// Multiplier of the amount of sediment a droplet can carry.
+    #[reflect(min_value = 0.0)]
+    pub sediment_capacity: f32,
+    /// Min amount of sediment a droplet can carry, even on flat areas.
+    #[reflect(min_value = 0.0)]
+    pub min_sediment_capacity: f32,
+    /// Fraction of the free capacity of a droplet, that is eroded per each step.
+    #[reflect(min_value = 0.0, max_value = 1.0)]
+    pub erode_speed: f32,
+    /// Fraction of the excess sediment, that is deposited per each step.
+    #[reflect(min_value = 0.0, max_value = 1.0)]
+    pub deposit_speed: f32,
+    /// Fraction of the water, that evaporates per each step.
+    #[reflect(min_value = 0.0, max_value = 1.0)]
+    pub evaporate_speed: f32,
+    /// Acceleration of droplets on slopes.
+    #[reflect(min_value = 0.0)]
+    pub gravity: f32,
+    /// Radius (in pixels) of the area a droplet erodes.
+    #[reflect(min_value = 0.0, max_value = 8.0, step = 1.0)]
+    pub radius: u32,
+}
+
+uuid_provider!(HydraulicErosionSettings = "0e8c4b2a-6d1f-4f9e-a3c7-9b5d1e7f3a48");
+
+impl Default for HydraulicErosionSettings {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            droplets: 50_000,
+            max_lifetime: 30,
+            inertia: 0.05,
+            sediment_capacity: 4.0,
+            min_sediment_capacity: 0.01,
+            erode_speed: 0.3,
+            deposit_speed: 0.3,
+            evaporate_speed: 0.01,
+            gravity: 4.0,
+            radius: 3,
+        }
+    }
+}
+
+/// Parameters of automatic generation of a layer mask.
+#[derive(Clone, Debug, PartialEq, Reflect)]
+pub struct LayerMaskRule {
+    /// Index of the terrain layer, whose mask will be generated.
+    pub layer: usize,
+    /// Range of the values (slope angles in degrees or heights in meters), where the mask is fully
+    /// opaque.
+    pub range: Range<f32>,
+    /// Width of the transition from opaque to transparent parts of the mask at each end of the
+    /// range.
+    #[reflect(min_value = 0.0)]
+    pub falloff: f32,
+}
+
+uuid_provider!(LayerMaskRule = "5f2e8a6c-3b9d-4c1e-8a7f-4d0b2e6c9a51");
+
+impl Default for LayerMaskRule {
+    fn default() -> Self {
+        Self {
+            layer: 1,
+            range: 30.0..90.0,
+            falloff: 5.0,
+        }
+    }
+}
+
+impl LayerMaskRule {
+    /// Calculates mask weight in `[0; 1]` range for the given value.
+    pub fn weight(&self, value: f32) -> f32 {
+        let falloff = self.falloff.max(f32::EPSILON);
+        let lower = ((value - self.range.start) / falloff + 1.0).clamp(0.0, 1.0);
+        let upper = ((self.range.end - value) / falloff + 1.0).clamp(0.0, 1.0);
+        let t = lower.min(upper);
+        t * t * (3.0 - 2.0 * t)
+    }
+}
+
+/// An operation that procedurally modifies height map or layer masks of a terrain. Use
+/// [`Terrain::apply_generator`] to apply it.
+///
+/// ## Example
+///
+/// ```rust
+/// # use fyrox_impl::scene::terrain::{
+/// #     HydraulicErosionSettings, NoiseSettings, Terrain, TerrainGenerator,
+/// # };
+/// fn make_mountains(terrain: &mut Terrain) {
+///     terrain.apply_generator(&TerrainGenerator::RidgedNoise(NoiseSettings {
+///         amplitude: 40.0,
+///         ..Default::default()
+///     }));
+///     terrain.apply_generator(&TerrainGenerator::HydraulicErosion(
+///         HydraulicErosionSettings::default(),
+///     ));
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq, Reflect)]
+pub enum TerrainGenerator {
+    /// Smooth fractal noise, that is good for hills and plains.
+    FractalNoise(NoiseSettings),
+    /// Ridged fractal noise, that is good for mountains.
+    RidgedNoise(NoiseSettings),
+    /// Quantizes heights into steps.
+    Terrace(TerraceSettings),
+    /// Crumbles steep slopes.
+    ThermalErosion(ThermalErosionSettings),
+    /// Carves gullies and river beds using water droplets.
+    HydraulicErosion(HydraulicErosionSettings),
+    /// Assigns a layer mask based on the slope angle (in degrees) of the terrain.
+    SlopeMask(LayerMaskRule),
+    /// Assigns a layer mask based on the height (in meters) of the terrain.
+    HeightMask(LayerMaskRule),
+}
+
+uuid_provider!(TerrainGenerator = "8a4c6e2f-9d1b-4b3e-a5c7-1f3e5d7b9c02");
+
+impl Default for TerrainGenerator {
+    fn default() -> Self {
+        Self::FractalNoise(Default::default())
+    }
+}
+
+impl TerrainGenerator {
+    /// Returns `true` if the generator modifies the height map, `false` - if a layer mask.
+    pub fn modifies_height(&self) -> bool {
+        !matches!(self, Self::SlopeMask(_) | Self::HeightMask(_))
+    }
+
+    /// Returns an index of the layer, whose mask is modified by the generator.
+    pub fn layer(&self) -> Option<usize> {
+        match self {
+            Self::SlopeMask(rule) | Self::HeightMask(rule) => Some(rule.layer),
+            _ => None,
+        }
+    }
+
+    /// Applies the generator to the given height field.
+    pub fn apply_to_height_field(&self, field: &mut HeightField) {
+        match self {
+            Self::FractalNoise(settings) => field.apply_noise(settings, false),
+            Self::RidgedNoise(settings) => field.apply_noise(settings, true),
+            Self::Terrace(settings) => field.terrace(settings),
+            Self::ThermalErosion(settings) => field.thermal_erosion(settings),
+            Self::HydraulicErosion(settings) => field.hydraulic_erosion(settings),
+            Self::SlopeMask(_) | Self::HeightMask(_) => (),
+        }
+    }
+}
+
+fn hash(seed: u32, x: i32, y: i32) -> u32 {
+    let mut h = seed ^ (x as u32).wrapping_mul(0x27d4_eb2d) ^ (y as u32).wrapping_mul(0x1656_67b1);
+    h ^= h >> 15;
+    h = h.wrapping_mul(0x85eb_ca6b);
+    h ^= h >> 13;
+    h = h.wrapping_mul(0xc2b2_ae35);
+    h ^ (h >> 16)
+}
+
+/// Gradient noise in `[-1; 1]` range.
+fn gradient_noise(seed: u32, position: Vector2<f32>) -> f32 {
+    const GRADIENTS: [(f32, f32); 8] = [
+        (1.0, 0.0),
+        (-1.0, 0.0),
+        (0.0, 1.0),
+        (0.0, -1.0),
+        (
+            std::f32::consts::FRAC_1_SQRT_2,
+            std::f32::consts::FRAC_1_SQRT_2,
+        ),
+        (
+            -std::f32::consts::FRAC_1_SQRT_2,
+            std::f32::consts::FRAC_1_SQRT_2,
+        ),
+        (
+            std::f32::consts::FRAC_1_SQRT_2,
+            -std::f32::consts::FRAC_1_SQRT_2,
+        ),
+        (
+            -std::f32::consts::FRAC_1_SQRT_2,
+            -std::f32::consts::FRAC_1_SQRT_2,
+        ),
+    ];
+
+    let x0 = position.x.floor();
+    let y0 = position.y.floor();
+    let (fx, fy) = (position.x - x0, position.y - y0);
+    let (x0, y0) = (x0 as i32, y0 as i32);
+
+    let corner = |dx: i32, dy: i32| {
+        let (gx, gy) = GRADIENTS[(hash(seed, x0 + dx, y0 + dy) & 7) as usize];
+        gx * (fx - dx as f32) + gy * (fy - dy as f32)
+    };
+
+    let fade = |t: f32| t * t * t * (t * (t * 6.0 - 15.0) + 10.0);
+    let (u, v) = (fade(fx), fade(fy));
+
+    let a = corner(0, 0) + (corner(1, 0) - corner(0, 0)) * u;
+    let b = corner(0, 1) + (corner(1, 1) - corner(0, 1)) * u;
+    ((a + (b - a) * v) * std::f32::consts::SQRT_2).clamp(-1.0, 1.0)
+}
+
+/// Fractal noise in `[-1; 1]` range.
+fn fractal_noise(settings: &NoiseSettings, position: Vector2<f32>) -> f32 {
+    let mut frequency = 1.0;
+    let mut amplitude = 1.0;
+    let mut sum = 0.0;
+    let mut total_amplitude = 0.0;
+    for octave in 0..settings.octaves.max(1) {
+        sum += amplitude * gradient_noise(settings.seed.wrapping_add(octave), position * frequency);
+        total_amplitude += amplitude;
+        frequency *= settings.lacunarity;
+        amplitude *= settings.gain;
+    }
+    sum / total_amplitude
+}
+
+/// Ridged fractal noise in `[0; 1]` range.
+fn ridged_noise(settings: &NoiseSettings, position: Vector2<f32>) -> f32 {
+    let mut frequency = 1.0;
+    let mut amplitude = 1.0;
+    let mut weight = 1.0;
+    let mut sum = 0.0;
+    let mut total_amplitude = 0.0;
+    for octave in 0..settings.octaves.max(1) {
+        let ridge =
+            1.0 - gradient_noise(settings.seed.wrapping_add(octave), position * frequency).abs();
+        let signal = ridge * ridge * weight;
+        // Sharp ridges of the previous octave amplify the details of the next one.
+        weight = ridge.clamp(0.0, 1.0);
+        sum += amplitude * signal;
+        total_amplitude += amplitude;
+        frequency *= settings.lacunarity;
+        amplitude *= settings.gain;
+    }
+    sum / total_amplitude
+}
+
+impl Terrain {
+    fn height_field_cell_size(&self) -> Vector2<f32> {
+        let pixels = self.height_map_size.map(|x| (x - 3) as f32);
+        Vector2::new(self.chunk_size.x / pixels.x, self.chunk_size.y / pixels.y)
+    }
+
+    /// Creates a height field, that contains the heights of every chunk of the terrain, stitched
+    /// together.
+    pub fn height_field(&self) -> HeightField {
+        let pixels = self.height_map_size.map(|x| x as i32 - 3);
+        let chunks = Vector2::new(self.width_chunks.len(), self.length_chunks.len());
+        let mut field = HeightField::new(
+            Vector2::new(
+                chunks.x as u32 * pixels.x as u32 + 1,
+                chunks.y as u32 * pixels.y as u32 + 1,
+            ),
+            self.height_field_cell_size(),
+        );
+
+        for chunk in self.chunks.iter() {
+            let origin = Vector2::new(
+                (chunk.grid_position.x - self.width_chunks.start) * pixels.x,
+                (chunk.grid_position.y - self.length_chunks.start) * pixels.y,
+            );
+            let data = chunk.height_data();
+            for y in 0..=pixels.y {
+                for x in 0..=pixels.x {
+                    field.set(origin.x + x, origin.y + y, data[Vector2::new(x, y)]);
+                }
+            }
+        }
+
+        field
+    }
+
+    /// Replaces the heights of every chunk of the terrain with the heights from the given height
+    /// field. The field must have the size of the one returned by [`Self::height_field`]. Margins
+    /// and edges of the chunks are filled from the field as well, so the chunks stay seamless.
+    pub fn set_height_field(&mut self, field: &HeightField) {
+        let pixels = self.height_map_size.map(|x| x as i32 - 3);
+        let size = *self.height_map_size;
+        for chunk in self.chunks.iter_mut() {
+            let origin = Vector2::new(
+                (chunk.grid_position.x - self.width_chunks.start) * pixels.x,
+                (chunk.grid_position.y - self.length_chunks.start) * pixels.y,
+            );
+            let mut texture_data = chunk.heightmap.as_ref().unwrap().data_ref();
+            let mut texture_modifier = texture_data.modify();
+            let height_map = texture_modifier.data_mut_of_type::<f32>().unwrap();
+            for iy in 0..size.y as i32 {
+                for ix in 0..size.x as i32 {
+                    // Pixel (1, 1) of a height map is the origin of the chunk.
+                    let index = (iy * size.x as i32 + ix) as usize;
+                    height_map[index] = field.get(origin.x + ix - 1, origin.y + iy - 1);
+                }
+            }
+            drop(texture_modifier);
+            drop(texture_data);
+
+            *chunk.quad_tree.safe_lock() =
+                make_quad_tree(&chunk.heightmap, chunk.height_map_size, chunk.block_size);
+        }
+
+        self.bounding_box_dirty.set(true);
+    }
+
+    /// Assigns the blending mask of the given layer using the given function, that maps a position
+    /// in the height field (in pixels) to the mask weight in `[0; 1]` range.
+    fn generate_layer_mask<F>(&mut self, layer: usize, mut func: F)
+    where
+        F: FnMut(Vector2<f32>) -> f32,
+    {
+        let mask_size = *self.mask_size;
+        let pixels = self.height_map_size.map(|x| (x - 3) as f32);
+        for chunk in self.chunks.iter_mut() {
+            let Some(mask) = chunk.layer_masks.get(layer) else {
+                continue;
+            };
+            let origin = Vector2::new(
+                (chunk.grid_position.x - self.width_chunks.start) as f32 * pixels.x,
+                (chunk.grid_position.y - self.length_chunks.start) as f32 * pixels.y,
+            );
+            let mut texture_data = mask.data_ref();
+            let mut texture_modifier = texture_data.modify();
+            let data = texture_modifier.data_mut_of_type::<u8>().unwrap();
+            for y in 0..mask_size.y {
+                for x in 0..mask_size.x {
+                    // Masks are sampled at the centers of their pixels.
+                    let position = origin
+                        + Vector2::new(
+                            (x as f32 + 0.5) / mask_size.x as f32 * pixels.x,
+                            (y as f32 + 0.5) / mask_size.y as f32 * pixels.y,
+                        );
+                    data[(y * mask_size.x + x) as usize] =
+                        (func(position).clamp(0.0, 1.0) * 255.0).round() as u8;
+                }
+            }
+        }
+    }
+
+    /// Applies the given generator to the terrain. See [`TerrainGenerator`] docs for more info.
+    pub fn apply_generator(&mut self, generator: &TerrainGenerator) {
+        match generator {
+            TerrainGenerator::SlopeMask(rule) => {
+                let field = self.height_field();
+                self.generate_layer_mask(rule.layer, |p| {
+                    // Use the slope at the closest pixel of the height field.
+                    let (x, y) = (p.x.round() as i32, p.y.round() as i32);
+                    rule.weight(field.slope(x, y))
+                });
+            }
+            TerrainGenerator::HeightMask(rule) => {
+                let field = self.height_field();
+                self.generate_layer_mask(rule.layer, |p| rule.weight(field.sample(p)));
+            }
+            _ => {
+                let mut field = self.height_field();
+                generator.apply_to_height_field(&mut field);
+                self.set_height_field(&field);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::scene::{base::BaseBuilder, terrain::TerrainBuilder};
+
+    fn slope_field() -> HeightField {
+        let mut field = HeightField::new(Vector2::new(16, 16), Vector2::new(1.0, 1.0));
+        for y in 0..16 {
+            for x in 0..16 {
+                field.set(x, y, if x < 8 { 0.0 } else { 10.0 });
+            }
+        }
+        field
+    }
+
+    #[test]
+    fn test_noise_is_deterministic() {
+        let settings = NoiseSettings::default();
+        let mut a = HeightField::new(Vector2::new(32, 32), Vector2::new(1.0, 1.0));
+        let mut b = a.clone();
+        a.apply_noise(&settings, false);
+        b.apply_noise(&settings, false);
+        assert_eq!(a, b);
+        assert!(a.data().iter().any(|h| *h != 0.0));
+        assert!(a.data().iter().all(|h| h.abs() <= settings.amplitude));
+
+        let mut ridged = HeightField::new(Vector2::new(32, 32), Vector2::new(1.0, 1.0));
+        ridged.apply_noise(&settings, true);
+        assert!(ridged
+            .data()
+            .iter()
+            .all(|h| (0.0..=settings.amplitude).contains(h)));
+    }
+
+    #[test]
+    fn test_terrace() {
+        let mut field = HeightField::new(Vector2::new(4, 1), Vector2::new(1.0, 1.0));
+        field.data_mut().copy_from_slice(&[0.5, 1.5, 2.5, 3.5]);
+        field.terrace(&TerraceSettings {
+            step_height: 2.0,
+            smoothness: 0.0,
+        });
+        for (height, expected) in field.data().iter().zip([0.0, 0.0, 2.0, 2.0]) {
+            assert!((height - expected).abs() < 0.01);
+        }
+    }
+
+    #[test]
+    fn test_thermal_erosion_flattens_cliffs() {
+        let mut field = slope_field();
+        let max_slope =
+            |f: &HeightField| (0..16).map(|x| f.slope(x, 8)).fold(0.0f32, |a, b| a.max(b));
+        let before = max_slope(&field);
+        let volume = field.data().iter().sum::<f32>();
+        field.thermal_erosion(&ThermalErosionSettings::default());
+        assert!(max_slope(&field) < before);
+        // Material is moved, not destroyed.
+        assert!((field.data().iter().sum::<f32>() - volume).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_hydraulic_erosion_moves_material_downhill() {
+        let mut field = slope_field();
+        field.hydraulic_erosion(&HydraulicErosionSettings {
+            droplets: 500,
+            ..Default::default()
+        });
+        assert!(field.data().iter().all(|h| h.is_finite()));
+        assert_ne!(field, slope_field());
+    }
+
+    #[test]
+    fn test_height_field_round_trip() {
+        let node = TerrainBuilder::new(BaseBuilder::new())
+            .with_width_chunks(0..2)
+            .with_length_chunks(0..2)
+            .with_height_map_size(Vector2::new(11, 11))
+            .with_block_size(Vector2::new(5, 5))
+            .with_mask_size(Vector2::new(8, 8))
+            .with_layers(vec![Default::default(), Default::default()])
+            .build_node();
+        let mut terrain = node.cast::<Terrain>().unwrap().clone();
+
+        let mut field = terrain.height_field();
+        assert_eq!(field.size(), Vector2::new(17, 17));
+        for (i, height) in field.data_mut().iter_mut().enumerate() {
+            *height = i as f32;
+        }
+        terrain.set_height_field(&field);
+        assert_eq!(terrain.height_field(), field);
+        // Heights are accessible through the regular API.
+        assert_eq!(
+            terrain.get_height(Vector2::new(8, 3)),
+            Some(field.get(8, 3))
+        );
+
+        terrain.apply_generator(&TerrainGenerator::HeightMask(LayerMaskRule {
+            layer: 1,
+            range: 0.0..150.0,
+            falloff: 1.0,
+        }));
+        assert_eq!(terrain.get_layer_mask(Vector2::new(0, 0), 1), Some(255));
+        assert_eq!(terrain.get_layer_mask(Vector2::new(15, 15), 1), Some(0));
+    }
+}
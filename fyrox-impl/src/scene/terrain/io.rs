@@ -0,0 +1,495 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Import and export of terrain height maps and layer masks in common image formats. Height maps
+//! and masks of all chunks are stitched together into a single image, so the terrain could be
+//! edited in external tools as a whole. See [`HeightMapFormat`] for the list of supported formats.
+
+use crate::{
+    core::algebra::Vector2,
+    scene::terrain::{resize_f32, resize_u8, HeightField, Terrain},
+};
+use image::{DynamicImage, ImageBuffer, ImageFormat, Luma, Rgb};
+use std::{
+    fmt::{Display, Formatter},
+    io::Cursor,
+    ops::Range,
+    path::Path,
+};
+
+/// A format of an exported or imported height map.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum HeightMapFormat {
+    /// Grayscale 16-bit PNG image. Heights are normalized to a range of heights.
+    Png16,
+    /// Headerless 16-bit unsigned integers in row-major order (also known as R16). Heights are
+    /// normalized to a range of heights. The image must be square, if its size does not match the
+    /// size of the terrain.
+    Raw16 {
+        /// Byte order of the integers. Most tools use little endian byte order.
+        little_endian: bool,
+    },
+    /// OpenEXR image with 32-bit floating point heights, stored as is, in meters.
+    Exr,
+}
+
+impl HeightMapFormat {
+    /// Tries to deduce a format from the extension of the given path (`png`, `raw`, `r16`, `exr`).
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Option<Self> {
+        let extension = path.as_ref().extension()?.to_str()?.to_lowercase();
+        match extension.as_str() {
+            "png" => Some(Self::Png16),
+            "raw" | "r16" => Some(Self::Raw16 {
+                little_endian: true,
+            }),
+            "exr" => Some(Self::Exr),
+            _ => None,
+        }
+    }
+}
+
+/// All possible errors that may occur during import or export of terrain data.
+#[derive(Debug)]
+pub enum TerrainIoError {
+    /// An I/O error.
+    Io(std::io::Error),
+    /// Image encoding or decoding error.
+    Image(image::ImageError),
+    /// The format could not be deduced from the file extension.
+    UnknownFormat,
+    /// The size of raw data does not match the size of the terrain and the data is not square.
+    InvalidRawSize(usize),
+    /// The terrain has no layer with the given index.
+    InvalidLayer(usize),
+}
+
+impl Display for TerrainIoError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TerrainIoError::Io(v) => write!(f, "I/O error: {v}"),
+            TerrainIoError::Image(v) => write!(f, "Image error: {v}"),
+            TerrainIoError::UnknownFormat => {
+                write!(f, "Unable to deduce the format from the file extension.")
+            }
+            TerrainIoError::InvalidRawSize(v) => write!(
+                f,
+                "Raw data of {v} bytes does not match the size of the terrain and is not square."
+            ),
+            TerrainIoError::InvalidLayer(v) => write!(f, "The terrain has no layer {v}."),
+        }
+    }
+}
+
+impl std::error::Error for TerrainIoError {}
+
+impl From<std::io::Error> for TerrainIoError {
+    fn from(value: std::io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+impl From<image::ImageError> for TerrainIoError {
+    fn from(value: image::ImageError) -> Self {
+        Self::Image(value)
+    }
+}
+
+fn encode_image(image: DynamicImage, format: ImageFormat) -> Result<Vec<u8>, TerrainIoError> {
+    let mut bytes = Cursor::new(Vec::new());
+    image.write_to(&mut bytes, format)?;
+    Ok(bytes.into_inner())
+}
+
+fn normalize(height: f32, range: &Range<f32>) -> u16 {
+    let length = range.end - range.start;
+    let k = if length.abs() > f32::EPSILON {
+        (height - range.start) / length
+    } else {
+        0.0
+    };
+    (k.clamp(0.0, 1.0) * u16::MAX as f32).round() as u16
+}
+
+fn denormalize(value: u16, range: &Range<f32>) -> f32 {
+    range.start + (value as f32 / u16::MAX as f32) * (range.end - range.start)
+}
+
+impl Terrain {
+    /// Size of the image with the layer masks of all chunks stitched together.
+    fn stitched_mask_size(&self) -> Vector2<u32> {
+        Vector2::new(
+            self.width_chunks.len() as u32 * self.mask_size.x,
+            self.length_chunks.len() as u32 * self.mask_size.y,
+        )
+    }
+
+    /// Returns the range of heights of the terrain.
+    pub fn height_range(&self) -> Range<f32> {
+        let field = self.height_field();
+        let min = field.data().iter().copied().fold(f32::MAX, f32::min);
+        let max = field.data().iter().copied().fold(f32::MIN, f32::max);
+        min..max
+    }
+
+    /// Encodes the height map of the terrain using the given format. The image covers the entire
+    /// terrain, its size is `chunks * (height_map_size - 3) + 1` pixels along each axis (adjacent
+    /// chunks share their edges). The first row of the image corresponds to the chunks with the
+    /// smallest Z coordinate.
+    ///
+    /// Integer formats store heights normalized to the given range of heights (or to
+    /// [`Self::height_range`], if `None`). The same range should be used to import the height map
+    /// back. The method returns the range that was used.
+    pub fn encode_height_map(
+        &self,
+        format: HeightMapFormat,
+        height_range: Option<Range<f32>>,
+    ) -> Result<(Vec<u8>, Range<f32>), TerrainIoError> {
+        let field = self.height_field();
+        let size = field.size();
+        let range = height_range.unwrap_or_else(|| self.height_range());
+
+        let bytes = match format {
+            HeightMapFormat::Png16 => {
+                let pixels = field
+                    .data()
+                    .iter()
+                    .map(|h| normalize(*h, &range))
+                    .collect::<Vec<_>>();
+                let image =
+                    ImageBuffer::<Luma<u16>, Vec<u16>>::from_raw(size.x, size.y, pixels).unwrap();
+                encode_image(DynamicImage::ImageLuma16(image), ImageFormat::Png)?
+            }
+            HeightMapFormat::Raw16 { little_endian } => field
+                .data()
+                .iter()
+                .flat_map(|h| {
+                    let value = normalize(*h, &range);
+                    if little_endian {
+                        value.to_le_bytes()
+                    } else {
+                        value.to_be_bytes()
+                    }
+                })
+                .collect(),
+            HeightMapFormat::Exr => {
+                // EXR encoder does not support single-channel images.
+                let pixels = field
+                    .data()
+                    .iter()
+                    .flat_map(|h| [*h; 3])
+                    .collect::<Vec<_>>();
+                let image =
+                    ImageBuffer::<Rgb<f32>, Vec<f32>>::from_raw(size.x, size.y, pixels).unwrap();
+                encode_image(DynamicImage::ImageRgb32F(image), ImageFormat::OpenExr)?
+            }
+        };
+
+        Ok((bytes, range))
+    }
+
+    /// Decodes the given height map and assigns it to the terrain. See [`Self::encode_height_map`]
+    /// for the layout of the image. Images of a different size are resampled to fit the terrain.
+    /// `height_range` defines the heights of the lowest and highest values of integer formats, it is
+    /// ignored for EXR images.
+    pub fn decode_height_map(
+        &mut self,
+        bytes: &[u8],
+        format: HeightMapFormat,
+        height_range: Range<f32>,
+    ) -> Result<(), TerrainIoError> {
+        let (size, mut heights) = match format {
+            HeightMapFormat::Png16 => {
+                let image = image::load_from_memory_with_format(bytes, ImageFormat::Png)?;
+                let image = image.to_luma16();
+                (
+                    Vector2::new(image.width(), image.height()),
+                    image
+                        .into_raw()
+                        .into_iter()
+                        .map(|v| denormalize(v, &height_range))
+                        .collect::<Vec<_>>(),
+                )
+            }
+            HeightMapFormat::Raw16 { little_endian } => {
+                let count = bytes.len() / 2;
+                let terrain_size = self.height_field().size();
+                let size = if bytes.len().is_multiple_of(2)
+                    && count == (terrain_size.x * terrain_size.y) as usize
+                {
+                    terrain_size
+                } else {
+                    let side = (count as f64).sqrt() as u32;
+                    if !bytes.len().is_multiple_of(2)
+                        || (side * side) as usize != count
+                        || side == 0
+                    {
+                        return Err(TerrainIoError::InvalidRawSize(bytes.len()));
+                    }
+                    Vector2::repeat(side)
+                };
+                let heights = bytes
+                    .chunks_exact(2)
+                    .map(|pair| {
+                        let pair = [pair[0], pair[1]];
+                        let value = if little_endian {
+                            u16::from_le_bytes(pair)
+                        } else {
+                            u16::from_be_bytes(pair)
+                        };
+                        denormalize(value, &height_range)
+                    })
+                    .collect::<Vec<_>>();
+                (size, heights)
+            }
+            HeightMapFormat::Exr => {
+                let image = image::load_from_memory_with_format(bytes, ImageFormat::OpenExr)?;
+                let image = image.to_rgb32f();
+                (
+                    Vector2::new(image.width(), image.height()),
+                    image.pixels().map(|p| p.0[0]).collect::<Vec<_>>(),
+                )
+            }
+        };
+
+        let mut field = self.height_field();
+        if size != field.size() {
+            heights = resize_f32(heights, size, field.size());
+        }
+        field.data_mut().copy_from_slice(&heights);
+        self.set_height_field(&field);
+
+        Ok(())
+    }
+
+    /// Exports the height map of the terrain to a file. The format is deduced from the extension of
+    /// the file. See [`Self::encode_height_map`] for more info.
+    pub fn export_height_map<P: AsRef<Path>>(
+        &self,
+        path: P,
+        height_range: Option<Range<f32>>,
+    ) -> Result<Range<f32>, TerrainIoError> {
+        let format = HeightMapFormat::from_path(&path).ok_or(TerrainIoError::UnknownFormat)?;
+        let (bytes, range) = self.encode_height_map(format, height_range)?;
+        std::fs::write(path, bytes)?;
+        Ok(range)
+    }
+
+    /// Imports the height map of the terrain from a file. The format is deduced from the extension
+    /// of the file. See [`Self::decode_height_map`] for more info.
+    pub fn import_height_map<P: AsRef<Path>>(
+        &mut self,
+        path: P,
+        height_range: Range<f32>,
+    ) -> Result<(), TerrainIoError> {
+        let format = HeightMapFormat::from_path(&path).ok_or(TerrainIoError::UnknownFormat)?;
+        let bytes = std::fs::read(path)?;
+        self.decode_height_map(&bytes, format, height_range)
+    }
+
+    /// Creates a grayscale image with the blending masks of the given layer of every chunk,
+    /// stitched together. The first row of the image corresponds to the chunks with the smallest
+    /// Z coordinate.
+    pub fn layer_mask_image(
+        &self,
+        layer: usize,
+    ) -> Result<ImageBuffer<Luma<u8>, Vec<u8>>, TerrainIoError> {
+        if layer >= self.layers.len() {
+            return Err(TerrainIoError::InvalidLayer(layer));
+        }
+        let size = self.stitched_mask_size();
+        let mask_size = *self.mask_size;
+        let mut image = ImageBuffer::<Luma<u8>, Vec<u8>>::new(size.x, size.y);
+        for chunk in self.chunks.iter() {
+            let Some(mask) = chunk.layer_masks.get(layer) else {
+                continue;
+            };
+            let origin = Vector2::new(
+                (chunk.grid_position.x - self.width_chunks.start) as u32 * mask_size.x,
+                (chunk.grid_position.y - self.length_chunks.start) as u32 * mask_size.y,
+            );
+            let data = mask.data_ref();
+            for (i, value) in data.data().iter().enumerate() {
+                let (x, y) = (i as u32 % mask_size.x, i as u32 / mask_size.x);
+                image.put_pixel(origin.x + x, origin.y + y, Luma([*value]));
+            }
+        }
+        Ok(image)
+    }
+
+    /// Assigns blending masks of the given layer of every chunk from the given image. Images of a
+    /// different size are resampled to fit the terrain. Colored images are converted to grayscale.
+    pub fn set_layer_mask_image(
+        &mut self,
+        layer: usize,
+        image: &DynamicImage,
+    ) -> Result<(), TerrainIoError> {
+        if layer >= self.layers.len() {
+            return Err(TerrainIoError::InvalidLayer(layer));
+        }
+        let size = self.stitched_mask_size();
+        let mut image = image.to_luma8();
+        if image.dimensions() != (size.x, size.y) {
+            let source_size = Vector2::new(image.width(), image.height());
+            image = ImageBuffer::from_raw(
+                size.x,
+                size.y,
+                resize_u8(image.into_raw(), source_size, size),
+            )
+            .unwrap();
+        }
+
+        let mask_size = *self.mask_size;
+        for chunk in self.chunks.iter_mut() {
+            let Some(mask) = chunk.layer_masks.get(layer) else {
+                continue;
+            };
+            let origin = Vector2::new(
+                (chunk.grid_position.x - self.width_chunks.start) as u32 * mask_size.x,
+                (chunk.grid_position.y - self.length_chunks.start) as u32 * mask_size.y,
+            );
+            let mut data = mask.data_ref();
+            let mut modifier = data.modify();
+            for (i, value) in modifier.data_mut().iter_mut().enumerate() {
+                let (x, y) = (i as u32 % mask_size.x, i as u32 / mask_size.x);
+                *value = image.get_pixel(origin.x + x, origin.y + y).0[0];
+            }
+        }
+        Ok(())
+    }
+
+    /// Exports blending masks of the given layer to an image file. The format is deduced from the
+    /// extension of the file. See [`Self::layer_mask_image`] for more info.
+    pub fn export_layer_mask<P: AsRef<Path>>(
+        &self,
+        layer: usize,
+        path: P,
+    ) -> Result<(), TerrainIoError> {
+        self.layer_mask_image(layer)?.save(path)?;
+        Ok(())
+    }
+
+    /// Imports blending masks of the given layer from an image file. See
+    /// [`Self::set_layer_mask_image`] for more info.
+    pub fn import_layer_mask<P: AsRef<Path>>(
+        &mut self,
+        layer: usize,
+        path: P,
+    ) -> Result<(), TerrainIoError> {
+        let image = image::open(path)?;
+        self.set_layer_mask_image(layer, &image)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::scene::{base::BaseBuilder, terrain::TerrainBuilder};
+
+    fn make_terrain() -> Terrain {
+        let node = TerrainBuilder::new(BaseBuilder::new())
+            .with_width_chunks(0..2)
+            .with_length_chunks(0..2)
+            .with_height_map_size(Vector2::new(11, 11))
+            .with_block_size(Vector2::new(5, 5))
+            .with_mask_size(Vector2::new(8, 8))
+            .with_layers(vec![Default::default(), Default::default()])
+            .build_node();
+        let mut terrain = node.cast::<Terrain>().unwrap().clone();
+        let mut field = terrain.height_field();
+        for (i, height) in field.data_mut().iter_mut().enumerate() {
+            *height = (i % 17) as f32 * 0.5;
+        }
+        terrain.set_height_field(&field);
+        terrain
+    }
+
+    #[test]
+    fn test_height_map_round_trip() {
+        for format in [
+            HeightMapFormat::Png16,
+            HeightMapFormat::Raw16 {
+                little_endian: true,
+            },
+            HeightMapFormat::Raw16 {
+                little_endian: false,
+            },
+            HeightMapFormat::Exr,
+        ] {
+            let source = make_terrain();
+            let (bytes, range) = source.encode_height_map(format, None).unwrap();
+            assert_eq!(range, 0.0..8.0);
+
+            let mut terrain = make_terrain();
+            terrain.set_height_field(&HeightField::new(
+                Vector2::new(17, 17),
+                Vector2::new(1.0, 1.0),
+            ));
+            terrain.decode_height_map(&bytes, format, range).unwrap();
+            for (a, b) in terrain
+                .height_field()
+                .data()
+                .iter()
+                .zip(source.height_field().data())
+            {
+                assert!((a - b).abs() < 0.001, "{format:?}: {a} != {b}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_layer_mask_round_trip() {
+        let mut terrain = make_terrain();
+        let mut image = ImageBuffer::<Luma<u8>, Vec<u8>>::new(16, 16);
+        for (x, y, pixel) in image.enumerate_pixels_mut() {
+            *pixel = Luma([(x * 16 + y) as u8]);
+        }
+        terrain
+            .set_layer_mask_image(1, &DynamicImage::ImageLuma8(image.clone()))
+            .unwrap();
+        assert_eq!(terrain.layer_mask_image(1).unwrap(), image);
+        assert_eq!(
+            terrain.get_layer_mask(Vector2::new(9, 3), 1),
+            Some((9 * 16 + 3) as u8)
+        );
+        assert!(matches!(
+            terrain.layer_mask_image(2),
+            Err(TerrainIoError::InvalidLayer(2))
+        ));
+    }
+
+    #[test]
+    fn test_format_from_path() {
+        assert_eq!(
+            HeightMapFormat::from_path("terrain.PNG"),
+            Some(HeightMapFormat::Png16)
+        );
+        assert_eq!(
+            HeightMapFormat::from_path("terrain.r16"),
+            Some(HeightMapFormat::Raw16 {
+                little_endian: true
+            })
+        );
+        assert_eq!(
+            HeightMapFormat::from_path("terrain.exr"),
+            Some(HeightMapFormat::Exr)
+        );
+        assert_eq!(HeightMapFormat::from_path("terrain.txt"), None);
+    }
+}
@@ -74,12 +74,18 @@ use std::{
 };
 
 pub mod brushstroke;
+mod foliage;
+mod generator;
 mod geometry;
+mod io;
 mod quadtree;
 
 use crate::scene::node::constructor::NodeConstructor;
 pub use brushstroke::*;
+pub use foliage::*;
 use fyrox_graph::constructor::ConstructorProvider;
+pub use generator::*;
+pub use io::*;
 
 use super::collider::BitMask;
 
@@ -373,6 +379,9 @@ pub struct Chunk {
     /// Layer blending masks of the chunk.
     #[reflect(hidden)]
     pub layer_masks: Vec<TextureResource>,
+    /// Density masks of the foliage layers of the terrain.
+    #[reflect(hidden)]
+    pub foliage_masks: Vec<TextureResource>,
     #[reflect(hidden)]
     height_map_modifications_count: u64,
 }
@@ -385,6 +394,7 @@ impl PartialEq for Chunk {
             && self.height_map_size == other.height_map_size
             && self.grid_position == other.grid_position
             && self.layer_masks == other.layer_masks
+            && self.foliage_masks == other.foliage_masks
     }
 }
 
@@ -404,6 +414,11 @@ impl Clone for Chunk {
                 .iter()
                 .map(|m| m.deep_clone())
                 .collect::<Vec<_>>(),
+            foliage_masks: self
+                .foliage_masks
+                .iter()
+                .map(|m| m.deep_clone())
+                .collect::<Vec<_>>(),
             quad_tree: Mutex::new(make_quad_tree(
                 &self.heightmap,
                 self.height_map_size,
@@ -436,6 +451,7 @@ impl Visit for Chunk {
                 self.position = self.position()
             }
             self.block_size.visit("BlockSize", &mut region)?;
+            let _ = self.foliage_masks.visit("FoliageMasks", &mut region);
         }
 
         self.quad_tree = Mutex::new(make_quad_tree(
@@ -460,6 +476,7 @@ impl Default for Chunk {
             block_size: Vector2::new(32, 32),
             grid_position: Default::default(),
             layer_masks: Default::default(),
+            foliage_masks: Default::default(),
             height_map_modifications_count: 0,
         }
     }
@@ -1082,6 +1099,10 @@ pub struct Terrain {
     #[reflect(min_value = 1.0, step = 1.0, setter = "set_mask_size")]
     mask_size: InheritableVariable<Vector2<u32>>,
 
+    /// Foliage layers of the terrain. See [`FoliageLayer`] docs for more info.
+    #[reflect(setter = "set_foliage_layers")]
+    foliage_layers: InheritableVariable<Vec<FoliageLayer>>,
+
     #[reflect(immutable_collection)]
     chunks: InheritableVariable<Vec<Chunk>>,
 
@@ -1095,6 +1116,9 @@ pub struct Terrain {
     /// all the chunks of the height map.
     #[reflect(hidden)]
     geometry: TerrainGeometry,
+
+    #[reflect(hidden)]
+    foliage_cache: FoliageCache,
 }
 
 impl Default for Terrain {
@@ -1109,10 +1133,12 @@ impl Default for Terrain {
             height_map_size: Vector2::new(259, 259).into(),
             block_size: Vector2::new(33, 33).into(),
             mask_size: Vector2::new(256, 256).into(),
+            foliage_layers: Default::default(),
             chunks: Default::default(),
             bounding_box_dirty: Cell::new(true),
             bounding_box: Cell::new(Default::default()),
             geometry: Default::default(),
+            foliage_cache: Default::default(),
         }
     }
 }
@@ -1136,6 +1162,7 @@ impl Visit for Terrain {
             self.block_size.visit("BlockSize", &mut region)?;
             self.mask_size.visit("MaskSize", &mut region)?;
             self.chunks.visit("Chunks", &mut region)?;
+            let _ = self.foliage_layers.visit("FoliageLayers", &mut region);
         }
 
         if region.is_reading() {
@@ -1577,6 +1604,11 @@ impl Terrain {
                                 )
                             })
                             .collect::<Vec<_>>(),
+                        foliage_masks: self
+                            .foliage_layers
+                            .iter()
+                            .map(|_| create_layer_mask(self.mask_size.x, self.mask_size.y, 255))
+                            .collect::<Vec<_>>(),
                     };
                     created_chunks.push(new_chunk.grid_position);
                     new_chunk
@@ -1724,6 +1756,18 @@ impl Terrain {
         Some(mask_data[index])
     }
 
+    /// Return the value of the foliage density mask at the given mask pixel position.
+    pub fn get_foliage_mask(&self, position: Vector2<i32>, layer: usize) -> Option<u8> {
+        let chunk_pos = self.chunk_containing_mask_pos(position);
+        let chunk = self.find_chunk(chunk_pos)?;
+        let origin = self.chunk_mask_pos_origin(chunk_pos);
+        let pos = (position - origin).map(|x| x as usize);
+        let index = pos.y * self.mask_size.x as usize + pos.x;
+        let texture_data = chunk.foliage_masks.get(layer)?.data_ref();
+        let mask_data = texture_data.data();
+        Some(mask_data[index])
+    }
+
     /// Return the value of the layer mask at the given mask pixel position.
     pub fn get_hole_mask(&self, position: Vector2<i32>) -> Option<u8> {
         let chunk_pos = self.chunk_containing_hole_pos(position);
@@ -1755,6 +1799,7 @@ impl Terrain {
         let grid_square = match target {
             BrushTarget::HeightMap => self.get_height_grid_square(position),
             BrushTarget::LayerMask { .. } => self.get_mask_grid_square(position),
+            BrushTarget::FoliageMask { .. } => self.get_mask_grid_square(position),
             BrushTarget::HoleMask => self.get_hole_grid_square(position),
         };
         let p = grid_square.grid_position;
@@ -1784,6 +1829,12 @@ impl Terrain {
                 self.get_layer_mask(p10, layer).unwrap_or(0) as f32 / 255.0,
                 self.get_layer_mask(p11, layer).unwrap_or(0) as f32 / 255.0,
             ),
+            BrushTarget::FoliageMask { layer } => (
+                self.get_foliage_mask(p00, layer).unwrap_or(0) as f32 / 255.0,
+                self.get_foliage_mask(p01, layer).unwrap_or(0) as f32 / 255.0,
+                self.get_foliage_mask(p10, layer).unwrap_or(0) as f32 / 255.0,
+                self.get_foliage_mask(p11, layer).unwrap_or(0) as f32 / 255.0,
+            ),
             BrushTarget::HoleMask => (
                 self.get_hole_mask(p00).unwrap_or(0) as f32 / 255.0,
                 self.get_hole_mask(p01).unwrap_or(0) as f32 / 255.0,
@@ -2136,11 +2187,92 @@ impl Terrain {
         }
     }
 
+    /// Sets new foliage layers of the terrain. Density masks of every chunk are created or removed
+    /// to match the new amount of layers.
+    pub fn set_foliage_layers(&mut self, layers: Vec<FoliageLayer>) -> Vec<FoliageLayer> {
+        let old = self.foliage_layers.set_value_and_mark_modified(layers);
+        self.ensure_foliage_masks();
+        old
+    }
+
+    /// Returns a reference to a slice with foliage layers of the terrain.
+    pub fn foliage_layers(&self) -> &[FoliageLayer] {
+        &self.foliage_layers
+    }
+
+    /// Returns a mutable reference to a slice with foliage layers of the terrain.
+    pub fn foliage_layers_mut(&mut self) -> &mut [FoliageLayer] {
+        self.foliage_layers.get_value_mut_and_mark_modified()
+    }
+
+    /// Adds new foliage layer with fully opaque density masks.
+    pub fn add_foliage_layer(&mut self, layer: FoliageLayer) {
+        self.insert_foliage_layer(layer, Default::default(), self.foliage_layers.len())
+    }
+
+    /// Inserts the foliage layer at the given index together with its density masks for each chunk.
+    /// Missing masks are created fully opaque.
+    pub fn insert_foliage_layer(
+        &mut self,
+        layer: FoliageLayer,
+        mut masks: Vec<TextureResource>,
+        index: usize,
+    ) {
+        self.ensure_foliage_masks();
+        self.foliage_layers
+            .get_value_mut_and_mark_modified()
+            .insert(index, layer);
+
+        for chunk in self.chunks.iter_mut().rev() {
+            let mask = masks
+                .pop()
+                .unwrap_or_else(|| create_layer_mask(self.mask_size.x, self.mask_size.y, 255));
+            chunk.foliage_masks.insert(index, mask);
+        }
+        self.foliage_cache.clear();
+    }
+
+    /// Removes a foliage layer at the given index together with its density masks from each chunk.
+    pub fn remove_foliage_layer(&mut self, index: usize) -> (FoliageLayer, Vec<TextureResource>) {
+        self.ensure_foliage_masks();
+        let layer = self
+            .foliage_layers
+            .get_value_mut_and_mark_modified()
+            .remove(index);
+        let masks = self
+            .chunks
+            .iter_mut()
+            .map(|chunk| chunk.foliage_masks.remove(index))
+            .collect();
+        self.foliage_cache.clear();
+        (layer, masks)
+    }
+
+    /// Makes sure that every chunk has a density mask for each foliage layer. Excessive masks are
+    /// removed, missing masks are created fully opaque.
+    pub fn ensure_foliage_masks(&mut self) {
+        let count = self.foliage_layers.len();
+        let mask_size = *self.mask_size;
+        for chunk in self.chunks.iter_mut() {
+            chunk.foliage_masks.truncate(count);
+            while chunk.foliage_masks.len() < count {
+                chunk
+                    .foliage_masks
+                    .push(create_layer_mask(mask_size.x, mask_size.y, 255));
+            }
+        }
+        self.foliage_cache.clear();
+    }
+
     fn resize_masks(&mut self, mut new_size: Vector2<u32>) {
         new_size = new_size.sup(&Vector2::repeat(1));
 
         for chunk in self.chunks.iter_mut() {
-            for mask in chunk.layer_masks.iter_mut() {
+            for mask in chunk
+                .layer_masks
+                .iter_mut()
+                .chain(chunk.foliage_masks.iter_mut())
+            {
                 let data = mask.data_ref();
                 let new_mask = resize_u8(data.data().to_vec(), *self.mask_size, new_size);
                 let new_mask_texture = TextureResource::from_bytes(
@@ -2217,11 +2349,13 @@ impl Terrain {
         let chunk_size = match target {
             BrushTarget::HeightMap => self.height_map_size(),
             BrushTarget::LayerMask { .. } => self.mask_size(),
+            BrushTarget::FoliageMask { .. } => self.mask_size(),
             BrushTarget::HoleMask => self.hole_mask_size(),
         };
         let kind = match target {
             BrushTarget::HeightMap => TerrainTextureKind::Height,
             BrushTarget::LayerMask { .. } => TerrainTextureKind::Mask,
+            BrushTarget::FoliageMask { .. } => TerrainTextureKind::Mask,
             BrushTarget::HoleMask => TerrainTextureKind::Mask,
         };
         let resources: FxHashMap<Vector2<i32>, TextureResource> = match target {
@@ -2248,6 +2382,11 @@ impl Terrain {
                 .iter()
                 .map(|c| (c.grid_position(), c.layer_masks[layer].clone()))
                 .collect(),
+            BrushTarget::FoliageMask { layer } => self
+                .chunks_ref()
+                .iter()
+                .filter_map(|c| Some((c.grid_position(), c.foliage_masks.get(layer)?.clone())))
+                .collect(),
         };
         TerrainTextureData {
             chunk_size,
@@ -2266,6 +2405,12 @@ impl Terrain {
             Log::err("Invalid brush stroke. Holes are not enabled on terrain.");
             return;
         }
+        if let BrushTarget::FoliageMask { layer } = brush.target {
+            if layer >= self.foliage_layers.len() {
+                Log::err("Invalid brush stroke. Foliage layer does not exist.");
+                return;
+            }
+        }
         stroke.start_stroke(brush, self.handle(), self.texture_data(target))
     }
     /// Modify the given BrushStroke to include a stamp of its brush at the given position.
@@ -2282,11 +2427,13 @@ impl Terrain {
         let position = match stroke.brush().target {
             BrushTarget::HeightMap => self.local_to_height_pixel(position),
             BrushTarget::LayerMask { .. } => self.local_to_mask_pixel(position),
+            BrushTarget::FoliageMask { .. } => self.local_to_mask_pixel(position),
             BrushTarget::HoleMask => self.local_to_hole_pixel(position),
         };
         let scale = match stroke.brush().target {
             BrushTarget::HeightMap => self.height_grid_scale(),
             BrushTarget::LayerMask { .. } => self.mask_grid_scale(),
+            BrushTarget::FoliageMask { .. } => self.mask_grid_scale(),
             BrushTarget::HoleMask => self.hole_grid_scale(),
         };
         stroke.stamp(position, scale, value);
@@ -2309,16 +2456,19 @@ impl Terrain {
         let start = match stroke.brush().target {
             BrushTarget::HeightMap => self.local_to_height_pixel(start),
             BrushTarget::LayerMask { .. } => self.local_to_mask_pixel(start),
+            BrushTarget::FoliageMask { .. } => self.local_to_mask_pixel(start),
             BrushTarget::HoleMask => self.local_to_hole_pixel(start),
         };
         let end = match stroke.brush().target {
             BrushTarget::HeightMap => self.local_to_height_pixel(end),
             BrushTarget::LayerMask { .. } => self.local_to_mask_pixel(end),
+            BrushTarget::FoliageMask { .. } => self.local_to_mask_pixel(end),
             BrushTarget::HoleMask => self.local_to_hole_pixel(end),
         };
         let scale = match stroke.brush().target {
             BrushTarget::HeightMap => self.height_grid_scale(),
             BrushTarget::LayerMask { .. } => self.mask_grid_scale(),
+            BrushTarget::FoliageMask { .. } => self.mask_grid_scale(),
             BrushTarget::HoleMask => self.hole_grid_scale(),
         };
         stroke.smear(start, end, scale, value);
@@ -2458,6 +2608,8 @@ impl NodeTrait for Terrain {
             c.update();
         }
 
+        self.collect_foliage_render_data(ctx);
+
         for (layer_index, layer) in self.layers().iter().enumerate() {
             for chunk in self.chunks_ref().iter() {
                 // Generate a list of distances for each LOD that the terrain can render.
@@ -2620,6 +2772,7 @@ pub struct TerrainBuilder {
     height_map_size: Vector2<u32>,
     block_size: Vector2<u32>,
     layers: Vec<Layer>,
+    foliage_layers: Vec<FoliageLayer>,
 }
 
 fn create_layer_mask(width: u32, height: u32, value: u8) -> TextureResource {
@@ -2653,6 +2806,7 @@ impl TerrainBuilder {
             height_map_size: Vector2::new(259, 259),
             block_size: Vector2::new(33, 33),
             layers: Default::default(),
+            foliage_layers: Default::default(),
         }
     }
 
@@ -2698,6 +2852,12 @@ impl TerrainBuilder {
         self
     }
 
+    /// Sets desired foliage layers of the terrain.
+    pub fn with_foliage_layers(mut self, foliage_layers: Vec<FoliageLayer>) -> Self {
+        self.foliage_layers = foliage_layers;
+        self
+    }
+
     /// Sets desired block size. Block - is a smallest renderable piece of terrain which will be used for
     /// level-of-detail functionality.
     pub fn with_block_size(mut self, block_size: Vector2<u32>) -> Self {
@@ -2752,6 +2912,11 @@ impl TerrainBuilder {
                             )
                         })
                         .collect::<Vec<_>>(),
+                    foliage_masks: self
+                        .foliage_layers
+                        .iter()
+                        .map(|_| create_layer_mask(self.mask_size.x, self.mask_size.y, 255))
+                        .collect::<Vec<_>>(),
                     block_size: self.block_size,
                 };
 
@@ -2764,6 +2929,7 @@ impl TerrainBuilder {
             base: self.base_builder.build_base(),
             holes_enabled: self.holes_enabled,
             layers: self.layers.into(),
+            foliage_layers: self.foliage_layers.into(),
             chunks: chunks.into(),
             bounding_box_dirty: Cell::new(true),
             bounding_box: Default::default(),
@@ -2773,6 +2939,7 @@ impl TerrainBuilder {
             length_chunks: self.length_chunks.into(),
             geometry: TerrainGeometry::new(self.block_size),
             block_size: self.block_size.into(),
+            foliage_cache: Default::default(),
         };
         Node::new(terrain)
     }
@@ -0,0 +1,818 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Vehicle is a node that turns a rigid body into a car with raycast wheels. See [`Vehicle`] docs
+//! for more info.
+
+use crate::scene::node::constructor::NodeConstructor;
+use crate::{
+    core::{
+        algebra::{Matrix4, Point3, UnitQuaternion, UnitVector3, Vector3},
+        color::Color,
+        math::{aabb::AxisAlignedBoundingBox, curve::Curve},
+        pool::Handle,
+        reflect::prelude::*,
+        type_traits::prelude::*,
+        uuid::{uuid, Uuid},
+        variable::InheritableVariable,
+        visitor::prelude::*,
+    },
+    scene::{
+        base::{Base, BaseBuilder},
+        debug::{Line, SceneDrawingContext},
+        graph::Graph,
+        node::{Node, NodeTrait, UpdateContext},
+        rigidbody::RigidBody,
+        Scene,
+    },
+};
+use fyrox_graph::constructor::ConstructorProvider;
+use fyrox_graph::SceneGraph;
+use rapier3d::control::DynamicRayCastVehicleController;
+use std::{
+    f32::consts::PI,
+    fmt::{Debug, Formatter},
+    ops::{Deref, DerefMut},
+};
+
+/// A raycast wheel of a [`Vehicle`]. All the positions and directions are defined in the local
+/// coordinates of the vehicle node.
+#[derive(Clone, Debug, PartialEq, Visit, Reflect)]
+pub struct VehicleWheel {
+    /// A point, where the suspension of the wheel is attached to the chassis.
+    pub position: Vector3<f32>,
+
+    /// Direction of the suspension, usually it points down.
+    pub direction: Vector3<f32>,
+
+    /// Rotation axis of the wheel.
+    pub axle: Vector3<f32>,
+
+    /// Radius of the wheel.
+    #[reflect(min_value = 0.0, step = 0.01)]
+    pub radius: f32,
+
+    /// Length of the suspension, when it is not loaded.
+    #[reflect(min_value = 0.0, step = 0.01)]
+    pub suspension_rest_length: f32,
+
+    /// Maximum distance the suspension can be compressed or stretched from its rest length.
+    #[reflect(min_value = 0.0, step = 0.01)]
+    pub max_suspension_travel: f32,
+
+    /// Stiffness of the suspension spring.
+    #[reflect(min_value = 0.0, step = 0.1)]
+    pub suspension_stiffness: f32,
+
+    /// Damping of the suspension, when it is being compressed.
+    #[reflect(min_value = 0.0, step = 0.01)]
+    pub suspension_compression: f32,
+
+    /// Damping of the suspension, when it is being stretched.
+    #[reflect(min_value = 0.0, step = 0.01)]
+    pub suspension_relaxation: f32,
+
+    /// Maximum force the suspension can apply to the chassis.
+    #[reflect(min_value = 0.0, step = 10.0)]
+    pub max_suspension_force: f32,
+
+    /// Friction of the tire in forward direction. Larger values make the wheel slip less.
+    #[reflect(min_value = 0.0, step = 0.1)]
+    pub friction_slip: f32,
+
+    /// Friction of the tire in sideways direction.
+    #[reflect(min_value = 0.0, step = 0.1)]
+    pub side_friction_stiffness: f32,
+
+    /// Defines how [`Self::friction_slip`] changes with the speed of the vehicle. The curve maps
+    /// the speed (in m/s) to a multiplier of the friction. An empty curve does not modify the
+    /// friction.
+    pub friction_curve: Curve,
+
+    /// Whether the wheel is turned by [`Vehicle::set_steering`] or not.
+    pub steered: bool,
+
+    /// Whether the engine torque is applied to the wheel or not.
+    pub driven: bool,
+
+    /// An optional node, that represents the wheel visually. It must be a direct child of the
+    /// vehicle node, its local transform will be set according to the state of the wheel.
+    pub visual: Handle<Node>,
+}
+
+impl Default for VehicleWheel {
+    fn default() -> Self {
+        Self {
+            position: Default::default(),
+            direction: -Vector3::y(),
+            axle: Vector3::x(),
+            radius: 0.35,
+            suspension_rest_length: 0.3,
+            max_suspension_travel: 0.2,
+            suspension_stiffness: 5.88,
+            suspension_compression: 0.83,
+            suspension_relaxation: 0.88,
+            max_suspension_force: 6000.0,
+            friction_slip: 10.5,
+            side_friction_stiffness: 1.0,
+            friction_curve: Default::default(),
+            steered: false,
+            driven: false,
+            visual: Default::default(),
+        }
+    }
+}
+
+impl VehicleWheel {
+    /// Creates a new wheel at the given position with default parameters.
+    pub fn new(position: Vector3<f32>) -> Self {
+        Self {
+            position,
+            ..Default::default()
+        }
+    }
+
+    /// Sets the desired radius of the wheel.
+    pub fn with_radius(mut self, radius: f32) -> Self {
+        self.radius = radius;
+        self
+    }
+
+    /// Sets the desired rest length and maximum travel of the suspension.
+    pub fn with_suspension(mut self, rest_length: f32, max_travel: f32) -> Self {
+        self.suspension_rest_length = rest_length;
+        self.max_suspension_travel = max_travel;
+        self
+    }
+
+    /// Sets the desired stiffness and damping of the suspension.
+    pub fn with_suspension_stiffness(
+        mut self,
+        stiffness: f32,
+        compression: f32,
+        relaxation: f32,
+    ) -> Self {
+        self.suspension_stiffness = stiffness;
+        self.suspension_compression = compression;
+        self.suspension_relaxation = relaxation;
+        self
+    }
+
+    /// Sets the desired forward and side friction of the tire.
+    pub fn with_friction(mut self, friction_slip: f32, side_friction_stiffness: f32) -> Self {
+        self.friction_slip = friction_slip;
+        self.side_friction_stiffness = side_friction_stiffness;
+        self
+    }
+
+    /// Sets the desired friction curve of the tire. See [`Self::friction_curve`] for more info.
+    pub fn with_friction_curve(mut self, curve: Curve) -> Self {
+        self.friction_curve = curve;
+        self
+    }
+
+    /// Sets whether the wheel is steered or not.
+    pub fn with_steered(mut self, steered: bool) -> Self {
+        self.steered = steered;
+        self
+    }
+
+    /// Sets whether the wheel is driven by the engine or not.
+    pub fn with_driven(mut self, driven: bool) -> Self {
+        self.driven = driven;
+        self
+    }
+
+    /// Sets the desired visual node of the wheel.
+    pub fn with_visual(mut self, visual: Handle<Node>) -> Self {
+        self.visual = visual;
+        self
+    }
+
+    fn friction_slip_at(&self, speed: f32) -> f32 {
+        if self.friction_curve.is_empty() {
+            self.friction_slip
+        } else {
+            self.friction_slip * self.friction_curve.value_at(speed.abs())
+        }
+    }
+}
+
+/// Runtime state of a wheel of a [`Vehicle`], that is updated every frame.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct VehicleWheelState {
+    /// Whether the wheel touches the ground or not.
+    pub in_contact: bool,
+    /// A handle of the collider the wheel touches.
+    pub ground: Handle<Node>,
+    /// World-space contact point.
+    pub contact_point: Vector3<f32>,
+    /// World-space normal at the contact point.
+    pub contact_normal: Vector3<f32>,
+    /// Current length of the suspension.
+    pub suspension_length: f32,
+    /// Current steering angle of the wheel (in radians).
+    pub steering: f32,
+    /// Current rotation angle of the wheel around its axle (in radians).
+    pub rotation: f32,
+}
+
+#[derive(Default)]
+pub(crate) struct NativeVehicle {
+    pub controller: Option<DynamicRayCastVehicleController>,
+    pub local_transform: Matrix4<f32>,
+}
+
+impl Debug for NativeVehicle {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "NativeVehicle")
+    }
+}
+
+/// Vehicle is a node that turns its parent rigid body (chassis) into a car with raycast wheels.
+/// Instead of simulating wheels as separate rigid bodies connected with joints, every wheel casts
+/// a ray along its suspension direction and applies suspension, friction, engine and brake
+/// impulses to the chassis. This is much more stable than joint-based vehicles and gives a lot of
+/// control over the handling.
+///
+/// # Setup
+///
+/// The vehicle node must be a direct child of a dynamic [`RigidBody`], that has at least one
+/// collider. Wheels are defined in the local coordinates of the vehicle node, the suspension of
+/// a wheel is attached at [`VehicleWheel::position`] and directed along
+/// [`VehicleWheel::direction`]. Visual representation of a wheel could be any node, that is a
+/// direct child of the vehicle node, it could be assigned to [`VehicleWheel::visual`].
+///
+/// Forward direction of the vehicle is +Z axis of the chassis.
+///
+/// # Controls
+///
+/// The vehicle is controlled by [`Vehicle::set_throttle`], [`Vehicle::set_brake`] and
+/// [`Vehicle::set_steering`], all of them take normalized values, that are scaled by
+/// [`Vehicle::max_engine_torque`], [`Vehicle::max_brake_force`] and
+/// [`Vehicle::max_steering_angle`] respectively.
+///
+/// ```rust
+/// use fyrox_impl::{
+///     core::pool::Handle,
+///     scene::{graph::Graph, vehicle::Vehicle},
+/// };
+///
+/// fn drive(graph: &mut Graph, vehicle: Handle<Vehicle>, forward: bool, left: bool) {
+///     let vehicle = &mut graph[vehicle];
+///     vehicle.set_throttle(if forward { 1.0 } else { 0.0 });
+///     vehicle.set_steering(if left { 1.0 } else { 0.0 });
+/// }
+/// ```
+#[derive(Reflect, Visit, Debug, ComponentProvider)]
+#[reflect(derived_type = "Node")]
+pub struct Vehicle {
+    base: Base,
+
+    #[reflect(setter = "set_wheels")]
+    pub(crate) wheels: InheritableVariable<Vec<VehicleWheel>>,
+
+    #[reflect(min_value = 0.0, step = 10.0)]
+    #[reflect(setter = "set_max_engine_torque")]
+    pub(crate) max_engine_torque: InheritableVariable<f32>,
+
+    #[reflect(min_value = 0.0, step = 1.0)]
+    #[reflect(setter = "set_max_brake_force")]
+    pub(crate) max_brake_force: InheritableVariable<f32>,
+
+    #[reflect(min_value = 0.0, max_value = 1.5708, step = 0.01)]
+    #[reflect(setter = "set_max_steering_angle")]
+    pub(crate) max_steering_angle: InheritableVariable<f32>,
+
+    #[visit(skip)]
+    #[reflect(hidden)]
+    throttle: f32,
+    #[visit(skip)]
+    #[reflect(hidden)]
+    brake: f32,
+    #[visit(skip)]
+    #[reflect(hidden)]
+    steering: f32,
+    #[visit(skip)]
+    #[reflect(hidden)]
+    pub(crate) speed: f32,
+    #[visit(skip)]
+    #[reflect(hidden)]
+    pub(crate) wheel_states: Vec<VehicleWheelState>,
+    #[visit(skip)]
+    #[reflect(hidden)]
+    pub(crate) native: NativeVehicle,
+}
+
+impl Default for Vehicle {
+    fn default() -> Self {
+        Self {
+            base: Default::default(),
+            wheels: Default::default(),
+            max_engine_torque: InheritableVariable::new_modified(600.0),
+            max_brake_force: InheritableVariable::new_modified(30.0),
+            max_steering_angle: InheritableVariable::new_modified(35.0f32.to_radians()),
+            throttle: 0.0,
+            brake: 0.0,
+            steering: 0.0,
+            speed: 0.0,
+            wheel_states: Default::default(),
+            native: Default::default(),
+        }
+    }
+}
+
+impl Clone for Vehicle {
+    fn clone(&self) -> Self {
+        Self {
+            base: self.base.clone(),
+            wheels: self.wheels.clone(),
+            max_engine_torque: self.max_engine_torque.clone(),
+            max_brake_force: self.max_brake_force.clone(),
+            max_steering_angle: self.max_steering_angle.clone(),
+            throttle: self.throttle,
+            brake: self.brake,
+            steering: self.steering,
+            speed: 0.0,
+            wheel_states: Default::default(),
+            // Do not copy. The copy will have its own native representation.
+            native: Default::default(),
+        }
+    }
+}
+
+impl Deref for Vehicle {
+    type Target = Base;
+
+    fn deref(&self) -> &Self::Target {
+        &self.base
+    }
+}
+
+impl DerefMut for Vehicle {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.base
+    }
+}
+
+impl TypeUuidProvider for Vehicle {
+    fn type_uuid() -> Uuid {
+        uuid!("8e3f3c0a-6a51-4d2b-b7e4-0f9d2c5a7e31")
+    }
+}
+
+impl Vehicle {
+    /// Sets new wheels of the vehicle.
+    pub fn set_wheels(&mut self, wheels: Vec<VehicleWheel>) -> Vec<VehicleWheel> {
+        self.wheels.set_value_and_mark_modified(wheels)
+    }
+
+    /// Returns a reference to the wheels of the vehicle.
+    pub fn wheels(&self) -> &[VehicleWheel] {
+        &self.wheels
+    }
+
+    /// Returns a mutable reference to the wheels of the vehicle.
+    pub fn wheels_mut(&mut self) -> &mut Vec<VehicleWheel> {
+        self.wheels.get_value_mut_and_mark_modified()
+    }
+
+    /// Returns runtime states of the wheels. The order of the states is the same as the order of
+    /// the wheels. The states are empty if the vehicle was not simulated yet.
+    pub fn wheel_states(&self) -> &[VehicleWheelState] {
+        &self.wheel_states
+    }
+
+    /// Sets the maximum torque of the engine. It is divided between all driven wheels.
+    pub fn set_max_engine_torque(&mut self, torque: f32) -> f32 {
+        self.max_engine_torque.set_value_and_mark_modified(torque)
+    }
+
+    /// Returns the maximum torque of the engine.
+    pub fn max_engine_torque(&self) -> f32 {
+        *self.max_engine_torque
+    }
+
+    /// Sets the maximum brake force, that is applied to every wheel.
+    pub fn set_max_brake_force(&mut self, force: f32) -> f32 {
+        self.max_brake_force.set_value_and_mark_modified(force)
+    }
+
+    /// Returns the maximum brake force, that is applied to every wheel.
+    pub fn max_brake_force(&self) -> f32 {
+        *self.max_brake_force
+    }
+
+    /// Sets the maximum steering angle (in radians) of the steered wheels.
+    pub fn set_max_steering_angle(&mut self, angle: f32) -> f32 {
+        self.max_steering_angle.set_value_and_mark_modified(angle)
+    }
+
+    /// Returns the maximum steering angle (in radians) of the steered wheels.
+    pub fn max_steering_angle(&self) -> f32 {
+        *self.max_steering_angle
+    }
+
+    /// Sets the throttle of the engine in `[-1.0; 1.0]` range. Negative values move the vehicle
+    /// backwards.
+    pub fn set_throttle(&mut self, throttle: f32) {
+        self.throttle = throttle.clamp(-1.0, 1.0);
+    }
+
+    /// Returns the throttle of the engine.
+    pub fn throttle(&self) -> f32 {
+        self.throttle
+    }
+
+    /// Sets the brake amount in `[0.0; 1.0]` range.
+    pub fn set_brake(&mut self, brake: f32) {
+        self.brake = brake.clamp(0.0, 1.0);
+    }
+
+    /// Returns the brake amount.
+    pub fn brake(&self) -> f32 {
+        self.brake
+    }
+
+    /// Sets the steering amount in `[-1.0; 1.0]` range. Positive values turn the vehicle left.
+    pub fn set_steering(&mut self, steering: f32) {
+        self.steering = steering.clamp(-1.0, 1.0);
+    }
+
+    /// Returns the steering amount.
+    pub fn steering(&self) -> f32 {
+        self.steering
+    }
+
+    /// Returns the forward speed of the vehicle calculated at the last update.
+    pub fn speed(&self) -> f32 {
+        self.speed
+    }
+
+    fn rebuild_native(&mut self, chassis: &RigidBody) {
+        let local_transform = self.local_transform().matrix();
+        let mut controller = DynamicRayCastVehicleController::new(chassis.native.get());
+        // Fyrox uses +Z as forward direction.
+        controller.index_up_axis = 1;
+        controller.index_forward_axis = 2;
+        for wheel in self.wheels.iter() {
+            let position = local_transform.transform_point(&Point3::from(wheel.position));
+            let direction = local_transform.transform_vector(&wheel.direction);
+            let axle = local_transform.transform_vector(&wheel.axle);
+            let native_wheel = controller.add_wheel(
+                position.coords.into(),
+                direction
+                    .try_normalize(f32::EPSILON)
+                    .unwrap_or_else(|| -Vector3::y())
+                    .into(),
+                axle.try_normalize(f32::EPSILON)
+                    .unwrap_or_else(Vector3::x)
+                    .into(),
+                wheel.suspension_rest_length,
+                wheel.radius,
+                &Default::default(),
+            );
+            native_wheel.suspension_stiffness = wheel.suspension_stiffness;
+            native_wheel.damping_compression = wheel.suspension_compression;
+            native_wheel.damping_relaxation = wheel.suspension_relaxation;
+            native_wheel.max_suspension_travel = wheel.max_suspension_travel;
+            native_wheel.max_suspension_force = wheel.max_suspension_force;
+            native_wheel.friction_slip = wheel.friction_slip;
+            native_wheel.side_friction_stiffness = wheel.side_friction_stiffness;
+        }
+        self.native = NativeVehicle {
+            controller: Some(controller),
+            local_transform,
+        };
+    }
+
+    fn wheel_pose(
+        wheel: &VehicleWheel,
+        state: &VehicleWheelState,
+    ) -> (Vector3<f32>, UnitQuaternion<f32>) {
+        let direction = wheel
+            .direction
+            .try_normalize(f32::EPSILON)
+            .unwrap_or_else(|| -Vector3::y());
+        let axle = wheel
+            .axle
+            .try_normalize(f32::EPSILON)
+            .unwrap_or_else(Vector3::x);
+        let position = wheel.position + direction * state.suspension_length;
+        let rotation =
+            UnitQuaternion::from_axis_angle(
+                &UnitVector3::new_unchecked(-direction),
+                state.steering,
+            ) * UnitQuaternion::from_axis_angle(&UnitVector3::new_unchecked(axle), state.rotation);
+        (position, rotation)
+    }
+}
+
+impl ConstructorProvider<Node, Graph> for Vehicle {
+    fn constructor() -> NodeConstructor {
+        NodeConstructor::new::<Self>()
+            .with_variant("Vehicle", |_| {
+                VehicleBuilder::new(BaseBuilder::new().with_name("Vehicle"))
+                    .build_node()
+                    .into()
+            })
+            .with_group("Physics")
+    }
+}
+
+impl NodeTrait for Vehicle {
+    fn local_bounding_box(&self) -> AxisAlignedBoundingBox {
+        self.base.local_bounding_box()
+    }
+
+    fn world_bounding_box(&self) -> AxisAlignedBoundingBox {
+        self.base.world_bounding_box()
+    }
+
+    fn id(&self) -> Uuid {
+        Self::type_uuid()
+    }
+
+    fn debug_draw(&self, ctx: &mut SceneDrawingContext) {
+        let transform = self.global_transform();
+        for (i, wheel) in self.wheels.iter().enumerate() {
+            let state = self
+                .wheel_states
+                .get(i)
+                .cloned()
+                .unwrap_or_else(|| VehicleWheelState {
+                    suspension_length: wheel.suspension_rest_length,
+                    ..Default::default()
+                });
+
+            let (center, rotation) = Self::wheel_pose(wheel, &state);
+            ctx.add_line(Line {
+                begin: transform
+                    .transform_point(&Point3::from(wheel.position))
+                    .coords,
+                end: transform.transform_point(&Point3::from(center)).coords,
+                color: Color::ORANGE,
+            });
+
+            // Circle is drawn in XY plane, rotate it to be perpendicular to the axle.
+            let wheel_transform = transform
+                * Matrix4::new_translation(&center)
+                * rotation.to_homogeneous()
+                * Matrix4::from_axis_angle(&Vector3::y_axis(), PI * 0.5);
+            let color = if state.in_contact {
+                Color::GREEN
+            } else {
+                Color::RED
+            };
+            ctx.draw_circle(Vector3::default(), wheel.radius, 16, wheel_transform, color);
+        }
+    }
+
+    fn update(&mut self, context: &mut UpdateContext) {
+        let Ok(chassis) = context
+            .nodes
+            .try_get_component_of_type::<RigidBody>(self.parent())
+        else {
+            return;
+        };
+
+        let chassis_changed = self
+            .native
+            .controller
+            .as_ref()
+            .is_none_or(|c| c.chassis != chassis.native.get());
+        let wheels_changed = self.wheels.try_sync_model(|_| {});
+        if chassis_changed
+            || wheels_changed
+            || self.native.local_transform != self.local_transform().matrix()
+        {
+            self.rebuild_native(chassis);
+        }
+
+        let max_engine_torque = *self.max_engine_torque;
+        let max_brake_force = *self.max_brake_force;
+        let max_steering_angle = *self.max_steering_angle;
+        let driven_count = self.wheels.iter().filter(|w| w.driven).count().max(1) as f32;
+        let (throttle, brake, steering, speed) =
+            (self.throttle, self.brake, self.steering, self.speed);
+
+        let Some(controller) = self.native.controller.as_mut() else {
+            return;
+        };
+
+        for (wheel, native_wheel) in self.wheels.iter().zip(controller.wheels_mut()) {
+            native_wheel.engine_force = if wheel.driven {
+                throttle * max_engine_torque / driven_count / wheel.radius.max(f32::EPSILON)
+            } else {
+                0.0
+            };
+            native_wheel.brake = brake * max_brake_force;
+            native_wheel.steering = if wheel.steered {
+                steering * max_steering_angle
+            } else {
+                0.0
+            };
+            native_wheel.friction_slip = wheel.friction_slip_at(speed);
+        }
+
+        // In deterministic mode a frame could contain no physics steps at all, keep the last state
+        // in this case.
+        let dt = context.physics.controller_dt(context.dt);
+        if dt > 0.0 {
+            self.speed = context
+                .physics
+                .update_vehicle(controller, dt, &mut self.wheel_states);
+        }
+
+        for (wheel, state) in self.wheels.iter().zip(self.wheel_states.iter()) {
+            if let Ok(visual) = context.nodes.try_get_mut(wheel.visual) {
+                let (position, rotation) = Self::wheel_pose(wheel, state);
+                visual
+                    .local_transform_mut()
+                    .set_position(position)
+                    .set_rotation(rotation);
+            }
+        }
+    }
+
+    fn validate(&self, scene: &Scene) -> Result<(), String> {
+        if scene
+            .graph
+            .try_get_of_type::<RigidBody>(self.parent())
+            .is_err()
+        {
+            return Err(
+                "Vehicle must be a direct child of a 3D Rigid Body node (chassis), \
+            otherwise it will not have any effect!"
+                    .to_string(),
+            );
+        }
+
+        for (i, wheel) in self.wheels.iter().enumerate() {
+            if wheel.visual.is_some()
+                && !scene
+                    .graph
+                    .try_get(wheel.visual)
+                    .is_ok_and(|v| v.parent() == self.handle())
+            {
+                return Err(format!(
+                    "Visual node of the wheel {i} must be a direct child of the vehicle!"
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Allows you to create vehicle in declarative manner.
+pub struct VehicleBuilder {
+    base_builder: BaseBuilder,
+    wheels: Vec<VehicleWheel>,
+    max_engine_torque: f32,
+    max_brake_force: f32,
+    max_steering_angle: f32,
+}
+
+impl VehicleBuilder {
+    /// Creates new vehicle builder.
+    pub fn new(base_builder: BaseBuilder) -> Self {
+        let default = Vehicle::default();
+        Self {
+            base_builder,
+            wheels: Default::default(),
+            max_engine_torque: *default.max_engine_torque,
+            max_brake_force: *default.max_brake_force,
+            max_steering_angle: *default.max_steering_angle,
+        }
+    }
+
+    /// Sets the desired wheels of the vehicle.
+    pub fn with_wheels(mut self, wheels: Vec<VehicleWheel>) -> Self {
+        self.wheels = wheels;
+        self
+    }
+
+    /// Sets the desired maximum torque of the engine.
+    pub fn with_max_engine_torque(mut self, torque: f32) -> Self {
+        self.max_engine_torque = torque;
+        self
+    }
+
+    /// Sets the desired maximum brake force.
+    pub fn with_max_brake_force(mut self, force: f32) -> Self {
+        self.max_brake_force = force;
+        self
+    }
+
+    /// Sets the desired maximum steering angle (in radians).
+    pub fn with_max_steering_angle(mut self, angle: f32) -> Self {
+        self.max_steering_angle = angle;
+        self
+    }
+
+    /// Creates vehicle node, but does not add it to a graph.
+    pub fn build_vehicle(self) -> Vehicle {
+        Vehicle {
+            base: self.base_builder.build_base(),
+            wheels: self.wheels.into(),
+            max_engine_torque: self.max_engine_torque.into(),
+            max_brake_force: self.max_brake_force.into(),
+            max_steering_angle: self.max_steering_angle.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Creates vehicle node, but does not add it to a graph.
+    pub fn build_node(self) -> Node {
+        Node::new(self.build_vehicle())
+    }
+
+    /// Creates vehicle node and adds it to the graph.
+    pub fn build(self, graph: &mut Graph) -> Handle<Vehicle> {
+        graph.add_node(self.build_node()).to_variant()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::algebra::{Vector2, Vector3},
+        scene::{
+            base::BaseBuilder,
+            collider::{ColliderBuilder, ColliderShape},
+            graph::Graph,
+            rigidbody::{RigidBodyBuilder, RigidBodyType},
+            transform::TransformBuilder,
+            vehicle::{VehicleBuilder, VehicleWheel},
+        },
+    };
+
+    #[test]
+    fn test_vehicle_stands_on_wheels() {
+        let mut graph = Graph::new();
+
+        let ground = ColliderBuilder::new(BaseBuilder::new())
+            .with_shape(ColliderShape::cuboid(50.0, 0.5, 50.0))
+            .build(&mut graph);
+        RigidBodyBuilder::new(BaseBuilder::new().with_child(ground))
+            .with_body_type(RigidBodyType::Static)
+            .build(&mut graph);
+
+        let wheels = [(-0.8, 1.2), (0.8, 1.2), (-0.8, -1.2), (0.8, -1.2)]
+            .into_iter()
+            .map(|(x, z)| {
+                VehicleWheel::new(Vector3::new(x, 0.0, z))
+                    .with_steered(z > 0.0)
+                    .with_driven(z < 0.0)
+            })
+            .collect::<Vec<_>>();
+        let vehicle = VehicleBuilder::new(BaseBuilder::new())
+            .with_wheels(wheels)
+            .build(&mut graph);
+        let body = ColliderBuilder::new(BaseBuilder::new())
+            .with_shape(ColliderShape::cuboid(1.0, 0.3, 2.0))
+            .build(&mut graph);
+        let chassis = RigidBodyBuilder::new(
+            BaseBuilder::new()
+                .with_children(&[body, vehicle.to_base()])
+                .with_local_transform(
+                    TransformBuilder::new()
+                        .with_local_position(Vector3::new(0.0, 1.5, 0.0))
+                        .build(),
+                ),
+        )
+        .with_body_type(RigidBodyType::Dynamic)
+        .build(&mut graph);
+
+        for _ in 0..180 {
+            graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+        }
+
+        let states = graph[vehicle].wheel_states();
+        assert_eq!(states.len(), 4);
+        assert!(states
+            .iter()
+            .all(|s| s.in_contact && s.ground == ground.to_base()));
+        // The chassis must be held above the ground by the suspension.
+        let height = graph[chassis].global_position().y;
+        assert!(height > 0.8, "{height}");
+    }
+}
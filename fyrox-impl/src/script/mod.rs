@@ -38,7 +38,12 @@ use crate::{
     event::Event,
     gui::UiContainer,
     plugin::{Plugin, PluginContainer},
-    scene::{base::NodeScriptMessage, node::Node, Scene},
+    scene::{
+        base::NodeScriptMessage,
+        graph::physics::{CollisionEvent, JointBreakEvent},
+        node::Node,
+        Scene,
+    },
 };
 use fyrox_core::pool::ObjectOrVariant;
 pub use fyrox_core_derive::ScriptMessagePayload;
@@ -657,6 +662,60 @@ pub trait ScriptTrait: BaseScript + ComponentProvider {
         Ok(())
     }
 
+    /// Called when a collider of the node (either the node itself or a child collider of a rigid
+    /// body) starts or stops touching another collider, or enters or leaves a sensor. `event.collider`
+    /// is the collider that belongs to the node, `event.other_collider` is the other one. The events
+    /// of a physics step are dispatched on the next update, after [`ScriptTrait::on_update`] is
+    /// called for every script. Works the same way for both 2D and 3D physics.
+    ///
+    /// ```rust
+    /// use fyrox_impl::{
+    ///     core::{reflect::prelude::*, visitor::prelude::*, type_traits::prelude::*},
+    ///     graph::SceneGraph,
+    ///     plugin::error::GameResult,
+    ///     scene::graph::physics::{CollisionEvent, CollisionEventKind},
+    ///     script::{ScriptContext, ScriptTrait},
+    /// };
+    ///
+    /// #[derive(Reflect, Visit, Debug, Clone, Default, TypeUuidProvider, ComponentProvider)]
+    /// #[type_uuid(id = "0b5d3b4f-5f0c-4b8c-a1f1-0a3f5d4c2e11")]
+    /// struct PickUp;
+    ///
+    /// impl ScriptTrait for PickUp {
+    ///     fn on_collision(
+    ///         &mut self,
+    ///         event: &CollisionEvent,
+    ///         ctx: &mut ScriptContext,
+    ///     ) -> GameResult {
+    ///         if event.kind == CollisionEventKind::SensorEntered {
+    ///             // Someone has picked up the item.
+    ///             ctx.scene.graph.remove_node(ctx.handle);
+    ///         }
+    ///         Ok(())
+    ///     }
+    /// }
+    /// ```
+    fn on_collision(
+        &mut self,
+        #[allow(unused_variables)] event: &CollisionEvent,
+        #[allow(unused_variables)] ctx: &mut ScriptContext,
+    ) -> GameResult {
+        Ok(())
+    }
+
+    /// Called when a joint breaks because the force or the torque it applied exceeded its break
+    /// threshold (see [`crate::scene::joint::Joint::set_break_force`]). The event is sent to the
+    /// scripts of the joint node and both rigid bodies connected by the joint. Just like
+    /// [`ScriptTrait::on_collision`], the events are dispatched on the next update, after
+    /// [`ScriptTrait::on_update`] is called for every script.
+    fn on_joint_break(
+        &mut self,
+        #[allow(unused_variables)] event: &JointBreakEvent,
+        #[allow(unused_variables)] ctx: &mut ScriptContext,
+    ) -> GameResult {
+        Ok(())
+    }
+
     /// Allows you to react to certain script messages. It could be used for communication between scripts; to
     /// bypass borrowing issues. If you need to receive messages of a particular type, you must subscribe to a type
     /// explicitly. Usually it is done in [`ScriptTrait::on_start`] method:
@@ -27,6 +27,7 @@ pub mod behavior;
 pub mod lightmap;
 pub mod navmesh;
 pub mod raw_mesh;
+pub mod simplify;
 pub mod uvgen;
 
 use crate::{
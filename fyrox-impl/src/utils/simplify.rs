@@ -0,0 +1,536 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Mesh simplification based on quadric error metrics. See [`simplify`] docs for more info.
+
+use crate::{
+    core::{
+        algebra::Vector3,
+        math::{aabb::AxisAlignedBoundingBox, TriangleDefinition},
+    },
+    scene::mesh::{
+        buffer::{TriangleBuffer, VertexAttributeUsage, VertexReadTrait},
+        surface::SurfaceData,
+    },
+};
+use fxhash::{FxHashMap, FxHashSet};
+use std::{cmp::Ordering, collections::BinaryHeap};
+
+/// A set of options for [`simplify`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct SimplificationOptions {
+    /// Desired ratio of triangles of the simplified mesh to the triangles of the source mesh. For
+    /// example, 0.25 means that the simplified mesh should have four times less triangles.
+    pub target_ratio: f32,
+    /// Maximum allowed geometric error of the simplification expressed as a fraction of the radius
+    /// of the mesh bounds. The error of a collapse is the root of the area-weighted mean squared
+    /// distance from the new position to the planes of the source triangles around it, so it does not
+    /// depend on the scale of the mesh. The simplification stops when the target ratio cannot be
+    /// reached without exceeding the error.
+    pub max_error: f32,
+}
+
+impl Default for SimplificationOptions {
+    fn default() -> Self {
+        Self {
+            target_ratio: 0.5,
+            max_error: 0.05,
+        }
+    }
+}
+
+/// Symmetric 4x4 matrix of a quadric, only the upper triangle is stored.
+#[derive(Copy, Clone, Default, Debug)]
+struct Quadric([f64; 10]);
+
+impl Quadric {
+    fn from_plane(normal: Vector3<f64>, d: f64, weight: f64) -> Self {
+        let (a, b, c) = (normal.x, normal.y, normal.z);
+        Self(
+            [
+                a * a,
+                a * b,
+                a * c,
+                a * d,
+                b * b,
+                b * c,
+                b * d,
+                c * c,
+                c * d,
+                d * d,
+            ]
+            .map(|v| v * weight),
+        )
+    }
+
+    fn add(&mut self, other: &Quadric) {
+        for (a, b) in self.0.iter_mut().zip(other.0.iter()) {
+            *a += *b;
+        }
+    }
+
+    fn sum(&self, other: &Quadric) -> Quadric {
+        let mut sum = *self;
+        sum.add(other);
+        sum
+    }
+
+    fn error(&self, p: &Vector3<f64>) -> f64 {
+        let [a2, ab, ac, ad, b2, bc, bd, c2, cd, d2] = self.0;
+        let (x, y, z) = (p.x, p.y, p.z);
+        a2 * x * x
+            + 2.0 * ab * x * y
+            + 2.0 * ac * x * z
+            + 2.0 * ad * x
+            + b2 * y * y
+            + 2.0 * bc * y * z
+            + 2.0 * bd * y
+            + c2 * z * z
+            + 2.0 * cd * z
+            + d2
+    }
+}
+
+/// A candidate collapse of all the vertices at one position (`from`) into another one (`to`).
+#[derive(Debug)]
+struct Collapse {
+    cost: f64,
+    from: usize,
+    to: usize,
+    from_version: u32,
+    to_version: u32,
+}
+
+impl PartialEq for Collapse {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Collapse {}
+
+impl PartialOrd for Collapse {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Collapse {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Reversed to make the binary heap a min-heap.
+        other.cost.total_cmp(&self.cost)
+    }
+}
+
+struct Simplifier {
+    // Every vertex is assigned to a class of vertices with the same position, this way seams (vertices
+    // with the same position, but different texture coordinates or normals) are kept intact.
+    class_of: Vec<usize>,
+    class_positions: Vec<Vector3<f64>>,
+    class_vertices: Vec<Vec<u32>>,
+    class_triangles: Vec<Vec<usize>>,
+    class_versions: Vec<u32>,
+    class_removed: Vec<bool>,
+    quadrics: Vec<Quadric>,
+    // Total area of the triangles, that contributed to the respective quadric. It is used to
+    // normalize the error of the quadric, which grows with the area.
+    quadric_areas: Vec<f64>,
+    triangles: Vec<[u32; 3]>,
+    triangle_alive: Vec<bool>,
+    heap: BinaryHeap<Collapse>,
+}
+
+impl Simplifier {
+    fn new(positions: &[Vector3<f32>], triangles: Vec<[u32; 3]>) -> Self {
+        let mut classes = FxHashMap::default();
+        let mut class_of = Vec::with_capacity(positions.len());
+        let mut class_positions = Vec::new();
+        let mut class_vertices: Vec<Vec<u32>> = Vec::new();
+        for (i, p) in positions.iter().enumerate() {
+            let key = [p.x.to_bits(), p.y.to_bits(), p.z.to_bits()];
+            let class = *classes.entry(key).or_insert_with(|| {
+                class_positions.push(p.cast::<f64>());
+                class_vertices.push(Vec::new());
+                class_positions.len() - 1
+            });
+            class_of.push(class);
+            class_vertices[class].push(i as u32);
+        }
+
+        let class_count = class_positions.len();
+        let mut this = Self {
+            class_of,
+            class_positions,
+            class_vertices,
+            class_triangles: vec![Vec::new(); class_count],
+            class_versions: vec![0; class_count],
+            class_removed: vec![false; class_count],
+            quadrics: vec![Quadric::default(); class_count],
+            quadric_areas: vec![0.0; class_count],
+            triangle_alive: vec![true; triangles.len()],
+            triangles,
+            heap: Default::default(),
+        };
+
+        let mut edges = FxHashMap::<(usize, usize), (u32, Vector3<f64>)>::default();
+        for (index, triangle) in this.triangles.iter().enumerate() {
+            let c = triangle.map(|i| this.class_of[i as usize]);
+            let [a, b, d] = c.map(|c| this.class_positions[c]);
+            let cross = (b - a).cross(&(d - a));
+            let double_area = cross.norm();
+            let normal = if double_area > f64::EPSILON {
+                cross / double_area
+            } else {
+                Vector3::default()
+            };
+            let area = double_area * 0.5;
+            let plane = Quadric::from_plane(normal, -normal.dot(&a), area);
+            for k in 0..3 {
+                this.quadrics[c[k]].add(&plane);
+                this.quadric_areas[c[k]] += area;
+                // Degenerated triangles may reference the same class twice.
+                if this.class_triangles[c[k]].last() != Some(&index) {
+                    this.class_triangles[c[k]].push(index);
+                }
+                let (e0, e1) = (c[k], c[(k + 1) % 3]);
+                if e0 == e1 {
+                    // Degenerated triangle.
+                    continue;
+                }
+                let entry = edges.entry((e0.min(e1), e0.max(e1))).or_insert((0, normal));
+                entry.0 += 1;
+            }
+        }
+
+        // Border edges get an additional perpendicular plane, that prevents the border from
+        // shrinking.
+        for (&(a, b), &(count, normal)) in edges.iter() {
+            if count == 1 {
+                let edge = this.class_positions[b] - this.class_positions[a];
+                let length_squared = edge.norm_squared();
+                let perpendicular = edge.cross(&normal);
+                let norm = perpendicular.norm();
+                if norm > f64::EPSILON {
+                    let perpendicular = perpendicular / norm;
+                    let plane = Quadric::from_plane(
+                        perpendicular,
+                        -perpendicular.dot(&this.class_positions[a]),
+                        length_squared * 100.0,
+                    );
+                    this.quadrics[a].add(&plane);
+                    this.quadrics[b].add(&plane);
+                }
+            }
+        }
+
+        for &(a, b) in edges.keys() {
+            this.push_collapse(a, b);
+            this.push_collapse(b, a);
+        }
+
+        this
+    }
+
+    fn push_collapse(&mut self, from: usize, to: usize) {
+        let area = self.quadric_areas[from] + self.quadric_areas[to];
+        let error = self.quadrics[from]
+            .sum(&self.quadrics[to])
+            .error(&self.class_positions[to])
+            .max(0.0);
+        let cost = if area > f64::EPSILON {
+            error / area
+        } else {
+            error
+        };
+        self.heap.push(Collapse {
+            cost,
+            from,
+            to,
+            from_version: self.class_versions[from],
+            to_version: self.class_versions[to],
+        });
+    }
+
+    fn triangle_classes(&self, triangle: usize) -> [usize; 3] {
+        self.triangles[triangle].map(|i| self.class_of[i as usize])
+    }
+
+    fn triangle_normal(&self, classes: [usize; 3], from: usize, to: usize) -> Vector3<f64> {
+        let [a, b, c] = classes.map(|c| {
+            if c == from {
+                self.class_positions[to]
+            } else {
+                self.class_positions[c]
+            }
+        });
+        (b - a).cross(&(c - a))
+    }
+
+    fn is_valid_collapse(&self, from: usize, to: usize) -> bool {
+        let mut shares_edge = false;
+        for &triangle in self.class_triangles[from].iter() {
+            if !self.triangle_alive[triangle] {
+                continue;
+            }
+            let classes = self.triangle_classes(triangle);
+            if classes.contains(&to) {
+                shares_edge = true;
+                continue;
+            }
+            // Reject collapses that flip or degenerate any of the remaining triangles.
+            let old = self.triangle_normal(classes, usize::MAX, usize::MAX);
+            if old.norm_squared() <= f64::EPSILON {
+                continue;
+            }
+            let new = self.triangle_normal(classes, from, to);
+            if new.norm_squared() <= f64::EPSILON * old.norm_squared() || old.dot(&new) <= 0.0 {
+                return false;
+            }
+        }
+        shares_edge
+    }
+
+    fn collapse(&mut self, from: usize, to: usize) -> usize {
+        // Each vertex of the removed class is replaced by a vertex of the target class, that shares
+        // an edge with it, so the attributes on both sides of a seam are preserved.
+        let mut remap = FxHashMap::default();
+        for &triangle in self.class_triangles[from].iter() {
+            if !self.triangle_alive[triangle] {
+                continue;
+            }
+            let vertices = self.triangles[triangle];
+            if let Some(target) = vertices.iter().find(|v| self.class_of[**v as usize] == to) {
+                for &v in vertices.iter() {
+                    if self.class_of[v as usize] == from {
+                        remap.entry(v).or_insert(*target);
+                    }
+                }
+            }
+        }
+        let fallback = self.class_vertices[to][0];
+
+        let mut removed = 0;
+        let triangles = std::mem::take(&mut self.class_triangles[from]);
+        for &triangle in triangles.iter() {
+            if !self.triangle_alive[triangle] {
+                continue;
+            }
+            if self.triangle_classes(triangle).contains(&to) {
+                self.triangle_alive[triangle] = false;
+                removed += 1;
+            } else {
+                for v in self.triangles[triangle].iter_mut() {
+                    if self.class_of[*v as usize] == from {
+                        *v = remap.get(v).copied().unwrap_or(fallback);
+                    }
+                }
+                self.class_triangles[to].push(triangle);
+            }
+        }
+
+        let quadric = self.quadrics[from];
+        self.quadrics[to].add(&quadric);
+        self.quadric_areas[to] += self.quadric_areas[from];
+        self.class_removed[from] = true;
+        self.class_versions[to] += 1;
+        self.class_triangles[to].retain(|t| self.triangle_alive[*t]);
+
+        let mut neighbours = FxHashSet::default();
+        for &triangle in self.class_triangles[to].iter() {
+            for class in self.triangle_classes(triangle) {
+                if class != to {
+                    neighbours.insert(class);
+                }
+            }
+        }
+        for neighbour in neighbours {
+            self.push_collapse(neighbour, to);
+            self.push_collapse(to, neighbour);
+        }
+
+        removed
+    }
+}
+
+/// Simplifies the given surface data using edge collapses driven by quadric error metrics (Garland and
+/// Heckbert, "Surface Simplification Using Quadric Error Metrics"). Every collapse moves all the vertices
+/// at one position to an adjacent position, so the vertices of the simplified mesh are always a subset of
+/// the source vertices and every vertex attribute (texture coordinates, normals, bone weights, etc.) is
+/// preserved as is. Borders of the mesh and attribute seams are preserved as well.
+///
+/// Blend shapes are not transferred to the simplified data.
+pub fn simplify(data: &SurfaceData, options: &SimplificationOptions) -> SurfaceData {
+    let positions = data
+        .vertex_buffer
+        .iter()
+        .map(|v| {
+            v.read_3_f32(VertexAttributeUsage::Position)
+                .unwrap_or_default()
+        })
+        .collect::<Vec<_>>();
+    let triangles = data
+        .geometry_buffer
+        .iter()
+        .map(|t| t.0)
+        .filter(|t| t.iter().all(|i| (*i as usize) < positions.len()))
+        .collect::<Vec<_>>();
+
+    let target = (triangles.len() as f32 * options.target_ratio.clamp(0.0, 1.0)).ceil() as usize;
+    if target >= triangles.len() {
+        return SurfaceData::new(data.vertex_buffer.clone(), data.geometry_buffer.clone());
+    }
+
+    let bounds = AxisAlignedBoundingBox::from_points(&positions);
+    let max_error = options.max_error.max(0.0) as f64 * bounds.half_extents().norm() as f64;
+    let max_cost = max_error * max_error;
+
+    let mut simplifier = Simplifier::new(&positions, triangles);
+    let mut alive = simplifier.triangles.len();
+    while alive > target {
+        let Some(collapse) = simplifier.heap.pop() else {
+            break;
+        };
+        if simplifier.class_removed[collapse.from]
+            || simplifier.class_removed[collapse.to]
+            || simplifier.class_versions[collapse.from] != collapse.from_version
+            || simplifier.class_versions[collapse.to] != collapse.to_version
+        {
+            // Outdated candidate.
+            continue;
+        }
+        if collapse.cost > max_cost {
+            break;
+        }
+        if !simplifier.is_valid_collapse(collapse.from, collapse.to) {
+            continue;
+        }
+        alive -= simplifier.collapse(collapse.from, collapse.to);
+    }
+
+    // Compact the vertex buffer, so it contains only the vertices used by the remaining triangles.
+    let vertex_size = data.vertex_buffer.vertex_size() as usize;
+    let raw_data = data.vertex_buffer.raw_data();
+    let mut vertex_buffer = data.vertex_buffer.clone_empty(alive * vertex_size);
+    let mut new_indices = vec![u32::MAX; positions.len()];
+    let mut vertex_count = 0;
+    let mut new_triangles = Vec::with_capacity(alive);
+    {
+        let mut vertex_buffer = vertex_buffer.modify();
+        for (triangle, _) in simplifier
+            .triangles
+            .iter()
+            .zip(simplifier.triangle_alive.iter())
+            .filter(|(_, alive)| **alive)
+        {
+            new_triangles.push(TriangleDefinition(triangle.map(|i| {
+                let new_index = &mut new_indices[i as usize];
+                if *new_index == u32::MAX {
+                    let offset = i as usize * vertex_size;
+                    vertex_buffer
+                        .push_vertex_raw(&raw_data[offset..offset + vertex_size])
+                        .unwrap();
+                    *new_index = vertex_count;
+                    vertex_count += 1;
+                }
+                *new_index
+            })));
+        }
+    }
+
+    SurfaceData::new(vertex_buffer, TriangleBuffer::new(new_triangles))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::algebra::Matrix4,
+        scene::mesh::surface::SurfaceData,
+        utils::simplify::{simplify, SimplificationOptions},
+    };
+
+    #[test]
+    fn test_simplify_sphere() {
+        let sphere = SurfaceData::make_sphere(32, 32, 1.0, &Matrix4::identity());
+        let source_triangles = sphere.geometry_buffer.len();
+
+        let simplified = simplify(
+            &sphere,
+            &SimplificationOptions {
+                target_ratio: 0.25,
+                max_error: 1.0,
+            },
+        );
+
+        let triangles = simplified.geometry_buffer.len();
+        assert!(triangles > 0);
+        assert!(triangles <= (source_triangles as f32 * 0.25).ceil() as usize);
+        assert!(simplified.vertex_buffer.vertex_count() < sphere.vertex_buffer.vertex_count());
+        assert!(simplified
+            .geometry_buffer
+            .iter()
+            .flat_map(|t| t.0)
+            .all(|i| i < simplified.vertex_buffer.vertex_count()));
+    }
+
+    #[test]
+    fn test_simplify_error_limit() {
+        let sphere = SurfaceData::make_sphere(16, 16, 1.0, &Matrix4::identity());
+
+        // Zero error allows only the collapses that do not change the shape.
+        let simplified = simplify(
+            &sphere,
+            &SimplificationOptions {
+                target_ratio: 0.1,
+                max_error: 0.0,
+            },
+        );
+        assert!(
+            simplified.geometry_buffer.len() > (sphere.geometry_buffer.len() as f32 * 0.1) as usize
+        );
+
+        let unchanged = simplify(
+            &sphere,
+            &SimplificationOptions {
+                target_ratio: 1.0,
+                max_error: 1.0,
+            },
+        );
+        assert_eq!(
+            unchanged.geometry_buffer.len(),
+            sphere.geometry_buffer.len()
+        );
+    }
+
+    #[test]
+    fn test_simplify_does_not_depend_on_scale() {
+        let options = SimplificationOptions {
+            target_ratio: 0.1,
+            max_error: 0.02,
+        };
+        let triangle_count = |radius: f32| {
+            let sphere = SurfaceData::make_sphere(32, 32, radius, &Matrix4::identity());
+            simplify(&sphere, &options).geometry_buffer.len()
+        };
+
+        let unit = triangle_count(1.0);
+        assert_eq!(triangle_count(0.125), unit);
+        assert_eq!(triangle_count(64.0), unit);
+    }
+}
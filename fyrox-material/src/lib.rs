@@ -742,6 +742,11 @@ impl Material {
         Self::from_shader(ShaderResource::standard_terrain())
     }
 
+    /// Creates new instance of standard foliage material.
+    pub fn standard_foliage() -> Self {
+        Self::from_shader(ShaderResource::standard_foliage())
+    }
+
     /// Creates new instance of standard tile material.
     pub fn standard_tile() -> Self {
         Self::from_shader(ShaderResource::standard_tile())
@@ -1161,6 +1166,19 @@ pub static STANDARD_TWOSIDES: LazyLock<BuiltInResource<Material>> = LazyLock::ne
     )
 });
 
+/// Standard foliage material. Keep in mind that this material is global, any modification
+/// of it will reflect on every other usage of it.
+pub static STANDARD_FOLIAGE: LazyLock<BuiltInResource<Material>> = LazyLock::new(|| {
+    BuiltInResource::new_no_source(
+        "Foliage Material",
+        MaterialResource::new_ok(
+            uuid!("6a0f3c1e-2b7d-4e58-8f94-1c2d3e4f5a6b"),
+            ResourceKind::External,
+            Material::from_shader(ShaderResource::standard_foliage()),
+        ),
+    )
+});
+
 /// Standard widget material. Keep in mind that this material is global, any modification
 /// of it will reflect on every other usage of it.
 pub static STANDARD_WIDGET: LazyLock<BuiltInResource<Material>> = LazyLock::new(|| {
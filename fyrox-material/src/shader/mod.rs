@@ -510,6 +510,9 @@ pub const STANDARD_SPRITE_SHADER_NAME: &str = "Sprite Shader";
 /// A name of the standard widget shader.
 pub const STANDARD_WIDGET_SHADER_NAME: &str = "Widget Shader";
 
+/// A name of the standard foliage shader.
+pub const STANDARD_FOLIAGE_SHADER_NAME: &str = "Foliage Shader";
+
 /// Internal state of the shader.
 ///
 /// # Notes
@@ -922,8 +925,11 @@ pub trait ShaderResourceExtension: Sized {
     /// Returns an instance of standard widget shader.
     fn standard_widget() -> Self;
 
+    /// Returns an instance of standard foliage shader.
+    fn standard_foliage() -> Self;
+
     /// Returns a list of standard shader.
-    fn standard_shaders() -> [&'static BuiltInResource<Shader>; 8];
+    fn standard_shaders() -> [&'static BuiltInResource<Shader>; 9];
 }
 
 impl ShaderResourceExtension for ShaderResource {
@@ -963,7 +969,11 @@ impl ShaderResourceExtension for ShaderResource {
         STANDARD_WIDGET.resource()
     }
 
-    fn standard_shaders() -> [&'static BuiltInResource<Shader>; 8] {
+    fn standard_foliage() -> Self {
+        STANDARD_FOLIAGE.resource()
+    }
+
+    fn standard_shaders() -> [&'static BuiltInResource<Shader>; 9] {
         [
             &STANDARD,
             &STANDARD_2D,
@@ -973,6 +983,7 @@ impl ShaderResourceExtension for ShaderResource {
             &STANDARD_TWOSIDES,
             &STANDARD_TILE,
             &STANDARD_WIDGET,
+            &STANDARD_FOLIAGE,
         ]
     }
 }
@@ -1082,6 +1093,22 @@ pub static STANDARD_TWOSIDES: LazyLock<BuiltInResource<Shader>> = LazyLock::new(
     )
 });
 
+/// Standard foliage shader. It is a two-sided alpha-tested shader with wind animation and
+/// distance-based dithered fading, that is used to render terrain foliage.
+pub static STANDARD_FOLIAGE: LazyLock<BuiltInResource<Shader>> = LazyLock::new(|| {
+    BuiltInResource::new(
+        STANDARD_FOLIAGE_SHADER_NAME,
+        embedded_data_source!("standard/foliage.shader"),
+        |data| {
+            ShaderResource::new_ok(
+                uuid!("b8a52e0b-6c43-4f1e-9d1a-3e6f0c5d7a24"),
+                ResourceKind::External,
+                Shader::from_string_bytes(data).unwrap(),
+            )
+        },
+    )
+});
+
 /// Standard widget shader.
 pub static STANDARD_WIDGET: LazyLock<BuiltInResource<Shader>> = LazyLock::new(|| {
     BuiltInResource::new(
@@ -0,0 +1,395 @@
+(
+    name: "FoliageShader",
+
+    resources: [
+        (
+            name: "diffuseTexture",
+            kind: Texture(kind: Sampler2D, fallback: White),
+            binding: 0
+        ),
+        (
+            name: "normalTexture",
+            kind: Texture(kind: Sampler2D, fallback: Normal),
+            binding: 1
+        ),
+        (
+            name: "roughnessTexture",
+            kind: Texture(kind: Sampler2D, fallback: White),
+            binding: 2
+        ),
+        (
+            name: "aoTexture",
+            kind: Texture(kind: Sampler2D, fallback: White),
+            binding: 3
+        ),
+        (
+            name: "properties",
+            kind: PropertyGroup([
+                (
+                    name: "texCoordScale",
+                    kind: Vector2(value: (1.0, 1.0)),
+                ),
+                (
+                    name: "layerIndex",
+                    kind: UInt(value: 0),
+                ),
+                (
+                    name: "diffuseColor",
+                    kind: Color(r: 255, g: 255, b: 255, a: 255),
+                ),
+                (
+                    // Normalized direction of the wind in XZ plane.
+                    name: "windDirection",
+                    kind: Vector2(value: (1.0, 0.0)),
+                ),
+                (
+                    // Displacement (in meters) per each meter of height of an instance.
+                    name: "windStrength",
+                    kind: Float(value: 0.05),
+                ),
+                (
+                    name: "windFrequency",
+                    kind: Float(value: 1.5),
+                ),
+                (
+                    // Time in seconds, it is set by the terrain every frame.
+                    name: "time",
+                    kind: Float(value: 0.0),
+                ),
+                (
+                    // Distance at which instances start to fade out.
+                    name: "fadeStart",
+                    kind: Float(value: 40.0),
+                ),
+                (
+                    // Distance at which instances are fully faded out.
+                    name: "fadeEnd",
+                    kind: Float(value: 50.0),
+                ),
+            ]),
+            binding: 0
+        ),
+        (
+            name: "fyrox_instanceData",
+            kind: PropertyGroup([
+                // Autogenerated
+            ]),
+            binding: 1
+        ),
+        (
+            name: "fyrox_cameraData",
+            kind: PropertyGroup([
+                // Autogenerated
+            ]),
+            binding: 2
+        ),
+        (
+            name: "fyrox_lightData",
+            kind: PropertyGroup([
+                // Autogenerated
+            ]),
+            binding: 3
+        ),
+    ],
+
+    passes: [
+        (
+            name: "GBuffer",
+            draw_parameters: DrawParameters(
+                cull_face: None,
+                color_write: ColorMask(
+                    red: true,
+                    green: true,
+                    blue: true,
+                    alpha: true,
+                ),
+                depth_write: true,
+                stencil_test: None,
+                depth_test: Some(Less),
+                blend: None,
+                stencil_op: StencilOp(
+                    fail: Keep,
+                    zfail: Keep,
+                    zpass: Keep,
+                    write_mask: 0xFFFF_FFFF,
+                ),
+                scissor_box: None
+            ),
+            vertex_shader:
+                r#"
+                layout(location = 0) in vec3 vertexPosition;
+                layout(location = 1) in vec2 vertexTexCoord;
+                layout(location = 2) in vec3 vertexNormal;
+                layout(location = 3) in vec4 vertexTangent;
+
+                out vec3 position;
+                out vec3 normal;
+                out vec2 texCoord;
+                out vec3 tangent;
+                out vec3 binormal;
+
+                void main()
+                {
+                    vec4 localPosition = vec4(vertexPosition, 1.0);
+                    vec3 worldPosition = (fyrox_instanceData.worldMatrix * localPosition).xyz;
+                    vec3 origin = fyrox_instanceData.worldMatrix[3].xyz;
+                    // Vertices are bent proportionally to their height above the root of the instance,
+                    // and every instance has its own phase, so the whole field does not move in sync.
+                    float bend = max(worldPosition.y - origin.y, 0.0);
+                    float phase = dot(origin.xz, vec2(0.37, 0.71));
+                    float sway = sin(properties.time * properties.windFrequency + phase)
+                        + 0.3 * sin(properties.time * properties.windFrequency * 2.7 + phase * 1.9);
+                    worldPosition.xz += properties.windDirection * (properties.windStrength * bend * sway);
+
+                    mat3 nm = mat3(fyrox_instanceData.worldMatrix);
+                    normal = normalize(nm * vertexNormal);
+                    tangent = normalize(nm * vertexTangent.xyz);
+                    binormal = normalize(vertexTangent.w * cross(normal, tangent));
+                    texCoord = vertexTexCoord * properties.texCoordScale;
+                    position = worldPosition;
+
+                    gl_Position = fyrox_cameraData.viewProjectionMatrix * vec4(worldPosition, 1.0);
+                }
+                "#,
+            fragment_shader:
+                r#"
+                layout(location = 0) out vec4 outColor;
+                layout(location = 1) out vec4 outNormal;
+                layout(location = 2) out vec4 outAmbient;
+                layout(location = 3) out vec4 outMaterial;
+                layout(location = 4) out uint outDecalMask;
+
+                in vec3 position;
+                in vec3 normal;
+                in vec2 texCoord;
+                in vec3 tangent;
+                in vec3 binormal;
+
+                // 4x4 ordered dithering matrix, used to fade out distant instances without blending.
+                const float ditherThresholds[16] = float[](
+                    0.0625, 0.5625, 0.1875, 0.6875,
+                    0.8125, 0.3125, 0.9375, 0.4375,
+                    0.25, 0.75, 0.125, 0.625,
+                    1.0, 0.5, 0.875, 0.375
+                );
+
+                void main()
+                {
+                    float distance = length(position - fyrox_cameraData.position);
+                    float fadeRange = max(properties.fadeEnd - properties.fadeStart, 0.0001);
+                    float visibility = 1.0 - clamp((distance - properties.fadeStart) / fadeRange, 0.0, 1.0);
+                    ivec2 ditherPosition = ivec2(gl_FragCoord.xy) % 4;
+                    if (visibility < ditherThresholds[ditherPosition.y * 4 + ditherPosition.x]) {
+                        discard;
+                    }
+
+                    outColor = properties.diffuseColor * texture(diffuseTexture, texCoord);
+
+                    // Alpha test.
+                    if (outColor.a < 0.5) {
+                        discard;
+                    }
+                    outColor.a = 1.0;
+
+                    // Foliage is two-sided, so the normal must face the camera.
+                    vec3 n = gl_FrontFacing ? normal : -normal;
+                    mat3 tangentSpace = mat3(tangent, binormal, n);
+                    vec4 tn = normalize(texture(normalTexture, texCoord) * 2.0 - 1.0);
+                    outNormal = vec4(normalize(tangentSpace * tn.xyz) * 0.5 + 0.5, 1.0);
+
+                    outMaterial.x = 0.0;
+                    outMaterial.y = texture(roughnessTexture, texCoord).r;
+                    outMaterial.z = texture(aoTexture, texCoord).r;
+                    outMaterial.a = 1.0;
+
+                    outAmbient = vec4(0.0, 0.0, 0.0, 1.0);
+
+                    outDecalMask = properties.layerIndex;
+                }
+                "#,
+        ),
+        (
+            name: "DirectionalShadow",
+
+            draw_parameters: DrawParameters (
+                cull_face: None,
+                color_write: ColorMask(
+                    red: false,
+                    green: false,
+                    blue: false,
+                    alpha: false,
+                ),
+                depth_write: true,
+                stencil_test: None,
+                depth_test: Some(Less),
+                blend: None,
+                stencil_op: StencilOp(
+                    fail: Keep,
+                    zfail: Keep,
+                    zpass: Keep,
+                    write_mask: 0xFFFF_FFFF,
+                ),
+                scissor_box: None
+            ),
+
+            vertex_shader:
+                r#"
+                layout(location = 0) in vec3 vertexPosition;
+                layout(location = 1) in vec2 vertexTexCoord;
+
+                out vec2 texCoord;
+
+                void main()
+                {
+                    vec4 localPosition = vec4(vertexPosition, 1.0);
+                    vec3 worldPosition = (fyrox_instanceData.worldMatrix * localPosition).xyz;
+                    vec3 origin = fyrox_instanceData.worldMatrix[3].xyz;
+                    // Vertices are bent proportionally to their height above the root of the instance,
+                    // and every instance has its own phase, so the whole field does not move in sync.
+                    float bend = max(worldPosition.y - origin.y, 0.0);
+                    float phase = dot(origin.xz, vec2(0.37, 0.71));
+                    float sway = sin(properties.time * properties.windFrequency + phase)
+                        + 0.3 * sin(properties.time * properties.windFrequency * 2.7 + phase * 1.9);
+                    worldPosition.xz += properties.windDirection * (properties.windStrength * bend * sway);
+
+                    gl_Position = fyrox_cameraData.viewProjectionMatrix * vec4(worldPosition, 1.0);
+                    texCoord = vertexTexCoord * properties.texCoordScale;
+                }
+                "#,
+
+            fragment_shader:
+                r#"
+                in vec2 texCoord;
+
+                void main()
+                {
+                    if (texture(diffuseTexture, texCoord).a < 0.5) discard;
+                }
+                "#,
+        ),
+        (
+            name: "SpotShadow",
+
+            draw_parameters: DrawParameters (
+                cull_face: None,
+                color_write: ColorMask(
+                    red: false,
+                    green: false,
+                    blue: false,
+                    alpha: false,
+                ),
+                depth_write: true,
+                stencil_test: None,
+                depth_test: Some(Less),
+                blend: None,
+                stencil_op: StencilOp(
+                    fail: Keep,
+                    zfail: Keep,
+                    zpass: Keep,
+                    write_mask: 0xFFFF_FFFF,
+                ),
+                scissor_box: None
+            ),
+
+            vertex_shader:
+                r#"
+                layout(location = 0) in vec3 vertexPosition;
+                layout(location = 1) in vec2 vertexTexCoord;
+
+                out vec2 texCoord;
+
+                void main()
+                {
+                    vec4 localPosition = vec4(vertexPosition, 1.0);
+                    vec3 worldPosition = (fyrox_instanceData.worldMatrix * localPosition).xyz;
+                    vec3 origin = fyrox_instanceData.worldMatrix[3].xyz;
+                    // Vertices are bent proportionally to their height above the root of the instance,
+                    // and every instance has its own phase, so the whole field does not move in sync.
+                    float bend = max(worldPosition.y - origin.y, 0.0);
+                    float phase = dot(origin.xz, vec2(0.37, 0.71));
+                    float sway = sin(properties.time * properties.windFrequency + phase)
+                        + 0.3 * sin(properties.time * properties.windFrequency * 2.7 + phase * 1.9);
+                    worldPosition.xz += properties.windDirection * (properties.windStrength * bend * sway);
+
+                    gl_Position = fyrox_cameraData.viewProjectionMatrix * vec4(worldPosition, 1.0);
+                    texCoord = vertexTexCoord * properties.texCoordScale;
+                }
+                "#,
+
+            fragment_shader:
+                r#"
+                in vec2 texCoord;
+
+                void main()
+                {
+                    if (texture(diffuseTexture, texCoord).a < 0.5) discard;
+                }
+                "#,
+        ),
+        (
+            name: "PointShadow",
+
+            draw_parameters: DrawParameters (
+                cull_face: None,
+                color_write: ColorMask(
+                    red: true,
+                    green: true,
+                    blue: true,
+                    alpha: true,
+                ),
+                depth_write: true,
+                stencil_test: None,
+                depth_test: Some(Less),
+                blend: None,
+                stencil_op: StencilOp(
+                    fail: Keep,
+                    zfail: Keep,
+                    zpass: Keep,
+                    write_mask: 0xFFFF_FFFF,
+                ),
+                scissor_box: None
+            ),
+
+            vertex_shader:
+                r#"
+                layout(location = 0) in vec3 vertexPosition;
+                layout(location = 1) in vec2 vertexTexCoord;
+
+                out vec2 texCoord;
+                out vec3 position;
+
+                void main()
+                {
+                    vec4 localPosition = vec4(vertexPosition, 1.0);
+                    vec3 worldPosition = (fyrox_instanceData.worldMatrix * localPosition).xyz;
+                    vec3 origin = fyrox_instanceData.worldMatrix[3].xyz;
+                    // Vertices are bent proportionally to their height above the root of the instance,
+                    // and every instance has its own phase, so the whole field does not move in sync.
+                    float bend = max(worldPosition.y - origin.y, 0.0);
+                    float phase = dot(origin.xz, vec2(0.37, 0.71));
+                    float sway = sin(properties.time * properties.windFrequency + phase)
+                        + 0.3 * sin(properties.time * properties.windFrequency * 2.7 + phase * 1.9);
+                    worldPosition.xz += properties.windDirection * (properties.windStrength * bend * sway);
+
+                    gl_Position = fyrox_cameraData.viewProjectionMatrix * vec4(worldPosition, 1.0);
+                    texCoord = vertexTexCoord * properties.texCoordScale;
+                    position = worldPosition;
+                }
+                "#,
+
+            fragment_shader:
+                r#"
+                in vec2 texCoord;
+                in vec3 position;
+
+                layout(location = 0) out float depth;
+
+                void main()
+                {
+                    if (texture(diffuseTexture, texCoord).a < 0.5) discard;
+                    depth = length(fyrox_lightData.lightPosition - position);
+                }
+                "#,
+        )
+    ],
+)
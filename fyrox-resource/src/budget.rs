@@ -0,0 +1,143 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Memory budgets for resources. See [`ResourceMemoryBudget`] docs for more info.
+
+use crate::core::{uuid::Uuid, TypeUuidProvider};
+use fxhash::FxHashMap;
+
+/// Memory budget for resources. When the memory usage of resources exceeds the budget, the
+/// resource manager evicts unused resources (the ones that are only cached by the resource manager
+/// itself) starting from the least recently used. If it is not enough, the resource manager
+/// downgrades the data of the least recently used resources (see [`crate::ResourceData::downgrade`]).
+///
+/// Memory usage of a resource is estimated by [`crate::ResourceData::memory_usage`].
+///
+/// ## Example
+///
+/// ```rust
+/// # use fyrox_resource::budget::ResourceMemoryBudget;
+/// # use fyrox_core::{uuid::{Uuid, uuid}, TypeUuidProvider};
+/// # struct Texture;
+/// # impl TypeUuidProvider for Texture {
+/// #     fn type_uuid() -> Uuid {
+/// #         uuid!("02c23a44-55fa-411a-bc39-eb7a5eadf15c")
+/// #     }
+/// # }
+/// // 2 GiB in total, 1 GiB for textures.
+/// let budget = ResourceMemoryBudget::default()
+///     .with_total(2 * 1024 * 1024 * 1024)
+///     .with_type_limit::<Texture>(1024 * 1024 * 1024);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ResourceMemoryBudget {
+    /// Maximum amount of memory (in bytes) that could be used by all resources. `None` means that
+    /// there's no limit.
+    pub total: Option<usize>,
+    /// Maximum amount of memory (in bytes) that could be used by resources of a particular type.
+    /// The key is a type uuid of the resource data.
+    pub per_type: FxHashMap<Uuid, usize>,
+}
+
+impl ResourceMemoryBudget {
+    /// Sets the maximum amount of memory (in bytes) that could be used by all resources.
+    pub fn with_total(mut self, total: usize) -> Self {
+        self.total = Some(total);
+        self
+    }
+
+    /// Sets the maximum amount of memory (in bytes) that could be used by resources of the given
+    /// type.
+    pub fn with_type_limit<T: TypeUuidProvider>(mut self, limit: usize) -> Self {
+        self.set_type_limit(T::type_uuid(), Some(limit));
+        self
+    }
+
+    /// Sets or removes the maximum amount of memory (in bytes) that could be used by resources
+    /// with the given type uuid.
+    pub fn set_type_limit(&mut self, type_uuid: Uuid, limit: Option<usize>) {
+        match limit {
+            Some(limit) => {
+                self.per_type.insert(type_uuid, limit);
+            }
+            None => {
+                self.per_type.remove(&type_uuid);
+            }
+        }
+    }
+
+    /// Returns the maximum amount of memory (in bytes) that could be used by resources with the
+    /// given type uuid.
+    pub fn type_limit(&self, type_uuid: Uuid) -> Option<usize> {
+        self.per_type.get(&type_uuid).cloned()
+    }
+
+    /// Returns `true` if the budget has no limits.
+    pub fn is_unlimited(&self) -> bool {
+        self.total.is_none() && self.per_type.is_empty()
+    }
+}
+
+/// Memory usage of resources.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ResourceMemoryUsage {
+    /// Total amount of memory (in bytes) used by all resources.
+    pub total: usize,
+    /// Amount of memory (in bytes) used by resources of a particular type. The key is a type uuid
+    /// of the resource data.
+    pub per_type: FxHashMap<Uuid, usize>,
+}
+
+impl ResourceMemoryUsage {
+    /// Returns the amount of memory (in bytes) used by resources with the given type uuid.
+    pub fn of_type(&self, type_uuid: Uuid) -> usize {
+        self.per_type.get(&type_uuid).cloned().unwrap_or_default()
+    }
+
+    pub(crate) fn add(&mut self, type_uuid: Uuid, size: usize) {
+        self.total += size;
+        *self.per_type.entry(type_uuid).or_default() += size;
+    }
+
+    pub(crate) fn remove(&mut self, type_uuid: Uuid, size: usize) {
+        self.total = self.total.saturating_sub(size);
+        if let Some(usage) = self.per_type.get_mut(&type_uuid) {
+            *usage = usage.saturating_sub(size);
+        }
+    }
+
+    /// Returns `true` if the resources of the given type uuid exceed the budget, either their own
+    /// limit or the total one.
+    pub fn exceeds(&self, budget: &ResourceMemoryBudget, type_uuid: Uuid) -> bool {
+        budget.total.is_some_and(|total| self.total > total)
+            || budget
+                .type_limit(type_uuid)
+                .is_some_and(|limit| self.of_type(type_uuid) > limit)
+    }
+
+    /// Returns `true` if the memory usage is within the budget.
+    pub fn is_within(&self, budget: &ResourceMemoryBudget) -> bool {
+        budget.total.is_none_or(|total| self.total <= total)
+            && budget
+                .per_type
+                .iter()
+                .all(|(type_uuid, limit)| self.of_type(*type_uuid) <= *limit)
+    }
+}
@@ -0,0 +1,244 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Derived-data cache for imported assets. See [`DerivedDataCache`] docs for more info.
+
+use crate::{
+    core::{io::FileError, log::Log},
+    io::ResourceIo,
+};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::{
+    fmt::{Display, Formatter},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+/// A key of an entry in [`DerivedDataCache`]. It is a cryptographic hash (SHA-256 truncated to 128
+/// bits) of everything that affects the output of an importer: the content of the source file, the
+/// name and the version of the importer and the import options. The size of the source file is
+/// stored alongside the hash and verified on every access to the cache.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct DerivedDataKey {
+    /// Truncated hash of the source file, the importer and the import options.
+    pub hash: [u8; 16],
+    /// Size of the source file in bytes.
+    pub source_size: u64,
+}
+
+impl Display for DerivedDataKey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for byte in self.hash {
+            write!(f, "{byte:02x}")?;
+        }
+        Ok(())
+    }
+}
+
+impl DerivedDataKey {
+    const HEADER_SIZE: usize = 16 + size_of::<u64>();
+
+    /// Creates a new key from the content of the source file, the name and the version of the
+    /// importer and serialized import options. The version of the importer must be bumped every
+    /// time when the output of the importer changes, otherwise stale data will be loaded from the
+    /// cache.
+    pub fn new(
+        source: &[u8],
+        importer: &str,
+        importer_version: u32,
+        import_options: &[u8],
+    ) -> Self {
+        let mut hasher = Sha256::new();
+        // Every variable-length part is prefixed with its length, so different combinations of
+        // the parts cannot produce the same input for the hasher.
+        for part in [source, importer.as_bytes(), import_options] {
+            hasher.update((part.len() as u64).to_le_bytes());
+            hasher.update(part);
+        }
+        hasher.update(importer_version.to_le_bytes());
+        let digest = hasher.finalize();
+        let mut hash = [0; 16];
+        hash.copy_from_slice(&digest[..16]);
+        Self {
+            hash,
+            source_size: source.len() as u64,
+        }
+    }
+
+    /// Same as [`Self::new`], but serializes the given import options by itself.
+    pub fn with_options<T: Serialize>(
+        source: &[u8],
+        importer: &str,
+        importer_version: u32,
+        import_options: &T,
+    ) -> Self {
+        let options = ron::ser::to_string(import_options).unwrap_or_default();
+        Self::new(source, importer, importer_version, options.as_bytes())
+    }
+
+    fn to_header_bytes(self) -> [u8; Self::HEADER_SIZE] {
+        let mut bytes = [0; Self::HEADER_SIZE];
+        bytes[..16].copy_from_slice(&self.hash);
+        bytes[16..].copy_from_slice(&self.source_size.to_le_bytes());
+        bytes
+    }
+}
+
+/// Derived-data cache stores processed output of asset importers (for example, compressed textures
+/// with generated mip maps or scenes imported from FBX files), so unchanged assets could be loaded
+/// from the cache instantly instead of being imported from their source files again.
+///
+/// Every entry of the cache is keyed by [`DerivedDataKey`], which includes the content of the source
+/// file, the version of the importer and the import options. This means that the cache does not need
+/// any explicit invalidation, a change of the source file, the options or the importer simply leads
+/// to a different key. Stale entries could be removed by [`Self::clear`].
+///
+/// The cache is stored in a folder next to the resource registry, see [`Self::FOLDER_NAME`]. It is
+/// read-only if the resource IO does not support writing.
+#[derive(Clone)]
+pub struct DerivedDataCache {
+    folder: PathBuf,
+    io: Arc<dyn ResourceIo>,
+}
+
+impl DerivedDataCache {
+    /// Default name of the folder with the cache.
+    pub const FOLDER_NAME: &'static str = "derived_data";
+
+    /// Extension of cache entry files.
+    pub const EXTENSION: &'static str = "ddc";
+
+    const MAGIC: &'static [u8; 8] = b"FYRXDDC2";
+
+    /// Creates a new cache that stores its entries in the given folder.
+    pub fn new(folder: impl Into<PathBuf>, io: Arc<dyn ResourceIo>) -> Self {
+        Self {
+            folder: folder.into(),
+            io,
+        }
+    }
+
+    /// Returns the folder with the cache entries.
+    pub fn folder(&self) -> &Path {
+        &self.folder
+    }
+
+    fn entry_path(&self, key: DerivedDataKey) -> PathBuf {
+        self.folder.join(format!("{key}.{}", Self::EXTENSION))
+    }
+
+    /// Tries to fetch the data for the given key. Returns `None` if there's no such entry or if the
+    /// entry is corrupted.
+    pub async fn get(&self, key: DerivedDataKey) -> Option<Vec<u8>> {
+        let data = self.io.load_file(&self.entry_path(key)).await.ok()?;
+        let header_len = Self::MAGIC.len() + DerivedDataKey::HEADER_SIZE;
+        if data.len() < header_len
+            || &data[..Self::MAGIC.len()] != Self::MAGIC
+            || data[Self::MAGIC.len()..header_len] != key.to_header_bytes()
+        {
+            Log::warn(format!(
+                "Derived data cache entry {key} is corrupted and will be ignored."
+            ));
+            return None;
+        }
+        Some(data[header_len..].to_vec())
+    }
+
+    /// Puts the data to the cache. Does nothing if the resource IO does not support writing.
+    pub async fn put(&self, key: DerivedDataKey, data: &[u8]) -> Result<(), FileError> {
+        if !self.io.can_write() {
+            return Ok(());
+        }
+        self.io.create_dir_all_sync(&self.folder)?;
+        let mut entry =
+            Vec::with_capacity(Self::MAGIC.len() + DerivedDataKey::HEADER_SIZE + data.len());
+        entry.extend_from_slice(Self::MAGIC);
+        entry.extend_from_slice(&key.to_header_bytes());
+        entry.extend_from_slice(data);
+        self.io.write_file(&self.entry_path(key), entry).await
+    }
+
+    /// Removes an entry with the given key from the cache.
+    pub async fn remove(&self, key: DerivedDataKey) -> Result<(), FileError> {
+        self.io.delete_file(&self.entry_path(key)).await
+    }
+
+    /// Removes all entries from the cache.
+    pub async fn clear(&self) -> Result<(), FileError> {
+        if !self.io.is_dir(&self.folder).await {
+            return Ok(());
+        }
+        for path in self.io.read_directory(&self.folder).await? {
+            if path.extension().is_some_and(|ext| ext == Self::EXTENSION) {
+                self.io.delete_file(&path).await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        cache::{DerivedDataCache, DerivedDataKey},
+        io::FsResourceIo,
+    };
+    use fyrox_core::futures::executor::block_on;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_derived_data_cache() {
+        let folder = std::env::temp_dir().join("fyrox_derived_data_cache_test");
+        let _ = std::fs::remove_dir_all(&folder);
+        let cache = DerivedDataCache::new(&folder, Arc::new(FsResourceIo));
+
+        let key = DerivedDataKey::with_options(b"source", "Importer", 1, &(1, "options"));
+        assert_ne!(
+            key,
+            DerivedDataKey::with_options(b"source", "Importer", 2, &(1, "options"))
+        );
+        assert_ne!(
+            key,
+            DerivedDataKey::with_options(b"source", "Importer", 1, &(2, "options"))
+        );
+        assert_ne!(
+            key,
+            DerivedDataKey::with_options(b"changed", "Importer", 1, &(1, "options"))
+        );
+
+        assert_eq!(key.source_size, 6);
+        assert_eq!(key.to_string().len(), 32);
+
+        assert!(block_on(cache.get(key)).is_none());
+        block_on(cache.put(key, b"processed")).unwrap();
+        assert_eq!(block_on(cache.get(key)).as_deref(), Some(&b"processed"[..]));
+
+        // An entry with the same hash, but with a different source size must be rejected.
+        let mismatched = DerivedDataKey {
+            source_size: 7,
+            ..key
+        };
+        assert!(block_on(cache.get(mismatched)).is_none());
+
+        block_on(cache.clear()).unwrap();
+        assert!(block_on(cache.get(key)).is_none());
+    }
+}
@@ -37,6 +37,9 @@ pub struct TimedEntry<T> {
     pub value: T,
     /// Time to live in seconds.
     pub time_to_live: f32,
+    /// Time (in seconds) at which the value was used for the last time. It is used to find the
+    /// least recently used entries.
+    pub last_used: f64,
 }
 
 impl<T> Deref for TimedEntry<T> {
@@ -61,6 +64,7 @@ where
         Self {
             value: Default::default(),
             time_to_live: DEFAULT_RESOURCE_LIFETIME,
+            last_used: 0.0,
         }
     }
 }
@@ -73,6 +77,7 @@ where
         Self {
             value: self.value.clone(),
             time_to_live: self.time_to_live,
+            last_used: self.last_used,
         }
     }
 }
@@ -114,10 +119,12 @@ mod test {
         let t = TimedEntry {
             value: 42,
             time_to_live: 15.0,
+            last_used: 2.0,
         };
         let t2 = t.clone();
 
         assert_eq!(t.value, t2.value);
         assert_eq!(t.time_to_live, t2.time_to_live);
+        assert_eq!(t.last_used, t2.last_used);
     }
 }
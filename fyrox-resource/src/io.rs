@@ -214,7 +214,11 @@ pub fn normalize_path(path: impl AsRef<Path>) -> Result<PathBuf, FileError> {
             Component::CurDir => {}
             Component::ParentDir => {
                 if !ret.pop() {
-                    panic!("Path may not start with ..");
+                    return Err(std::io::Error::new(
+                        std::io::ErrorKind::InvalidInput,
+                        format!("Path goes outside of the root: {:?}", path.as_ref()),
+                    )
+                    .into());
                 }
             }
             Component::Normal(c) => {
@@ -438,6 +442,8 @@ mod test {
         assert_eq!(normalize_path(&path).unwrap().as_os_str(), "alpha/beta");
         let path = PathBuf::from("alpha/..");
         assert_eq!(normalize_path(&path).unwrap().as_os_str(), ".");
+        let path = PathBuf::from("alpha/../..");
+        assert!(normalize_path(&path).is_err());
     }
     #[test]
     fn test_canonicalize() {
@@ -20,7 +20,7 @@
 
 //! Resource management
 
-#![forbid(unsafe_code)]
+#![deny(unsafe_code)]
 #![allow(clippy::doc_lazy_continuation)]
 #![allow(clippy::mutable_key_type)]
 #![warn(missing_docs)]
@@ -55,7 +55,9 @@ use std::{
     task::{Context, Poll},
 };
 
+pub mod budget;
 pub mod builtin;
+pub mod cache;
 pub mod constructor;
 pub mod entry;
 pub mod event;
@@ -65,9 +67,13 @@ pub mod loader;
 pub mod manager;
 pub mod metadata;
 pub mod options;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod pack;
+pub mod queue;
 pub mod registry;
 pub mod state;
 pub mod untyped;
+pub mod vfs;
 
 /// A trait for resource data.
 pub trait ResourceData: Debug + Visit + Send + Reflect {
@@ -90,6 +96,21 @@ pub trait ResourceData: Debug + Visit + Send + Reflect {
     /// Tries to clone the resource data. This method can return `None` if the underlying type is
     /// non-cloneable.
     fn try_clone_box(&self) -> Option<Box<dyn ResourceData>>;
+
+    /// Returns an estimated amount of memory (in bytes) used by the resource data. It is used by
+    /// the resource manager to keep memory usage within a budget (see [`budget::ResourceMemoryBudget`]).
+    /// Default implementation returns zero, which means that the resource is not accounted.
+    fn memory_usage(&self) -> usize {
+        0
+    }
+
+    /// Tries to reduce memory usage of the resource data by lowering its quality, for example a
+    /// texture could drop its most detailed mip level. Returns `true` if the data was downgraded,
+    /// `false` if it cannot be downgraded any further. The resource manager calls this method when
+    /// a memory budget is exceeded and there are no unused resources left to evict.
+    fn downgrade(&mut self) -> bool {
+        false
+    }
 }
 
 /// Extension trait for a resource data of a particular type, which adds additional functionality,
@@ -69,12 +69,12 @@ where
             let data = io.load_file(&src_path).await?;
             match Visitor::detect_format_from_slice(&data) {
                 Format::Unknown => Err(FileError::Custom("Unknown format!".to_string())),
-                Format::Binary => {
-                    // Copy the binary format as-is.
+                Format::Binary | Format::IndexedBinary => {
+                    // Copy the binary formats as-is.
                     Ok(io.copy_file(&src_path, &dest_path).await?)
                 }
-                Format::Ascii => {
-                    // Resave the ascii format as binary.
+                Format::Ascii | Format::Ron => {
+                    // Resave the text formats as binary.
                     let visitor = Visitor::load_from_memory(&data).map_err(|err| {
                         FileError::Custom(format!(
                             "Unable to load {}. Reason: {err}",
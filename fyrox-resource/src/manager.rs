@@ -23,6 +23,8 @@
 
 pub use crate::builtin::*;
 use crate::{
+    budget::{ResourceMemoryBudget, ResourceMemoryUsage},
+    cache::DerivedDataCache,
     constructor::ResourceConstructorContainer,
     core::{
         append_extension, err,
@@ -42,18 +44,21 @@ use crate::{
     loader::{ResourceLoader, ResourceLoadersContainer},
     metadata::ResourceMetadata,
     options::OPTIONS_EXTENSION,
+    queue::{LoadPriority, LoadQueue},
     registry::{RegistryUpdate, ResourceRegistry, ResourceRegistryRefMut},
     state::{LoadError, ResourceDataWrapper, ResourceState},
     untyped::ResourceKind,
+    vfs::{LayeredResourceIo, ResourceIoLayer},
     Resource, TypedResourceData, UntypedResource,
 };
-use fxhash::FxHashSet;
+use fxhash::{FxHashMap, FxHashSet};
 use fyrox_core::{
     futures::executor::block_on, make_relative_path, notify::Event, ok_or_return, some_or_continue,
     some_or_return,
 };
 use std::{
     fmt::{Debug, Display, Formatter},
+    future::Future,
     io::Error,
     marker::PhantomData,
     path::{Path, PathBuf},
@@ -61,9 +66,10 @@ use std::{
     time::Duration,
 };
 
-/// A set of resources that can be waited for.
+/// A set of resources that can be waited for. It could also be used as a load group for loading
+/// screens, see [`ResourceManager::request_in_group`] for more info.
 #[must_use]
-#[derive(Default)]
+#[derive(Default, Clone)]
 pub struct ResourceWaitContext {
     resources: Vec<UntypedResource>,
 }
@@ -79,6 +85,58 @@ impl ResourceWaitContext {
         }
         true
     }
+
+    /// Adds a new resource to the context. Does nothing if the resource is already in the context.
+    pub fn add(&mut self, resource: UntypedResource) {
+        if !self.resources.contains(&resource) {
+            self.resources.push(resource);
+        }
+    }
+
+    /// Returns a slice of all resources in the context.
+    pub fn resources(&self) -> &[UntypedResource] {
+        &self.resources
+    }
+
+    /// Returns total amount of resources in the context.
+    pub fn len(&self) -> usize {
+        self.resources.len()
+    }
+
+    /// Returns `true` if the context has no resources.
+    pub fn is_empty(&self) -> bool {
+        self.resources.is_empty()
+    }
+
+    /// Returns total amount of resources that were loaded successfully.
+    pub fn count_loaded(&self) -> usize {
+        self.resources.iter().filter(|r| r.is_ok()).count()
+    }
+
+    /// Returns total amount of resources that failed to load.
+    pub fn count_failed(&self) -> usize {
+        self.resources
+            .iter()
+            .filter(|r| r.is_failed_to_load())
+            .count()
+    }
+
+    /// Returns loading progress of the resources in percents in `[0; 100]` range. Failed resources
+    /// are counted as finished, so the progress always reaches 100%. Empty context has 100% progress.
+    pub fn progress(&self) -> usize {
+        let finished = self.resources.iter().filter(|r| !r.is_loading()).count();
+        (finished * 100)
+            .checked_div(self.resources.len())
+            .unwrap_or(100)
+    }
+
+    /// Returns a future that resolves when all resources are loaded (or failed to load).
+    pub fn wait(&self) -> impl Future<Output = ()> {
+        let resources = self.resources.clone();
+        async move {
+            join_all(resources).await;
+        }
+    }
 }
 
 /// Internal state of the resource manager.
@@ -100,6 +158,10 @@ pub struct ResourceManagerState {
     resources: Vec<TimedEntry<UntypedResource>>,
     task_pool: Arc<TaskPool>,
     watcher: Option<FileSystemWatcher>,
+    memory_budget: ResourceMemoryBudget,
+    time: f64,
+    load_queue: LoadQueue,
+    load_groups: FxHashMap<String, ResourceWaitContext>,
 }
 
 /// Resource manager controls loading and lifetime of resource in the engine. Resource manager can hold
@@ -422,6 +484,20 @@ impl ResourceManager {
         self.state().registry_folder()
     }
 
+    /// Creates a derived-data cache that is stored in a folder next to the resource registry and
+    /// uses the resource IO of this manager. See [`DerivedDataCache`] docs for more info.
+    pub fn derived_data_cache(&self) -> DerivedDataCache {
+        let state = self.state();
+        let folder = state
+            .resource_registry
+            .safe_lock()
+            .path()
+            .parent()
+            .map(|folder| folder.join(DerivedDataCache::FOLDER_NAME))
+            .unwrap_or_else(|| PathBuf::from(DerivedDataCache::FOLDER_NAME));
+        DerivedDataCache::new(folder, state.resource_io.clone())
+    }
+
     /// Registers a new built-in resource, so it becomes accessible via [`Self::request`].
     pub fn register_built_in_resource<T: TypedResourceData>(
         &self,
@@ -741,6 +817,94 @@ impl ResourceManager {
         self.state().uuid_to_resource_path(resource_uuid)
     }
 
+    /// Same as [`Self::request`], but with the given load priority. Resources with higher priority
+    /// are loaded first, when there are more pending loads than the task pool can handle at once.
+    /// If the resource is already waiting for its load, its priority is raised to the given one.
+    /// See [`LoadPriority`] docs for more info.
+    ///
+    /// Keep in mind, that a pending load is cancelled if all handles to the resource (except the
+    /// one held by the resource manager) were dropped before the load has started. Such resource
+    /// returns to [`ResourceState::Unloaded`] state and will be loaded on the next request.
+    ///
+    /// ## Panic
+    ///
+    /// This method will panic, if type UUID of `T` does not match the actual type UUID of the resource.
+    pub fn request_with_priority<T>(
+        &self,
+        path: impl AsRef<Path>,
+        priority: LoadPriority,
+    ) -> Resource<T>
+    where
+        T: TypedResourceData,
+    {
+        let untyped = self.state().request_with_priority(path.as_ref(), priority);
+        if let Some(type_uuid) = untyped.type_uuid_non_blocking() {
+            let needed_type_uuid = <T as TypeUuidProvider>::type_uuid();
+            if type_uuid != needed_type_uuid {
+                panic!(
+                    "Unable to get a resource of type {needed_type_uuid} from {:?}! The resource \
+                    has data of type {type_uuid}!",
+                    path.as_ref()
+                );
+            }
+        }
+        Resource {
+            untyped,
+            phantom: PhantomData::<T>,
+        }
+    }
+
+    /// Requests a resource with the given priority and adds it to a load group with the given name.
+    /// Load groups are useful for loading screens: all resources of a level could be requested in
+    /// a single group, and then the progress of the group could be displayed by using
+    /// [`Self::load_group_progress`] and the completion could be awaited by using
+    /// [`Self::load_group`] and [`ResourceWaitContext::wait`]. Call [`Self::remove_load_group`]
+    /// when the group is no longer needed, because it holds strong references to its resources.
+    ///
+    /// ## Example
+    ///
+    /// ```rust,no_run
+    /// # use fyrox_resource::{manager::ResourceManager, queue::LoadPriority};
+    /// async fn load_level(resource_manager: &ResourceManager) {
+    ///     for path in ["level.rgs", "music.ogg", "sky.png"] {
+    ///         resource_manager.request_in_group("level", path, LoadPriority::High);
+    ///     }
+    ///
+    ///     if let Some(group) = resource_manager.load_group("level") {
+    ///         group.wait().await;
+    ///     }
+    ///
+    ///     resource_manager.remove_load_group("level");
+    /// }
+    /// ```
+    pub fn request_in_group(
+        &self,
+        group: &str,
+        path: impl AsRef<Path>,
+        priority: LoadPriority,
+    ) -> UntypedResource {
+        let mut state = self.state();
+        let resource = state.request_with_priority(path, priority);
+        state.add_to_load_group(group, resource.clone());
+        resource
+    }
+
+    /// Returns a copy of a load group with the given name. See [`Self::request_in_group`] for more
+    /// info.
+    pub fn load_group(&self, group: &str) -> Option<ResourceWaitContext> {
+        self.state().load_group(group).cloned()
+    }
+
+    /// Returns loading progress of a load group with the given name in percents in `[0; 100]` range.
+    pub fn load_group_progress(&self, group: &str) -> Option<usize> {
+        self.state().load_group_progress(group)
+    }
+
+    /// Removes a load group with the given name and returns it.
+    pub fn remove_load_group(&self, group: &str) -> Option<ResourceWaitContext> {
+        self.state().remove_load_group(group)
+    }
+
     /// Same as [`Self::request`], but returns untyped resource.
     pub fn request_untyped<P>(&self, path: P) -> UntypedResource
     where
@@ -753,7 +917,8 @@ impl ResourceManager {
     /// an existing one. Some platforms do not have a file system, so the registry must be prepared
     /// on a platform that **does** have it and then saved to be loaded later on. For example,
     /// WebAssembly platform does not have a file system and the resource manager will try to load
-    /// an existing registry instead of updating it.
+    /// an existing registry instead of updating it. The same applies to read-only resource IO
+    /// (for example, [`crate::pack::PackedResourceIo`]) on any platform.
     pub fn update_or_load_registry(&self) {
         self.state().update_or_load_registry();
     }
@@ -861,6 +1026,45 @@ impl ResourceManager {
         join_all(resources).await;
     }
 
+    /// Mounts a new layer in the given layered resource IO and reloads all the loaded resources whose
+    /// files are affected by the new layer. The resource IO should be the one used by this resource
+    /// manager. Returns a list of affected files. Newly added files are not registered automatically,
+    /// call [`Self::update_or_load_registry`] if the layer adds new resources.
+    pub async fn mount_layer(
+        &self,
+        io: &LayeredResourceIo,
+        layer: ResourceIoLayer,
+    ) -> Vec<PathBuf> {
+        let affected = io.mount(layer).await;
+        self.reload_resources_at(&affected);
+        affected
+    }
+
+    /// Unmounts a layer with the given name from the given layered resource IO and reloads all the
+    /// loaded resources whose files were provided by the layer. Returns the unmounted layer and a
+    /// list of affected files, or `None` if there's no such layer.
+    pub async fn unmount_layer(
+        &self,
+        io: &LayeredResourceIo,
+        name: &str,
+    ) -> Option<(ResourceIoLayer, Vec<PathBuf>)> {
+        let (layer, affected) = io.unmount(name).await?;
+        self.reload_resources_at(&affected);
+        Some((layer, affected))
+    }
+
+    fn reload_resources_at(&self, paths: &[PathBuf]) {
+        let mut state = self.state();
+        for path in paths {
+            if state.try_reload_resource_from_path(path) {
+                info!(
+                    "Reloading {} resource, because its source has changed.",
+                    path.display()
+                );
+            }
+        }
+    }
+
     /// Checks if there's a loader for the given resource path.
     pub fn is_supported_resource(&self, path: &Path) -> bool {
         self.state().is_supported_resource(path)
@@ -930,6 +1134,10 @@ impl ResourceManagerState {
             event_broadcaster: Default::default(),
             constructors_container: Default::default(),
             watcher: None,
+            memory_budget: Default::default(),
+            time: 0.0,
+            load_queue: Default::default(),
+            load_groups: Default::default(),
             built_in_resources: Default::default(),
             resource_registry: Arc::new(Mutex::new(ResourceRegistry::new(io.clone()))),
             task_pool,
@@ -941,7 +1149,8 @@ impl ResourceManagerState {
     /// an existing one. Some platforms do not have a file system, so the registry must be prepared
     /// on a platform that **does** have it and then saved to be loaded later on. For example,
     /// WebAssembly platform does not have a file system and the resource manager will try to load
-    /// an existing registry instead of updating it.
+    /// an existing registry instead of updating it. The same applies to read-only resource IO
+    /// (for example, [`crate::pack::PackedResourceIo`]) on any platform.
     pub fn update_or_load_registry(&self) {
         let resource_io = self.resource_io.clone();
         let resource_registry = self.resource_registry.clone();
@@ -953,7 +1162,8 @@ impl ResourceManagerState {
         let task_loaders = self.loaders.clone();
         let path = resource_registry.safe_lock().path().to_path_buf();
 
-        // Try to update the registry first.
+        // Try to update the registry first. It is possible only if the IO can write, read-only
+        // sources (such as packs) must contain an already prepared registry.
         #[cfg(not(any(target_arch = "wasm32", target_os = "android")))]
         if resource_io.can_read_directories() && resource_io.can_write() {
             block_on(async move {
@@ -973,28 +1183,32 @@ impl ResourceManagerState {
                 }
                 registry_status.mark_as_loaded();
             });
+            return;
         }
 
+        let load = async move {
+            use crate::registry::RegistryContainerExt;
+            // Then load the registry.
+            info!("Trying to load or update the registry at {path:?}...");
+            match crate::registry::RegistryContainer::load_from_file(&path, &*resource_io).await {
+                Ok(registry) => {
+                    let mut registry_lock = resource_registry.safe_lock();
+                    registry_lock.modify().set_container(registry);
+                    info!("Resource registry was loaded from {path:?} successfully!");
+                }
+                Err(error) => {
+                    err!("Unable to load resource registry! Reason: {error}.");
+                }
+            };
+            registry_status.mark_as_loaded();
+        };
+
+        // Resource requests fail while the registry is not loaded, so load it immediately on the
+        // platforms that allow blocking.
+        #[cfg(not(any(target_arch = "wasm32", target_os = "android")))]
+        block_on(load);
         #[cfg(any(target_arch = "wasm32", target_os = "android"))]
-        if !resource_io.can_read_directories() || !resource_io.can_write() {
-            self.task_pool.spawn_task(async move {
-                use crate::registry::RegistryContainerExt;
-                // Then load the registry.
-                info!("Trying to load or update the registry at {path:?}...");
-                match crate::registry::RegistryContainer::load_from_file(&path, &*resource_io).await
-                {
-                    Ok(registry) => {
-                        let mut registry_lock = resource_registry.safe_lock();
-                        registry_lock.modify().set_container(registry);
-                        info!("Resource registry was loaded from {path:?} successfully!");
-                    }
-                    Err(error) => {
-                        err!("Unable to load resource registry! Reason: {error}.");
-                    }
-                };
-                registry_status.mark_as_loaded();
-            });
-        }
+        self.task_pool.spawn_task(load);
     }
 
     /// Tries to find all the native resources registered in the resource registry, returns a collection
@@ -1048,6 +1262,37 @@ impl ResourceManagerState {
             .unwrap_or(100)
     }
 
+    /// Returns total amount of resource loads that are waiting for a free worker of the task pool.
+    pub fn count_queued_loads(&self) -> usize {
+        self.load_queue.len()
+    }
+
+    /// Adds the given resource to a load group with the given name. The group is created if it
+    /// does not exist. Load groups hold strong references to their resources, so the resources
+    /// stay alive until the group is removed by [`Self::remove_load_group`].
+    pub fn add_to_load_group(&mut self, group: &str, resource: UntypedResource) {
+        self.load_groups
+            .entry(group.to_string())
+            .or_default()
+            .add(resource);
+    }
+
+    /// Returns a load group with the given name.
+    pub fn load_group(&self, group: &str) -> Option<&ResourceWaitContext> {
+        self.load_groups.get(group)
+    }
+
+    /// Returns loading progress of a load group with the given name in percents in `[0; 100]` range.
+    /// See [`ResourceWaitContext::progress`] for more info.
+    pub fn load_group_progress(&self, group: &str) -> Option<usize> {
+        self.load_groups.get(group).map(|group| group.progress())
+    }
+
+    /// Removes a load group with the given name and returns it.
+    pub fn remove_load_group(&mut self, group: &str) -> Option<ResourceWaitContext> {
+        self.load_groups.remove(group)
+    }
+
     fn try_get_event(&self) -> Option<Event> {
         self.watcher.as_ref()?.try_get_event()
     }
@@ -1309,6 +1554,8 @@ impl ResourceManagerState {
     /// Normally, this is called from `Engine::update()`.
     /// You should only call this manually if you don't use that method.
     pub fn update(&mut self, dt: f32) {
+        self.time += dt as f64;
+        let time = self.time;
         self.resources.retain_mut(|resource| {
             // One usage means that the resource has single owner, and that owner
             // is this container. Such resources have limited life time, if the time
@@ -1332,11 +1579,124 @@ impl ResourceManagerState {
             } else {
                 // Make sure to reset timer if a resource is used by more than one owner.
                 resource.time_to_live = DEFAULT_RESOURCE_LIFETIME;
+                resource.last_used = time;
 
                 // Keep resource alive while it has more than one owner.
                 true
             }
         });
+
+        if !self.memory_budget.is_unlimited() {
+            self.enforce_memory_budget();
+        }
+    }
+
+    /// Sets a new memory budget for resources. See [`ResourceMemoryBudget`] docs for more info.
+    /// The budget is enforced on every [`Self::update`] call.
+    pub fn set_memory_budget(&mut self, budget: ResourceMemoryBudget) {
+        self.memory_budget = budget;
+    }
+
+    /// Returns current memory budget for resources.
+    pub fn memory_budget(&self) -> &ResourceMemoryBudget {
+        &self.memory_budget
+    }
+
+    /// Calculates memory usage of all loaded resources, using [`crate::ResourceData::memory_usage`] estimates.
+    pub fn memory_usage(&self) -> ResourceMemoryUsage {
+        let mut usage = ResourceMemoryUsage::default();
+        for entry in self.resources.iter() {
+            if let ResourceState::Ok { data } = &entry.value.lock().state {
+                usage.add(data.type_uuid(), data.memory_usage());
+            }
+        }
+        usage
+    }
+
+    /// Brings memory usage of resources within the current memory budget. At first, unused resources
+    /// (the ones that are held by the resource manager only) are evicted, starting from the least
+    /// recently used. If it is not enough, the least recently used resources are downgraded (see
+    /// [`crate::ResourceData::downgrade`]) until the memory usage is within the budget or there's nothing
+    /// left to downgrade.
+    pub fn enforce_memory_budget(&mut self) {
+        struct Candidate {
+            index: usize,
+            type_uuid: Uuid,
+            size: usize,
+            last_used: f64,
+            unused: bool,
+        }
+
+        let mut usage = ResourceMemoryUsage::default();
+        let mut candidates = Vec::new();
+        for (index, entry) in self.resources.iter().enumerate() {
+            if let ResourceState::Ok { data } = &entry.value.lock().state {
+                let type_uuid = data.type_uuid();
+                let size = data.memory_usage();
+                usage.add(type_uuid, size);
+                candidates.push(Candidate {
+                    index,
+                    type_uuid,
+                    size,
+                    last_used: entry.last_used,
+                    unused: entry.value.use_count() <= 1,
+                });
+            }
+        }
+
+        if usage.is_within(&self.memory_budget) {
+            return;
+        }
+
+        candidates.sort_by(|a, b| a.last_used.total_cmp(&b.last_used));
+
+        let mut evicted = FxHashSet::default();
+        for candidate in candidates.iter() {
+            if candidate.unused
+                && candidate.size > 0
+                && usage.exceeds(&self.memory_budget, candidate.type_uuid)
+            {
+                usage.remove(candidate.type_uuid, candidate.size);
+                evicted.insert(candidate.index);
+            }
+        }
+
+        for candidate in candidates.iter() {
+            if evicted.contains(&candidate.index) {
+                continue;
+            }
+            let mut header = self.resources[candidate.index].value.lock();
+            let ResourceState::Ok { data } = &mut header.state else {
+                continue;
+            };
+            while usage.exceeds(&self.memory_budget, candidate.type_uuid) {
+                let size_before = data.memory_usage();
+                if !data.downgrade() {
+                    break;
+                }
+                usage.remove(candidate.type_uuid, size_before);
+                usage.add(candidate.type_uuid, data.memory_usage());
+            }
+        }
+
+        if evicted.is_empty() {
+            return;
+        }
+
+        let registry = self.resource_registry.safe_lock();
+        let mut index = 0;
+        self.resources.retain(|resource| {
+            let keep = !evicted.contains(&index);
+            index += 1;
+            if !keep {
+                if let Some(path) = registry.uuid_to_path(resource.resource_uuid()) {
+                    info!("Resource {path:?} evicted, because the memory budget is exceeded!");
+                    self.event_broadcaster
+                        .broadcast(ResourceEvent::Removed(path.to_path_buf()));
+                }
+            }
+            keep
+        });
     }
 
     fn add_resource_and_notify(&mut self, resource: UntypedResource) {
@@ -1346,6 +1706,7 @@ impl ResourceManagerState {
         self.resources.push(TimedEntry {
             value: resource,
             time_to_live: DEFAULT_RESOURCE_LIFETIME,
+            last_used: self.time,
         });
     }
 
@@ -1430,8 +1791,8 @@ impl ResourceManagerState {
             return built_in_resource.resource.clone();
         }
 
-        if let Some(existing) = self.find_by_uuid(uuid) {
-            existing.clone()
+        if let Some(existing) = self.use_by_uuid(uuid) {
+            existing
         } else {
             let resource = UntypedResource::new_unloaded(uuid);
             self.add_resource_and_notify(resource.clone());
@@ -1458,8 +1819,8 @@ impl ResourceManagerState {
 
         let path = self.resource_io.canonicalize_path(path).unwrap();
 
-        if let Some(existing) = self.find_by_resource_path(&path) {
-            existing.clone()
+        if let Some(existing) = self.use_by_resource_path(&path) {
+            existing
         } else {
             let uuid = match self.find_uuid_or_register_new(path.clone()) {
                 Ok(uuid) => uuid,
@@ -1480,6 +1841,20 @@ impl ResourceManagerState {
     /// Panics if the path is invalid, such as if it includes a directory that does not exist
     /// or contains invalid characters.
     pub fn request<P>(&mut self, path: P) -> UntypedResource
+    where
+        P: AsRef<Path>,
+    {
+        self.request_with_priority(path, LoadPriority::Normal)
+    }
+
+    /// Same as [`Self::request`], but with the given load priority. If the resource is already
+    /// waiting for its load, its priority is raised to the given one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the path is invalid, such as if it includes a directory that does not exist
+    /// or contains invalid characters.
+    pub fn request_with_priority<P>(&mut self, path: P, priority: LoadPriority) -> UntypedResource
     where
         P: AsRef<Path>,
     {
@@ -1490,7 +1865,7 @@ impl ResourceManagerState {
 
         let path = self.resource_io.canonicalize_path(path).unwrap();
 
-        self.find_or_load(path)
+        self.find_or_load(path, priority)
     }
 
     /// Tries to load the resource for the given UUID.
@@ -1500,6 +1875,31 @@ impl ResourceManagerState {
         resource
     }
 
+    /// Finds a resource with the given UUID and marks it as used, so the memory budget will not
+    /// evict it as a least recently used one.
+    fn use_by_uuid(&mut self, uuid: Uuid) -> Option<UntypedResource> {
+        let time = self.time;
+        let entry = self
+            .resources
+            .iter_mut()
+            .find(|entry| entry.value.resource_uuid() == uuid)?;
+        entry.last_used = time;
+        Some(entry.value.clone())
+    }
+
+    /// Same as [`Self::use_by_uuid`], but uses the registry to find a resource with the given path,
+    /// blocking until the registry is loaded if necessary.
+    fn use_by_resource_path(&mut self, path_to_search: &Path) -> Option<UntypedResource> {
+        let time = self.time;
+        let registry = self.resource_registry.safe_lock();
+        let entry = self
+            .resources
+            .iter_mut()
+            .find(|entry| registry.uuid_to_path(entry.resource_uuid()) == Some(path_to_search))?;
+        entry.last_used = time;
+        Some(entry.value.clone())
+    }
+
     /// Use the registry to find a resource with the given path, blocking until the registry is loaded if necessary.
     fn find_by_resource_path(&self, path_to_search: &Path) -> Option<&UntypedResource> {
         let registry = self.resource_registry.safe_lock();
@@ -1514,10 +1914,19 @@ impl ResourceManagerState {
     /// a resource, begin loading, and return the resource.
     /// If the given path does not correspond to any registered UUID,
     /// create and return an error resource.
-    fn find_or_load(&mut self, path: PathBuf) -> UntypedResource {
-        match self.find_by_resource_path(&path) {
-            Some(existing) => existing.clone(),
-            None => self.load_resource(path),
+    fn find_or_load(&mut self, path: PathBuf, priority: LoadPriority) -> UntypedResource {
+        match self.use_by_resource_path(&path) {
+            Some(mut existing) => {
+                if existing.is_unloaded() {
+                    // The resource could be unloaded if its previous load was cancelled.
+                    existing.make_pending();
+                    self.spawn_loading_task(existing.clone(), false, priority);
+                } else if existing.is_loading() {
+                    self.load_queue.raise_priority(&existing, priority);
+                }
+                existing
+            }
+            None => self.load_resource(path, priority),
         }
     }
 
@@ -1540,24 +1949,30 @@ impl ResourceManagerState {
         }
     }
 
-    fn load_resource(&mut self, path: PathBuf) -> UntypedResource {
+    fn load_resource(&mut self, path: PathBuf, priority: LoadPriority) -> UntypedResource {
         let uuid = match self.find_uuid_or_register_new(path.clone()) {
             Ok(uuid) => uuid,
             Err(err) => return UntypedResource::new_load_error(ResourceKind::External, path, err),
         };
         let resource = UntypedResource::new_pending(uuid, ResourceKind::External);
         self.add_resource_and_notify(resource.clone());
-        self.spawn_loading_task(resource.clone(), false);
+        self.spawn_loading_task(resource.clone(), false, priority);
         resource
     }
 
     /// Add a task to the task pool to load the given resource.
     /// Panic if the given resource is unregistered or embedded.
-    fn spawn_loading_task(&self, mut resource: UntypedResource, reload: bool) {
+    fn spawn_loading_task(
+        &self,
+        mut resource: UntypedResource,
+        reload: bool,
+        priority: LoadPriority,
+    ) {
         let event_broadcaster = self.event_broadcaster.clone();
         let loaders = self.loaders.clone();
         let registry = self.resource_registry.clone();
         let io = self.resource_io.clone();
+        let load_queue = self.load_queue.clone();
 
         if !registry.safe_lock().status_flag().is_loaded() {
             resource.commit_error(
@@ -1570,7 +1985,15 @@ impl ResourceManagerState {
             return;
         }
 
+        // The task will pick the pending load with the highest priority, which is not necessarily
+        // the one pushed here.
+        self.load_queue.push(resource, priority, reload);
+
         self.task_pool.spawn_task(async move {
+            let Some((mut resource, reload)) = load_queue.pop() else {
+                return;
+            };
+
             let Some(path) = registry
                 .safe_lock()
                 .uuid_to_path(resource.resource_uuid())
@@ -1692,14 +2115,14 @@ impl ResourceManagerState {
     /// Tries to load the given resource, based on the resource's UUID,
     /// and adds the resource to the manager if it is not already in the manager.
     pub fn request_resource(&mut self, resource: &mut UntypedResource) {
-        if let Some(r) = self.find_by_uuid(resource.resource_uuid()) {
+        if let Some(r) = self.use_by_uuid(resource.resource_uuid()) {
             // We are already managing a resource with this UUID, so modify the given
             // resource to point to our resource.
-            *resource = r.clone();
+            *resource = r;
             if resource.is_unloaded() {
                 // If the resource is in the unloaded state, start it loading, because it has been requested.
                 resource.make_pending();
-                self.spawn_loading_task(resource.clone(), false);
+                self.spawn_loading_task(resource.clone(), false, LoadPriority::Normal);
             }
         } else if let Some(r) = self
             .built_in_resources
@@ -1717,7 +2140,7 @@ impl ResourceManagerState {
             // resources and begin the loading process.
             resource.make_pending();
             self.add_resource_and_notify(resource.clone());
-            self.spawn_loading_task(resource.clone(), false);
+            self.spawn_loading_task(resource.clone(), false, LoadPriority::Normal);
         }
     }
 
@@ -1725,10 +2148,10 @@ impl ResourceManagerState {
     /// without initiating the loading of the resource. The given resource may be modified
     /// to be a reference to the shared data of an existing resource with the same UUID.
     pub fn add_resource(&mut self, resource: &mut UntypedResource) {
-        if let Some(r) = self.find_by_uuid(resource.resource_uuid()) {
+        if let Some(r) = self.use_by_uuid(resource.resource_uuid()) {
             // We are already managing a resource with this UUID, so modify the given
             // resource to point to our resource.
-            *resource = r.clone();
+            *resource = r;
         } else if let Some(r) = self
             .built_in_resources
             .find_by_uuid(resource.resource_uuid())
@@ -1782,13 +2205,9 @@ impl ResourceManagerState {
         if self.built_in_resources.is_built_in_resource(&resource) {
             return;
         }
-        let mut header = resource.lock();
-        if !header.state.is_loading() {
-            if !header.state.is_ok() {
-                header.state.switch_to_pending_state();
-            }
-            drop(header);
-            self.spawn_loading_task(resource, true)
+        // The resource is switched to the pending state by the load queue.
+        if !resource.is_loading() {
+            self.spawn_loading_task(resource, true, LoadPriority::Normal)
         }
     }
 
@@ -2161,6 +2580,34 @@ mod test {
         assert!(!cx.is_all_loaded());
     }
 
+    #[test]
+    fn resource_wait_context_progress() {
+        assert_eq!(ResourceWaitContext::default().progress(), 100);
+
+        let pending = UntypedResource::new_pending(Uuid::new_v4(), ResourceKind::External);
+        let mut cx = ResourceWaitContext::default();
+        cx.add(pending.clone());
+        cx.add(pending.clone());
+        cx.add(UntypedResource::new_ok(
+            Uuid::new_v4(),
+            ResourceKind::External,
+            Stub {},
+        ));
+        cx.add(UntypedResource::new_load_error(
+            ResourceKind::External,
+            Default::default(),
+            LoadError::default(),
+        ));
+        assert_eq!(cx.len(), 3);
+        assert_eq!(cx.count_loaded(), 1);
+        assert_eq!(cx.count_failed(), 1);
+        assert_eq!(cx.progress(), 66);
+
+        pending.commit_ok(Stub {});
+        assert_eq!(cx.progress(), 100);
+        block_on(cx.wait());
+    }
+
     #[test]
     fn resource_manager_state_new() {
         let state = new_resource_manager();
@@ -2286,6 +2733,99 @@ mod test {
         assert_eq!(state.len(), 0);
     }
 
+    #[derive(Debug, Default, Clone, Reflect, Visit)]
+    struct Blob {
+        size: usize,
+    }
+
+    impl TypeUuidProvider for Blob {
+        fn type_uuid() -> Uuid {
+            uuid!("3c1ab0b4-c0e6-4ab5-9d3c-8d3a1c2a35f4")
+        }
+    }
+
+    impl ResourceData for Blob {
+        fn type_uuid(&self) -> Uuid {
+            <Self as TypeUuidProvider>::type_uuid()
+        }
+
+        fn save(&mut self, _path: &Path) -> Result<(), Box<dyn Error>> {
+            Err("Saving is not supported!".to_string().into())
+        }
+
+        fn can_be_saved(&self) -> bool {
+            false
+        }
+
+        fn try_clone_box(&self) -> Option<Box<dyn ResourceData>> {
+            Some(Box::new(self.clone()))
+        }
+
+        fn memory_usage(&self) -> usize {
+            self.size
+        }
+
+        fn downgrade(&mut self) -> bool {
+            if self.size > 1 {
+                self.size /= 2;
+                true
+            } else {
+                false
+            }
+        }
+    }
+
+    #[test]
+    fn resource_manager_state_memory_budget() {
+        let mut state = new_resource_manager();
+
+        let blob =
+            |size| UntypedResource::new_ok(Uuid::new_v4(), ResourceKind::External, Blob { size });
+
+        let oldest = blob(100);
+        let newer = blob(100);
+        let used = blob(100);
+        state.add_resource_and_notify(oldest.clone());
+        state.update(1.0);
+        state.add_resource_and_notify(newer.clone());
+        state.update(1.0);
+        state.add_resource_and_notify(used.clone());
+        state.update(1.0);
+        let (oldest_uuid, newer_uuid) = (oldest.resource_uuid(), newer.resource_uuid());
+        drop(oldest);
+        drop(newer);
+
+        assert_eq!(
+            state
+                .memory_usage()
+                .of_type(<Blob as TypeUuidProvider>::type_uuid()),
+            300
+        );
+
+        // Any access to a resource makes it recently used, even if it is not used anymore.
+        state.update(1.0);
+        drop(state.find_uuid(oldest_uuid));
+
+        state.set_memory_budget(ResourceMemoryBudget::default().with_type_limit::<Blob>(200));
+        state.update(0.0);
+
+        // The least recently used resource must be evicted first.
+        assert_eq!(state.len(), 2);
+        assert!(state.find_by_uuid(newer_uuid).is_none());
+        assert!(state.find_by_uuid(oldest_uuid).is_some());
+        assert_eq!(state.memory_usage().total, 200);
+
+        // Nothing else can be evicted, but the used resource can be downgraded.
+        state.set_memory_budget(ResourceMemoryBudget::default().with_total(50));
+        state.update(0.0);
+        assert_eq!(state.len(), 1);
+        assert_eq!(state.memory_usage().total, 50);
+        assert_eq!(
+            used.lock().state.data_ref_of_type::<Blob>().unwrap().size,
+            50
+        );
+    }
+
     #[test]
     fn resource_manager_state_request() {
         let mut state = new_resource_manager();
@@ -0,0 +1,888 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Packed archive support. See [`PackedResourceIo`] docs for more info.
+//!
+//! # Format
+//!
+//! A pack file consists of a header, a set of data blobs and an index. All numbers are stored in
+//! little-endian byte order.
+//!
+//! ```text
+//! Header: magic (8 bytes, "FYRXPACK"), version (u32), entry count (u32), index offset (u64)
+//! Data:   entry blobs, each blob is compressed independently
+//! Index:  for each entry - path length (u32), UTF-8 path, offset (u64), stored size (u64),
+//!         size (u64), compression (u8)
+//! ```
+
+use crate::io::{normalize_path, FileReader, PathIter, ResourceIo, ResourceIoFuture};
+use fxhash::{FxHashMap, FxHashSet};
+use fyrox_core::{io::FileError, replace_slashes};
+use memmap2::Mmap;
+use std::{
+    ffi::OsStr,
+    fmt::{Debug, Formatter},
+    fs::File,
+    future::ready,
+    io::{self, Read, Seek, SeekFrom, Write},
+    ops::Range,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+const MAGIC: &[u8; 8] = b"FYRXPACK";
+const VERSION: u32 = 1;
+const HEADER_SIZE: u64 = 8 + 4 + 4 + 8;
+
+/// Compression method of a single entry of a pack.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum PackCompression {
+    /// The entry is stored as is. Such entries are read directly from the memory-mapped file
+    /// without any copying.
+    None,
+    /// Fast compression with moderate compression ratio. Good choice for the most of the assets.
+    #[default]
+    Lz4,
+    /// Slower compression with high compression ratio. Decompression is still quite fast.
+    Zstd,
+}
+
+impl PackCompression {
+    const ZSTD_LEVEL: i32 = 9;
+
+    // Maximum possible ratio of decompressed size to compressed size. LZ4 cannot compress better
+    // than ~255:1, zstd could go much further on highly redundant data.
+    fn max_ratio(self) -> u64 {
+        match self {
+            PackCompression::None => 1,
+            PackCompression::Lz4 => 256,
+            PackCompression::Zstd => 1 << 16,
+        }
+    }
+
+    fn id(self) -> u8 {
+        match self {
+            PackCompression::None => 0,
+            PackCompression::Lz4 => 1,
+            PackCompression::Zstd => 2,
+        }
+    }
+
+    fn from_id(id: u8) -> Option<Self> {
+        match id {
+            0 => Some(PackCompression::None),
+            1 => Some(PackCompression::Lz4),
+            2 => Some(PackCompression::Zstd),
+            _ => None,
+        }
+    }
+
+    fn compress(self, data: &[u8]) -> io::Result<Vec<u8>> {
+        match self {
+            PackCompression::None => Ok(data.to_vec()),
+            PackCompression::Lz4 => Ok(lz4_flex::compress(data)),
+            PackCompression::Zstd => zstd::bulk::compress(data, Self::ZSTD_LEVEL),
+        }
+    }
+
+    fn decompress(self, data: &[u8], size: usize) -> io::Result<Vec<u8>> {
+        match self {
+            PackCompression::None => Ok(data.to_vec()),
+            PackCompression::Lz4 => lz4_flex::decompress(data, size)
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err)),
+            PackCompression::Zstd => zstd::bulk::decompress(data, size),
+        }
+    }
+}
+
+/// A single file stored in a pack.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PackEntry {
+    /// Offset of the entry data from the beginning of the pack file.
+    pub offset: u64,
+    /// Size of the entry data in the pack file.
+    pub stored_size: u64,
+    /// Size of the file after decompression.
+    pub size: u64,
+    /// Compression method of the entry.
+    pub compression: PackCompression,
+}
+
+impl PackEntry {
+    /// Maximum size of a single file in a pack after decompression.
+    pub const MAX_SIZE: u64 = 1 << 32;
+
+    // The sizes come from the pack index, that could be corrupted (or malicious), so the
+    // decompressed size must be checked before allocating memory for it.
+    fn is_size_valid(&self) -> bool {
+        match self.compression {
+            PackCompression::None => self.size == self.stored_size,
+            compression => {
+                self.size <= Self::MAX_SIZE
+                    && self.size <= self.stored_size.saturating_mul(compression.max_ratio())
+            }
+        }
+    }
+}
+
+/// Writes a pack file entry by entry. See [`PackedResourceIo`] docs for more info.
+pub struct PackWriter<W: Write + Seek> {
+    writer: W,
+    entries: Vec<(String, PackEntry)>,
+    position: u64,
+}
+
+fn invalid_data(message: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.into())
+}
+
+fn path_to_entry_name(path: &Path) -> io::Result<String> {
+    let path = normalize_path(path).map_err(|err| invalid_data(err.to_string()))?;
+    path.to_str()
+        .map(|s| s.to_string())
+        .ok_or_else(|| invalid_data(format!("{} is not a valid UTF-8 path", path.display())))
+}
+
+impl<W: Write + Seek> PackWriter<W> {
+    /// Creates a new pack writer. The header will be written immediately.
+    pub fn new(mut writer: W) -> io::Result<Self> {
+        writer.write_all(&[0; HEADER_SIZE as usize])?;
+        Ok(Self {
+            writer,
+            entries: Default::default(),
+            position: HEADER_SIZE,
+        })
+    }
+
+    /// Adds a new file to the pack. The path must be relative (for example `data/scene.rgs`) and it
+    /// will be normalized. If the compression does not make the data smaller, the entry will be
+    /// stored uncompressed.
+    pub fn add_file(
+        &mut self,
+        path: impl AsRef<Path>,
+        data: &[u8],
+        compression: PackCompression,
+    ) -> io::Result<()> {
+        let name = path_to_entry_name(path.as_ref())?;
+        if self.entries.iter().any(|(n, _)| *n == name) {
+            return Err(invalid_data(format!("duplicate pack entry {name}")));
+        }
+
+        let (compression, stored) = match compression.compress(data)? {
+            compressed if compressed.len() < data.len() => (compression, compressed),
+            _ => (PackCompression::None, data.to_vec()),
+        };
+
+        self.writer.write_all(&stored)?;
+        self.entries.push((
+            name,
+            PackEntry {
+                offset: self.position,
+                stored_size: stored.len() as u64,
+                size: data.len() as u64,
+                compression,
+            },
+        ));
+        self.position += stored.len() as u64;
+        Ok(())
+    }
+
+    /// Writes the index and the header and returns the inner writer.
+    pub fn finish(mut self) -> io::Result<W> {
+        let index_offset = self.position;
+        for (name, entry) in self.entries.iter() {
+            self.writer.write_all(&(name.len() as u32).to_le_bytes())?;
+            self.writer.write_all(name.as_bytes())?;
+            self.writer.write_all(&entry.offset.to_le_bytes())?;
+            self.writer.write_all(&entry.stored_size.to_le_bytes())?;
+            self.writer.write_all(&entry.size.to_le_bytes())?;
+            self.writer.write_all(&[entry.compression.id()])?;
+        }
+
+        self.writer.seek(SeekFrom::Start(0))?;
+        self.writer.write_all(MAGIC)?;
+        self.writer.write_all(&VERSION.to_le_bytes())?;
+        self.writer
+            .write_all(&(self.entries.len() as u32).to_le_bytes())?;
+        self.writer.write_all(&index_offset.to_le_bytes())?;
+        self.writer.flush()?;
+
+        Ok(self.writer)
+    }
+}
+
+/// Packs the given folders into a single pack file. Folders must be relative to the `root` folder
+/// and the entries of the pack will have paths relative to it as well. The filter could be used to
+/// exclude some files from the pack.
+pub fn pack_folders(
+    root: &Path,
+    folders: &[PathBuf],
+    pack_path: &Path,
+    compression: PackCompression,
+    filter: &dyn Fn(&Path) -> bool,
+) -> io::Result<()> {
+    let mut writer = PackWriter::new(io::BufWriter::new(File::create(pack_path)?))?;
+    for folder in folders {
+        for entry in walkdir::WalkDir::new(root.join(folder))
+            .into_iter()
+            .flatten()
+        {
+            let path = entry.path();
+            if !entry.file_type().is_file() || !filter(path) {
+                continue;
+            }
+            let relative_path = path.strip_prefix(root).map_err(|_| {
+                invalid_data(format!("{} is outside of the root folder", path.display()))
+            })?;
+            writer.add_file(relative_path, &std::fs::read(path)?, compression)?;
+        }
+    }
+    writer.finish()?;
+    Ok(())
+}
+
+/// A reader of an uncompressed entry of a memory-mapped pack.
+struct MappedEntryReader {
+    mmap: Arc<Mmap>,
+    range: Range<usize>,
+    position: u64,
+}
+
+impl Debug for MappedEntryReader {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MappedEntryReader")
+            .field("range", &self.range)
+            .field("position", &self.position)
+            .finish()
+    }
+}
+
+impl Read for MappedEntryReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let data = &self.mmap[self.range.clone()];
+        let start = (self.position as usize).min(data.len());
+        let count = buf.len().min(data.len() - start);
+        buf[..count].copy_from_slice(&data[start..start + count]);
+        self.position += count as u64;
+        Ok(count)
+    }
+}
+
+impl Seek for MappedEntryReader {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let len = self.range.len() as i64;
+        let position = match pos {
+            SeekFrom::Start(offset) => offset as i64,
+            SeekFrom::End(offset) => len + offset,
+            SeekFrom::Current(offset) => self.position as i64 + offset,
+        };
+        if position < 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid seek to a negative position",
+            ));
+        }
+        self.position = position as u64;
+        Ok(self.position)
+    }
+}
+
+impl FileReader for MappedEntryReader {
+    fn byte_len(&self) -> Option<u64> {
+        Some(self.range.len() as u64)
+    }
+}
+
+/// Resource IO that reads files from a single memory-mapped pack file. Shipping a game with one
+/// pack instead of thousands of loose files significantly speeds up installation and eliminates
+/// stalls caused by antivirus scans of every single file. Each entry of the pack could be
+/// compressed individually (see [`PackCompression`]).
+///
+/// Packs are read-only, all write operations return an error. Use [`PackWriter`] or
+/// [`pack_folders`] to create a pack.
+///
+/// ## Example
+///
+/// ```rust,no_run
+/// # use fyrox_resource::{manager::ResourceManager, pack::PackedResourceIo};
+/// # use std::sync::Arc;
+/// fn make_resource_manager() -> ResourceManager {
+///     let io = PackedResourceIo::open(PackedResourceIo::DEFAULT_FILE_NAME).unwrap();
+///     ResourceManager::new(Arc::new(io), Default::default())
+/// }
+/// ```
+pub struct PackedResourceIo {
+    mmap: Arc<Mmap>,
+    entries: FxHashMap<PathBuf, PackEntry>,
+    directories: FxHashSet<PathBuf>,
+}
+
+impl Debug for PackedResourceIo {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PackedResourceIo")
+            .field("entries", &self.entries.len())
+            .finish()
+    }
+}
+
+fn read_bytes<'a>(data: &'a [u8], offset: &mut usize, len: usize) -> io::Result<&'a [u8]> {
+    let bytes = offset
+        .checked_add(len)
+        .and_then(|end| data.get(*offset..end))
+        .ok_or_else(|| invalid_data("unexpected end of the pack index"))?;
+    *offset += len;
+    Ok(bytes)
+}
+
+fn read_u32(data: &[u8], offset: &mut usize) -> io::Result<u32> {
+    let bytes = read_bytes(data, offset, 4)?;
+    Ok(u32::from_le_bytes(bytes.try_into().unwrap()))
+}
+
+fn read_u64(data: &[u8], offset: &mut usize) -> io::Result<u64> {
+    let bytes = read_bytes(data, offset, 8)?;
+    Ok(u64::from_le_bytes(bytes.try_into().unwrap()))
+}
+
+impl PackedResourceIo {
+    /// Default name of the pack file, that is used by the project exporter.
+    pub const DEFAULT_FILE_NAME: &'static str = "data.fyrpack";
+
+    /// Opens a pack file and reads its index.
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let file = File::open(path)?;
+        // SAFETY: The pack file must not be modified while it is mapped. Packs are produced once
+        // when a game is exported and they're read-only afterward.
+        #[allow(unsafe_code)]
+        let mmap = unsafe { Mmap::map(&file)? };
+        Self::from_mmap(mmap)
+    }
+
+    fn from_mmap(mmap: Mmap) -> io::Result<Self> {
+        if mmap.len() < HEADER_SIZE as usize || &mmap[0..8] != MAGIC {
+            return Err(invalid_data("not a pack file"));
+        }
+
+        let mut offset = 8;
+        let version = read_u32(&mmap, &mut offset)?;
+        if version != VERSION {
+            return Err(invalid_data(format!("unsupported pack version {version}")));
+        }
+        let count = read_u32(&mmap, &mut offset)?;
+        let mut offset = read_u64(&mmap, &mut offset)? as usize;
+
+        let mut entries = FxHashMap::default();
+        let mut directories = FxHashSet::default();
+        directories.insert(PathBuf::from("."));
+        for _ in 0..count {
+            let name_len = read_u32(&mmap, &mut offset)? as usize;
+            let name = std::str::from_utf8(read_bytes(&mmap, &mut offset, name_len)?)
+                .map_err(|_| invalid_data("invalid pack entry name"))?;
+            let path = PathBuf::from(name);
+
+            let entry = PackEntry {
+                offset: read_u64(&mmap, &mut offset)?,
+                stored_size: read_u64(&mmap, &mut offset)?,
+                size: read_u64(&mmap, &mut offset)?,
+                compression: mmap
+                    .get(offset)
+                    .and_then(|id| PackCompression::from_id(*id))
+                    .ok_or_else(|| invalid_data("invalid pack entry compression"))?,
+            };
+            offset += 1;
+
+            if entry
+                .offset
+                .checked_add(entry.stored_size)
+                .is_none_or(|end| end > mmap.len() as u64)
+            {
+                return Err(invalid_data(format!("pack entry {name} is out of bounds")));
+            }
+
+            if !entry.is_size_valid() {
+                return Err(invalid_data(format!("pack entry {name} has invalid size")));
+            }
+
+            let mut parent = path.parent();
+            while let Some(dir) = parent.filter(|p| !p.as_os_str().is_empty()) {
+                directories.insert(dir.to_path_buf());
+                parent = dir.parent();
+            }
+
+            entries.insert(path, entry);
+        }
+
+        Ok(Self {
+            mmap: Arc::new(mmap),
+            entries,
+            directories,
+        })
+    }
+
+    /// Returns an iterator over all the entries of the pack.
+    pub fn entries(&self) -> impl Iterator<Item = (&Path, &PackEntry)> {
+        self.entries
+            .iter()
+            .map(|(path, entry)| (path.as_path(), entry))
+    }
+
+    fn key(&self, path: &Path) -> Option<PathBuf> {
+        self.canonicalize_path(path).ok()
+    }
+
+    fn entry(&self, path: &Path) -> Result<&PackEntry, FileError> {
+        self.key(path)
+            .and_then(|key| self.entries.get(&key))
+            .ok_or_else(|| {
+                FileError::Io(io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!("{} does not exist in the pack", path.display()),
+                ))
+            })
+    }
+
+    fn entry_range(entry: &PackEntry) -> Range<usize> {
+        entry.offset as usize..(entry.offset + entry.stored_size) as usize
+    }
+
+    fn read_entry(&self, path: &Path) -> Result<Vec<u8>, FileError> {
+        let entry = self.entry(path)?;
+        let data = &self.mmap[Self::entry_range(entry)];
+        Ok(entry.compression.decompress(data, entry.size as usize)?)
+    }
+
+    // Collects all entries and directories inside the given directory up to the given depth. The
+    // directory itself is included only if requested, mimicking the behaviour of file system
+    // traversal.
+    fn children(
+        &self,
+        path: &Path,
+        max_depth: usize,
+        include_self: bool,
+    ) -> Result<PathIter, FileError> {
+        let dir = self
+            .key(path)
+            .filter(|key| self.directories.contains(key))
+            .ok_or_else(|| FileError::Custom(format!("{} is not a directory", path.display())))?;
+
+        let depth_of = |p: &Path| {
+            if p == dir {
+                return None;
+            }
+            let relative = if dir == Path::new(".") {
+                Some(p)
+            } else {
+                p.strip_prefix(&dir).ok()
+            };
+            relative
+                .filter(|r| !r.as_os_str().is_empty())
+                .map(|r| r.components().count())
+        };
+
+        let paths = include_self
+            .then(|| dir.clone())
+            .into_iter()
+            .chain(
+                self.entries
+                    .keys()
+                    .chain(self.directories.iter())
+                    .filter(|p| depth_of(p).is_some_and(|depth| depth <= max_depth))
+                    .cloned(),
+            )
+            .collect::<Vec<_>>();
+
+        Ok(Box::new(paths.into_iter()))
+    }
+
+    fn read_only_error() -> FileError {
+        FileError::Custom("Packed resource io is read-only!".to_string())
+    }
+}
+
+impl ResourceIo for PackedResourceIo {
+    fn can_write(&self) -> bool {
+        false
+    }
+
+    fn can_read_directories(&self) -> bool {
+        true
+    }
+
+    fn load_file<'a>(&'a self, path: &'a Path) -> ResourceIoFuture<'a, Result<Vec<u8>, FileError>> {
+        Box::pin(ready(self.read_entry(path)))
+    }
+
+    fn write_file<'a>(
+        &'a self,
+        _path: &'a Path,
+        _data: Vec<u8>,
+    ) -> ResourceIoFuture<'a, Result<(), FileError>> {
+        Box::pin(ready(Err(Self::read_only_error())))
+    }
+
+    fn write_file_sync(&self, _path: &Path, _data: &[u8]) -> Result<(), FileError> {
+        Err(Self::read_only_error())
+    }
+
+    fn create_dir_all_sync(&self, _path: &Path) -> Result<(), FileError> {
+        Err(Self::read_only_error())
+    }
+
+    fn move_file<'a>(
+        &'a self,
+        _source: &'a Path,
+        _dest: &'a Path,
+    ) -> ResourceIoFuture<'a, Result<(), FileError>> {
+        Box::pin(ready(Err(Self::read_only_error())))
+    }
+
+    fn delete_file<'a>(&'a self, _path: &'a Path) -> ResourceIoFuture<'a, Result<(), FileError>> {
+        Box::pin(ready(Err(Self::read_only_error())))
+    }
+
+    fn delete_file_sync(&self, _path: &Path) -> Result<(), FileError> {
+        Err(Self::read_only_error())
+    }
+
+    fn copy_file<'a>(
+        &'a self,
+        _source: &'a Path,
+        _dest: &'a Path,
+    ) -> ResourceIoFuture<'a, Result<(), FileError>> {
+        Box::pin(ready(Err(Self::read_only_error())))
+    }
+
+    fn canonicalize_path<'a>(&'a self, path: &'a Path) -> Result<PathBuf, FileError> {
+        if path.is_absolute() {
+            // Absolute paths are treated as relative to the working directory, where the pack is
+            // usually located.
+            let current_dir = std::env::current_dir()?;
+            let relative = path.strip_prefix(&current_dir).map_err(|_| {
+                FileError::Custom(format!("{} is outside of the pack", path.display()))
+            })?;
+            normalize_path(relative)
+        } else {
+            normalize_path(replace_slashes(path))
+        }
+    }
+
+    fn read_directory<'a>(
+        &'a self,
+        path: &'a Path,
+    ) -> ResourceIoFuture<'a, Result<PathIter, FileError>> {
+        Box::pin(ready(self.children(path, 1, false)))
+    }
+
+    fn walk_directory<'a>(
+        &'a self,
+        path: &'a Path,
+        max_depth: usize,
+    ) -> ResourceIoFuture<'a, Result<PathIter, FileError>> {
+        Box::pin(ready(self.children(path, max_depth, true)))
+    }
+
+    fn file_reader<'a>(
+        &'a self,
+        path: &'a Path,
+    ) -> ResourceIoFuture<'a, Result<Box<dyn FileReader>, FileError>> {
+        Box::pin(async move {
+            let entry = self.entry(path)?;
+            let reader: Box<dyn FileReader> = if entry.compression == PackCompression::None {
+                Box::new(MappedEntryReader {
+                    mmap: self.mmap.clone(),
+                    range: Self::entry_range(entry),
+                    position: 0,
+                })
+            } else {
+                Box::new(io::Cursor::new(self.read_entry(path)?))
+            };
+            Ok(reader)
+        })
+    }
+
+    fn is_valid_file_name(&self, name: &OsStr) -> bool {
+        !name
+            .as_encoded_bytes()
+            .iter()
+            .any(|byte| matches!(byte, b'\0' | b'/' | b'\\'))
+    }
+
+    fn exists<'a>(&'a self, path: &'a Path) -> ResourceIoFuture<'a, bool> {
+        Box::pin(ready(self.exists_sync(path)))
+    }
+
+    fn exists_sync(&self, path: &Path) -> bool {
+        self.key(path)
+            .is_some_and(|key| self.entries.contains_key(&key) || self.directories.contains(&key))
+    }
+
+    fn is_file<'a>(&'a self, path: &'a Path) -> ResourceIoFuture<'a, bool> {
+        Box::pin(ready(
+            self.key(path)
+                .is_some_and(|key| self.entries.contains_key(&key)),
+        ))
+    }
+
+    fn is_dir<'a>(&'a self, path: &'a Path) -> ResourceIoFuture<'a, bool> {
+        Box::pin(ready(
+            self.key(path)
+                .is_some_and(|key| self.directories.contains(&key)),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        io::ResourceIo,
+        loader::{BoxedLoaderFuture, LoaderPayload, ResourceLoader},
+        manager::ResourceManager,
+        pack::{PackCompression, PackWriter, PackedResourceIo, HEADER_SIZE, MAGIC, VERSION},
+        registry::{RegistryContainer, RegistryContainerExt, ResourceRegistry},
+        state::LoadError,
+        ResourceData,
+    };
+    use fyrox_core::{
+        futures::executor::block_on,
+        reflect::prelude::*,
+        uuid::{uuid, Uuid},
+        visitor::prelude::*,
+        TypeUuidProvider,
+    };
+    use std::{
+        error::Error,
+        fs::File,
+        io::{Read, Write},
+        path::{Path, PathBuf},
+        sync::Arc,
+    };
+
+    #[derive(Debug, Default, Clone, Reflect, Visit)]
+    struct Text {
+        text: String,
+    }
+
+    impl TypeUuidProvider for Text {
+        fn type_uuid() -> Uuid {
+            uuid!("4c1a7e52-93d8-4f06-b2e1-6a8f5c3d9b17")
+        }
+    }
+
+    impl ResourceData for Text {
+        fn type_uuid(&self) -> Uuid {
+            <Self as TypeUuidProvider>::type_uuid()
+        }
+
+        fn save(&mut self, _path: &Path) -> Result<(), Box<dyn Error>> {
+            Err("Saving is not supported!".to_string().into())
+        }
+
+        fn can_be_saved(&self) -> bool {
+            false
+        }
+
+        fn try_clone_box(&self) -> Option<Box<dyn ResourceData>> {
+            Some(Box::new(self.clone()))
+        }
+    }
+
+    struct TextLoader;
+
+    impl ResourceLoader for TextLoader {
+        fn extensions(&self) -> &[&str] {
+            &["txt"]
+        }
+
+        fn data_type_uuid(&self) -> Uuid {
+            <Text as TypeUuidProvider>::type_uuid()
+        }
+
+        fn load(&self, path: PathBuf, io: Arc<dyn ResourceIo>) -> BoxedLoaderFuture {
+            Box::pin(async move {
+                let bytes = io.load_file(&path).await.map_err(LoadError::new)?;
+                Ok(LoaderPayload::new(Text {
+                    text: String::from_utf8(bytes).unwrap(),
+                }))
+            })
+        }
+    }
+
+    fn make_pack(name: &str) -> PackedResourceIo {
+        let path = std::env::temp_dir().join(name);
+        let mut writer = PackWriter::new(File::create(&path).unwrap()).unwrap();
+        let text = "The quick brown fox jumps over the lazy dog. ".repeat(100);
+        writer
+            .add_file("data/a.txt", text.as_bytes(), PackCompression::Lz4)
+            .unwrap();
+        writer
+            .add_file("data/models/b.txt", text.as_bytes(), PackCompression::Zstd)
+            .unwrap();
+        writer
+            .add_file("./data/c.bin", &[1, 2, 3], PackCompression::None)
+            .unwrap();
+        writer.finish().unwrap();
+        PackedResourceIo::open(&path).unwrap()
+    }
+
+    #[test]
+    fn test_pack_read() {
+        let io = make_pack("fyrox_test_pack_read.fyrpack");
+        let text = "The quick brown fox jumps over the lazy dog. ".repeat(100);
+
+        assert_eq!(
+            block_on(io.load_file(Path::new("data/a.txt"))).unwrap(),
+            text.as_bytes()
+        );
+        assert_eq!(
+            block_on(io.load_file(Path::new("data/models/b.txt"))).unwrap(),
+            text.as_bytes()
+        );
+        assert!(block_on(io.load_file(Path::new("data/missing.txt"))).is_err());
+
+        let mut reader = block_on(io.file_reader(Path::new("data/./c.bin"))).unwrap();
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).unwrap();
+        assert_eq!(bytes, [1, 2, 3]);
+
+        // Compressed entries must actually be smaller.
+        assert!(io
+            .entries()
+            .filter(|(_, e)| e.compression != PackCompression::None)
+            .all(|(_, e)| e.stored_size < e.size));
+    }
+
+    #[test]
+    fn test_pack_directories() {
+        let io = make_pack("fyrox_test_pack_directories.fyrpack");
+
+        assert!(block_on(io.is_dir(Path::new("data/models"))));
+        assert!(block_on(io.is_file(Path::new("data/a.txt"))));
+        assert!(io.exists_sync(Path::new("data")));
+        assert!(!io.can_write());
+
+        let mut children = block_on(io.read_directory(Path::new("data")))
+            .unwrap()
+            .collect::<Vec<_>>();
+        children.sort();
+        assert_eq!(
+            children,
+            [
+                PathBuf::from("data/a.txt"),
+                PathBuf::from("data/c.bin"),
+                PathBuf::from("data/models")
+            ]
+        );
+
+        let all = block_on(io.walk_directory(Path::new("."), usize::MAX))
+            .unwrap()
+            .count();
+        // Three files, two directories and the root itself.
+        assert_eq!(all, 6);
+    }
+
+    #[test]
+    fn test_pack_resource_manager() {
+        let uuid = Uuid::new_v4();
+        let mut registry = RegistryContainer::new();
+        registry.insert(uuid, PathBuf::from("data/a.txt"));
+
+        let path = std::env::temp_dir().join("fyrox_test_pack_resource_manager.fyrpack");
+        let mut writer = PackWriter::new(File::create(&path).unwrap()).unwrap();
+        writer
+            .add_file(
+                ResourceRegistry::DEFAULT_PATH,
+                registry.serialize_to_string().unwrap().as_bytes(),
+                PackCompression::Lz4,
+            )
+            .unwrap();
+        writer
+            .add_file("data/a.txt", b"packed text", PackCompression::Zstd)
+            .unwrap();
+        writer.finish().unwrap();
+
+        let manager = ResourceManager::new(
+            Arc::new(PackedResourceIo::open(&path).unwrap()),
+            Arc::new(Default::default()),
+        );
+        manager.add_loader(TextLoader);
+        manager.update_or_load_registry();
+
+        let resource = manager.request::<Text>("data/a.txt");
+        assert_eq!(resource.resource_uuid(), uuid);
+        block_on(resource.clone()).unwrap();
+        assert_eq!(resource.data_ref().text, "packed text");
+    }
+
+    #[test]
+    fn test_pack_corrupted_index() {
+        let path = std::env::temp_dir().join("fyrox_test_pack_corrupted_index.fyrpack");
+        let mut file = File::create(&path).unwrap();
+        file.write_all(MAGIC).unwrap();
+        file.write_all(&VERSION.to_le_bytes()).unwrap();
+        file.write_all(&1u32.to_le_bytes()).unwrap();
+        file.write_all(&HEADER_SIZE.to_le_bytes()).unwrap();
+        // An entry, whose offset + stored size overflows.
+        file.write_all(&1u32.to_le_bytes()).unwrap();
+        file.write_all(b"a").unwrap();
+        file.write_all(&u64::MAX.to_le_bytes()).unwrap();
+        file.write_all(&2u64.to_le_bytes()).unwrap();
+        file.write_all(&2u64.to_le_bytes()).unwrap();
+        file.write_all(&[0]).unwrap();
+        drop(file);
+
+        assert!(PackedResourceIo::open(&path).is_err());
+    }
+
+    #[test]
+    fn test_pack_invalid_entry_size() {
+        let write_pack = |name: &str, size: u64, compression: PackCompression| {
+            let path = std::env::temp_dir().join(name);
+            let mut file = File::create(&path).unwrap();
+            file.write_all(MAGIC).unwrap();
+            file.write_all(&VERSION.to_le_bytes()).unwrap();
+            file.write_all(&1u32.to_le_bytes()).unwrap();
+            file.write_all(&(HEADER_SIZE + 4).to_le_bytes()).unwrap();
+            file.write_all(&[1, 2, 3, 4]).unwrap();
+            file.write_all(&1u32.to_le_bytes()).unwrap();
+            file.write_all(b"a").unwrap();
+            file.write_all(&HEADER_SIZE.to_le_bytes()).unwrap();
+            file.write_all(&4u64.to_le_bytes()).unwrap();
+            file.write_all(&size.to_le_bytes()).unwrap();
+            file.write_all(&[compression.id()]).unwrap();
+            drop(file);
+            PackedResourceIo::open(&path)
+        };
+
+        assert!(write_pack("fyrox_test_pack_size_ok.fyrpack", 4, PackCompression::None).is_ok());
+        assert!(write_pack("fyrox_test_pack_size_raw.fyrpack", 5, PackCompression::None).is_err());
+        assert!(write_pack(
+            "fyrox_test_pack_size_lz4.fyrpack",
+            1 << 40,
+            PackCompression::Lz4
+        )
+        .is_err());
+        assert!(write_pack(
+            "fyrox_test_pack_size_zstd.fyrpack",
+            u64::MAX,
+            PackCompression::Zstd
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,218 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Prioritized queue of pending resource loads. See [`LoadPriority`] docs for more info.
+
+use crate::{
+    state::{LoadError, ResourceState},
+    UntypedResource,
+};
+use fyrox_core::{log::Log, parking_lot::Mutex};
+use std::{path::PathBuf, sync::Arc};
+
+/// Priority of a resource load. When there are more pending loads than the task pool can handle at
+/// once, loads with higher priority are started first. Loads with the same priority are started in
+/// the order they were requested.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LoadPriority {
+    /// Resources that could be loaded in background, for example assets of distant parts of a
+    /// level.
+    Low,
+    /// Default priority.
+    #[default]
+    Normal,
+    /// Resources that are needed as soon as possible, for example assets of a loading screen.
+    High,
+}
+
+struct QueuedLoad {
+    resource: UntypedResource,
+    priority: LoadPriority,
+    reload: bool,
+    // An error of a failed resource, that is being reloaded. It is restored if the reload is
+    // cancelled.
+    previous_error: Option<(PathBuf, LoadError)>,
+    order: u64,
+}
+
+#[derive(Default)]
+struct LoadQueueState {
+    entries: Vec<QueuedLoad>,
+    counter: u64,
+}
+
+/// A shared queue of pending resource loads. Every load task spawned by the resource manager picks
+/// the pending load with the highest priority, instead of the one it was spawned for.
+#[derive(Clone, Default)]
+pub(crate) struct LoadQueue {
+    state: Arc<Mutex<LoadQueueState>>,
+}
+
+impl LoadQueue {
+    /// Amount of strong references to a resource that are held by the resource manager itself and
+    /// the queue. If a resource has no other references, nobody needs it anymore.
+    const INTERNAL_REFERENCES: usize = 2;
+
+    /// Adds a new pending load of the given resource. The resource is switched to
+    /// [`ResourceState::Pending`] state, unless it is a reload of a resource with data - such
+    /// resource keeps its data until the new data is loaded.
+    pub(crate) fn push(&self, resource: UntypedResource, priority: LoadPriority, reload: bool) {
+        let previous_error = {
+            let mut header = resource.lock();
+            let previous_error = match header.state {
+                ResourceState::LoadError {
+                    ref path,
+                    ref error,
+                } if reload => Some((path.clone(), error.clone())),
+                _ => None,
+            };
+            if !header.state.is_ok() && !header.state.is_loading() {
+                header.state.switch_to_pending_state();
+            }
+            previous_error
+        };
+
+        let mut state = self.state.lock();
+        let order = state.counter;
+        state.counter += 1;
+        state.entries.push(QueuedLoad {
+            resource,
+            priority,
+            reload,
+            previous_error,
+            order,
+        });
+    }
+
+    /// Raises priority of a pending load of the given resource. Does nothing if the resource is not
+    /// in the queue or if it already has higher priority.
+    pub(crate) fn raise_priority(&self, resource: &UntypedResource, priority: LoadPriority) {
+        let mut state = self.state.lock();
+        if let Some(entry) = state
+            .entries
+            .iter_mut()
+            .find(|entry| &entry.resource == resource)
+        {
+            entry.priority = entry.priority.max(priority);
+        }
+    }
+
+    /// Takes the pending load with the highest priority. Loads of resources that are no longer used
+    /// by anyone are cancelled on the way: the resource returns to the state it had before the load
+    /// was queued and could be requested again later. It is [`ResourceState::Unloaded`] for regular
+    /// loads, while reloaded resources keep their current data or their previous load error.
+    pub(crate) fn pop(&self) -> Option<(UntypedResource, bool)> {
+        loop {
+            let entry = {
+                let mut state = self.state.lock();
+                let index = state
+                    .entries
+                    .iter()
+                    .enumerate()
+                    .max_by(|(_, a), (_, b)| {
+                        a.priority
+                            .cmp(&b.priority)
+                            .then_with(|| b.order.cmp(&a.order))
+                    })
+                    .map(|(index, _)| index)?;
+                state.entries.remove(index)
+            };
+
+            if entry.resource.use_count() > Self::INTERNAL_REFERENCES {
+                return Some((entry.resource, entry.reload));
+            }
+
+            let mut header = entry.resource.lock();
+            if header.state.is_loading() {
+                header.state.commit(match entry.previous_error {
+                    Some((path, error)) => ResourceState::LoadError { path, error },
+                    None => ResourceState::Unloaded,
+                });
+            }
+            Log::info(format!(
+                "Loading of resource {} was cancelled, because it is not used anymore.",
+                header.uuid
+            ));
+        }
+    }
+
+    /// Returns the amount of pending loads.
+    pub(crate) fn len(&self) -> usize {
+        self.state.lock().entries.len()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        queue::{LoadPriority, LoadQueue},
+        state::LoadError,
+        untyped::ResourceKind,
+        UntypedResource,
+    };
+    use fyrox_core::uuid::Uuid;
+
+    #[test]
+    fn test_load_queue_order_and_cancellation() {
+        let queue = LoadQueue::default();
+
+        let low = UntypedResource::new_pending(Uuid::new_v4(), ResourceKind::External);
+        let normal = UntypedResource::new_pending(Uuid::new_v4(), ResourceKind::External);
+        let high = UntypedResource::new_pending(Uuid::new_v4(), ResourceKind::External);
+        let dropped = UntypedResource::new_pending(Uuid::new_v4(), ResourceKind::External);
+
+        // Imitate references held by the resource manager.
+        let internal = [low.clone(), normal.clone(), high.clone(), dropped.clone()];
+
+        queue.push(low.clone(), LoadPriority::Low, false);
+        queue.push(dropped.clone(), LoadPriority::High, false);
+        queue.push(normal.clone(), LoadPriority::Normal, false);
+        queue.push(high.clone(), LoadPriority::Low, false);
+        queue.raise_priority(&high, LoadPriority::High);
+        drop(dropped);
+
+        assert_eq!(queue.len(), 4);
+        assert_eq!(queue.pop().unwrap().0, high);
+        assert_eq!(queue.pop().unwrap().0, normal);
+        assert_eq!(queue.pop().unwrap().0, low);
+        assert!(queue.pop().is_none());
+        assert!(internal[3].is_unloaded());
+    }
+
+    #[test]
+    fn test_load_queue_cancelled_reload() {
+        let queue = LoadQueue::default();
+
+        let failed = UntypedResource::new_load_error(
+            ResourceKind::External,
+            "failed.txt".into(),
+            LoadError::new("error"),
+        );
+
+        // Imitate references held by the resource manager.
+        let internal = failed.clone();
+
+        queue.push(failed, LoadPriority::Normal, true);
+        assert!(internal.is_loading());
+
+        assert!(queue.pop().is_none());
+        assert!(internal.is_failed_to_load());
+    }
+}
@@ -0,0 +1,731 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Layered virtual file system. See [`LayeredResourceIo`] docs for more info.
+
+use crate::io::{normalize_path, FileReader, FsResourceIo, PathIter, ResourceIo, ResourceIoFuture};
+use fxhash::FxHashSet;
+use fyrox_core::{io::FileError, parking_lot::RwLock};
+use std::{
+    ffi::OsStr,
+    fmt::{Debug, Formatter},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+/// Resource IO that reads and writes files in a specific directory of the file system. Paths are
+/// given relative to the directory, for example `data/scene.rgs` will be mapped to
+/// `<root>/data/scene.rgs`. It is useful to mount a directory with a mod as a layer of
+/// [`LayeredResourceIo`].
+#[derive(Debug, Clone)]
+pub struct DirectoryResourceIo {
+    root: PathBuf,
+}
+
+impl DirectoryResourceIo {
+    /// Creates a new resource IO for the given directory.
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// Returns the root directory of the resource IO.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    fn full_path(&self, path: &Path) -> Result<PathBuf, FileError> {
+        Ok(self.root.join(self.canonicalize_path(path)?))
+    }
+
+    fn strip_root(root: &Path, path: PathBuf) -> PathBuf {
+        path.strip_prefix(root)
+            .ok()
+            .and_then(|p| normalize_path(p).ok())
+            .unwrap_or(path)
+    }
+}
+
+impl ResourceIo for DirectoryResourceIo {
+    fn can_write(&self) -> bool {
+        FsResourceIo.can_write()
+    }
+
+    fn can_read_directories(&self) -> bool {
+        FsResourceIo.can_read_directories()
+    }
+
+    fn load_file<'a>(&'a self, path: &'a Path) -> ResourceIoFuture<'a, Result<Vec<u8>, FileError>> {
+        Box::pin(async move { FsResourceIo.load_file(&self.full_path(path)?).await })
+    }
+
+    fn write_file<'a>(
+        &'a self,
+        path: &'a Path,
+        data: Vec<u8>,
+    ) -> ResourceIoFuture<'a, Result<(), FileError>> {
+        Box::pin(async move { FsResourceIo.write_file(&self.full_path(path)?, data).await })
+    }
+
+    fn write_file_sync(&self, path: &Path, data: &[u8]) -> Result<(), FileError> {
+        FsResourceIo.write_file_sync(&self.full_path(path)?, data)
+    }
+
+    fn create_dir_all_sync(&self, path: &Path) -> Result<(), FileError> {
+        FsResourceIo.create_dir_all_sync(&self.full_path(path)?)
+    }
+
+    fn move_file<'a>(
+        &'a self,
+        source: &'a Path,
+        dest: &'a Path,
+    ) -> ResourceIoFuture<'a, Result<(), FileError>> {
+        Box::pin(async move {
+            FsResourceIo
+                .move_file(&self.full_path(source)?, &self.full_path(dest)?)
+                .await
+        })
+    }
+
+    fn delete_file<'a>(&'a self, path: &'a Path) -> ResourceIoFuture<'a, Result<(), FileError>> {
+        Box::pin(async move { FsResourceIo.delete_file(&self.full_path(path)?).await })
+    }
+
+    fn delete_file_sync(&self, path: &Path) -> Result<(), FileError> {
+        FsResourceIo.delete_file_sync(&self.full_path(path)?)
+    }
+
+    fn copy_file<'a>(
+        &'a self,
+        source: &'a Path,
+        dest: &'a Path,
+    ) -> ResourceIoFuture<'a, Result<(), FileError>> {
+        Box::pin(async move {
+            FsResourceIo
+                .copy_file(&self.full_path(source)?, &self.full_path(dest)?)
+                .await
+        })
+    }
+
+    fn canonicalize_path<'a>(&'a self, path: &'a Path) -> Result<PathBuf, FileError> {
+        match path.strip_prefix(&self.root) {
+            Ok(relative) if path.is_absolute() => normalize_path(relative),
+            _ => normalize_path(path),
+        }
+    }
+
+    fn read_directory<'a>(
+        &'a self,
+        path: &'a Path,
+    ) -> ResourceIoFuture<'a, Result<PathIter, FileError>> {
+        Box::pin(async move {
+            let root = self.root.clone();
+            let iter = FsResourceIo
+                .read_directory(&self.full_path(path)?)
+                .await?
+                .map(move |p| Self::strip_root(&root, p));
+            let iter: PathIter = Box::new(iter);
+            Ok(iter)
+        })
+    }
+
+    fn walk_directory<'a>(
+        &'a self,
+        path: &'a Path,
+        max_depth: usize,
+    ) -> ResourceIoFuture<'a, Result<PathIter, FileError>> {
+        Box::pin(async move {
+            let root = self.root.clone();
+            let iter = FsResourceIo
+                .walk_directory(&self.full_path(path)?, max_depth)
+                .await?
+                .map(move |p| Self::strip_root(&root, p));
+            let iter: PathIter = Box::new(iter);
+            Ok(iter)
+        })
+    }
+
+    fn file_reader<'a>(
+        &'a self,
+        path: &'a Path,
+    ) -> ResourceIoFuture<'a, Result<Box<dyn FileReader>, FileError>> {
+        Box::pin(async move { FsResourceIo.file_reader(&self.full_path(path)?).await })
+    }
+
+    fn is_valid_file_name(&self, name: &OsStr) -> bool {
+        FsResourceIo.is_valid_file_name(name)
+    }
+
+    fn exists<'a>(&'a self, path: &'a Path) -> ResourceIoFuture<'a, bool> {
+        Box::pin(async move { self.exists_sync(path) })
+    }
+
+    fn exists_sync(&self, path: &Path) -> bool {
+        self.full_path(path)
+            .is_ok_and(|full_path| FsResourceIo.exists_sync(&full_path))
+    }
+
+    fn is_file<'a>(&'a self, path: &'a Path) -> ResourceIoFuture<'a, bool> {
+        Box::pin(async move {
+            match self.full_path(path) {
+                Ok(full_path) => FsResourceIo.is_file(&full_path).await,
+                Err(_) => false,
+            }
+        })
+    }
+
+    fn is_dir<'a>(&'a self, path: &'a Path) -> ResourceIoFuture<'a, bool> {
+        Box::pin(async move {
+            match self.full_path(path) {
+                Ok(full_path) => FsResourceIo.is_dir(&full_path).await,
+                Err(_) => false,
+            }
+        })
+    }
+}
+
+/// A single layer of [`LayeredResourceIo`].
+#[derive(Clone)]
+pub struct ResourceIoLayer {
+    /// Unique name of the layer, for example a name of a mod.
+    pub name: String,
+    /// Priority of the layer. Layers with higher priority override files of the layers with lower
+    /// priority. If the priorities are equal, the layer that was mounted last wins.
+    pub priority: i32,
+    /// Source of files of the layer.
+    pub io: Arc<dyn ResourceIo>,
+}
+
+impl Debug for ResourceIoLayer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ResourceIoLayer")
+            .field("name", &self.name)
+            .field("priority", &self.priority)
+            .finish()
+    }
+}
+
+impl ResourceIoLayer {
+    /// Creates a new layer.
+    pub fn new(name: impl Into<String>, priority: i32, io: Arc<dyn ResourceIo>) -> Self {
+        Self {
+            name: name.into(),
+            priority,
+            io,
+        }
+    }
+}
+
+/// Resource IO that combines multiple sources of files (directories, pack archives, etc.) into a
+/// single virtual file system. Each source is mounted as a layer with a priority and a path
+/// resolves to the highest-priority layer that has it. This way mods and DLCs could override
+/// assets of the base game without patching its files.
+///
+/// Layers could be mounted and unmounted at runtime. Use [`crate::manager::ResourceManager::mount_layer`]
+/// and [`crate::manager::ResourceManager::unmount_layer`] to also reload all the resources affected
+/// by the change.
+///
+/// Write operations are performed on the base layer, which is the lowest-priority layer that
+/// supports writing, so mods never receive files written by the game (saves, configs, etc.). Use
+/// [`LayeredResourceIo::set_write_layer`] to write to a specific layer instead. Directory listings
+/// are merged from all the layers.
+///
+/// ## Example
+///
+/// ```rust,no_run
+/// # use fyrox_resource::{
+/// #     io::FsResourceIo,
+/// #     manager::ResourceManager,
+/// #     vfs::{DirectoryResourceIo, LayeredResourceIo, ResourceIoLayer},
+/// # };
+/// # use std::sync::Arc;
+/// fn make_resource_manager() -> (ResourceManager, Arc<LayeredResourceIo>) {
+///     let io = Arc::new(LayeredResourceIo::new());
+///     io.mount_sync(ResourceIoLayer::new("base", 0, Arc::new(FsResourceIo)));
+///     io.mount_sync(ResourceIoLayer::new(
+///         "my_mod",
+///         1,
+///         Arc::new(DirectoryResourceIo::new("mods/my_mod")),
+///     ));
+///     (ResourceManager::new(io.clone(), Default::default()), io)
+/// }
+/// ```
+#[derive(Default)]
+pub struct LayeredResourceIo {
+    // Sorted by priority in descending order.
+    layers: RwLock<Vec<ResourceIoLayer>>,
+    write_layer: RwLock<Option<String>>,
+}
+
+impl Debug for LayeredResourceIo {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LayeredResourceIo")
+            .field("layers", &*self.layers.read())
+            .field("write_layer", &*self.write_layer.read())
+            .finish()
+    }
+}
+
+impl LayeredResourceIo {
+    /// Creates a new layered resource IO without any layers.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns a list of all mounted layers sorted by priority (the highest first).
+    pub fn layers(&self) -> Vec<ResourceIoLayer> {
+        self.layers.read().clone()
+    }
+
+    fn ios(&self) -> Vec<Arc<dyn ResourceIo>> {
+        self.layers
+            .read()
+            .iter()
+            .map(|layer| layer.io.clone())
+            .collect()
+    }
+
+    /// Sets a name of the layer that will receive all write operations. `None` means that the base
+    /// layer (the lowest-priority layer that supports writing) is used.
+    pub fn set_write_layer(&self, name: Option<&str>) {
+        *self.write_layer.write() = name.map(|name| name.to_string());
+    }
+
+    /// Returns a name of the layer that was explicitly chosen for write operations, if any.
+    pub fn write_layer(&self) -> Option<String> {
+        self.write_layer.read().clone()
+    }
+
+    fn writable_io(&self) -> Result<Arc<dyn ResourceIo>, FileError> {
+        let layers = self.layers.read();
+        let layer = match self.write_layer.read().as_deref() {
+            Some(name) => layers
+                .iter()
+                .find(|l| l.name == name)
+                .ok_or_else(|| FileError::Custom(format!("There's no {name} layer!")))?,
+            None => layers
+                .iter()
+                .rev()
+                .find(|l| l.io.can_write())
+                .ok_or_else(|| FileError::Custom("There's no writable layer!".to_string()))?,
+        };
+        if !layer.io.can_write() {
+            return Err(FileError::Custom(format!(
+                "The {} layer is read-only!",
+                layer.name
+            )));
+        }
+        Ok(layer.io.clone())
+    }
+
+    async fn resolve_io(&self, path: &Path) -> Result<Arc<dyn ResourceIo>, FileError> {
+        for io in self.ios() {
+            if io.exists(path).await {
+                return Ok(io);
+            }
+        }
+        Err(FileError::Io(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            format!("{} does not exist in any layer", path.display()),
+        )))
+    }
+
+    /// Returns a name of the layer the given path resolves to.
+    pub fn resolve(&self, path: &Path) -> Option<String> {
+        self.layers
+            .read()
+            .iter()
+            .find(|layer| layer.io.exists_sync(path))
+            .map(|layer| layer.name.clone())
+    }
+
+    /// Mounts a new layer without collecting affected files. Any existing layer with the same name
+    /// is replaced.
+    pub fn mount_sync(&self, layer: ResourceIoLayer) {
+        let mut layers = self.layers.write();
+        layers.retain(|l| l.name != layer.name);
+        let index = layers
+            .iter()
+            .position(|l| l.priority <= layer.priority)
+            .unwrap_or(layers.len());
+        layers.insert(index, layer);
+    }
+
+    // Returns the files of the layer that are not overridden by any layer with higher priority.
+    // Paths are normalized, so they could be compared with the paths of the resources.
+    async fn visible_files(&self, name: &str) -> Vec<PathBuf> {
+        let (layer, higher) = {
+            let layers = self.layers.read();
+            let Some(index) = layers.iter().position(|l| l.name == name) else {
+                return Default::default();
+            };
+            (
+                layers[index].io.clone(),
+                layers[..index]
+                    .iter()
+                    .map(|l| l.io.clone())
+                    .collect::<Vec<_>>(),
+            )
+        };
+
+        let Ok(files) = layer.walk_directory(Path::new("."), usize::MAX).await else {
+            return Default::default();
+        };
+
+        let mut paths = FxHashSet::default();
+        let mut result = Vec::new();
+        for file in files {
+            let Ok(file) = normalize_path(&file) else {
+                continue;
+            };
+            if !paths.insert(file.clone()) || !layer.is_file(&file).await {
+                continue;
+            }
+            let mut overridden = false;
+            for io in higher.iter() {
+                if io.exists(&file).await {
+                    overridden = true;
+                    break;
+                }
+            }
+            if !overridden {
+                result.push(file);
+            }
+        }
+        result
+    }
+
+    /// Mounts a new layer and returns a list of files whose content may change because of the new
+    /// layer. Any existing layer with the same name is replaced.
+    pub async fn mount(&self, layer: ResourceIoLayer) -> Vec<PathBuf> {
+        let name = layer.name.clone();
+        let mut affected = self.visible_files(&name).await;
+        self.mount_sync(layer);
+        affected.extend(self.visible_files(&name).await);
+        affected.sort();
+        affected.dedup();
+        affected
+    }
+
+    /// Unmounts a layer with the given name. Returns the layer and a list of files whose content may
+    /// change because the layer is no longer available.
+    pub async fn unmount(&self, name: &str) -> Option<(ResourceIoLayer, Vec<PathBuf>)> {
+        let affected = self.visible_files(name).await;
+        let mut layers = self.layers.write();
+        let index = layers.iter().position(|l| l.name == name)?;
+        Some((layers.remove(index), affected))
+    }
+
+    async fn merged_listing(
+        &self,
+        path: &Path,
+        max_depth: Option<usize>,
+    ) -> Result<PathIter, FileError> {
+        let mut paths = FxHashSet::default();
+        let mut result = Vec::new();
+        let mut any_ok = false;
+        let mut last_error = None;
+        for io in self.ios() {
+            let listing = match max_depth {
+                Some(max_depth) => io.walk_directory(path, max_depth).await,
+                None => io.read_directory(path).await,
+            };
+            match listing {
+                Ok(iter) => {
+                    any_ok = true;
+                    for p in iter {
+                        if paths.insert(p.clone()) {
+                            result.push(p);
+                        }
+                    }
+                }
+                Err(err) => last_error = Some(err),
+            }
+        }
+        match last_error {
+            Some(err) if !any_ok => Err(err),
+            _ => {
+                let iter: PathIter = Box::new(result.into_iter());
+                Ok(iter)
+            }
+        }
+    }
+}
+
+impl ResourceIo for LayeredResourceIo {
+    fn can_write(&self) -> bool {
+        self.layers.read().iter().any(|l| l.io.can_write())
+    }
+
+    fn can_read_directories(&self) -> bool {
+        self.layers
+            .read()
+            .iter()
+            .any(|l| l.io.can_read_directories())
+    }
+
+    fn load_file<'a>(&'a self, path: &'a Path) -> ResourceIoFuture<'a, Result<Vec<u8>, FileError>> {
+        Box::pin(async move { self.resolve_io(path).await?.load_file(path).await })
+    }
+
+    fn write_file<'a>(
+        &'a self,
+        path: &'a Path,
+        data: Vec<u8>,
+    ) -> ResourceIoFuture<'a, Result<(), FileError>> {
+        Box::pin(async move { self.writable_io()?.write_file(path, data).await })
+    }
+
+    fn write_file_sync(&self, path: &Path, data: &[u8]) -> Result<(), FileError> {
+        self.writable_io()?.write_file_sync(path, data)
+    }
+
+    fn create_dir_all_sync(&self, path: &Path) -> Result<(), FileError> {
+        self.writable_io()?.create_dir_all_sync(path)
+    }
+
+    fn move_file<'a>(
+        &'a self,
+        source: &'a Path,
+        dest: &'a Path,
+    ) -> ResourceIoFuture<'a, Result<(), FileError>> {
+        Box::pin(async move { self.writable_io()?.move_file(source, dest).await })
+    }
+
+    fn delete_file<'a>(&'a self, path: &'a Path) -> ResourceIoFuture<'a, Result<(), FileError>> {
+        Box::pin(async move { self.writable_io()?.delete_file(path).await })
+    }
+
+    fn delete_file_sync(&self, path: &Path) -> Result<(), FileError> {
+        self.writable_io()?.delete_file_sync(path)
+    }
+
+    fn copy_file<'a>(
+        &'a self,
+        source: &'a Path,
+        dest: &'a Path,
+    ) -> ResourceIoFuture<'a, Result<(), FileError>> {
+        Box::pin(async move { self.writable_io()?.copy_file(source, dest).await })
+    }
+
+    fn canonicalize_path<'a>(&'a self, path: &'a Path) -> Result<PathBuf, FileError> {
+        let mut last_error = None;
+        for io in self.ios() {
+            match io.canonicalize_path(path) {
+                Ok(path) => return Ok(path),
+                Err(err) => last_error = Some(err),
+            }
+        }
+        match last_error {
+            Some(err) => Err(err),
+            None => normalize_path(path),
+        }
+    }
+
+    fn read_directory<'a>(
+        &'a self,
+        path: &'a Path,
+    ) -> ResourceIoFuture<'a, Result<PathIter, FileError>> {
+        Box::pin(self.merged_listing(path, None))
+    }
+
+    fn walk_directory<'a>(
+        &'a self,
+        path: &'a Path,
+        max_depth: usize,
+    ) -> ResourceIoFuture<'a, Result<PathIter, FileError>> {
+        Box::pin(self.merged_listing(path, Some(max_depth)))
+    }
+
+    fn file_reader<'a>(
+        &'a self,
+        path: &'a Path,
+    ) -> ResourceIoFuture<'a, Result<Box<dyn FileReader>, FileError>> {
+        Box::pin(async move { self.resolve_io(path).await?.file_reader(path).await })
+    }
+
+    fn is_valid_file_name(&self, name: &OsStr) -> bool {
+        self.layers
+            .read()
+            .iter()
+            .all(|l| l.io.is_valid_file_name(name))
+    }
+
+    fn exists<'a>(&'a self, path: &'a Path) -> ResourceIoFuture<'a, bool> {
+        Box::pin(async move { self.resolve_io(path).await.is_ok() })
+    }
+
+    fn exists_sync(&self, path: &Path) -> bool {
+        self.layers.read().iter().any(|l| l.io.exists_sync(path))
+    }
+
+    fn is_file<'a>(&'a self, path: &'a Path) -> ResourceIoFuture<'a, bool> {
+        Box::pin(async move {
+            for io in self.ios() {
+                if io.is_file(path).await {
+                    return true;
+                }
+            }
+            false
+        })
+    }
+
+    fn is_dir<'a>(&'a self, path: &'a Path) -> ResourceIoFuture<'a, bool> {
+        Box::pin(async move {
+            for io in self.ios() {
+                if io.is_dir(path).await {
+                    return true;
+                }
+            }
+            false
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        io::ResourceIo,
+        vfs::{DirectoryResourceIo, LayeredResourceIo, ResourceIoLayer},
+    };
+    use fyrox_core::{futures::executor::block_on, io::FileError};
+    use std::{
+        io::ErrorKind,
+        path::{Path, PathBuf},
+        sync::Arc,
+    };
+
+    fn make_dir(name: &str, files: &[(&str, &str)]) -> Arc<DirectoryResourceIo> {
+        let root = std::env::temp_dir().join(name);
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(&root).unwrap();
+        for (path, content) in files {
+            let path = root.join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        }
+        Arc::new(DirectoryResourceIo::new(root))
+    }
+
+    #[test]
+    fn test_layered_io() {
+        let base = make_dir(
+            "fyrox_vfs_test_base",
+            &[("data/a.txt", "base a"), ("data/b.txt", "base b")],
+        );
+        let dlc = make_dir(
+            "fyrox_vfs_test_dlc",
+            &[("data/b.txt", "dlc b"), ("data/c.txt", "dlc c")],
+        );
+        let io = LayeredResourceIo::new();
+        io.mount_sync(ResourceIoLayer::new("base", 0, base));
+
+        let load = |path: &str| {
+            String::from_utf8(block_on(io.load_file(Path::new(path))).unwrap()).unwrap()
+        };
+
+        assert_eq!(load("data/b.txt"), "base b");
+
+        let affected = block_on(io.mount(ResourceIoLayer::new("dlc", 1, dlc)));
+        assert_eq!(
+            affected,
+            [PathBuf::from("data/b.txt"), PathBuf::from("data/c.txt")]
+        );
+        assert_eq!(load("data/a.txt"), "base a");
+        assert_eq!(load("data/b.txt"), "dlc b");
+        assert_eq!(load("data/c.txt"), "dlc c");
+        assert_eq!(io.resolve(Path::new("data/b.txt")).as_deref(), Some("dlc"));
+
+        let mut listing = block_on(io.read_directory(Path::new("data")))
+            .unwrap()
+            .collect::<Vec<_>>();
+        listing.sort();
+        assert_eq!(
+            listing,
+            [
+                PathBuf::from("data/a.txt"),
+                PathBuf::from("data/b.txt"),
+                PathBuf::from("data/c.txt")
+            ]
+        );
+
+        let (layer, affected) = block_on(io.unmount("dlc")).unwrap();
+        assert_eq!(layer.name, "dlc");
+        assert_eq!(affected.len(), 2);
+        assert_eq!(load("data/b.txt"), "base b");
+        assert!(block_on(io.load_file(Path::new("data/c.txt"))).is_err());
+    }
+
+    #[test]
+    fn test_layer_priority_order() {
+        let io = LayeredResourceIo::new();
+        let a = make_dir("fyrox_vfs_test_order_a", &[("x.txt", "a")]);
+        let b = make_dir("fyrox_vfs_test_order_b", &[("x.txt", "b")]);
+        let c = make_dir("fyrox_vfs_test_order_c", &[("x.txt", "c")]);
+        io.mount_sync(ResourceIoLayer::new("a", 5, a));
+        io.mount_sync(ResourceIoLayer::new("b", 0, b));
+        io.mount_sync(ResourceIoLayer::new("c", 5, c));
+        let names = io.layers().into_iter().map(|l| l.name).collect::<Vec<_>>();
+        assert_eq!(names, ["c", "a", "b"]);
+        assert_eq!(io.resolve(Path::new("x.txt")).as_deref(), Some("c"));
+    }
+
+    #[test]
+    fn test_layered_io_write_layer() {
+        let base = make_dir("fyrox_vfs_test_write_base", &[]);
+        let dlc = make_dir("fyrox_vfs_test_write_dlc", &[]);
+        let io = LayeredResourceIo::new();
+        io.mount_sync(ResourceIoLayer::new("base", 0, base.clone()));
+        io.mount_sync(ResourceIoLayer::new("dlc", 1, dlc.clone()));
+
+        io.write_file_sync(Path::new("a.txt"), b"a").unwrap();
+        assert!(base.root().join("a.txt").exists());
+        assert!(!dlc.root().join("a.txt").exists());
+
+        io.set_write_layer(Some("dlc"));
+        io.write_file_sync(Path::new("b.txt"), b"b").unwrap();
+        assert!(dlc.root().join("b.txt").exists());
+        assert!(!base.root().join("b.txt").exists());
+
+        io.set_write_layer(Some("unknown"));
+        assert!(io.write_file_sync(Path::new("c.txt"), b"c").is_err());
+    }
+
+    #[test]
+    fn test_layered_io_normalized_affected_paths() {
+        let io = LayeredResourceIo::new();
+        let dlc = make_dir("fyrox_vfs_test_normalize_dlc", &[("data/b.txt", "dlc b")]);
+        io.mount_sync(ResourceIoLayer::new("dlc", 1, dlc));
+        let base = make_dir(
+            "fyrox_vfs_test_normalize_base",
+            &[("data/a.txt", "base a"), ("data/b.txt", "base b")],
+        );
+        let affected = block_on(io.mount(ResourceIoLayer::new("base", 0, base)));
+        assert_eq!(affected, vec![PathBuf::from("data/a.txt")]);
+    }
+
+    #[test]
+    fn test_directory_io_rejects_paths_outside_of_root() {
+        let dir = make_dir("fyrox_vfs_test_outside_root", &[("data/a.txt", "a")]);
+        assert!(block_on(dir.load_file(Path::new("data/../data/a.txt"))).is_ok());
+        let err = block_on(dir.load_file(Path::new("data/../../a.txt"))).unwrap_err();
+        assert!(matches!(err, FileError::Io(ref err) if err.kind() == ErrorKind::InvalidInput));
+        assert!(!dir.exists_sync(Path::new("../fyrox_vfs_test_outside_root/data/a.txt")));
+    }
+}
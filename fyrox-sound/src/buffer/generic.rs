@@ -43,7 +43,10 @@
 
 #![allow(clippy::manual_range_contains)]
 
-use crate::{buffer::DataSource, decoder::Decoder};
+use crate::{
+    buffer::{metadata::SoundMetadata, DataSource},
+    decoder::Decoder,
+};
 use fyrox_core::{reflect::prelude::*, visitor::prelude::*};
 use std::ops::{Deref, DerefMut};
 use std::time::Duration;
@@ -82,6 +85,8 @@ pub struct GenericBuffer {
     pub(crate) sample_rate: usize,
     #[visit(skip)]
     pub(crate) channel_duration_in_samples: usize,
+    #[visit(skip)]
+    pub(crate) metadata: SoundMetadata,
 }
 
 impl GenericBuffer {
@@ -110,6 +115,7 @@ impl GenericBuffer {
                         samples: Samples(samples),
                         channel_count,
                         sample_rate,
+                        metadata: Default::default(),
                     })
                 }
             }
@@ -121,6 +127,7 @@ impl GenericBuffer {
                 }
 
                 Ok(Self {
+                    metadata: decoder.metadata.clone(),
                     sample_rate: decoder.get_sample_rate(),
                     channel_count: decoder.get_channel_count(),
                     channel_duration_in_samples: decoder.channel_duration_in_samples(),
@@ -176,4 +183,104 @@ impl GenericBuffer {
     pub fn channel_duration_in_samples(&self) -> usize {
         self.channel_duration_in_samples
     }
+
+    /// Returns loop points and cue markers of the buffer. See [`SoundMetadata`] docs for more info.
+    #[inline]
+    pub fn metadata(&self) -> &SoundMetadata {
+        &self.metadata
+    }
+
+    /// Mixes all channels of the buffer into a single one. Does nothing if the buffer is already mono.
+    pub(crate) fn downmix_to_mono(&mut self) {
+        if self.channel_count <= 1 {
+            return;
+        }
+
+        let channel_count = self.channel_count;
+        let samples = self
+            .samples
+            .chunks_exact(channel_count)
+            .map(|frame| frame.iter().sum::<f32>() / channel_count as f32)
+            .collect();
+
+        self.samples = Samples(samples);
+        self.channel_count = 1;
+    }
+
+    /// Converts the buffer to the given sample rate using linear interpolation. Positions of loop points and
+    /// cue markers are adjusted accordingly.
+    pub(crate) fn resample(&mut self, sample_rate: usize) {
+        if sample_rate == 0 || self.sample_rate == 0 || sample_rate == self.sample_rate {
+            return;
+        }
+
+        let channel_count = self.channel_count.max(1);
+        let src_frames = self.samples.len() / channel_count;
+        let k = sample_rate as f64 / self.sample_rate as f64;
+        let dst_frames = (src_frames as f64 * k).round() as usize;
+
+        let mut samples = Vec::with_capacity(dst_frames * channel_count);
+        for frame in 0..dst_frames {
+            let position = frame as f64 / k;
+            let index = position as usize;
+            let t = (position - index as f64) as f32;
+            let next = (index + 1).min(src_frames.saturating_sub(1));
+            for channel in 0..channel_count {
+                let a = self.samples[index.min(src_frames - 1) * channel_count + channel];
+                let b = self.samples[next * channel_count + channel];
+                samples.push(a + (b - a) * t);
+            }
+        }
+
+        self.samples = Samples(samples);
+        self.channel_duration_in_samples = dst_frames;
+        self.sample_rate = sample_rate;
+        self.metadata.scale_positions(k);
+        self.metadata.validate(dst_frames);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::buffer::{
+        generic::GenericBuffer,
+        metadata::{CueMarker, SoundMetadata},
+        DataSource,
+    };
+
+    #[test]
+    fn test_downmix_to_mono() {
+        let mut buffer = GenericBuffer::new(DataSource::Raw {
+            sample_rate: 100,
+            channel_count: 2,
+            samples: vec![1.0, 0.0, 0.5, 0.5, -1.0, 1.0],
+        })
+        .unwrap();
+        buffer.downmix_to_mono();
+        assert_eq!(buffer.channel_count(), 1);
+        assert_eq!(buffer.channel_duration_in_samples(), 3);
+        assert_eq!(buffer.samples(), &[0.5, 0.5, 0.0]);
+    }
+
+    #[test]
+    fn test_resample() {
+        let mut buffer = GenericBuffer::new(DataSource::Raw {
+            sample_rate: 100,
+            channel_count: 1,
+            samples: vec![0.0, 1.0, 2.0, 3.0],
+        })
+        .unwrap();
+        buffer.metadata = SoundMetadata {
+            loop_region: None,
+            cue_markers: vec![CueMarker {
+                name: "A".to_string(),
+                position: 2,
+            }],
+        };
+        buffer.resample(200);
+        assert_eq!(buffer.sample_rate(), 200);
+        assert_eq!(buffer.channel_duration_in_samples(), 8);
+        assert_eq!(&buffer.samples()[..7], &[0.0, 0.5, 1.0, 1.5, 2.0, 2.5, 3.0]);
+        assert_eq!(buffer.metadata().cue_markers[0].position, 4);
+    }
 }
@@ -21,7 +21,7 @@
 //! Sound buffer loader.
 
 use crate::buffer::{DataSource, SoundBuffer};
-use fyrox_core::{reflect::prelude::*, uuid::Uuid, TypeUuidProvider};
+use fyrox_core::{log::Log, reflect::prelude::*, uuid::Uuid, TypeUuidProvider};
 use fyrox_resource::{
     io::ResourceIo,
     loader::{BoxedImportOptionsLoaderFuture, BoxedLoaderFuture, LoaderPayload, ResourceLoader},
@@ -38,6 +38,39 @@ use std::{path::PathBuf, sync::Arc};
 pub struct SoundBufferImportOptions {
     /// Whether the buffer is streaming or not.
     pub stream: bool,
+    /// Whether all channels of the sound should be mixed into a single one. Mono sounds take half the memory
+    /// of stereo ones and they're the only ones that could be positioned in 3D space. Ignored for streaming
+    /// buffers.
+    #[serde(default)]
+    pub downmix_to_mono: bool,
+    /// Optional sample rate (in Hz) the sound should be converted to. Could be used to reduce memory usage
+    /// of sounds with high sample rate. Ignored for streaming buffers.
+    #[serde(default)]
+    pub sample_rate: Option<u32>,
+}
+
+impl SoundBufferImportOptions {
+    fn apply(&self, path: &std::path::Path, buffer: &mut SoundBuffer) {
+        match buffer {
+            SoundBuffer::Generic(generic) => {
+                if self.downmix_to_mono {
+                    generic.downmix_to_mono();
+                }
+                if let Some(sample_rate) = self.sample_rate {
+                    generic.resample(sample_rate as usize);
+                }
+            }
+            SoundBuffer::Streaming(_) => {
+                if self.downmix_to_mono || self.sample_rate.is_some() {
+                    Log::warn(format!(
+                        "Downmixing and resampling is not supported for streaming buffers. \
+                        These options will be ignored for {}.",
+                        path.display()
+                    ));
+                }
+            }
+        }
+    }
 }
 
 impl ImportOptions for SoundBufferImportOptions {}
@@ -50,7 +83,7 @@ pub struct SoundBufferLoader {
 
 impl ResourceLoader for SoundBufferLoader {
     fn extensions(&self) -> &[&str] {
-        &["wav", "ogg"]
+        &["wav", "ogg", "flac", "mp3"]
     }
 
     fn data_type_uuid(&self) -> Uuid {
@@ -78,7 +111,10 @@ impl ResourceLoader for SoundBufferLoader {
             };
 
             match result {
-                Ok(buffer) => Ok(LoaderPayload::new(buffer)),
+                Ok(mut buffer) => {
+                    import_options.apply(&path, &mut buffer);
+                    Ok(LoaderPayload::new(buffer))
+                }
                 Err(_) => Err(LoadError::new("Invalid data source.")),
             }
         })
@@ -0,0 +1,464 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Sound metadata module.
+//!
+//! # Overview
+//!
+//! Sound files may contain additional information besides samples. This module extracts loop points and cue
+//! markers from such files, so looping music can start from an intro section and game logic can react on
+//! specific moments of a sound. The following sources of metadata are supported:
+//!
+//! - WAV: `smpl` chunk (loop points), `cue ` chunk (cue markers) and `labl` sub-chunks of `LIST` chunk (cue
+//!   marker names).
+//! - Ogg, FLAC and others: `LOOPSTART`, `LOOPEND` and `LOOPLENGTH` tags (in frames), `CUESHEET` tag and
+//!   embedded cue sheets.
+
+use fyrox_core::{reflect::prelude::*, visitor::prelude::*};
+use std::io::{Read, Seek, SeekFrom};
+use symphonia::core::{formats::Cue, meta::Tag};
+
+/// A region of a sound that should be repeated when the sound is looping. All positions are given in frames
+/// (samples per channel).
+#[derive(Clone, Debug, Default, PartialEq, Eq, Visit, Reflect)]
+pub struct LoopRegion {
+    /// First frame of the region.
+    pub start: usize,
+    /// Frame after the last frame of the region.
+    pub end: usize,
+}
+
+/// A named position in a sound.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Visit, Reflect)]
+pub struct CueMarker {
+    /// Name of the marker.
+    pub name: String,
+    /// Position of the marker in frames (samples per channel).
+    pub position: usize,
+}
+
+/// Loop points and cue markers of a sound. See module docs for more info.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Visit, Reflect)]
+pub struct SoundMetadata {
+    /// Loop region of the sound, if any. Looping sound sources play the sound from the beginning to the end
+    /// of the region and then jump to the start of the region.
+    pub loop_region: Option<LoopRegion>,
+    /// Cue markers of the sound, sorted by their position.
+    pub cue_markers: Vec<CueMarker>,
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    data.get(offset..offset + 4)
+        .map(|bytes| u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+impl SoundMetadata {
+    // Chunks larger than this are certainly not metadata, there's no need to load them into memory.
+    const MAX_CHUNK_SIZE: u32 = 16 * 1024 * 1024;
+
+    /// Returns `true` if there's no loop region and no cue markers.
+    pub fn is_empty(&self) -> bool {
+        self.loop_region.is_none() && self.cue_markers.is_empty()
+    }
+
+    /// Searches for a cue marker with the given name.
+    pub fn find_cue_marker(&self, name: &str) -> Option<&CueMarker> {
+        self.cue_markers.iter().find(|marker| marker.name == name)
+    }
+
+    /// Makes sure that all positions are within the given duration (in frames).
+    pub(crate) fn validate(&mut self, channel_duration_in_samples: usize) {
+        if let Some(region) = self.loop_region.as_mut() {
+            region.end = region.end.min(channel_duration_in_samples);
+        }
+        if self
+            .loop_region
+            .as_ref()
+            .is_some_and(|region| region.start >= region.end)
+        {
+            self.loop_region = None;
+        }
+        self.cue_markers
+            .retain(|marker| marker.position <= channel_duration_in_samples);
+        self.cue_markers.sort_by_key(|marker| marker.position);
+    }
+
+    /// Scales all positions by the given factor. Used when a sound is resampled.
+    pub(crate) fn scale_positions(&mut self, k: f64) {
+        let scale = |position: usize| (position as f64 * k).round() as usize;
+        if let Some(region) = self.loop_region.as_mut() {
+            region.start = scale(region.start);
+            region.end = scale(region.end);
+        }
+        for marker in self.cue_markers.iter_mut() {
+            marker.position = scale(marker.position);
+        }
+    }
+
+    fn merge(&mut self, other: SoundMetadata) {
+        if self.loop_region.is_none() {
+            self.loop_region = other.loop_region;
+        }
+        if self.cue_markers.is_empty() {
+            self.cue_markers = other.cue_markers;
+        }
+    }
+
+    /// Tries to read metadata chunks of a RIFF/WAVE file. The position of the reader is restored after
+    /// reading. Returns empty metadata if the data is not a WAVE file.
+    pub(crate) fn read_riff<R: Read + Seek>(reader: &mut R) -> std::io::Result<Self> {
+        let start = reader.stream_position()?;
+        let result = Self::read_riff_chunks(reader);
+        reader.seek(SeekFrom::Start(start))?;
+        result
+    }
+
+    fn read_riff_chunks<R: Read + Seek>(reader: &mut R) -> std::io::Result<Self> {
+        let mut metadata = SoundMetadata::default();
+
+        let mut header = [0u8; 12];
+        reader.read_exact(&mut header)?;
+        if &header[0..4] != b"RIFF" || &header[8..12] != b"WAVE" {
+            return Ok(metadata);
+        }
+
+        let mut cue_points = Vec::new();
+        let mut labels = Vec::new();
+
+        let mut chunk_header = [0u8; 8];
+        while reader.read_exact(&mut chunk_header).is_ok() {
+            let id = &chunk_header[0..4];
+            let size = read_u32(&chunk_header, 4).unwrap_or_default();
+            let padded_size = size as i64 + (size % 2) as i64;
+
+            if !matches!(id, b"smpl" | b"cue " | b"LIST") || size > Self::MAX_CHUNK_SIZE {
+                reader.seek(SeekFrom::Current(padded_size))?;
+                continue;
+            }
+
+            let mut data = vec![0u8; size as usize];
+            reader.read_exact(&mut data)?;
+            if size % 2 == 1 {
+                reader.seek(SeekFrom::Current(1))?;
+            }
+
+            match id {
+                b"smpl" => {
+                    // The first loop is the one that is used by the most of the software.
+                    let loop_count = read_u32(&data, 28).unwrap_or_default();
+                    if loop_count > 0 {
+                        if let (Some(start), Some(end)) = (read_u32(&data, 44), read_u32(&data, 48))
+                        {
+                            // End of the loop is inclusive in the chunk.
+                            metadata.loop_region = Some(LoopRegion {
+                                start: start as usize,
+                                end: end as usize + 1,
+                            });
+                        }
+                    }
+                }
+                b"cue " => {
+                    let count = read_u32(&data, 0).unwrap_or_default() as usize;
+                    for i in 0..count {
+                        let offset = 4 + i * 24;
+                        if let (Some(id), Some(position)) =
+                            (read_u32(&data, offset), read_u32(&data, offset + 20))
+                        {
+                            cue_points.push((id, position as usize));
+                        }
+                    }
+                }
+                b"LIST" if data.starts_with(b"adtl") => {
+                    let mut offset = 4;
+                    while let Some(sub_size) = read_u32(&data, offset + 4) {
+                        let sub_size = sub_size as usize;
+                        let body_start = offset + 8;
+                        let Some(body) = data.get(body_start..body_start + sub_size) else {
+                            break;
+                        };
+                        if &data[offset..offset + 4] == b"labl" {
+                            if let Some(id) = read_u32(body, 0) {
+                                let text = &body[4..];
+                                let text = &text
+                                    [..text.iter().position(|c| *c == 0).unwrap_or(text.len())];
+                                labels.push((id, String::from_utf8_lossy(text).into_owned()));
+                            }
+                        }
+                        offset = body_start + sub_size + sub_size % 2;
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        metadata.cue_markers = cue_points
+            .into_iter()
+            .map(|(id, position)| CueMarker {
+                name: labels
+                    .iter()
+                    .find_map(|(label_id, name)| (*label_id == id).then(|| name.clone()))
+                    .unwrap_or_else(|| format!("Cue {id}")),
+                position,
+            })
+            .collect();
+
+        Ok(metadata)
+    }
+
+    /// Extracts metadata from tags and cues provided by a decoder.
+    pub(crate) fn from_tags_and_cues(tags: &[Tag], cues: &[Cue], sample_rate: usize) -> Self {
+        let mut metadata = SoundMetadata::default();
+
+        let find_tag = |key: &str| {
+            tags.iter()
+                .find(|tag| tag.key.eq_ignore_ascii_case(key))
+                .map(|tag| tag.value.to_string())
+        };
+        let parse_frames = |key: &str| find_tag(key).and_then(|v| v.trim().parse::<usize>().ok());
+
+        if let Some(start) = parse_frames("LOOPSTART") {
+            let end = parse_frames("LOOPEND")
+                .or_else(|| parse_frames("LOOPLENGTH").map(|length| start + length))
+                .unwrap_or(usize::MAX);
+            metadata.loop_region = Some(LoopRegion { start, end });
+        }
+
+        metadata.cue_markers = cues
+            .iter()
+            .map(|cue| CueMarker {
+                name: cue
+                    .tags
+                    .iter()
+                    .find(|tag| tag.key.eq_ignore_ascii_case("TITLE"))
+                    .map(|tag| tag.value.to_string())
+                    .unwrap_or_else(|| format!("Cue {}", cue.index)),
+                position: cue.start_ts as usize,
+            })
+            .collect();
+
+        if metadata.cue_markers.is_empty() {
+            if let Some(cue_sheet) = find_tag("CUESHEET") {
+                metadata.cue_markers = parse_cue_sheet(&cue_sheet, sample_rate);
+            }
+        }
+
+        metadata
+    }
+
+    /// Combines metadata from all available sources, the ones that come first have priority.
+    pub(crate) fn combine(sources: impl IntoIterator<Item = SoundMetadata>) -> Self {
+        let mut result = SoundMetadata::default();
+        for source in sources {
+            result.merge(source);
+        }
+        result
+    }
+}
+
+// Parses `TRACK`, `TITLE` and `INDEX 01 mm:ss:ff` commands of a cue sheet, where `ff` is a frame of 1/75
+// of a second.
+fn parse_cue_sheet(cue_sheet: &str, sample_rate: usize) -> Vec<CueMarker> {
+    let mut markers = Vec::new();
+    let mut title = None;
+    let mut track = 0;
+    for line in cue_sheet.lines() {
+        let mut words = line.split_whitespace();
+        match words.next() {
+            Some("TRACK") => {
+                track += 1;
+                title = None;
+            }
+            Some("TITLE") if track > 0 => {
+                let rest = line.trim_start().trim_start_matches("TITLE").trim();
+                title = Some(rest.trim_matches('"').to_string());
+            }
+            Some("INDEX") if words.next() == Some("01") => {
+                let Some(time) = words.next() else {
+                    continue;
+                };
+                let parts = time
+                    .split(':')
+                    .filter_map(|p| p.parse::<usize>().ok())
+                    .collect::<Vec<_>>();
+                if let [minutes, seconds, frames] = parts[..] {
+                    let position =
+                        (minutes * 60 + seconds) * sample_rate + frames * sample_rate / 75;
+                    markers.push(CueMarker {
+                        name: title.clone().unwrap_or_else(|| format!("Track {track}")),
+                        position,
+                    });
+                }
+            }
+            _ => (),
+        }
+    }
+    markers
+}
+
+#[cfg(test)]
+mod test {
+    use crate::buffer::{
+        generic::GenericBuffer,
+        metadata::{parse_cue_sheet, CueMarker, LoopRegion, SoundMetadata},
+        DataSource,
+    };
+    use std::io::{Cursor, Seek};
+
+    fn chunk(id: &[u8; 4], data: &[u8]) -> Vec<u8> {
+        let mut bytes = id.to_vec();
+        bytes.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(data);
+        if data.len() % 2 == 1 {
+            bytes.push(0);
+        }
+        bytes
+    }
+
+    fn u32s(values: &[u32]) -> Vec<u8> {
+        values.iter().flat_map(|v| v.to_le_bytes()).collect()
+    }
+
+    fn make_wav_with_metadata() -> Vec<u8> {
+        let fmt = [
+            1u16.to_le_bytes().as_slice(),
+            1u16.to_le_bytes().as_slice(),
+            100u32.to_le_bytes().as_slice(),
+            200u32.to_le_bytes().as_slice(),
+            2u16.to_le_bytes().as_slice(),
+            16u16.to_le_bytes().as_slice(),
+        ]
+        .concat();
+
+        let mut smpl = u32s(&[0, 0, 0, 60, 0, 0, 0, 1, 0]);
+        smpl.extend(u32s(&[0, 0, 20, 79, 0, 0]));
+
+        let mut cue = u32s(&[2]);
+        cue.extend(u32s(&[1, 0]));
+        cue.extend_from_slice(b"data");
+        cue.extend(u32s(&[0, 0, 10]));
+        cue.extend(u32s(&[2, 0]));
+        cue.extend_from_slice(b"data");
+        cue.extend(u32s(&[0, 0, 50]));
+
+        let mut label = u32s(&[2]);
+        label.extend_from_slice(b"Drop\0");
+        let mut list = b"adtl".to_vec();
+        list.extend(chunk(b"labl", &label));
+
+        let mut body = b"WAVE".to_vec();
+        body.extend(chunk(b"fmt ", &fmt));
+        body.extend(chunk(b"data", &[0u8; 200]));
+        body.extend(chunk(b"smpl", &smpl));
+        body.extend(chunk(b"cue ", &cue));
+        body.extend(chunk(b"LIST", &list));
+        chunk(b"RIFF", &body)
+    }
+
+    #[test]
+    fn test_read_riff_metadata() {
+        let mut cursor = Cursor::new(make_wav_with_metadata());
+        let metadata = SoundMetadata::read_riff(&mut cursor).unwrap();
+        assert_eq!(cursor.stream_position().unwrap(), 0);
+        assert_eq!(
+            metadata.loop_region,
+            Some(LoopRegion { start: 20, end: 80 })
+        );
+        assert_eq!(
+            metadata.cue_markers,
+            vec![
+                CueMarker {
+                    name: "Cue 1".to_string(),
+                    position: 10
+                },
+                CueMarker {
+                    name: "Drop".to_string(),
+                    position: 50
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn test_decoded_buffer_metadata() {
+        let buffer =
+            GenericBuffer::new(DataSource::Memory(Cursor::new(make_wav_with_metadata()))).unwrap();
+        assert_eq!(buffer.channel_duration_in_samples(), 100);
+        assert_eq!(
+            buffer.metadata().loop_region,
+            Some(LoopRegion { start: 20, end: 80 })
+        );
+        assert_eq!(
+            buffer.metadata().find_cue_marker("Drop").unwrap().position,
+            50
+        );
+    }
+
+    #[test]
+    fn test_non_wav_data() {
+        let mut cursor = Cursor::new(b"OggS and some other data".to_vec());
+        assert!(SoundMetadata::read_riff(&mut cursor).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_cue_sheet() {
+        let cue_sheet = "FILE \"music.ogg\" WAVE\n  TRACK 01 AUDIO\n    TITLE \"Intro\"\n    INDEX 01 00:00:00\n  TRACK 02 AUDIO\n    INDEX 01 00:02:15\n";
+        assert_eq!(
+            parse_cue_sheet(cue_sheet, 100),
+            vec![
+                CueMarker {
+                    name: "Intro".to_string(),
+                    position: 0
+                },
+                CueMarker {
+                    name: "Track 2".to_string(),
+                    position: 220
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_and_scale() {
+        let mut metadata = SoundMetadata {
+            loop_region: Some(LoopRegion {
+                start: 10,
+                end: usize::MAX,
+            }),
+            cue_markers: vec![CueMarker {
+                name: "A".to_string(),
+                position: 40,
+            }],
+        };
+        metadata.validate(50);
+        assert_eq!(
+            metadata.loop_region,
+            Some(LoopRegion { start: 10, end: 50 })
+        );
+        metadata.scale_positions(2.0);
+        assert_eq!(
+            metadata.loop_region,
+            Some(LoopRegion {
+                start: 20,
+                end: 100
+            })
+        );
+        assert_eq!(metadata.cue_markers[0].position, 80);
+    }
+}
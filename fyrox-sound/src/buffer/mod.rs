@@ -52,6 +52,7 @@ use symphonia::core::io::MediaSource;
 
 pub mod generic;
 pub mod loader;
+pub mod metadata;
 pub mod streaming;
 
 /// Data source enumeration. Provides unified way of selecting data source for sound buffers. It can be either
@@ -67,8 +68,8 @@ pub enum DataSource {
         data: Box<dyn FileReader>,
     },
 
-    /// Data source is a memory block. Memory block must be in valid format (wav or vorbis/ogg). This variant can
-    /// be used together with virtual file system.
+    /// Data source is a memory block. Memory block must be in valid format (wav, vorbis/ogg, flac or mp3). This
+    /// variant can be used together with virtual file system.
     Memory(Cursor<Vec<u8>>),
 
     /// Raw samples in interleaved format with specified sample rate and channel count. Can be used for procedural
@@ -362,4 +363,8 @@ impl ResourceData for SoundBuffer {
     fn try_clone_box(&self) -> Option<Box<dyn ResourceData>> {
         None
     }
+
+    fn memory_usage(&self) -> usize {
+        std::mem::size_of_val(self.samples())
+    }
 }
@@ -49,7 +49,7 @@
 
 use crate::buffer::generic::Samples;
 use crate::{
-    buffer::{generic::GenericBuffer, DataSource, RawStreamingDataSource},
+    buffer::{generic::GenericBuffer, metadata::SoundMetadata, DataSource, RawStreamingDataSource},
     decoder::Decoder,
     error::SoundError,
 };
@@ -121,6 +121,13 @@ impl StreamingSource {
         }
     }
 
+    fn metadata(&self) -> SoundMetadata {
+        match self {
+            StreamingSource::Decoder(decoder) => decoder.metadata.clone(),
+            StreamingSource::Null | StreamingSource::Raw(_) => Default::default(),
+        }
+    }
+
     fn rewind(&mut self) -> Result<(), SoundError> {
         match self {
             StreamingSource::Null => Ok(()),
@@ -193,6 +200,7 @@ impl StreamingBuffer {
                 sample_rate: streaming_source.sample_rate(),
                 channel_count: streaming_source.channel_count(),
                 channel_duration_in_samples: streaming_source.channel_duration_in_samples(),
+                metadata: streaming_source.metadata(),
             },
             use_count: 0,
             streaming_source,
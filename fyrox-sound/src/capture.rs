@@ -0,0 +1,824 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Audio capture module.
+//!
+//! # Overview
+//!
+//! Audio capture receives samples from an input device (microphone) and provides them in three ways:
+//!
+//! - Through callbacks, that are called every time a new portion of samples is captured. This is the most
+//!   flexible way and it is suitable for voice chats, level meters, speech recognition, etc.
+//! - Through streaming sound buffers (see [`AudioCapture::make_streaming_buffer`]), that can be played by sound
+//!   sources as any other sound. Keep in mind, that streaming buffers read data in large blocks, so this way has
+//!   noticeable latency and it is suitable mostly for monitoring.
+//! - Through recordings (see [`AudioCapture::start_recording`]), that collect all captured samples in memory and
+//!   can be saved to a WAV file later on.
+//!
+//! Real input devices are available only with `input` feature. Unlike the output, which works on every
+//! platform supported by tinyaudio, input devices are supported only on Linux (ALSA - the same backend
+//! that is used for output on Linux). Windows (WASAPI), macOS and iOS (CoreAudio), Android (AAudio) and
+//! WebAssembly (WebAudio) have no input backend yet, [`AudioCapture::new`] returns [`SoundError::NoBackend`]
+//! there. Audio capture can work without any device as well,
+//! in this case samples can be fed manually using [`AudioCapture::feed`], or from a sound file using
+//! [`FileCaptureDevice`]. This is useful for tests and headless tools.
+//!
+//! # Usage
+//!
+//! ```no_run
+//! use fyrox_sound::capture::{AudioCapture, CaptureParameters};
+//!
+//! let capture = AudioCapture::without_device(CaptureParameters {
+//!     sample_rate: 44100,
+//!     channel_count: 1,
+//! })
+//! .unwrap();
+//!
+//! capture.add_callback(|samples, parameters| {
+//!     println!("Captured {} samples at {} Hz", samples.len(), parameters.sample_rate);
+//! });
+//!
+//! capture.start_recording();
+//! capture.feed(&[0.0, 0.25, 0.5]);
+//! let recording = capture.stop_recording().unwrap();
+//! assert_eq!(recording.samples.len(), 3);
+//! ```
+
+use crate::{
+    buffer::streaming::StreamingBuffer,
+    buffer::{
+        generic::GenericBuffer, DataSource, RawStreamingDataSource, SoundBufferResource,
+        SoundBufferResourceExtension, SoundBufferResourceLoadError,
+    },
+    error::SoundError,
+};
+use fyrox_core::SafeLock;
+use std::{
+    collections::VecDeque,
+    fmt::{Debug, Formatter},
+    io::{Seek, Write},
+    sync::{Arc, Mutex, MutexGuard, Weak},
+    time::Duration,
+};
+
+/// Format of captured samples.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct CaptureParameters {
+    /// Sample rate of captured samples, typical values are 44100 and 48000.
+    pub sample_rate: u32,
+    /// Amount of channels of captured samples. Samples are always interleaved.
+    pub channel_count: usize,
+}
+
+impl CaptureParameters {
+    fn validate(self) -> Result<Self, SoundError> {
+        if self.sample_rate == 0 || self.channel_count == 0 {
+            Err(SoundError::InvalidParameters(format!(
+                "audio capture must have non-zero sample rate and channel count, got {self:?}"
+            )))
+        } else {
+            Ok(self)
+        }
+    }
+}
+
+/// A callback that receives interleaved captured samples.
+pub type CaptureCallback = Box<dyn FnMut(&[f32], CaptureParameters) + Send>;
+
+/// Captured audio data.
+#[derive(Clone, Debug, Default)]
+pub struct Recording {
+    /// Sample rate of the recording.
+    pub sample_rate: u32,
+    /// Amount of channels of the recording.
+    pub channel_count: usize,
+    /// Interleaved samples of the recording.
+    pub samples: Vec<f32>,
+}
+
+impl Recording {
+    /// Returns duration of the recording.
+    pub fn duration(&self) -> Duration {
+        if self.sample_rate == 0 || self.channel_count == 0 {
+            return Duration::ZERO;
+        }
+        Duration::from_secs_f64(
+            (self.samples.len() / self.channel_count) as f64 / self.sample_rate as f64,
+        )
+    }
+
+    /// Converts the recording into a raw data source, that can be used to create a generic sound buffer.
+    pub fn into_data_source(self) -> DataSource {
+        DataSource::Raw {
+            sample_rate: self.sample_rate as usize,
+            channel_count: self.channel_count,
+            samples: self.samples,
+        }
+    }
+
+    /// Writes the recording in WAV format (32-bit float samples) to the given writer.
+    pub fn write_wav<W: Write + Seek>(&self, writer: W) -> Result<(), SoundError> {
+        fn convert(err: hound::Error) -> SoundError {
+            match err {
+                hound::Error::IoError(err) => SoundError::Io(err),
+                err => SoundError::Io(std::io::Error::other(err.to_string())),
+            }
+        }
+
+        let spec = hound::WavSpec {
+            channels: self.channel_count as u16,
+            sample_rate: self.sample_rate,
+            bits_per_sample: 32,
+            sample_format: hound::SampleFormat::Float,
+        };
+        let mut wav_writer = hound::WavWriter::new(writer, spec).map_err(convert)?;
+        for &sample in self.samples.iter() {
+            wav_writer.write_sample(sample).map_err(convert)?;
+        }
+        wav_writer.finalize().map_err(convert)
+    }
+}
+
+type SharedQueue = Arc<Mutex<VecDeque<f32>>>;
+
+/// Raw streaming data source, that produces captured samples. Use [`AudioCapture::make_streaming_buffer`]
+/// to create a streaming buffer with this source.
+pub struct CaptureStream {
+    queue: SharedQueue,
+    local: VecDeque<f32>,
+    sample_rate: usize,
+    channel_count: usize,
+}
+
+impl Debug for CaptureStream {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CaptureStream")
+            .field("sample_rate", &self.sample_rate)
+            .field("channel_count", &self.channel_count)
+            .finish()
+    }
+}
+
+impl CaptureStream {
+    // Streaming buffers can read up to this amount of frames at once, keep twice as much to handle
+    // jitter of the input device.
+    const MAX_QUEUED_FRAMES: usize = 2 * StreamingBuffer::STREAM_SAMPLE_COUNT;
+}
+
+impl Iterator for CaptureStream {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.local.is_empty() {
+            // Take everything at once to not lock the queue on every sample.
+            if let Ok(mut queue) = self.queue.lock() {
+                std::mem::swap(&mut *queue, &mut self.local);
+            }
+        }
+        // Produce silence when there's no captured data, otherwise the stream will end.
+        Some(self.local.pop_front().unwrap_or_default())
+    }
+}
+
+impl RawStreamingDataSource for CaptureStream {
+    fn sample_rate(&self) -> usize {
+        self.sample_rate
+    }
+
+    fn channel_count(&self) -> usize {
+        self.channel_count
+    }
+
+    fn channel_duration_in_samples(&self) -> usize {
+        // The stream is endless.
+        u32::MAX as usize
+    }
+}
+
+struct CaptureState {
+    parameters: CaptureParameters,
+    callbacks: Vec<CaptureCallback>,
+    streams: Vec<Weak<Mutex<VecDeque<f32>>>>,
+    recording: Option<Vec<f32>>,
+    #[cfg(feature = "input")]
+    input_device: Option<input::InputDevice>,
+}
+
+impl CaptureState {
+    fn feed(&mut self, samples: &[f32]) {
+        let parameters = self.parameters;
+
+        for callback in self.callbacks.iter_mut() {
+            callback(samples, parameters);
+        }
+
+        if let Some(recording) = self.recording.as_mut() {
+            recording.extend_from_slice(samples);
+        }
+
+        self.streams.retain(|stream| {
+            let Some(queue) = stream.upgrade() else {
+                return false;
+            };
+            let Ok(mut queue) = queue.lock() else {
+                return false;
+            };
+            if parameters.channel_count <= 2 {
+                queue.extend(samples.iter().copied());
+            } else {
+                // Streaming buffers support only mono and stereo, so downmix everything else into mono.
+                queue.extend(
+                    samples
+                        .chunks_exact(parameters.channel_count)
+                        .map(|frame| frame.iter().sum::<f32>() / frame.len() as f32),
+                );
+            }
+            let max_len = CaptureStream::MAX_QUEUED_FRAMES * parameters.channel_count.min(2);
+            if queue.len() > max_len {
+                let excess = queue.len() - max_len;
+                queue.drain(..excess);
+            }
+            true
+        });
+    }
+}
+
+/// Audio capture, see module docs for more info. Audio capture instance can be cloned, however this is always
+/// a "shallow" clone, because actual data is wrapped in Arc.
+#[derive(Clone)]
+pub struct AudioCapture(Arc<Mutex<CaptureState>>);
+
+impl Debug for AudioCapture {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "AudioCapture({:?})", self.parameters())
+    }
+}
+
+impl AudioCapture {
+    /// Creates new audio capture that receives samples from the default input device of the OS. Parameters of
+    /// the capture are defined by the device.
+    ///
+    /// # Platform support
+    ///
+    /// Only Linux (ALSA) is supported at the moment. Returns [`SoundError::NoBackend`] on every other
+    /// platform, use [`Self::without_device`] with a custom device there.
+    #[cfg(feature = "input")]
+    pub fn new() -> Result<Self, SoundError> {
+        let capture = Self::new_unchecked(CaptureParameters {
+            sample_rate: 0,
+            channel_count: 0,
+        });
+        let device = input::InputDevice::new(Arc::downgrade(&capture.0))?;
+        let mut state = capture.state();
+        state.parameters = device.parameters().validate()?;
+        state.input_device = Some(device);
+        drop(state);
+        Ok(capture)
+    }
+
+    /// Creates new audio capture without any input device (so called headless mode). Samples should be fed
+    /// using [`Self::feed`] or by a fake device, such as [`FileCaptureDevice`]. Returns
+    /// [`SoundError::InvalidParameters`] if the sample rate or the channel count is zero.
+    pub fn without_device(parameters: CaptureParameters) -> Result<Self, SoundError> {
+        Ok(Self::new_unchecked(parameters.validate()?))
+    }
+
+    fn new_unchecked(parameters: CaptureParameters) -> Self {
+        Self(Arc::new(Mutex::new(CaptureState {
+            parameters,
+            callbacks: Default::default(),
+            streams: Default::default(),
+            recording: None,
+            #[cfg(feature = "input")]
+            input_device: None,
+        })))
+    }
+
+    fn state(&self) -> MutexGuard<'_, CaptureState> {
+        self.0.safe_lock().unwrap()
+    }
+
+    /// Returns parameters (format) of captured samples.
+    pub fn parameters(&self) -> CaptureParameters {
+        self.state().parameters
+    }
+
+    /// Passes interleaved samples to every consumer of the capture (callbacks, streams, recording). Input
+    /// devices call this method automatically, it could be used directly to implement custom devices.
+    pub fn feed(&self, samples: &[f32]) {
+        self.state().feed(samples)
+    }
+
+    /// Adds a new callback, that will be called every time when a new portion of samples is captured. Keep
+    /// in mind, that the callback is called from the thread of input device and while the capture is
+    /// locked, so it must not use the capture and should be as fast as possible.
+    pub fn add_callback<F>(&self, callback: F)
+    where
+        F: FnMut(&[f32], CaptureParameters) + Send + 'static,
+    {
+        self.state().callbacks.push(Box::new(callback));
+    }
+
+    /// Removes all callbacks.
+    pub fn clear_callbacks(&self) {
+        self.state().callbacks.clear();
+    }
+
+    /// Creates a new endless data source with captured samples. Every stream receives its own copy of the
+    /// samples, that were captured after the stream was created.
+    pub fn make_stream(&self) -> CaptureStream {
+        let mut state = self.state();
+        let queue = SharedQueue::default();
+        state.streams.push(Arc::downgrade(&queue));
+        CaptureStream {
+            queue,
+            local: Default::default(),
+            sample_rate: state.parameters.sample_rate as usize,
+            channel_count: state.parameters.channel_count.min(2),
+        }
+    }
+
+    /// Creates a new streaming sound buffer, that plays captured samples. See module docs for latency notes.
+    pub fn make_streaming_buffer(
+        &self,
+    ) -> Result<SoundBufferResource, SoundBufferResourceLoadError> {
+        SoundBufferResource::new_streaming(DataSource::RawStreaming(Box::new(self.make_stream())))
+    }
+
+    /// Starts a new recording. All samples captured after this call will be stored in memory until
+    /// [`Self::stop_recording`] is called. Previous recording (if any) is discarded.
+    pub fn start_recording(&self) {
+        self.state().recording = Some(Vec::new());
+    }
+
+    /// Returns `true` if the capture is recording at the moment.
+    pub fn is_recording(&self) -> bool {
+        self.state().recording.is_some()
+    }
+
+    /// Stops current recording and returns it. Returns `None` if there was no recording.
+    pub fn stop_recording(&self) -> Option<Recording> {
+        let mut state = self.state();
+        let parameters = state.parameters;
+        state.recording.take().map(|samples| Recording {
+            sample_rate: parameters.sample_rate,
+            channel_count: parameters.channel_count,
+            samples,
+        })
+    }
+}
+
+/// A fake input device, that feeds samples from a sound file (or any other data source) to an audio capture.
+/// It is useful to test audio capture pipelines without real hardware.
+pub struct FileCaptureDevice {
+    buffer: GenericBuffer,
+    position: usize,
+    looping: bool,
+}
+
+impl Debug for FileCaptureDevice {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FileCaptureDevice")
+            .field("parameters", &self.parameters())
+            .field("position", &self.position)
+            .field("looping", &self.looping)
+            .finish()
+    }
+}
+
+impl FileCaptureDevice {
+    /// Creates new device from the given data source. The data is decoded entirely on creation.
+    pub fn new(data_source: DataSource) -> Result<Self, SoundBufferResourceLoadError> {
+        Ok(Self {
+            buffer: GenericBuffer::new(data_source)?,
+            position: 0,
+            looping: false,
+        })
+    }
+
+    /// Returns format of the samples of the device. Use it to create an audio capture with
+    /// [`AudioCapture::without_device`].
+    pub fn parameters(&self) -> CaptureParameters {
+        CaptureParameters {
+            sample_rate: self.buffer.sample_rate() as u32,
+            channel_count: self.buffer.channel_count(),
+        }
+    }
+
+    /// Sets whether the device should restart from the beginning when it reaches the end of the data.
+    pub fn set_looping(&mut self, looping: bool) {
+        self.looping = looping;
+    }
+
+    /// Returns `true` if the device is looping.
+    pub fn is_looping(&self) -> bool {
+        self.looping
+    }
+
+    /// Returns `true` if the device has fed all of its data and it is not looping.
+    pub fn is_finished(&self) -> bool {
+        !self.looping && self.position >= self.buffer.samples().len()
+    }
+
+    /// Feeds next `frame_count` frames of the data to the given capture and returns the amount of frames that
+    /// were actually fed. Call it periodically (with the amount of frames that corresponds to the elapsed time)
+    /// to simulate a real device.
+    pub fn pump(&mut self, capture: &AudioCapture, frame_count: usize) -> usize {
+        let channel_count = self.buffer.channel_count();
+        let samples = self.buffer.samples();
+        if samples.is_empty() {
+            return 0;
+        }
+
+        let mut chunk = Vec::with_capacity(frame_count * channel_count);
+        while chunk.len() < frame_count * channel_count {
+            if self.position >= samples.len() {
+                if self.looping {
+                    self.position = 0;
+                } else {
+                    break;
+                }
+            }
+            let count =
+                (frame_count * channel_count - chunk.len()).min(samples.len() - self.position);
+            chunk.extend_from_slice(&samples[self.position..self.position + count]);
+            self.position += count;
+        }
+
+        if !chunk.is_empty() {
+            capture.feed(&chunk);
+        }
+
+        chunk.len() / channel_count
+    }
+}
+
+#[cfg(feature = "input")]
+mod input {
+    #[cfg(not(target_os = "linux"))]
+    pub use unsupported::InputDevice;
+
+    #[cfg(target_os = "linux")]
+    pub use alsa::InputDevice;
+
+    #[cfg(not(target_os = "linux"))]
+    mod unsupported {
+        use crate::{
+            capture::{CaptureParameters, CaptureState},
+            error::SoundError,
+        };
+        use std::sync::{Mutex, Weak};
+
+        pub enum InputDevice {}
+
+        impl InputDevice {
+            pub fn new(_state: Weak<Mutex<CaptureState>>) -> Result<Self, SoundError> {
+                Err(SoundError::NoBackend)
+            }
+
+            pub fn parameters(&self) -> CaptureParameters {
+                match *self {}
+            }
+        }
+    }
+
+    // Linux input device via ALSA. It mirrors the output device of tinyaudio: the device is read
+    // in blocking mode by a separate thread, which feeds the captured samples to the capture.
+    #[cfg(target_os = "linux")]
+    mod alsa {
+        use crate::{
+            capture::{CaptureParameters, CaptureState},
+            error::SoundError,
+        };
+        use alsa_sys::*;
+        use fyrox_core::{log::Log, SafeLock};
+        use std::{
+            ffi::{CStr, CString},
+            os::raw::{c_int, c_uint, c_ulong},
+            sync::{
+                atomic::{AtomicBool, Ordering},
+                Arc, Mutex, Weak,
+            },
+            thread::JoinHandle,
+        };
+
+        // Amount of frames read at once, ~10 ms at 44100 Hz.
+        const PERIOD_FRAME_COUNT: usize = 441;
+
+        #[derive(Copy, Clone)]
+        struct Pcm(*mut snd_pcm_t);
+
+        // The handle is used either by the thread that reads the data, or by the device when the
+        // thread is stopped.
+        unsafe impl Send for Pcm {}
+
+        pub struct InputDevice {
+            pcm: Pcm,
+            parameters: CaptureParameters,
+            is_running: Arc<AtomicBool>,
+            thread: Option<JoinHandle<()>>,
+        }
+
+        fn check(err_code: c_int) -> Result<(), SoundError> {
+            if err_code < 0 {
+                let message = unsafe { CStr::from_ptr(snd_strerror(err_code)) };
+                Err(SoundError::FailedToInitializeDevice(
+                    message.to_string_lossy().to_string(),
+                ))
+            } else {
+                Ok(())
+            }
+        }
+
+        unsafe fn set_hw_params(
+            pcm: *mut snd_pcm_t,
+            hw_params: *mut snd_pcm_hw_params_t,
+        ) -> Result<CaptureParameters, SoundError> {
+            check(snd_pcm_hw_params_any(pcm, hw_params))?;
+            check(snd_pcm_hw_params_set_access(
+                pcm,
+                hw_params,
+                SND_PCM_ACCESS_RW_INTERLEAVED,
+            ))?;
+            check(snd_pcm_hw_params_set_format(
+                pcm,
+                hw_params,
+                SND_PCM_FORMAT_S16_LE,
+            ))?;
+            let mut sample_rate = 44100 as c_uint;
+            check(snd_pcm_hw_params_set_rate_near(
+                pcm,
+                hw_params,
+                &mut sample_rate,
+                std::ptr::null_mut(),
+            ))?;
+            // Microphones are mono in most cases, but some devices support stereo only.
+            let mut channel_count = 1 as c_uint;
+            check(snd_pcm_hw_params_set_channels_near(
+                pcm,
+                hw_params,
+                &mut channel_count,
+            ))?;
+            let mut period = PERIOD_FRAME_COUNT as snd_pcm_uframes_t;
+            let mut direction = 0;
+            check(snd_pcm_hw_params_set_period_size_near(
+                pcm,
+                hw_params,
+                &mut period,
+                &mut direction,
+            ))?;
+            check(snd_pcm_hw_params(pcm, hw_params))?;
+            Ok(CaptureParameters {
+                sample_rate,
+                channel_count: channel_count as usize,
+            })
+        }
+
+        unsafe fn configure(pcm: *mut snd_pcm_t) -> Result<CaptureParameters, SoundError> {
+            let mut hw_params = std::ptr::null_mut();
+            check(snd_pcm_hw_params_malloc(&mut hw_params))?;
+            let result = set_hw_params(pcm, hw_params);
+            snd_pcm_hw_params_free(hw_params);
+            let parameters = result?;
+            check(snd_pcm_prepare(pcm))?;
+            check(snd_pcm_start(pcm))?;
+            Ok(parameters)
+        }
+
+        fn read_loop(
+            pcm: Pcm,
+            parameters: CaptureParameters,
+            state: Weak<Mutex<CaptureState>>,
+            is_running: Arc<AtomicBool>,
+        ) {
+            let mut input = vec![0i16; PERIOD_FRAME_COUNT * parameters.channel_count];
+            let mut samples = Vec::with_capacity(input.len());
+            while is_running.load(Ordering::SeqCst) {
+                let frames = unsafe {
+                    snd_pcm_readi(
+                        pcm.0,
+                        input.as_mut_ptr() as *mut _,
+                        PERIOD_FRAME_COUNT as c_ulong,
+                    )
+                };
+                if frames < 0 {
+                    // Try to recover from overruns and other errors and read again. Errors that cannot
+                    // be recovered (for example, when the device was unplugged) stop the capture.
+                    let err_code = unsafe { snd_pcm_recover(pcm.0, frames as c_int, 1) };
+                    if let Err(err) = check(err_code) {
+                        Log::err(format!("Audio capture stopped. Reason: {err}"));
+                        break;
+                    }
+                    continue;
+                }
+
+                let Some(state) = state.upgrade() else {
+                    break;
+                };
+                samples.clear();
+                samples.extend(
+                    input[..frames as usize * parameters.channel_count]
+                        .iter()
+                        .map(|s| *s as f32 / i16::MAX as f32),
+                );
+                if let Ok(mut state) = state.safe_lock() {
+                    state.feed(&samples);
+                };
+            }
+        }
+
+        impl InputDevice {
+            pub fn new(state: Weak<Mutex<CaptureState>>) -> Result<Self, SoundError> {
+                let name = CString::new("default").unwrap();
+                let mut pcm = std::ptr::null_mut();
+                unsafe {
+                    check(snd_pcm_open(
+                        &mut pcm,
+                        name.as_ptr(),
+                        SND_PCM_STREAM_CAPTURE,
+                        0,
+                    ))?;
+                }
+                let pcm = Pcm(pcm);
+                let parameters = match unsafe { configure(pcm.0) } {
+                    Ok(parameters) => parameters,
+                    Err(err) => {
+                        unsafe { snd_pcm_close(pcm.0) };
+                        return Err(err);
+                    }
+                };
+
+                let is_running = Arc::new(AtomicBool::new(true));
+                let thread = {
+                    let is_running = is_running.clone();
+                    std::thread::Builder::new()
+                        .name("AlsaDataReceiver".to_string())
+                        .spawn(move || read_loop(pcm, parameters, state, is_running))
+                };
+                let thread = match thread {
+                    Ok(thread) => thread,
+                    Err(err) => {
+                        unsafe { snd_pcm_close(pcm.0) };
+                        return Err(err.into());
+                    }
+                };
+
+                Ok(Self {
+                    pcm,
+                    parameters,
+                    is_running,
+                    thread: Some(thread),
+                })
+            }
+
+            pub fn parameters(&self) -> CaptureParameters {
+                self.parameters
+            }
+        }
+
+        impl Drop for InputDevice {
+            fn drop(&mut self) {
+                self.is_running.store(false, Ordering::SeqCst);
+                if let Some(thread) = self.thread.take() {
+                    // The reading thread could hold the last reference to the capture state, then the
+                    // device is dropped on that thread. It cannot be joined then, but it will stop
+                    // right after that, because the running flag is already reset.
+                    if thread.thread().id() != std::thread::current().id() {
+                        let _ = thread.join();
+                    }
+                }
+                unsafe {
+                    snd_pcm_close(self.pcm.0);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        buffer::{DataSource, SoundBuffer},
+        capture::{AudioCapture, CaptureParameters, FileCaptureDevice},
+    };
+    use std::{
+        io::Cursor,
+        sync::{Arc, Mutex},
+    };
+
+    fn test_data_source(channel_count: usize) -> DataSource {
+        DataSource::Raw {
+            sample_rate: 100,
+            channel_count,
+            samples: (0..100 * channel_count)
+                .map(|i| i as f32 / 1000.0)
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_file_device_callback_and_recording() {
+        let mut device = FileCaptureDevice::new(test_data_source(2)).unwrap();
+        let capture = AudioCapture::without_device(device.parameters()).unwrap();
+        assert_eq!(
+            capture.parameters(),
+            CaptureParameters {
+                sample_rate: 100,
+                channel_count: 2
+            }
+        );
+
+        let received = Arc::new(Mutex::new(0));
+        let received_clone = received.clone();
+        capture.add_callback(move |samples, _| *received_clone.lock().unwrap() += samples.len());
+
+        capture.start_recording();
+        assert_eq!(device.pump(&capture, 60), 60);
+        assert_eq!(device.pump(&capture, 60), 40);
+        assert!(device.is_finished());
+        assert_eq!(device.pump(&capture, 60), 0);
+
+        let recording = capture.stop_recording().unwrap();
+        assert!(!capture.is_recording());
+        assert_eq!(*received.lock().unwrap(), 200);
+        assert_eq!(recording.samples.len(), 200);
+        assert_eq!(recording.duration().as_secs_f32(), 1.0);
+        assert_eq!(recording.samples[3], 0.003);
+    }
+
+    #[test]
+    fn test_invalid_parameters() {
+        for (sample_rate, channel_count) in [(44100, 0), (0, 1)] {
+            assert!(AudioCapture::without_device(CaptureParameters {
+                sample_rate,
+                channel_count,
+            })
+            .is_err());
+        }
+    }
+
+    #[test]
+    fn test_file_device_looping() {
+        let mut device = FileCaptureDevice::new(test_data_source(1)).unwrap();
+        device.set_looping(true);
+        let capture = AudioCapture::without_device(device.parameters()).unwrap();
+        capture.start_recording();
+        assert_eq!(device.pump(&capture, 150), 150);
+        let recording = capture.stop_recording().unwrap();
+        assert_eq!(recording.samples[100], 0.0);
+        assert_eq!(recording.samples[149], 0.049);
+    }
+
+    #[test]
+    fn test_streaming_buffer() {
+        let mut device = FileCaptureDevice::new(test_data_source(1)).unwrap();
+        let capture = AudioCapture::without_device(device.parameters()).unwrap();
+        let buffer = capture.make_streaming_buffer().unwrap();
+
+        device.pump(&capture, 100);
+
+        let mut state = buffer.state();
+        let Some(SoundBuffer::Streaming(streaming)) = state.data() else {
+            unreachable!()
+        };
+        // The first block was read on creation, when there was no data.
+        assert!(streaming.samples().iter().all(|s| *s == 0.0));
+        streaming.read_next_block();
+        assert_eq!(streaming.samples()[10], 0.01);
+        assert_eq!(streaming.samples()[99], 0.099);
+        // Silence after the end of captured data.
+        assert_eq!(streaming.samples()[100], 0.0);
+    }
+
+    #[test]
+    fn test_recording_to_wav() {
+        let mut device = FileCaptureDevice::new(test_data_source(1)).unwrap();
+        let capture = AudioCapture::without_device(device.parameters()).unwrap();
+        capture.start_recording();
+        device.pump(&capture, 100);
+        let recording = capture.stop_recording().unwrap();
+
+        let mut wav = Cursor::new(Vec::new());
+        recording.write_wav(&mut wav).unwrap();
+
+        let device = FileCaptureDevice::new(DataSource::from_memory(wav.into_inner())).unwrap();
+        assert_eq!(device.parameters(), capture.parameters());
+    }
+}
@@ -123,7 +123,7 @@ pub struct SerializationOptions {
 }
 
 /// Internal state of context.
-#[derive(Default, Debug, Clone, Reflect)]
+#[derive(Debug, Clone, Reflect)]
 pub struct State {
     sources: Pool<SoundSource>,
     listener: Listener,
@@ -131,6 +131,10 @@ pub struct State {
     renderer: Renderer,
     bus_graph: AudioBusGraph,
     distance_model: DistanceModel,
+    #[reflect(min_value = 0.0, step = 0.05)]
+    doppler_factor: f32,
+    #[reflect(min_value = 0.0, step = 1.0)]
+    speed_of_sound: f32,
     paused: bool,
     /// A set of flags, that can be used to define what should be skipped during the
     /// serialization of a sound context.
@@ -138,7 +142,27 @@ pub struct State {
     pub serialization_options: SerializationOptions,
 }
 
+impl Default for State {
+    fn default() -> Self {
+        Self {
+            sources: Pool::new(),
+            listener: Listener::new(),
+            render_duration: Default::default(),
+            renderer: Renderer::Default,
+            bus_graph: Default::default(),
+            distance_model: DistanceModel::InverseDistance,
+            doppler_factor: 1.0,
+            speed_of_sound: State::DEFAULT_SPEED_OF_SOUND,
+            paused: false,
+            serialization_options: Default::default(),
+        }
+    }
+}
+
 impl State {
+    /// Speed of sound in the air (in meters per second) at 20 degrees Celsius.
+    pub const DEFAULT_SPEED_OF_SOUND: f32 = 343.3;
+
     /// Extracts a source from the context and reserves its handle. It is used to temporarily take
     /// ownership over source, and then put node back using given ticket.
     pub fn take_reserve(
@@ -182,6 +206,30 @@ impl State {
         self.distance_model
     }
 
+    /// Sets new Doppler factor. It scales the pitch shift caused by relative motion of sound sources
+    /// and the listener. 0.0 disables Doppler effect, 1.0 (default) gives physically correct shift
+    /// and larger values exaggerate it.
+    pub fn set_doppler_factor(&mut self, doppler_factor: f32) {
+        self.doppler_factor = doppler_factor.max(0.0);
+    }
+
+    /// Returns current Doppler factor.
+    pub fn doppler_factor(&self) -> f32 {
+        self.doppler_factor
+    }
+
+    /// Sets new speed of sound (in units per second). It is used to calculate pitch shift caused by
+    /// Doppler effect. Default value is [`Self::DEFAULT_SPEED_OF_SOUND`], change it if your world
+    /// units are not meters.
+    pub fn set_speed_of_sound(&mut self, speed_of_sound: f32) {
+        self.speed_of_sound = speed_of_sound.max(f32::EPSILON);
+    }
+
+    /// Returns current speed of sound.
+    pub fn speed_of_sound(&self) -> f32 {
+        self.speed_of_sound
+    }
+
     /// Returns amount of time context spent on rendering all sound sources.
     pub fn full_render_duration(&self) -> Duration {
         self.render_duration
@@ -284,6 +332,11 @@ impl State {
             {
                 if let Some(bus_input_buffer) = self.bus_graph.try_get_bus_input_buffer(&source.bus)
                 {
+                    source.update_doppler_pitch(
+                        &self.listener,
+                        self.doppler_factor,
+                        self.speed_of_sound,
+                    );
                     source.render(sample_rate, output_device_buffer.len());
 
                     match self.renderer {
@@ -332,14 +385,8 @@ impl SoundContext {
     pub fn new() -> Self {
         Self {
             state: Some(Arc::new(Mutex::new(State {
-                sources: Pool::new(),
-                listener: Listener::new(),
-                render_duration: Default::default(),
-                renderer: Renderer::Default,
                 bus_graph: AudioBusGraph::new(),
-                distance_model: DistanceModel::InverseDistance,
-                paused: false,
-                serialization_options: Default::default(),
+                ..Default::default()
             }))),
         }
     }
@@ -397,6 +444,8 @@ impl Visit for State {
         self.renderer.visit("Renderer", &mut region)?;
         self.paused.visit("Paused", &mut region)?;
         self.distance_model.visit("DistanceModel", &mut region)?;
+        let _ = self.doppler_factor.visit("DopplerFactor", &mut region);
+        let _ = self.speed_of_sound.visit("SpeedOfSound", &mut region);
 
         Ok(())
     }
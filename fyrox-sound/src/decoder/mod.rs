@@ -30,7 +30,10 @@ use symphonia::core::probe::Hint;
 use symphonia::core::units::Time;
 use symphonia::default;
 
-use crate::{buffer::DataSource, error::SoundError};
+use crate::{
+    buffer::{metadata::SoundMetadata, DataSource},
+    error::SoundError,
+};
 
 pub(crate) struct Decoder {
     reader: Box<dyn FormatReader>,
@@ -39,6 +42,7 @@ pub(crate) struct Decoder {
     pub channel_count: usize,
     pub sample_rate: usize,
     pub channel_duration_in_samples: usize,
+    pub metadata: SoundMetadata,
 }
 
 impl std::fmt::Debug for Decoder {
@@ -50,6 +54,7 @@ impl std::fmt::Debug for Decoder {
                 "channel_duration_in_samples",
                 &self.channel_duration_in_samples,
             )
+            .field("metadata", &self.metadata)
             .finish()
     }
 }
@@ -71,6 +76,9 @@ impl Decoder {
     pub fn new(mut source: DataSource) -> Result<Self, SoundError> {
         let initial_stream_position = source.stream_position()?;
 
+        // Symphonia does not expose loop points and cue markers of WAV files, so they're read separately.
+        let riff_metadata = SoundMetadata::read_riff(&mut source).unwrap_or_default();
+
         let codec_registry = default::get_codecs();
         let probe = default::get_probe();
 
@@ -88,33 +96,54 @@ impl Decoder {
         let media_source_stream =
             MediaSourceStream::new(Box::new(source), MediaSourceStreamOptions::default());
 
-        let res = probe.format(
+        let mut res = probe.format(
             &hint,
             media_source_stream,
             &FormatOptions::default(),
             &MetadataOptions::default(),
         )?;
 
+        let mut tags = res
+            .metadata
+            .get()
+            .and_then(|m| m.current().map(|r| r.tags().to_vec()))
+            .unwrap_or_default();
+
         let mut reader = res.format;
+        if let Some(revision) = reader.metadata().current() {
+            tags.extend_from_slice(revision.tags());
+        }
+        let cues = reader.cues().to_vec();
         let tracks = reader.tracks();
         let first_track = tracks.first().ok_or(SoundError::InvalidHeader)?;
         let codec_params = &first_track.codec_params;
         let mut decoder = codec_registry.make(codec_params, &DecoderOptions::default())?;
 
-        // Get duration
-        let mut last_packet = None;
-        while let Ok(packet) = reader.next_packet() {
-            last_packet = Some(packet);
-        }
-        reader.seek(
-            SeekMode::Accurate,
-            SeekTo::Time {
-                time: Time::new(initial_stream_position, 0.0),
-                track_id: None,
-            },
-        )?;
-
-        let channel_duration_in_samples = last_packet.map(|p| p.ts as usize).unwrap_or_default();
+        // Get duration. Prefer the total frame count from the stream header, because scanning the
+        // whole stream is slow and some demuxers (FLAC) fail to parse the stream after seeking back
+        // from its end.
+        let channel_duration_in_samples = match codec_params.n_frames {
+            Some(n_frames) => n_frames as usize,
+            None => {
+                let mut last_packet = None;
+                while let Ok(packet) = reader.next_packet() {
+                    last_packet = Some(packet);
+                }
+                reader.seek(
+                    SeekMode::Accurate,
+                    SeekTo::Time {
+                        time: Time::new(initial_stream_position, 0.0),
+                        track_id: None,
+                    },
+                )?;
+
+                // Timestamp of the last packet points to its beginning, so its duration must be
+                // added as well.
+                last_packet
+                    .map(|p| (p.ts + p.dur) as usize)
+                    .unwrap_or_default()
+            }
+        };
 
         let samples = Self::samples(&mut reader, &mut decoder)?;
 
@@ -124,13 +153,22 @@ impl Decoder {
             .ok_or(SoundError::InvalidHeader)?
             .codec_params;
 
+        let sample_rate = params.sample_rate.ok_or(SoundError::InvalidHeader)? as usize;
+
+        let mut metadata = SoundMetadata::combine([
+            riff_metadata,
+            SoundMetadata::from_tags_and_cues(&tags, &cues, sample_rate),
+        ]);
+        metadata.validate(channel_duration_in_samples);
+
         Ok(Self {
             samples,
             channel_count: params.channels.unwrap_or_default().count(),
-            sample_rate: params.sample_rate.ok_or(SoundError::InvalidHeader)? as usize,
+            sample_rate,
             reader,
             decoder,
             channel_duration_in_samples,
+            metadata,
         })
     }
 
@@ -198,3 +236,114 @@ impl Decoder {
         self.channel_duration_in_samples
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{buffer::DataSource, decoder::Decoder};
+    use std::io::Cursor;
+
+    fn crc8(data: &[u8]) -> u8 {
+        data.iter().fold(0u8, |mut crc, byte| {
+            crc ^= byte;
+            for _ in 0..8 {
+                crc = if crc & 0x80 != 0 {
+                    (crc << 1) ^ 0x07
+                } else {
+                    crc << 1
+                };
+            }
+            crc
+        })
+    }
+
+    fn crc16(data: &[u8]) -> u16 {
+        data.iter().fold(0u16, |mut crc, byte| {
+            crc ^= (*byte as u16) << 8;
+            for _ in 0..8 {
+                crc = if crc & 0x8000 != 0 {
+                    (crc << 1) ^ 0x8005
+                } else {
+                    crc << 1
+                };
+            }
+            crc
+        })
+    }
+
+    // Writes a FLAC stream of 16-bit mono samples with fixed block size, every subframe is stored
+    // uncompressed (verbatim). The last block is shorter if the sample count is not a multiple of
+    // the block size. Total sample count is written to the header only if `write_sample_count` is
+    // set, otherwise it is unknown.
+    fn make_flac(
+        sample_rate: u32,
+        block_size: usize,
+        samples: &[i16],
+        write_sample_count: bool,
+    ) -> Vec<u8> {
+        let mut data = b"fLaC".to_vec();
+
+        // STREAMINFO block, it is the last metadata block.
+        data.extend_from_slice(&[0x80, 0, 0, 34]);
+        data.extend_from_slice(&(block_size as u16).to_be_bytes());
+        data.extend_from_slice(&(block_size as u16).to_be_bytes());
+        // Unknown min and max frame sizes.
+        data.extend_from_slice(&[0; 6]);
+        // Sample rate (20 bits), channel count - 1 (3 bits), bits per sample - 1 (5 bits), total
+        // sample count (36 bits).
+        let sample_count = if write_sample_count { samples.len() } else { 0 };
+        let info = ((sample_rate as u64) << 44) | (15 << 36) | sample_count as u64;
+        data.extend_from_slice(&info.to_be_bytes());
+        // MD5 of the samples is not checked when it is zero.
+        data.extend_from_slice(&[0; 16]);
+
+        for (frame_number, block) in samples.chunks(block_size).enumerate() {
+            let mut frame = vec![
+                // Sync code, fixed block size.
+                0xFF,
+                0xF8,
+                // Block size is stored at the end of the header, sample rate is from STREAMINFO.
+                0x70,
+                // Mono, 16 bits per sample.
+                0x08,
+                frame_number as u8,
+            ];
+            frame.extend_from_slice(&(block.len() as u16 - 1).to_be_bytes());
+            frame.push(crc8(&frame));
+            // Verbatim subframe.
+            frame.push(0x02);
+            for sample in block {
+                frame.extend_from_slice(&sample.to_be_bytes());
+            }
+            frame.extend_from_slice(&crc16(&frame).to_be_bytes());
+            data.extend_from_slice(&frame);
+        }
+
+        data
+    }
+
+    #[test]
+    fn test_flac_duration() {
+        let samples = (0..1000).map(|i| (i * 16) as i16).collect::<Vec<_>>();
+        let data = make_flac(8000, 256, &samples, true);
+
+        let decoder = Decoder::new(DataSource::Memory(Cursor::new(data))).unwrap();
+        assert_eq!(decoder.get_sample_rate(), 8000);
+        assert_eq!(decoder.get_channel_count(), 1);
+        assert_eq!(decoder.channel_duration_in_samples(), samples.len());
+
+        let decoded = decoder.into_samples();
+        assert_eq!(decoded.len(), samples.len());
+        assert_eq!(decoded[500], samples[500] as f32 / 32768.0);
+    }
+
+    #[test]
+    fn test_flac_duration_unknown_sample_count() {
+        let samples = (0..1000).map(|i| (i * 16) as i16).collect::<Vec<_>>();
+        let data = make_flac(8000, 256, &samples, false);
+
+        // Duration is calculated from the packets, the last block is shorter than the others, so
+        // its duration must be taken into account.
+        let decoder = Decoder::new(DataSource::Memory(Cursor::new(data))).unwrap();
+        assert_eq!(decoder.channel_duration_in_samples(), samples.len());
+    }
+}
@@ -49,7 +49,8 @@ use crate::{
     dsp::filters::{AllPass, LpfComb},
     effects::EffectRenderTrait,
 };
-use fyrox_core::{reflect::prelude::*, visitor::prelude::*};
+use fyrox_core::{reflect::prelude::*, uuid_provider, visitor::prelude::*};
+use strum_macros::{AsRefStr, EnumString, VariantNames};
 
 #[derive(Default, Debug, Clone, PartialEq, Visit)]
 struct ChannelReverb {
@@ -162,6 +163,135 @@ impl ChannelReverb {
     }
 }
 
+/// A set of parameters of [`Reverb`] effect, that fully defines its sound. Could be interpolated to
+/// smoothly change acoustics of an environment.
+#[derive(Debug, Clone, Copy, PartialEq, Visit, Reflect)]
+pub struct ReverbParameters {
+    /// See [`Reverb::set_dry`].
+    #[reflect(min_value = 0.0, max_value = 1.0, step = 0.05)]
+    pub dry: f32,
+    /// See [`Reverb::set_wet`].
+    #[reflect(min_value = 0.0, max_value = 1.0, step = 0.05)]
+    pub wet: f32,
+    /// See [`Reverb::set_decay_time`].
+    #[reflect(min_value = 0.0, step = 0.1)]
+    pub decay_time: f32,
+    /// See [`Reverb::set_fc`].
+    #[reflect(min_value = 0.0, max_value = 1.0, step = 0.01)]
+    pub fc: f32,
+}
+
+uuid_provider!(ReverbParameters = "9a1f3c51-2a0e-4d43-8d6b-8a3fb36cf0e5");
+
+impl Default for ReverbParameters {
+    fn default() -> Self {
+        Self {
+            dry: 1.0,
+            wet: 1.0,
+            decay_time: 2.0,
+            fc: Reverb::DEFAULT_FC,
+        }
+    }
+}
+
+impl ReverbParameters {
+    /// Linearly interpolates the parameters with the other ones using the given factor in `[0; 1]` range.
+    pub fn lerp(&self, other: &Self, t: f32) -> Self {
+        let t = t.clamp(0.0, 1.0);
+        let lerp = |a: f32, b: f32| a + (b - a) * t;
+        Self {
+            dry: lerp(self.dry, other.dry),
+            wet: lerp(self.wet, other.wet),
+            decay_time: lerp(self.decay_time, other.decay_time),
+            fc: lerp(self.fc, other.fc),
+        }
+    }
+
+    /// Blends a set of weighted parameters on top of the base ones. Weights are in `[0; 1]` range, where
+    /// `1.0` means that the parameters fully override the base. Overlapping parameters are averaged
+    /// proportionally to their weights. Could be used to mix acoustics of multiple environments.
+    pub fn blend<'a>(
+        &self,
+        weighted: impl IntoIterator<Item = (f32, &'a ReverbParameters)>,
+    ) -> Self {
+        let mut total_weight = 0.0;
+        let mut max_weight = 0.0f32;
+        let mut sum = ReverbParameters {
+            dry: 0.0,
+            wet: 0.0,
+            decay_time: 0.0,
+            fc: 0.0,
+        };
+        for (weight, parameters) in weighted {
+            let weight = weight.clamp(0.0, 1.0);
+            total_weight += weight;
+            max_weight = max_weight.max(weight);
+            sum.dry += parameters.dry * weight;
+            sum.wet += parameters.wet * weight;
+            sum.decay_time += parameters.decay_time * weight;
+            sum.fc += parameters.fc * weight;
+        }
+
+        if total_weight <= 0.0 {
+            return *self;
+        }
+
+        let average = ReverbParameters {
+            dry: sum.dry / total_weight,
+            wet: sum.wet / total_weight,
+            decay_time: sum.decay_time / total_weight,
+            fc: sum.fc / total_weight,
+        };
+
+        self.lerp(&average, max_weight)
+    }
+}
+
+/// Predefined sets of reverb parameters for common environments.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Default, Visit, Reflect, AsRefStr, EnumString, VariantNames,
+)]
+pub enum ReverbPreset {
+    /// Small room with short reverberation.
+    #[default]
+    Room,
+    /// Large hall with long and bright reverberation.
+    Hall,
+    /// Cave with very long and muffled reverberation.
+    Cave,
+    /// User-defined parameters.
+    Custom(ReverbParameters),
+}
+
+uuid_provider!(ReverbPreset = "0c9b3b8e-5c0b-4a43-b6a4-28bdeab5ee5c");
+
+impl ReverbPreset {
+    /// Returns reverb parameters of the preset.
+    pub fn parameters(&self) -> ReverbParameters {
+        match self {
+            ReverbPreset::Room => ReverbParameters {
+                dry: 1.0,
+                wet: 1.0,
+                decay_time: 0.8,
+                fc: 0.3,
+            },
+            ReverbPreset::Hall => ReverbParameters {
+                dry: 1.0,
+                wet: 1.0,
+                decay_time: 3.5,
+                fc: 0.35,
+            },
+            ReverbPreset::Cave => ReverbParameters {
+                dry: 1.0,
+                wet: 1.0,
+                decay_time: 6.0,
+                fc: 0.12,
+            },
+            ReverbPreset::Custom(parameters) => *parameters,
+        }
+    }
+}
+
 /// See module docs.
 #[derive(Debug, Clone, Reflect, PartialEq)]
 pub struct Reverb {
@@ -203,11 +333,12 @@ impl Default for Reverb {
 
 impl Reverb {
     const FEEDBACK: f32 = 0.84;
+    const DEFAULT_FC: f32 = 0.25615; // 11296 Hz at 44100 Hz sample rate
 
     /// Creates new instance of reverb effect with cutoff frequency of ~11.2 kHz and
     /// 5 seconds decay time.
     pub fn new() -> Self {
-        let fc = 0.25615; // 11296 Hz at 44100 Hz sample rate
+        let fc = Self::DEFAULT_FC;
 
         let decay_time = 2.0;
 
@@ -287,6 +418,29 @@ impl Reverb {
     pub fn fc(&self) -> f32 {
         self.fc
     }
+
+    /// Returns current parameters of the reverb.
+    pub fn parameters(&self) -> ReverbParameters {
+        ReverbParameters {
+            dry: self.dry,
+            wet: self.wet,
+            decay_time: self.decay_time,
+            fc: self.fc,
+        }
+    }
+
+    /// Sets new parameters of the reverb. Internal filters are updated only for the parameters that
+    /// were actually changed, so this method could be called every frame.
+    pub fn set_parameters(&mut self, parameters: &ReverbParameters) {
+        self.set_dry(parameters.dry);
+        self.set_wet(parameters.wet);
+        if self.decay_time != parameters.decay_time {
+            self.set_decay_time(parameters.decay_time);
+        }
+        if self.fc != parameters.fc {
+            self.set_fc(parameters.fc);
+        }
+    }
 }
 
 impl EffectRenderTrait for Reverb {
@@ -308,7 +462,7 @@ impl EffectRenderTrait for Reverb {
 
 #[cfg(test)]
 mod test {
-    use crate::effects::reverb::{ChannelReverb, Reverb};
+    use crate::effects::reverb::{ChannelReverb, Reverb, ReverbParameters, ReverbPreset};
 
     // Test reverberation for convergence and energy conservation law.
     #[test]
@@ -329,4 +483,29 @@ mod test {
             counter += 1;
         }
     }
+
+    #[test]
+    fn test_reverb_parameters_blend() {
+        let base = ReverbParameters::default();
+        let hall = ReverbPreset::Hall.parameters();
+        let cave = ReverbPreset::Cave.parameters();
+
+        // No zones - base parameters.
+        assert_eq!(base.blend([]), base);
+        // Fully inside of a zone.
+        assert_eq!(base.blend([(1.0, &hall)]), hall);
+        // Half way through blend distance.
+        assert_eq!(base.blend([(0.5, &hall)]), base.lerp(&hall, 0.5));
+        // Equally inside of two overlapping zones.
+        let mixed = base.blend([(1.0, &hall), (1.0, &cave)]);
+        assert_eq!(mixed.decay_time, (hall.decay_time + cave.decay_time) * 0.5);
+    }
+
+    #[test]
+    fn test_reverb_set_parameters() {
+        let mut reverb = Reverb::new();
+        let cave = ReverbPreset::Cave.parameters();
+        reverb.set_parameters(&cave);
+        assert_eq!(reverb.parameters(), cave);
+    }
 }
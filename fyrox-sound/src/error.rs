@@ -69,6 +69,10 @@ pub enum SoundError {
 
     /// A buffer is not loaded yet, consider to `await` it before use.
     BufferIsNotLoaded,
+
+    /// Invalid parameters of a device or an audio capture (for example, zero channels), exact
+    /// reason stored in inner value.
+    InvalidParameters(String),
 }
 
 impl From<std::io::Error> for SoundError {
@@ -109,6 +113,7 @@ impl Display for SoundError {
             SoundError::DecoderError(de) => write!(f, "internal decoder error: {de:?}"),
             SoundError::BufferFailedToLoad => write!(f, "a buffer failed to load"),
             SoundError::BufferIsNotLoaded => write!(f, "a buffer is not loaded yet"),
+            SoundError::InvalidParameters(reason) => write!(f, "invalid parameters: {reason}"),
         }
     }
 }
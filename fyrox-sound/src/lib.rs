@@ -23,10 +23,13 @@
 //! ## Features
 //!
 //! - Generic and spatial sounds.
-//! - WAV and OGG/Vorbis formats support.
+//! - WAV, OGG/Vorbis, FLAC and MP3 formats support.
+//! - Loop points and cue markers.
 //! - Streaming.
 //! - Head-related transfer function support ([HRTF](https://en.wikipedia.org/wiki/Head-related_transfer_function)).
 //! - Reverb effect.
+//! - Music controller with crossfades, playlists and beat-synced transitions.
+//! - Audio input capture and recording.
 //!
 //! ## Examples
 //!
@@ -92,11 +95,13 @@ pub mod buffer;
 pub mod context;
 
 pub mod bus;
+pub mod capture;
 pub mod dsp;
 pub mod effects;
 pub mod engine;
 pub mod error;
 pub mod listener;
+pub mod music;
 pub mod renderer;
 pub mod source;
 
@@ -37,6 +37,8 @@ use fyrox_core::{
 pub struct Listener {
     basis: Matrix3<f32>,
     position: Vector3<f32>,
+    #[visit(optional)]
+    velocity: Vector3<f32>,
 }
 
 impl Default for Listener {
@@ -50,6 +52,7 @@ impl Listener {
         Self {
             basis: Matrix3::identity(),
             position: Vector3::new(0.0, 0.0, 0.0),
+            velocity: Vector3::new(0.0, 0.0, 0.0),
         }
     }
 
@@ -106,6 +109,17 @@ impl Listener {
         self.position
     }
 
+    /// Sets current velocity in world space (in units per second). Velocity is used only to
+    /// calculate pitch shift caused by Doppler effect.
+    pub fn set_velocity(&mut self, velocity: Vector3<f32>) {
+        self.velocity = velocity;
+    }
+
+    /// Returns velocity of listener.
+    pub fn velocity(&self) -> Vector3<f32> {
+        self.velocity
+    }
+
     /// Returns up axis from basis.
     pub fn up_axis(&self) -> Vector3<f32> {
         self.basis.up()
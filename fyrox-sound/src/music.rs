@@ -0,0 +1,1027 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Music module.
+//!
+//! # Overview
+//!
+//! Music controller is a high-level layer on top of sound sources, that handles typical game music tasks:
+//! crossfades between tracks with various fade curves, playlists (queues) of tracks and switching of tracks or
+//! stems in sync with music tempo (on the next beat or bar).
+//!
+//! Each [`MusicTrack`] consists of one or more stems - sound sources that are played simultaneously, for
+//! example drums, bass and strings of the same composition. Stems can be faded in and out individually, which
+//! allows you to change intensity of music without switching tracks. The first stem of a track is used as a
+//! clock for beat-synced transitions and for playlist advancing.
+//!
+//! # Usage
+//!
+//! ```no_run
+//! use std::time::Duration;
+//! use fyrox_sound::{
+//!     context::SoundContext,
+//!     music::{MusicController, MusicStem, MusicTrack, Tempo, Transition, TransitionSync},
+//!     pool::Handle,
+//!     source::SoundSource,
+//! };
+//!
+//! fn setup(exploration: Handle<SoundSource>, combat: Handle<SoundSource>) -> MusicController {
+//!     let mut controller = MusicController::new();
+//!
+//!     let exploration = controller.add_track(
+//!         MusicTrack::new("Exploration").with_stem(MusicStem::new(exploration)),
+//!     );
+//!     controller.add_track(
+//!         MusicTrack::new("Combat")
+//!             .with_tempo(Tempo::new(140.0, 4))
+//!             .with_stem(MusicStem::new(combat)),
+//!     );
+//!
+//!     controller.play(exploration, Transition::default());
+//!     controller
+//! }
+//!
+//! fn enter_combat(controller: &mut MusicController) {
+//!     let combat = controller.find_track("Combat");
+//!     controller.play(
+//!         combat,
+//!         Transition::crossfade(Duration::from_secs(2)).with_sync(TransitionSync::NextBar),
+//!     );
+//! }
+//!
+//! fn update(controller: &mut MusicController, context: &SoundContext, dt: f32) {
+//!     controller.update(&mut context.state(), dt);
+//! }
+//! ```
+
+use crate::{
+    context::State,
+    pool::{Handle, Pool},
+    source::{SoundSource, Status},
+};
+use fyrox_core::{reflect::prelude::*, uuid_provider, visitor::prelude::*};
+use std::{f32::consts::FRAC_PI_2, time::Duration};
+use strum_macros::{AsRefStr, EnumString, VariantNames};
+
+/// Defines the shape of gain change during a fade.
+#[derive(
+    Copy, Clone, Debug, Default, PartialEq, Eq, Visit, Reflect, AsRefStr, EnumString, VariantNames,
+)]
+pub enum FadeCurve {
+    /// Gain changes linearly. Crossfades with this curve have a noticeable dip of loudness in the middle.
+    Linear,
+
+    /// Sine/cosine curves, that keep the sum of powers of two crossfading tracks constant. This is the best
+    /// choice for crossfades between uncorrelated tracks.
+    #[default]
+    EqualPower,
+
+    /// Smooth S-shaped curve (smoothstep), that starts and ends slowly.
+    SCurve,
+}
+
+uuid_provider!(FadeCurve = "7bc544ca-285c-491a-93dc-c25a01c7d463");
+
+impl FadeCurve {
+    /// Calculates gain for the given normalized time `t` (in `0..1` range) of a fade that goes from `from` to
+    /// `to` gain.
+    pub fn evaluate(self, from: f32, to: f32, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        let k = match self {
+            FadeCurve::Linear => t,
+            FadeCurve::EqualPower => {
+                if to >= from {
+                    (t * FRAC_PI_2).sin()
+                } else {
+                    1.0 - (t * FRAC_PI_2).cos()
+                }
+            }
+            FadeCurve::SCurve => t * t * (3.0 - 2.0 * t),
+        };
+        from + (to - from) * k
+    }
+}
+
+/// Defines a moment at which a transition starts.
+#[derive(
+    Copy, Clone, Debug, Default, PartialEq, Eq, Visit, Reflect, AsRefStr, EnumString, VariantNames,
+)]
+pub enum TransitionSync {
+    /// Transition starts right away.
+    #[default]
+    Immediate,
+
+    /// Transition starts on the next beat of the currently playing track.
+    NextBeat,
+
+    /// Transition starts on the next bar of the currently playing track.
+    NextBar,
+}
+
+uuid_provider!(TransitionSync = "44fea281-ef83-4223-a5b0-0cc4673ffaa9");
+
+/// Describes how a change of music should happen.
+#[derive(Copy, Clone, Debug, PartialEq, Visit, Reflect)]
+pub struct Transition {
+    /// Duration of the fade. Zero duration means instant switch.
+    pub duration: Duration,
+    /// Shape of the fade.
+    pub curve: FadeCurve,
+    /// A moment at which the transition starts.
+    pub sync: TransitionSync,
+}
+
+impl Default for Transition {
+    fn default() -> Self {
+        Self::crossfade(Duration::from_secs(1))
+    }
+}
+
+impl Transition {
+    /// Creates a transition that happens instantly.
+    pub fn instant() -> Self {
+        Self::crossfade(Duration::ZERO)
+    }
+
+    /// Creates an immediate equal-power crossfade of the given duration.
+    pub fn crossfade(duration: Duration) -> Self {
+        Self {
+            duration,
+            curve: FadeCurve::EqualPower,
+            sync: TransitionSync::Immediate,
+        }
+    }
+
+    /// Sets desired fade curve.
+    pub fn with_curve(mut self, curve: FadeCurve) -> Self {
+        self.curve = curve;
+        self
+    }
+
+    /// Sets desired synchronization mode.
+    pub fn with_sync(mut self, sync: TransitionSync) -> Self {
+        self.sync = sync;
+        self
+    }
+}
+
+/// Tempo of a music track. It is used to find beat and bar boundaries for synced transitions.
+#[derive(Copy, Clone, Debug, PartialEq, Visit, Reflect)]
+pub struct Tempo {
+    /// Amount of beats per minute.
+    #[reflect(min_value = 0.0)]
+    pub bpm: f32,
+    /// Amount of beats in a single bar.
+    #[reflect(min_value = 1.0)]
+    pub beats_per_bar: u32,
+    /// Time of the first beat in the track. Useful for tracks that have silence or a pickup at the
+    /// beginning.
+    pub offset: Duration,
+}
+
+impl Default for Tempo {
+    fn default() -> Self {
+        Self::new(120.0, 4)
+    }
+}
+
+impl Tempo {
+    /// Creates new tempo with the given amount of beats per minute and beats per bar.
+    pub fn new(bpm: f32, beats_per_bar: u32) -> Self {
+        Self {
+            bpm,
+            beats_per_bar,
+            offset: Duration::ZERO,
+        }
+    }
+
+    /// Sets time of the first beat in the track.
+    pub fn with_offset(mut self, offset: Duration) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    /// Returns duration of a single beat in seconds.
+    pub fn beat_duration(&self) -> f64 {
+        if self.bpm > 0.0 {
+            60.0 / self.bpm as f64
+        } else {
+            0.0
+        }
+    }
+
+    /// Returns duration of a single bar in seconds.
+    pub fn bar_duration(&self) -> f64 {
+        self.beat_duration() * self.beats_per_bar.max(1) as f64
+    }
+
+    /// Returns time (in seconds) of the closest beat or bar boundary (depending on `sync`) that is at or
+    /// after the given time.
+    pub fn next_boundary(&self, time: f64, sync: TransitionSync) -> f64 {
+        let period = match sync {
+            TransitionSync::Immediate => return time,
+            TransitionSync::NextBeat => self.beat_duration(),
+            TransitionSync::NextBar => self.bar_duration(),
+        };
+
+        let offset = self.offset.as_secs_f64();
+        if period <= 0.0 {
+            time
+        } else if time <= offset {
+            offset
+        } else {
+            offset + ((time - offset) / period).ceil() * period
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Visit)]
+struct Fade {
+    from: f32,
+    to: f32,
+    elapsed: f32,
+    duration: f32,
+    curve: FadeCurve,
+}
+
+impl Fade {
+    fn new(from: f32, to: f32, transition: &Transition) -> Self {
+        Self {
+            from,
+            to,
+            elapsed: 0.0,
+            duration: transition.duration.as_secs_f32(),
+            curve: transition.curve,
+        }
+    }
+
+    fn advance(&mut self, dt: f32) {
+        self.elapsed = (self.elapsed + dt).min(self.duration);
+    }
+
+    fn is_finished(&self) -> bool {
+        self.elapsed >= self.duration
+    }
+
+    fn value(&self) -> f32 {
+        if self.duration <= 0.0 {
+            self.to
+        } else {
+            self.curve
+                .evaluate(self.from, self.to, self.elapsed / self.duration)
+        }
+    }
+}
+
+/// A single layer of a music track.
+#[derive(Clone, Debug, Default, Visit, Reflect)]
+pub struct MusicStem {
+    source: Handle<SoundSource>,
+    #[reflect(min_value = 0.0, step = 0.05)]
+    gain: f32,
+    #[reflect(hidden)]
+    fade: Option<Fade>,
+}
+
+impl MusicStem {
+    /// Creates new stem that uses the given sound source. The source must be added to the sound context
+    /// that will be passed to [`MusicController::update`].
+    pub fn new(source: Handle<SoundSource>) -> Self {
+        Self {
+            source,
+            gain: 1.0,
+            fade: None,
+        }
+    }
+
+    /// Sets initial gain of the stem. Stems with zero gain are silent, but still play in sync with other
+    /// stems of the track, so they can be faded in later.
+    pub fn with_gain(mut self, gain: f32) -> Self {
+        self.gain = gain;
+        self
+    }
+
+    /// Returns a handle of the sound source of the stem.
+    pub fn source(&self) -> Handle<SoundSource> {
+        self.source
+    }
+
+    /// Returns current gain of the stem.
+    pub fn gain(&self) -> f32 {
+        self.fade.as_ref().map_or(self.gain, |fade| fade.value())
+    }
+
+    /// Returns `true` if the stem is fading in or out at the moment.
+    pub fn is_fading(&self) -> bool {
+        self.fade.is_some()
+    }
+}
+
+/// Playback status of a music track.
+#[derive(
+    Copy, Clone, Debug, Default, PartialEq, Eq, Visit, Reflect, AsRefStr, EnumString, VariantNames,
+)]
+pub enum TrackStatus {
+    /// The track is not playing.
+    #[default]
+    Stopped,
+
+    /// The track is playing (and possibly fading in).
+    Playing,
+
+    /// The track is fading out and will be stopped when the fade ends.
+    Stopping,
+}
+
+uuid_provider!(TrackStatus = "1e2c52f8-1717-4d37-9e93-7a3db79d5bbc");
+
+/// A music track, that consists of one or more stems. See module docs for more info.
+#[derive(Clone, Debug, Default, Visit, Reflect)]
+pub struct MusicTrack {
+    name: String,
+    stems: Vec<MusicStem>,
+    tempo: Tempo,
+    #[reflect(hidden)]
+    level: f32,
+    #[reflect(hidden)]
+    fade: Option<Fade>,
+    #[reflect(hidden)]
+    status: TrackStatus,
+}
+
+impl MusicTrack {
+    /// Creates new track without stems.
+    pub fn new<N: AsRef<str>>(name: N) -> Self {
+        Self {
+            name: name.as_ref().to_owned(),
+            ..Default::default()
+        }
+    }
+
+    /// Adds a new stem to the track.
+    pub fn with_stem(mut self, stem: MusicStem) -> Self {
+        self.stems.push(stem);
+        self
+    }
+
+    /// Sets tempo of the track.
+    pub fn with_tempo(mut self, tempo: Tempo) -> Self {
+        self.tempo = tempo;
+        self
+    }
+
+    /// Returns the name of the track.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns a slice with all stems of the track.
+    pub fn stems(&self) -> &[MusicStem] {
+        &self.stems
+    }
+
+    /// Returns tempo of the track.
+    pub fn tempo(&self) -> Tempo {
+        self.tempo
+    }
+
+    /// Returns current playback status of the track.
+    pub fn status(&self) -> TrackStatus {
+        self.status
+    }
+
+    /// Returns current fade level of the track in `0..1` range.
+    pub fn level(&self) -> f32 {
+        self.fade.as_ref().map_or(self.level, |fade| fade.value())
+    }
+
+    fn clock_source(&self) -> Handle<SoundSource> {
+        self.stems.first().map(|s| s.source).unwrap_or_default()
+    }
+
+    fn start(&mut self, state: &mut State, transition: &Transition) {
+        if self.status == TrackStatus::Stopped {
+            for stem in self.stems.iter() {
+                if let Ok(source) = state.try_get_source_mut(stem.source) {
+                    // Rewind to the beginning, so all stems start in sync.
+                    let _ = source.stop();
+                    source.play();
+                }
+            }
+        }
+        self.fade = Some(Fade::new(self.level(), 1.0, transition));
+        self.status = TrackStatus::Playing;
+    }
+
+    fn fade_out(&mut self, transition: &Transition) {
+        if self.status == TrackStatus::Playing {
+            self.fade = Some(Fade::new(self.level(), 0.0, transition));
+            self.status = TrackStatus::Stopping;
+        }
+    }
+
+    fn stop(&mut self, state: &mut State) {
+        for stem in self.stems.iter() {
+            if let Ok(source) = state.try_get_source_mut(stem.source) {
+                let _ = source.stop();
+            }
+        }
+        self.status = TrackStatus::Stopped;
+        self.level = 0.0;
+        self.fade = None;
+    }
+
+    fn update(&mut self, state: &mut State, master_gain: f32, dt: f32) {
+        if self.status == TrackStatus::Stopped {
+            return;
+        }
+
+        if let Some(fade) = self.fade.as_mut() {
+            fade.advance(dt);
+            if fade.is_finished() {
+                self.level = fade.to;
+                self.fade = None;
+            }
+        }
+
+        if self.status == TrackStatus::Stopping && self.fade.is_none() {
+            self.stop(state);
+            return;
+        }
+
+        let level = self.level();
+        for stem in self.stems.iter_mut() {
+            if let Some(fade) = stem.fade.as_mut() {
+                fade.advance(dt);
+                if fade.is_finished() {
+                    stem.gain = fade.to;
+                    stem.fade = None;
+                }
+            }
+
+            let gain = master_gain * level * stem.gain();
+            if let Ok(source) = state.try_get_source_mut(stem.source) {
+                source.set_gain(gain);
+            }
+        }
+
+        // Check if the track has ended by itself.
+        let clock = self.clock_source();
+        if state
+            .try_get_source_mut(clock)
+            .is_ok_and(|source| source.status() == Status::Stopped)
+        {
+            self.stop(state);
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Visit)]
+enum MusicAction {
+    #[default]
+    Stop,
+    Play(Handle<MusicTrack>),
+    SetStemGain {
+        track: Handle<MusicTrack>,
+        stem: usize,
+        gain: f32,
+    },
+}
+
+#[derive(Clone, Debug, Default, Visit)]
+struct PendingAction {
+    action: MusicAction,
+    transition: Transition,
+    // Time (in seconds of the clock track) at which the action should be performed.
+    time: f64,
+    // Last seen time of the clock track, used to detect wrapping of looping tracks.
+    last_time: f64,
+}
+
+/// Music controller manages a set of music tracks, see module docs for more info.
+#[derive(Clone, Debug, Visit, Reflect)]
+pub struct MusicController {
+    tracks: Pool<MusicTrack>,
+    #[reflect(hidden)]
+    current: Handle<MusicTrack>,
+    #[reflect(hidden)]
+    queue: Vec<Handle<MusicTrack>>,
+    queue_looping: bool,
+    queue_transition: Transition,
+    #[reflect(min_value = 0.0, step = 0.05)]
+    gain: f32,
+    #[reflect(hidden)]
+    pending: Vec<PendingAction>,
+}
+
+impl Default for MusicController {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MusicController {
+    /// Creates new music controller without any tracks.
+    pub fn new() -> Self {
+        Self {
+            tracks: Default::default(),
+            current: Default::default(),
+            queue: Default::default(),
+            queue_looping: false,
+            queue_transition: Default::default(),
+            gain: 1.0,
+            pending: Default::default(),
+        }
+    }
+
+    /// Adds a new track to the controller and returns its handle.
+    pub fn add_track(&mut self, track: MusicTrack) -> Handle<MusicTrack> {
+        self.tracks.spawn(track)
+    }
+
+    /// Removes a track from the controller. Sound sources of the track are left intact, but the track
+    /// won't control them anymore.
+    pub fn remove_track(&mut self, handle: Handle<MusicTrack>) -> Option<MusicTrack> {
+        if self.current == handle {
+            self.current = Handle::NONE;
+        }
+        self.queue.retain(|h| *h != handle);
+        self.tracks.try_free(handle).ok()
+    }
+
+    /// Returns a reference to a track with the given handle, if any.
+    pub fn track(&self, handle: Handle<MusicTrack>) -> Option<&MusicTrack> {
+        self.tracks.try_borrow(handle).ok()
+    }
+
+    /// Searches for a track with the given name and returns its handle, or [`Handle::NONE`] if there's
+    /// no such track.
+    pub fn find_track<N: AsRef<str>>(&self, name: N) -> Handle<MusicTrack> {
+        self.tracks
+            .pair_iter()
+            .find_map(|(h, t)| (t.name == name.as_ref()).then_some(h))
+            .unwrap_or_default()
+    }
+
+    /// Returns a handle of the currently playing track (the one that was played last).
+    pub fn current_track(&self) -> Handle<MusicTrack> {
+        self.current
+    }
+
+    /// Sets master gain of the music.
+    pub fn set_gain(&mut self, gain: f32) {
+        self.gain = gain;
+    }
+
+    /// Returns master gain of the music.
+    pub fn gain(&self) -> f32 {
+        self.gain
+    }
+
+    /// Switches the music to the given track using the given transition. The current track (if any) will
+    /// be faded out, while the new one will be faded in. Switching to the track that is already current
+    /// does nothing.
+    pub fn play(&mut self, track: Handle<MusicTrack>, transition: Transition) {
+        self.schedule(MusicAction::Play(track), transition);
+    }
+
+    /// Fades out and stops the current track. The playlist queue is cleared, otherwise the next queued
+    /// track would start right after the current one is stopped.
+    pub fn stop(&mut self, transition: Transition) {
+        self.queue.clear();
+        self.schedule(MusicAction::Stop, transition);
+    }
+
+    /// Changes gain of a stem of the given track using the given transition. It is a way to add or remove
+    /// layers of the music without switching tracks.
+    pub fn set_stem_gain(
+        &mut self,
+        track: Handle<MusicTrack>,
+        stem: usize,
+        gain: f32,
+        transition: Transition,
+    ) {
+        self.schedule(MusicAction::SetStemGain { track, stem, gain }, transition);
+    }
+
+    /// Adds a track to the end of the playlist queue. Queued tracks start playing one after another, each
+    /// time when the current track is about to end. Tracks in the queue should not be looping, otherwise
+    /// they will never end.
+    pub fn enqueue(&mut self, track: Handle<MusicTrack>) {
+        self.queue.push(track);
+    }
+
+    /// Returns the playlist queue.
+    pub fn queue(&self) -> &[Handle<MusicTrack>] {
+        &self.queue
+    }
+
+    /// Removes all tracks from the playlist queue.
+    pub fn clear_queue(&mut self) {
+        self.queue.clear();
+    }
+
+    /// Makes the playlist queue looping. In this case, every track that starts playing from the queue is
+    /// put back to the end of the queue.
+    pub fn set_queue_looping(&mut self, looping: bool) {
+        self.queue_looping = looping;
+    }
+
+    /// Returns `true` if the playlist queue is looping.
+    pub fn is_queue_looping(&self) -> bool {
+        self.queue_looping
+    }
+
+    /// Sets transition that is used to switch between the tracks of the playlist queue.
+    pub fn set_queue_transition(&mut self, transition: Transition) {
+        self.queue_transition = transition;
+    }
+
+    /// Returns transition that is used to switch between the tracks of the playlist queue.
+    pub fn queue_transition(&self) -> Transition {
+        self.queue_transition
+    }
+
+    fn clock_time(&self, state: &State) -> Option<f64> {
+        let track = self.tracks.try_borrow(self.current).ok()?;
+        if track.status == TrackStatus::Stopped {
+            return None;
+        }
+        let clock = track.clock_source();
+        state
+            .is_valid_handle(clock)
+            .then(|| state.source(clock).playback_time().as_secs_f64())
+    }
+
+    fn schedule(&mut self, action: MusicAction, transition: Transition) {
+        self.pending.push(PendingAction {
+            action,
+            transition,
+            // Actual time will be calculated on the next update, when the clock is available.
+            time: f64::NAN,
+            last_time: 0.0,
+        });
+    }
+
+    fn perform(&mut self, state: &mut State, action: MusicAction, transition: &Transition) {
+        match action {
+            MusicAction::Stop => {
+                if let Ok(current) = self.tracks.try_borrow_mut(self.current) {
+                    current.fade_out(transition);
+                }
+            }
+            MusicAction::Play(track) => {
+                if track == self.current
+                    && self
+                        .tracks
+                        .try_borrow(track)
+                        .is_ok_and(|t| t.status == TrackStatus::Playing)
+                {
+                    return;
+                }
+                if !self.tracks.is_valid_handle(track) {
+                    return;
+                }
+                if let Ok(current) = self.tracks.try_borrow_mut(self.current) {
+                    current.fade_out(transition);
+                }
+                self.tracks[track].start(state, transition);
+                self.current = track;
+            }
+            MusicAction::SetStemGain { track, stem, gain } => {
+                if let Some(stem) = self
+                    .tracks
+                    .try_borrow_mut(track)
+                    .ok()
+                    .and_then(|t| t.stems.get_mut(stem))
+                {
+                    stem.fade = Some(Fade::new(stem.gain(), gain, transition));
+                }
+            }
+        }
+    }
+
+    fn process_pending(&mut self, state: &mut State) {
+        let clock = self.clock_time(state);
+        let tempo = self
+            .tracks
+            .try_borrow(self.current)
+            .map(|t| t.tempo)
+            .unwrap_or_default();
+
+        let mut i = 0;
+        while i < self.pending.len() {
+            let ready = match clock {
+                // Nothing is playing, so there's nothing to sync with.
+                None => true,
+                Some(time) => {
+                    let pending = &mut self.pending[i];
+                    if pending.time.is_nan() {
+                        pending.time = tempo.next_boundary(time, pending.transition.sync);
+                        pending.last_time = time;
+                    }
+                    // The second condition handles looping tracks, that rewind before reaching the boundary.
+                    let ready = time >= pending.time || time < pending.last_time;
+                    pending.last_time = time;
+                    ready
+                }
+            };
+
+            if ready {
+                let pending = self.pending.remove(i);
+                self.perform(state, pending.action, &pending.transition);
+            } else {
+                i += 1;
+            }
+        }
+    }
+
+    fn advance_queue(&mut self, state: &mut State) {
+        if self.queue.is_empty() {
+            return;
+        }
+
+        let should_advance = match self.tracks.try_borrow(self.current) {
+            Ok(current) if current.status == TrackStatus::Playing => {
+                let clock = current.clock_source();
+                state.is_valid_handle(clock) && {
+                    let source = state.source(clock);
+                    let duration = source
+                        .buffer()
+                        .and_then(|b| b.state().data().map(|d| d.duration()))
+                        .unwrap_or_default();
+                    !source.is_looping()
+                        && duration.saturating_sub(source.playback_time())
+                            <= self.queue_transition.duration
+                }
+            }
+            _ => true,
+        };
+
+        // Do not interfere with explicit track switches.
+        let has_pending_play = self
+            .pending
+            .iter()
+            .any(|p| matches!(p.action, MusicAction::Play(_)));
+
+        if should_advance && !has_pending_play {
+            let next = self.queue.remove(0);
+            if self.queue_looping {
+                self.queue.push(next);
+            }
+            let transition = Transition {
+                sync: TransitionSync::Immediate,
+                ..self.queue_transition
+            };
+            self.perform(state, MusicAction::Play(next), &transition);
+        }
+    }
+
+    /// Updates the controller: performs pending transitions, advances the playlist queue and applies fades
+    /// to the sound sources. `dt` is the time (in seconds) passed since the last update. This method should
+    /// be called every frame.
+    pub fn update(&mut self, state: &mut State, dt: f32) {
+        self.process_pending(state);
+        self.advance_queue(state);
+
+        for track in self.tracks.iter_mut() {
+            track.update(state, self.gain, dt);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        buffer::{DataSource, SoundBufferResource, SoundBufferResourceExtension},
+        context::{SoundContext, State},
+        music::{
+            FadeCurve, MusicController, MusicStem, MusicTrack, Tempo, TrackStatus, Transition,
+            TransitionSync,
+        },
+        pool::Handle,
+        source::{SoundSource, SoundSourceBuilder, Status},
+    };
+    use std::time::Duration;
+
+    fn add_source(state: &mut State, seconds: usize) -> Handle<SoundSource> {
+        let buffer = SoundBufferResource::new_generic(DataSource::Raw {
+            sample_rate: 100,
+            channel_count: 1,
+            samples: vec![0.5; 100 * seconds],
+        })
+        .unwrap();
+        state.add_source(
+            SoundSourceBuilder::new()
+                .with_buffer(buffer)
+                .build()
+                .unwrap(),
+        )
+    }
+
+    // Renders the context to advance playback time of the sources.
+    fn advance(state: &mut State, seconds: f32) {
+        let mut output = vec![(0.0, 0.0); (seconds * 100.0) as usize];
+        state.render(100, &mut output);
+    }
+
+    #[test]
+    fn test_fade_curves() {
+        for curve in [FadeCurve::Linear, FadeCurve::EqualPower, FadeCurve::SCurve] {
+            assert_eq!(curve.evaluate(0.0, 1.0, 0.0), 0.0);
+            assert_eq!(curve.evaluate(0.0, 1.0, 1.0), 1.0);
+            assert_eq!(curve.evaluate(1.0, 0.0, 0.0), 1.0);
+            assert!(curve.evaluate(1.0, 0.0, 1.0).abs() < 1.0e-6);
+        }
+        // Equal power crossfade keeps total power constant.
+        let t = 0.3;
+        let a = FadeCurve::EqualPower.evaluate(0.0, 1.0, t);
+        let b = FadeCurve::EqualPower.evaluate(1.0, 0.0, t);
+        assert!((a * a + b * b - 1.0).abs() < 1.0e-5);
+    }
+
+    #[test]
+    fn test_tempo_boundaries() {
+        let tempo = Tempo::new(120.0, 4);
+        assert_eq!(tempo.beat_duration(), 0.5);
+        assert_eq!(tempo.bar_duration(), 2.0);
+        assert_eq!(tempo.next_boundary(0.7, TransitionSync::Immediate), 0.7);
+        assert_eq!(tempo.next_boundary(0.7, TransitionSync::NextBeat), 1.0);
+        assert_eq!(tempo.next_boundary(0.7, TransitionSync::NextBar), 2.0);
+        assert_eq!(tempo.next_boundary(2.0, TransitionSync::NextBar), 2.0);
+
+        let tempo = tempo.with_offset(Duration::from_millis(250));
+        assert_eq!(tempo.next_boundary(0.1, TransitionSync::NextBeat), 0.25);
+        assert_eq!(tempo.next_boundary(0.3, TransitionSync::NextBeat), 0.75);
+    }
+
+    #[test]
+    fn test_crossfade() {
+        let context = SoundContext::new();
+        let mut state = context.state();
+        let a = add_source(&mut state, 10);
+        let b = add_source(&mut state, 10);
+
+        let mut controller = MusicController::new();
+        let track_a = controller.add_track(MusicTrack::new("A").with_stem(MusicStem::new(a)));
+        let track_b = controller.add_track(MusicTrack::new("B").with_stem(MusicStem::new(b)));
+
+        controller.play(track_a, Transition::instant());
+        controller.update(&mut state, 0.1);
+        assert_eq!(state.source(a).status(), Status::Playing);
+        assert_eq!(state.source(a).gain(), 1.0);
+
+        let transition =
+            Transition::crossfade(Duration::from_secs(1)).with_curve(FadeCurve::Linear);
+        controller.play(track_b, transition);
+        controller.update(&mut state, 0.5);
+        assert_eq!(controller.current_track(), track_b);
+        assert!((state.source(a).gain() - 0.5).abs() < 1.0e-5);
+        assert!((state.source(b).gain() - 0.5).abs() < 1.0e-5);
+
+        controller.update(&mut state, 0.5);
+        controller.update(&mut state, 0.1);
+        assert_eq!(state.source(b).gain(), 1.0);
+        assert_eq!(state.source(a).status(), Status::Stopped);
+        assert_eq!(
+            controller.track(track_a).unwrap().status(),
+            TrackStatus::Stopped
+        );
+    }
+
+    #[test]
+    fn test_synced_transition() {
+        let context = SoundContext::new();
+        let mut state = context.state();
+        let a = add_source(&mut state, 10);
+        let b = add_source(&mut state, 10);
+
+        let mut controller = MusicController::new();
+        let track_a = controller.add_track(
+            MusicTrack::new("A")
+                .with_tempo(Tempo::new(60.0, 4))
+                .with_stem(MusicStem::new(a)),
+        );
+        let track_b = controller.add_track(MusicTrack::new("B").with_stem(MusicStem::new(b)));
+
+        controller.play(track_a, Transition::instant());
+        controller.update(&mut state, 0.0);
+
+        advance(&mut state, 0.5);
+        controller.play(
+            track_b,
+            Transition::instant().with_sync(TransitionSync::NextBeat),
+        );
+        controller.update(&mut state, 0.0);
+        assert_eq!(controller.current_track(), track_a);
+
+        advance(&mut state, 0.5);
+        controller.update(&mut state, 0.0);
+        assert_eq!(controller.current_track(), track_b);
+    }
+
+    #[test]
+    fn test_stem_fade() {
+        let context = SoundContext::new();
+        let mut state = context.state();
+        let drums = add_source(&mut state, 10);
+        let strings = add_source(&mut state, 10);
+
+        let mut controller = MusicController::new();
+        let track = controller.add_track(
+            MusicTrack::new("A")
+                .with_stem(MusicStem::new(drums))
+                .with_stem(MusicStem::new(strings).with_gain(0.0)),
+        );
+
+        controller.play(track, Transition::instant());
+        controller.update(&mut state, 0.0);
+        assert_eq!(state.source(strings).status(), Status::Playing);
+        assert_eq!(state.source(strings).gain(), 0.0);
+
+        controller.set_stem_gain(track, 1, 1.0, Transition::crossfade(Duration::from_secs(1)));
+        controller.update(&mut state, 1.0);
+        assert_eq!(state.source(strings).gain(), 1.0);
+        assert_eq!(state.source(drums).gain(), 1.0);
+    }
+
+    #[test]
+    fn test_playlist() {
+        let context = SoundContext::new();
+        let mut state = context.state();
+        let a = add_source(&mut state, 2);
+        let b = add_source(&mut state, 2);
+
+        let mut controller = MusicController::new();
+        let track_a = controller.add_track(MusicTrack::new("A").with_stem(MusicStem::new(a)));
+        let track_b = controller.add_track(MusicTrack::new("B").with_stem(MusicStem::new(b)));
+
+        controller.set_queue_transition(Transition::crossfade(Duration::from_millis(500)));
+        controller.set_queue_looping(true);
+        controller.enqueue(track_a);
+        controller.enqueue(track_b);
+
+        controller.update(&mut state, 0.0);
+        assert_eq!(controller.current_track(), track_a);
+        assert_eq!(controller.queue(), &[track_b, track_a]);
+
+        // Not yet close to the end.
+        advance(&mut state, 1.0);
+        controller.update(&mut state, 0.0);
+        assert_eq!(controller.current_track(), track_a);
+
+        // Close to the end - next track must start.
+        advance(&mut state, 0.6);
+        controller.update(&mut state, 0.0);
+        assert_eq!(controller.current_track(), track_b);
+        assert_eq!(state.source(b).status(), Status::Playing);
+        assert_eq!(controller.queue(), &[track_a, track_b]);
+    }
+
+    #[test]
+    fn test_stop_playlist() {
+        let context = SoundContext::new();
+        let mut state = context.state();
+        let a = add_source(&mut state, 2);
+        let b = add_source(&mut state, 2);
+
+        let mut controller = MusicController::new();
+        let track_a = controller.add_track(MusicTrack::new("A").with_stem(MusicStem::new(a)));
+        let track_b = controller.add_track(MusicTrack::new("B").with_stem(MusicStem::new(b)));
+
+        controller.enqueue(track_a);
+        controller.enqueue(track_b);
+        controller.update(&mut state, 0.0);
+        assert_eq!(controller.current_track(), track_a);
+
+        controller.stop(Transition::instant());
+        assert!(controller.queue().is_empty());
+        for _ in 0..3 {
+            advance(&mut state, 0.1);
+            controller.update(&mut state, 0.1);
+        }
+        assert_eq!(state.source(a).status(), Status::Stopped);
+        assert_eq!(state.source(b).status(), Status::Stopped);
+        assert_eq!(
+            controller.track(track_b).unwrap().status(),
+            TrackStatus::Stopped
+        );
+    }
+}
@@ -99,7 +99,8 @@ pub(crate) fn render_source_default(
 ) {
     let distance_gain = lerpf(
         1.0,
-        source.calculate_distance_gain(listener, distance_model),
+        source.calculate_distance_gain(listener, distance_model)
+            * source.calculate_cone_gain(listener),
         source.spatial_blend(),
     );
     let panning = lerpf(
@@ -49,7 +49,7 @@
 #![allow(clippy::float_cmp)]
 
 use crate::{
-    buffer::{streaming::StreamingBuffer, SoundBuffer, SoundBufferResource},
+    buffer::{metadata::LoopRegion, streaming::StreamingBuffer, SoundBuffer, SoundBufferResource},
     bus::AudioBusGraph,
     context::DistanceModel,
     error::SoundError,
@@ -58,12 +58,16 @@ use crate::{
 use fyrox_core::{
     algebra::Vector3,
     log::Log,
+    math::lerpf,
     reflect::prelude::*,
     uuid_provider,
     visitor::{Visit, VisitResult, Visitor},
 };
 use std::time::Duration;
 
+/// Maximum pitch multiplier that could be caused by Doppler effect.
+const MAX_DOPPLER_PITCH: f32 = 4.0;
+
 /// Status (state) of sound source.
 #[derive(Eq, PartialEq, Copy, Clone, Debug, Reflect, Visit)]
 #[repr(u32)]
@@ -136,6 +140,23 @@ pub struct SoundSource {
     max_distance: f32,
     #[reflect(min_value = 0.0, step = 0.05)]
     rolloff_factor: f32,
+    #[visit(optional)]
+    velocity: Vector3<f32>,
+    #[visit(optional)]
+    direction: Vector3<f32>,
+    #[visit(optional)]
+    #[reflect(min_value = 0.0, step = 0.05)]
+    inner_cone_angle: f32,
+    #[visit(optional)]
+    #[reflect(min_value = 0.0, step = 0.05)]
+    outer_cone_angle: f32,
+    #[visit(optional)]
+    #[reflect(min_value = 0.0, max_value = 1.0, step = 0.05)]
+    outer_cone_gain: f32,
+    // Pitch multiplier caused by Doppler effect, it is calculated right before rendering.
+    #[reflect(hidden)]
+    #[visit(skip)]
+    pub(crate) doppler_pitch: f64,
     // Some data that needed for iterative overlap-save convolution.
     #[reflect(hidden)]
     #[visit(skip)]
@@ -174,6 +195,12 @@ impl Default for SoundSource {
             position: Vector3::new(0.0, 0.0, 0.0),
             max_distance: f32::MAX,
             rolloff_factor: 1.0,
+            velocity: Vector3::new(0.0, 0.0, 0.0),
+            direction: Vector3::new(0.0, 0.0, 0.0),
+            inner_cone_angle: std::f32::consts::TAU,
+            outer_cone_angle: std::f32::consts::TAU,
+            outer_cone_gain: 0.0,
+            doppler_pitch: 1.0,
             prev_left_samples: Default::default(),
             prev_right_samples: Default::default(),
             prev_sampling_vector: Vector3::new(0.0, 0.0, 1.0),
@@ -402,6 +429,70 @@ impl SoundSource {
         self.max_distance
     }
 
+    /// Sets velocity of the source in world space (in units per second). Velocity is used only to
+    /// calculate pitch shift caused by Doppler effect, it does not move the source. See
+    /// [`crate::context::State::set_doppler_factor`] for more info.
+    pub fn set_velocity(&mut self, velocity: Vector3<f32>) -> &mut Self {
+        self.velocity = velocity;
+        self
+    }
+
+    /// Returns velocity of the source.
+    pub fn velocity(&self) -> Vector3<f32> {
+        self.velocity
+    }
+
+    /// Sets direction of the source in world space. Direction defines the axis of sound cone of
+    /// directional sources (speakers, engine exhausts, etc.). Zero vector (default) makes the
+    /// source omnidirectional.
+    pub fn set_direction(&mut self, direction: Vector3<f32>) -> &mut Self {
+        self.direction = direction;
+        self
+    }
+
+    /// Returns direction of the source.
+    pub fn direction(&self) -> Vector3<f32> {
+        self.direction
+    }
+
+    /// Sets full angle (in radians) of inner sound cone. The listener inside of the inner cone
+    /// hears the source with no cone attenuation at all. Default value is `2*pi`, which means that
+    /// the source is omnidirectional.
+    pub fn set_inner_cone_angle(&mut self, angle: f32) -> &mut Self {
+        self.inner_cone_angle = angle.clamp(0.0, std::f32::consts::TAU);
+        self
+    }
+
+    /// Returns full angle (in radians) of inner sound cone.
+    pub fn inner_cone_angle(&self) -> f32 {
+        self.inner_cone_angle
+    }
+
+    /// Sets full angle (in radians) of outer sound cone. The listener outside of the outer cone
+    /// hears the source with [`Self::outer_cone_gain`], between inner and outer cones the gain is
+    /// linearly interpolated. Default value is `2*pi`.
+    pub fn set_outer_cone_angle(&mut self, angle: f32) -> &mut Self {
+        self.outer_cone_angle = angle.clamp(0.0, std::f32::consts::TAU);
+        self
+    }
+
+    /// Returns full angle (in radians) of outer sound cone.
+    pub fn outer_cone_angle(&self) -> f32 {
+        self.outer_cone_angle
+    }
+
+    /// Sets gain that will be applied to the source when the listener is outside of outer
+    /// sound cone. Value must be in 0..1 range. Default value is 0.0.
+    pub fn set_outer_cone_gain(&mut self, gain: f32) -> &mut Self {
+        self.outer_cone_gain = gain.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Returns gain of the source outside of outer sound cone.
+    pub fn outer_cone_gain(&self) -> f32 {
+        self.outer_cone_gain
+    }
+
     /// Sets new name of the target audio bus. The name must be valid, otherwise the sound won't play!
     /// Default is [`AudioBusGraph::PRIMARY_BUS`].
     pub fn set_bus<S: AsRef<str>>(&mut self, bus: S) {
@@ -437,6 +528,69 @@ impl SoundSource {
         }
     }
 
+    // Cone attenuation also follows OpenAL Specification. Angles are full cone angles, so
+    // they're halved before comparison with the angle between source direction and listener.
+    pub(crate) fn calculate_cone_gain(&self, listener: &Listener) -> f32 {
+        let (Some(direction), Some(to_listener)) = (
+            self.direction.try_normalize(f32::EPSILON),
+            (listener.position() - self.position).try_normalize(f32::EPSILON),
+        ) else {
+            return 1.0;
+        };
+
+        let angle = direction.dot(&to_listener).clamp(-1.0, 1.0).acos();
+        let inner = 0.5 * self.inner_cone_angle;
+        let outer = 0.5 * self.outer_cone_angle.max(self.inner_cone_angle);
+        if angle <= inner {
+            1.0
+        } else if angle >= outer {
+            self.outer_cone_gain
+        } else {
+            lerpf(1.0, self.outer_cone_gain, (angle - inner) / (outer - inner))
+        }
+    }
+
+    // Doppler shift formula from OpenAL Specification, velocities are projected on the
+    // source-to-listener axis and clamped so the sound never goes faster than sound itself. A source
+    // that approaches the listener at the speed of sound would still have an infinite pitch, so the
+    // pitch is clamped too.
+    pub(crate) fn calculate_doppler_pitch(
+        &self,
+        listener: &Listener,
+        doppler_factor: f32,
+        speed_of_sound: f32,
+    ) -> f64 {
+        if doppler_factor <= 0.0 || speed_of_sound <= 0.0 {
+            return 1.0;
+        }
+
+        let Some(source_to_listener) =
+            (listener.position() - self.position).try_normalize(f32::EPSILON)
+        else {
+            return 1.0;
+        };
+
+        let limit = speed_of_sound / doppler_factor;
+        let listener_speed = listener.velocity().dot(&source_to_listener).min(limit);
+        let source_speed = self.velocity.dot(&source_to_listener).min(limit);
+
+        let numerator = speed_of_sound - doppler_factor * listener_speed;
+        let denominator = (speed_of_sound - doppler_factor * source_speed)
+            .max(speed_of_sound / MAX_DOPPLER_PITCH);
+
+        (numerator / denominator).min(MAX_DOPPLER_PITCH) as f64
+    }
+
+    pub(crate) fn update_doppler_pitch(
+        &mut self,
+        listener: &Listener,
+        doppler_factor: f32,
+        speed_of_sound: f32,
+    ) {
+        let doppler_pitch = self.calculate_doppler_pitch(listener, doppler_factor, speed_of_sound);
+        self.doppler_pitch = 1.0 + (doppler_pitch - 1.0) * self.spatial_blend as f64;
+    }
+
     pub(crate) fn calculate_panning(&self, listener: &Listener) -> f32 {
         (listener.position() - self.position)
             .try_normalize(f32::EPSILON)
@@ -531,20 +685,35 @@ impl SoundSource {
 
             let channel_count = buffer.channel_count();
             let len = buffer.samples().len();
+            let block_len = self.block_len(buffer);
+            let loop_region = self.loop_region(buffer);
             let mut end_reached = true;
             if let SoundBuffer::Streaming(streaming) = buffer {
                 // Means that this is the last available block.
-                if len != channel_count * StreamingBuffer::STREAM_SAMPLE_COUNT {
+                let last_block = len != channel_count * StreamingBuffer::STREAM_SAMPLE_COUNT;
+                if let Some(region) = loop_region
+                    .as_ref()
+                    .filter(|_| last_block || block_len < len / channel_count)
+                {
+                    let _ = streaming.time_seek(Duration::from_secs_f64(
+                        region.start as f64 / streaming.sample_rate as f64,
+                    ));
+                } else if last_block {
                     let _ = streaming.rewind();
                 } else {
                     end_reached = false;
                 }
-                self.prev_buffer_sample = get_last_sample(streaming);
+                self.prev_buffer_sample = get_last_sample(streaming, block_len);
                 streaming.read_next_block();
             }
             if end_reached {
-                self.buf_read_pos = 0.0;
-                self.playback_pos = 0.0;
+                let loop_start = loop_region.map_or(0.0, |region| region.start as f64);
+                self.buf_read_pos = match buffer {
+                    SoundBuffer::Generic(_) => loop_start,
+                    // Streaming buffer is already at the start of the loop.
+                    SoundBuffer::Streaming(_) => 0.0,
+                };
+                self.playback_pos = loop_start;
                 if !self.looping {
                     self.status = Status::Stopped;
                     return;
@@ -555,6 +724,28 @@ impl SoundSource {
         }
     }
 
+    // Returns loop region of the buffer (in frames), that should be used by the source.
+    fn loop_region(&self, buffer: &SoundBuffer) -> Option<LoopRegion> {
+        if self.looping {
+            buffer.metadata.loop_region.clone()
+        } else {
+            None
+        }
+    }
+
+    // Returns amount of frames of the current block that could be played before the end of the
+    // block or the end of the loop region.
+    fn block_len(&self, buffer: &SoundBuffer) -> usize {
+        let len = buffer.samples.len() / buffer.channel_count;
+        match self.loop_region(buffer) {
+            Some(region) => {
+                let block_start = (self.playback_pos - self.buf_read_pos).round().max(0.0) as usize;
+                region.end.saturating_sub(block_start).min(len)
+            }
+            None => len,
+        }
+    }
+
     // Renders until the end of the block or until amount samples is written and returns
     // the number of written samples.
     fn render_until_block_end(
@@ -573,7 +764,7 @@ impl SoundSource {
         // However such auto-resampling has poor quality, but it is fast.
         let resampling_multiplier = buffer.sample_rate as f64 / f64::from(sample_rate);
 
-        let step = self.pitch * resampling_multiplier;
+        let step = self.pitch * self.doppler_pitch * resampling_multiplier;
         if step == 1.0 {
             if self.buf_read_pos < 0.0 {
                 // This can theoretically happen if we change pitch on the fly.
@@ -583,8 +774,8 @@ impl SoundSource {
             }
             // Fast-path for common case when there is no resampling and no pitch change.
             let from = self.buf_read_pos as usize;
-            let buffer_len = buffer.samples.len() / buffer.channel_count;
-            let rendered = (buffer_len - from).min(amount);
+            let buffer_len = self.block_len(buffer);
+            let rendered = buffer_len.saturating_sub(from).min(amount);
             if buffer.channel_count == 2 {
                 for i in from..from + rendered {
                     self.frame_samples
@@ -640,7 +831,7 @@ impl SoundSource {
         let rel_step = step as f32;
         // We skip one last element because the hot loop resampling between current and next
         // element. Last elements are appended after the hot loop.
-        let buffer_last = buffer.samples.len() / buffer.channel_count - 1;
+        let buffer_last = self.block_len(buffer).saturating_sub(1);
         if buffer.channel_count == 2 {
             while rendered < amount {
                 let (idx, w) = {
@@ -685,8 +876,8 @@ impl SoundSource {
     }
 }
 
-fn get_last_sample(buffer: &StreamingBuffer) -> (f32, f32) {
-    let len = buffer.samples.len();
+fn get_last_sample(buffer: &StreamingBuffer, block_len: usize) -> (f32, f32) {
+    let len = (block_len * buffer.channel_count).min(buffer.samples.len());
     if len == 0 {
         return (0.0, 0.0);
     }
@@ -742,6 +933,11 @@ pub struct SoundSourceBuilder {
     position: Vector3<f32>,
     max_distance: f32,
     rolloff_factor: f32,
+    velocity: Vector3<f32>,
+    direction: Vector3<f32>,
+    inner_cone_angle: f32,
+    outer_cone_angle: f32,
+    outer_cone_gain: f32,
     spatial_blend: f32,
     bus: String,
 }
@@ -769,6 +965,11 @@ impl SoundSourceBuilder {
             position: Vector3::new(0.0, 0.0, 0.0),
             max_distance: f32::MAX,
             rolloff_factor: 1.0,
+            velocity: Vector3::new(0.0, 0.0, 0.0),
+            direction: Vector3::new(0.0, 0.0, 0.0),
+            inner_cone_angle: std::f32::consts::TAU,
+            outer_cone_angle: std::f32::consts::TAU,
+            outer_cone_gain: 0.0,
             spatial_blend: 1.0,
             bus: AudioBusGraph::PRIMARY_BUS.to_string(),
         }
@@ -864,6 +1065,36 @@ impl SoundSourceBuilder {
         self
     }
 
+    /// See [`SoundSource::set_velocity`]
+    pub fn with_velocity(mut self, velocity: Vector3<f32>) -> Self {
+        self.velocity = velocity;
+        self
+    }
+
+    /// See [`SoundSource::set_direction`]
+    pub fn with_direction(mut self, direction: Vector3<f32>) -> Self {
+        self.direction = direction;
+        self
+    }
+
+    /// See [`SoundSource::set_inner_cone_angle`]
+    pub fn with_inner_cone_angle(mut self, angle: f32) -> Self {
+        self.inner_cone_angle = angle.clamp(0.0, std::f32::consts::TAU);
+        self
+    }
+
+    /// See [`SoundSource::set_outer_cone_angle`]
+    pub fn with_outer_cone_angle(mut self, angle: f32) -> Self {
+        self.outer_cone_angle = angle.clamp(0.0, std::f32::consts::TAU);
+        self
+    }
+
+    /// See [`SoundSource::set_outer_cone_gain`]
+    pub fn with_outer_cone_gain(mut self, gain: f32) -> Self {
+        self.outer_cone_gain = gain.clamp(0.0, 1.0);
+        self
+    }
+
     /// Sets desired output bus for the sound source.
     pub fn with_bus<S: AsRef<str>>(mut self, bus: S) -> Self {
         self.bus = bus.as_ref().to_string();
@@ -886,6 +1117,11 @@ impl SoundSourceBuilder {
             position: self.position,
             max_distance: self.max_distance,
             rolloff_factor: self.rolloff_factor,
+            velocity: self.velocity,
+            direction: self.direction,
+            inner_cone_angle: self.inner_cone_angle,
+            outer_cone_angle: self.outer_cone_angle,
+            outer_cone_gain: self.outer_cone_gain,
             spatial_blend: self.spatial_blend,
             prev_left_samples: Default::default(),
             prev_right_samples: Default::default(),
@@ -899,3 +1135,165 @@ impl SoundSourceBuilder {
         Ok(source)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        algebra::Vector3,
+        buffer::{
+            generic::GenericBuffer,
+            metadata::{LoopRegion, SoundMetadata},
+            DataSource, SoundBuffer, SoundBufferResource,
+        },
+        listener::Listener,
+        source::{SoundSource, SoundSourceBuilder, Status, MAX_DOPPLER_PITCH},
+    };
+    use fyrox_core::uuid::Uuid;
+    use fyrox_resource::untyped::ResourceKind;
+
+    const SPEED_OF_SOUND: f32 = 343.3;
+
+    fn source_at_origin() -> SoundSource {
+        SoundSourceBuilder::new().build().unwrap()
+    }
+
+    fn listener_at(position: Vector3<f32>) -> Listener {
+        let mut listener = Listener::new();
+        listener.set_position(position);
+        listener
+    }
+
+    #[test]
+    fn test_doppler_pitch_at_rest() {
+        let source = source_at_origin();
+        let listener = listener_at(Vector3::new(0.0, 0.0, 10.0));
+        assert_eq!(
+            source.calculate_doppler_pitch(&listener, 1.0, SPEED_OF_SOUND),
+            1.0
+        );
+    }
+
+    #[test]
+    fn test_doppler_pitch_approaching_and_receding() {
+        let mut source = source_at_origin();
+        let listener = listener_at(Vector3::new(0.0, 0.0, 10.0));
+
+        source.set_velocity(Vector3::new(0.0, 0.0, 30.0));
+        assert!(source.calculate_doppler_pitch(&listener, 1.0, SPEED_OF_SOUND) > 1.0);
+
+        source.set_velocity(Vector3::new(0.0, 0.0, -30.0));
+        assert!(source.calculate_doppler_pitch(&listener, 1.0, SPEED_OF_SOUND) < 1.0);
+
+        // Zero doppler factor disables the effect.
+        assert_eq!(
+            source.calculate_doppler_pitch(&listener, 0.0, SPEED_OF_SOUND),
+            1.0
+        );
+    }
+
+    #[test]
+    fn test_doppler_pitch_moving_listener() {
+        let source = source_at_origin();
+        let mut listener = listener_at(Vector3::new(0.0, 0.0, 10.0));
+
+        listener.set_velocity(Vector3::new(0.0, 0.0, -30.0));
+        assert!(source.calculate_doppler_pitch(&listener, 1.0, SPEED_OF_SOUND) > 1.0);
+
+        listener.set_velocity(Vector3::new(0.0, 0.0, 30.0));
+        assert!(source.calculate_doppler_pitch(&listener, 1.0, SPEED_OF_SOUND) < 1.0);
+    }
+
+    #[test]
+    fn test_doppler_pitch_at_speed_of_sound() {
+        let mut source = source_at_origin();
+        let mut listener = listener_at(Vector3::new(0.0, 0.0, 10.0));
+
+        // Approaching at or faster than sound must not produce an infinite pitch.
+        for speed in [SPEED_OF_SOUND, 2.0 * SPEED_OF_SOUND] {
+            source.set_velocity(Vector3::new(0.0, 0.0, speed));
+            let pitch = source.calculate_doppler_pitch(&listener, 1.0, SPEED_OF_SOUND);
+            assert!(pitch.is_finite());
+            assert_eq!(pitch, MAX_DOPPLER_PITCH as f64);
+        }
+
+        // Receding listener cancels approaching source.
+        listener.set_velocity(Vector3::new(0.0, 0.0, SPEED_OF_SOUND));
+        let pitch = source.calculate_doppler_pitch(&listener, 1.0, SPEED_OF_SOUND);
+        assert!(pitch.is_finite());
+        assert!((0.0..=MAX_DOPPLER_PITCH as f64).contains(&pitch));
+    }
+
+    #[test]
+    fn test_cone_gain() {
+        let mut source = source_at_origin();
+        source
+            .set_direction(Vector3::new(0.0, 0.0, 1.0))
+            .set_inner_cone_angle(90.0f32.to_radians())
+            .set_outer_cone_angle(180.0f32.to_radians())
+            .set_outer_cone_gain(0.25);
+
+        // In front of the source - inside inner cone.
+        let listener = listener_at(Vector3::new(0.0, 0.0, 10.0));
+        assert_eq!(source.calculate_cone_gain(&listener), 1.0);
+
+        // Behind the source - outside outer cone.
+        let listener = listener_at(Vector3::new(0.0, 0.0, -10.0));
+        assert_eq!(source.calculate_cone_gain(&listener), 0.25);
+
+        // Between cones.
+        let listener = listener_at(Vector3::new(10.0, 0.0, 10.0 * 0.4));
+        let gain = source.calculate_cone_gain(&listener);
+        assert!(gain > 0.25 && gain < 1.0);
+
+        // Omnidirectional source is not attenuated.
+        source.set_direction(Vector3::new(0.0, 0.0, 0.0));
+        let listener = listener_at(Vector3::new(0.0, 0.0, -10.0));
+        assert_eq!(source.calculate_cone_gain(&listener), 1.0);
+    }
+
+    #[test]
+    fn test_loop_region() {
+        let mut buffer = GenericBuffer::new(DataSource::Raw {
+            sample_rate: 100,
+            channel_count: 1,
+            samples: (0..10).map(|i| i as f32).collect(),
+        })
+        .unwrap();
+        buffer.metadata = SoundMetadata {
+            loop_region: Some(LoopRegion { start: 2, end: 5 }),
+            cue_markers: vec![],
+        };
+        let buffer = SoundBufferResource::new_ok(
+            Uuid::new_v4(),
+            ResourceKind::Embedded,
+            SoundBuffer::Generic(buffer),
+        );
+
+        let mut source = SoundSourceBuilder::new()
+            .with_buffer(buffer.clone())
+            .with_looping(true)
+            .with_status(Status::Playing)
+            .build()
+            .unwrap();
+        source.render(100, 12);
+        let rendered = source
+            .frame_samples()
+            .iter()
+            .map(|(l, _)| *l)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            rendered,
+            [0.0, 1.0, 2.0, 3.0, 4.0, 2.0, 3.0, 4.0, 2.0, 3.0, 4.0, 2.0]
+        );
+
+        // Loop region is ignored when the source is not looping.
+        let mut source = SoundSourceBuilder::new()
+            .with_buffer(buffer)
+            .with_status(Status::Playing)
+            .build()
+            .unwrap();
+        source.render(100, 12);
+        assert_eq!(source.frame_samples()[9].0, 9.0);
+        assert_eq!(source.status(), Status::Stopped);
+    }
+}
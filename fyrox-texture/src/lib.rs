@@ -291,6 +291,32 @@ impl ResourceData for Texture {
         <Self as TypeUuidProvider>::type_uuid()
     }
 
+    fn memory_usage(&self) -> usize {
+        self.bytes.len()
+    }
+
+    fn downgrade(&mut self) -> bool {
+        // Drop the most detailed mip level, the rest of the chain stays intact.
+        let TextureKind::Rectangle { width, height } = self.kind else {
+            return false;
+        };
+        if self.is_render_target || self.mip_count <= 1 || width <= 1 || height <= 1 {
+            return false;
+        }
+        let first_mip_size = bytes_in_mip_level(self.kind, self.pixel_kind, 0) as usize;
+        if first_mip_size >= self.bytes.len() {
+            return false;
+        }
+        self.bytes.0.drain(..first_mip_size);
+        self.kind = TextureKind::Rectangle {
+            width: width / 2,
+            height: height / 2,
+        };
+        self.mip_count -= 1;
+        self.modifications_counter += 1;
+        true
+    }
+
     fn save(&mut self, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
         let color_type = match self.pixel_kind {
             TexturePixelKind::R8 => ColorType::L8,
@@ -1486,6 +1512,15 @@ where
 }
 
 impl Texture {
+    /// Checks whether loading of the given data with the given import options does any processing
+    /// of the pixels (mip map generation, compression, etc.). DDS textures are always loaded as-is.
+    pub(crate) fn is_processed_on_load(data: &[u8], import_options: &TextureImportOptions) -> bool {
+        !data.starts_with(b"DDS ")
+            && (import_options.minification_filter.is_using_mip_mapping()
+                || import_options.compression != CompressionOptions::NoCompression
+                || import_options.flip_green_channel)
+    }
+
     /// Tries to load a texture from given data in one of the following formats: PNG, BMP, TGA, JPG, DDS, GIF. Use
     /// this method if you want to load a texture from embedded data.
     ///
@@ -1745,6 +1780,21 @@ impl Texture {
         }
     }
 
+    /// Restores a processed texture, that was stored by [`Self::to_derived_data`].
+    pub(crate) fn from_derived_data(data: &[u8]) -> Option<Self> {
+        let mut visitor = Visitor::load_binary_from_memory(data).ok()?;
+        let mut texture = Texture::default();
+        texture.visit("Texture", &mut visitor).ok()?;
+        Some(texture)
+    }
+
+    /// Serializes the processed texture, so it could be stored in a derived-data cache.
+    pub(crate) fn to_derived_data(&mut self) -> Option<Vec<u8>> {
+        let mut visitor = Visitor::new();
+        self.visit("Texture", &mut visitor).ok()?;
+        visitor.save_binary_to_vec().ok()
+    }
+
     /// Tries to load a texture from a file.
     ///
     /// # Notes
@@ -21,10 +21,11 @@
 //! Texture loader.
 
 use crate::{Texture, TextureImportOptions};
-use fyrox_core::{uuid::Uuid, TypeUuidProvider};
+use fyrox_core::{log::Log, uuid::Uuid, TypeUuidProvider};
 use fyrox_resource::{
-    io::ResourceIo, loader::BoxedImportOptionsLoaderFuture, loader::BoxedLoaderFuture,
-    loader::LoaderPayload, loader::ResourceLoader, options::try_get_import_settings,
+    cache::DerivedDataCache, cache::DerivedDataKey, io::ResourceIo,
+    loader::BoxedImportOptionsLoaderFuture, loader::BoxedLoaderFuture, loader::LoaderPayload,
+    loader::ResourceLoader, options::try_get_import_settings,
     options::try_get_import_settings_opaque, options::BaseImportOptions, state::LoadError,
 };
 use std::{path::PathBuf, sync::Arc};
@@ -33,6 +34,16 @@ use std::{path::PathBuf, sync::Arc};
 pub struct TextureLoader {
     /// Default import options for textures.
     pub default_import_options: TextureImportOptions,
+    /// Optional cache for imported textures. If set, processed textures (compressed, with generated
+    /// mip maps, etc.) are stored in the cache and loaded from it while their source files and
+    /// import options remain the same.
+    pub derived_data_cache: Option<DerivedDataCache>,
+}
+
+impl TextureLoader {
+    /// Version of the texture importer. It is a part of derived-data cache keys, so it must be
+    /// bumped every time when the output of the importer changes.
+    pub const IMPORTER_VERSION: u32 = 1;
 }
 
 impl ResourceLoader for TextureLoader {
@@ -48,6 +59,7 @@ impl ResourceLoader for TextureLoader {
 
     fn load(&self, path: PathBuf, io: Arc<dyn ResourceIo>) -> BoxedLoaderFuture {
         let default_import_options = self.default_import_options.clone();
+        let derived_data_cache = self.derived_data_cache.clone();
         Box::pin(async move {
             let io = io.as_ref();
 
@@ -55,11 +67,47 @@ impl ResourceLoader for TextureLoader {
                 .await
                 .unwrap_or(default_import_options);
 
-            let raw_texture = Texture::load_from_file(&path, io, import_options)
+            let Some(cache) = derived_data_cache else {
+                let raw_texture = Texture::load_from_file(&path, io, import_options)
+                    .await
+                    .map_err(LoadError::new)?;
+                return Ok(LoaderPayload::new(raw_texture));
+            };
+
+            let source = io.load_file(&path).await.map_err(LoadError::new)?;
+            if !Texture::is_processed_on_load(&source, &import_options) {
+                // There's nothing to cache, the texture is loaded as-is.
+                let raw_texture =
+                    Texture::load_from_memory(&source, import_options).map_err(LoadError::new)?;
+                return Ok(LoaderPayload::new(raw_texture));
+            }
+
+            let key = DerivedDataKey::with_options(
+                &source,
+                "Texture",
+                Self::IMPORTER_VERSION,
+                &import_options,
+            );
+            if let Some(texture) = cache
+                .get(key)
                 .await
-                .map_err(LoadError::new)?;
+                .and_then(|data| Texture::from_derived_data(&data))
+            {
+                return Ok(LoaderPayload::new(texture));
+            }
+
+            let mut texture =
+                Texture::load_from_memory(&source, import_options).map_err(LoadError::new)?;
+            if let Some(data) = texture.to_derived_data() {
+                if let Err(err) = cache.put(key, &data).await {
+                    Log::warn(format!(
+                        "Unable to put {} texture to the derived data cache. Reason: {err}",
+                        path.display()
+                    ));
+                }
+            }
 
-            Ok(LoaderPayload::new(raw_texture))
+            Ok(LoaderPayload::new(texture))
         })
     }
 
@@ -0,0 +1,105 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Semantic diff and three-way merge tool for visitor files (scenes, user interfaces, etc.).
+
+use clap::{Parser, Subcommand};
+use fyrox_core::visitor::{
+    diff::{diff, merge},
+    error::VisitError,
+    Visitor,
+};
+use std::{path::PathBuf, process::ExitCode};
+
+#[derive(Parser, Debug)]
+#[clap(author, version, about, long_about = None)]
+struct Args {
+    #[clap(subcommand)]
+    command: Commands,
+}
+
+#[derive(Subcommand, Debug)]
+enum Commands {
+    /// Prints semantic difference between two files.
+    #[clap(arg_required_else_help = true)]
+    Diff { old: PathBuf, new: PathBuf },
+    /// Merges two files that were derived from the common base. Conflicts are resolved
+    /// automatically and printed, the exit code is 1 if there were any. Nothing is written if
+    /// some conflicts could not be resolved.
+    #[clap(arg_required_else_help = true)]
+    Merge {
+        base: PathBuf,
+        ours: PathBuf,
+        theirs: PathBuf,
+        /// Path to the merged file. The merged file is printed if not specified.
+        #[clap(short, long)]
+        output: Option<PathBuf>,
+    },
+}
+
+fn load(path: &PathBuf) -> Result<Visitor, VisitError> {
+    Visitor::load_from_memory(&std::fs::read(path)?)
+}
+
+fn run(command: Commands) -> Result<bool, VisitError> {
+    match command {
+        Commands::Diff { old, new } => {
+            let diff = diff(&load(&old)?, &load(&new)?);
+            print!("{diff}");
+            Ok(diff.is_empty())
+        }
+        Commands::Merge {
+            base,
+            ours,
+            theirs,
+            output,
+        } => {
+            let merged = merge(&load(&base)?, &load(&ours)?, &load(&theirs)?);
+            for conflict in merged.conflicts.iter() {
+                if conflict.resolved {
+                    eprintln!("conflict: {conflict}");
+                } else {
+                    eprintln!("unresolved conflict: {conflict}");
+                }
+            }
+            if !merged.is_resolved() {
+                return Ok(false);
+            }
+            match output {
+                Some(output) => merged.visitor.save_ascii_to_file(output)?,
+                None => print!("{}", merged.visitor.save_ascii_to_string()),
+            }
+            Ok(merged.is_clean())
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let args: Args = Args::parse();
+
+    match run(args.command) {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::from(1),
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::from(2)
+        }
+    }
+}